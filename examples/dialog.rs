@@ -33,6 +33,51 @@ fn main() -> anyhow::Result<()> {
                 };
                 let path = path.to_string_lossy();
                 println!("FileSaveDialog: {path}");
+            } else if k.is(wiard::VirtualKey::F, wiard::KeyState::Pressed) {
+                let Some(folder) = wiard::FileOpenDialog::new_folder(&window).show() else {
+                    println!("FileOpenDialog: Cancelled");
+                    continue;
+                };
+                println!("FileOpenDialog: {}", folder.0.to_string_lossy());
+            } else if k.is(wiard::VirtualKey::E, wiard::KeyState::Pressed) {
+                let ret = wiard::FileSaveDialog::new(&window)
+                    .file_name("untitled.txt")
+                    .file_types([("Text", "*.txt"), ("All files", "*.*")])
+                    .combo_box("Encoding", ["UTF-8", "UTF-16"], 0)
+                    .check_button("Add BOM", false)
+                    .on_type_change(|index| println!("FileSaveDialog: type {index}"))
+                    .try_show();
+                match ret {
+                    Ok(Some(ret)) => println!("FileSaveDialog: {ret:?}"),
+                    Ok(None) => println!("FileSaveDialog: Cancelled"),
+                    Err(e) => println!("FileSaveDialog: {e}"),
+                }
+            } else if k.is(wiard::VirtualKey::B, wiard::KeyState::Pressed) {
+                let ret = wiard::MessageDialog::new(&window, "Do you want to save changes?")
+                    .title("wiard dialog")
+                    .icon(wiard::MessageIcon::Question)
+                    .buttons(wiard::MessageButtons::YesNoCancel)
+                    .default_button(wiard::DialogButton::Cancel)
+                    .show();
+                println!("MessageDialog: {ret:?}");
+            } else if k.is(wiard::VirtualKey::T, wiard::KeyState::Pressed) {
+                let ret = wiard::TaskDialog::new(&window)
+                    .title("wiard dialog")
+                    .main_instruction("Select an action")
+                    .content("See <a href=\"https://github.com/btanno/wiard\">wiard</a>")
+                    .icon(wiard::TaskDialogIcon::Information)
+                    .button("Open\nOpens the file")
+                    .button("Discard\nCloses without saving")
+                    .command_links(true)
+                    .common_buttons(&[wiard::DialogButton::Cancel])
+                    .radio_button("Only this file")
+                    .radio_button("All files")
+                    .verification("Don't ask again", false)
+                    .expanded_information("Details")
+                    .footer("Footer")
+                    .hyperlinks(true)
+                    .show();
+                println!("TaskDialog: {ret:?}");
             }
         }
     }
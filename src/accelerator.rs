@@ -0,0 +1,299 @@
+//! Keyboard accelerators.
+//!
+//! A [`Chord`] is a key with modifier keys which parses from and formats to strings like
+//! `"Ctrl+Shift+S"`, `"Alt+F4"` and `"Ctrl+Num+"`. [`Accelerators`] binds chords to command ids.
+//! When `Accelerators` is set to a window, the key input which matches a chord is received as
+//! `Event::Accelerator` instead of `Event::KeyInput`.
+//!
+//! ```
+//! use wiard::{Accelerators, Chord, Modifiers, VirtualKey};
+//!
+//! let accels = Accelerators::parse([("Ctrl+S", 1), ("Ctrl+Shift+S", 2)]).unwrap();
+//! assert_eq!(accels.find(VirtualKey::S, Modifiers::CTRL | Modifiers::SHIFT), Some(2));
+//!
+//! let chord: Chord = "ctrl+num+".parse().unwrap();
+//! assert_eq!(chord.to_string(), "Ctrl+Num+");
+//! ```
+
+use crate::*;
+use bitflags::bitflags;
+use std::str::FromStr;
+
+bitflags! {
+    /// Modifier keys of a chord.
+    #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Default)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct Modifiers: u32 {
+        const CTRL = 0x01;
+        const SHIFT = 0x02;
+        const ALT = 0x04;
+        const WIN = 0x08;
+    }
+}
+
+const MODIFIER_NAMES: [(Modifiers, &str); 4] = [
+    (Modifiers::CTRL, "Ctrl"),
+    (Modifiers::SHIFT, "Shift"),
+    (Modifiers::ALT, "Alt"),
+    (Modifiers::WIN, "Win"),
+];
+
+const KEY_ALIASES: [(&str, VirtualKey); 8] = [
+    ("Control", VirtualKey::Ctrl),
+    ("Escape", VirtualKey::Esc),
+    ("Return", VirtualKey::Enter),
+    ("Del", VirtualKey::Delete),
+    ("Ins", VirtualKey::Insert),
+    ("PgUp", VirtualKey::PageUp),
+    ("PgDn", VirtualKey::PageDown),
+    ("Back", VirtualKey::BackSpace),
+];
+
+fn parse_modifier(s: &str) -> Option<Modifiers> {
+    if s.eq_ignore_ascii_case("Control") {
+        return Some(Modifiers::CTRL);
+    }
+    MODIFIER_NAMES
+        .iter()
+        .find(|(_, name)| s.eq_ignore_ascii_case(name))
+        .map(|(m, _)| *m)
+}
+
+fn key_name(key: VirtualKey) -> String {
+    match key {
+        VirtualKey::Key0 => "0".into(),
+        VirtualKey::Key1 => "1".into(),
+        VirtualKey::Key2 => "2".into(),
+        VirtualKey::Key3 => "3".into(),
+        VirtualKey::Key4 => "4".into(),
+        VirtualKey::Key5 => "5".into(),
+        VirtualKey::Key6 => "6".into(),
+        VirtualKey::Key7 => "7".into(),
+        VirtualKey::Key8 => "8".into(),
+        VirtualKey::Key9 => "9".into(),
+        VirtualKey::NumAdd => "Num+".into(),
+        VirtualKey::NumSub => "Num-".into(),
+        VirtualKey::NumMul => "Num*".into(),
+        VirtualKey::NumDiv => "Num/".into(),
+        VirtualKey::NumDecimal => "Num.".into(),
+        VirtualKey::BackSpace => "Backspace".into(),
+        VirtualKey::Other(v) => format!("0x{v:02X}"),
+        _ => format!("{key:?}"),
+    }
+}
+
+fn parse_key(s: &str) -> std::result::Result<VirtualKey, ParseChordError> {
+    if s.is_empty() {
+        return Err(ParseChordError::Empty);
+    }
+    if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        return u32::from_str_radix(hex, 16)
+            .map(VirtualKey::Other)
+            .map_err(|_| ParseChordError::UnknownKey(s.into()));
+    }
+    VIRTUAL_KEYS
+        .iter()
+        .find(|key| s.eq_ignore_ascii_case(&key_name(**key)))
+        .copied()
+        .or_else(|| {
+            KEY_ALIASES
+                .iter()
+                .find(|(name, _)| s.eq_ignore_ascii_case(name))
+                .map(|(_, key)| *key)
+        })
+        .ok_or_else(|| ParseChordError::UnknownKey(s.into()))
+}
+
+/// The error type for parsing `Chord`.
+#[derive(Clone, PartialEq, Eq, Debug, thiserror::Error)]
+pub enum ParseChordError {
+    #[error("Empty")]
+    Empty,
+    #[error("UnknownKey: {0}")]
+    UnknownKey(String),
+}
+
+/// A key with modifier keys.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Chord {
+    pub key: VirtualKey,
+    pub modifiers: Modifiers,
+}
+
+impl Chord {
+    #[inline]
+    pub const fn new(key: VirtualKey, modifiers: Modifiers) -> Self {
+        Self { key, modifiers }
+    }
+
+    /// Checks if the key and modifier keys are same as this chord.
+    #[inline]
+    pub fn matches(&self, key: VirtualKey, modifiers: Modifiers) -> bool {
+        self.key == key && self.modifiers == modifiers
+    }
+}
+
+impl From<VirtualKey> for Chord {
+    #[inline]
+    fn from(value: VirtualKey) -> Self {
+        Self::new(value, Modifiers::empty())
+    }
+}
+
+impl FromStr for Chord {
+    type Err = ParseChordError;
+
+    /// Parses `"Ctrl+Shift+S"` style strings. Names are case insensitive.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut modifiers = Modifiers::empty();
+        let mut rest = s.trim();
+        while let Some((head, tail)) = rest.split_once('+') {
+            let Some(m) = parse_modifier(head.trim()) else {
+                break;
+            };
+            if tail.trim().is_empty() {
+                break;
+            }
+            modifiers |= m;
+            rest = tail.trim_start();
+        }
+        Ok(Self::new(parse_key(rest.trim())?, modifiers))
+    }
+}
+
+impl std::fmt::Display for Chord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (m, name) in MODIFIER_NAMES {
+            if self.modifiers.contains(m) {
+                write!(f, "{name}+")?;
+            }
+        }
+        write!(f, "{}", key_name(self.key))
+    }
+}
+
+/// A table of accelerators which binds chords to command ids.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Accelerators {
+    table: Vec<(Chord, u32)>,
+}
+
+impl Accelerators {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a table from pairs of a chord string and a command id.
+    pub fn parse<'a>(
+        iter: impl IntoIterator<Item = (&'a str, u32)>,
+    ) -> std::result::Result<Self, ParseChordError> {
+        let mut accels = Self::new();
+        for (chord, id) in iter {
+            accels.insert(chord.parse()?, id);
+        }
+        Ok(accels)
+    }
+
+    /// Binds `chord` to `id`, and returns the previous command id.
+    pub fn insert(&mut self, chord: Chord, id: u32) -> Option<u32> {
+        match self.table.iter_mut().find(|(c, _)| *c == chord) {
+            Some((_, prev)) => Some(std::mem::replace(prev, id)),
+            None => {
+                self.table.push((chord, id));
+                None
+            }
+        }
+    }
+
+    /// Unbinds `chord`, and returns the command id.
+    pub fn remove(&mut self, chord: &Chord) -> Option<u32> {
+        let index = self.table.iter().position(|(c, _)| c == chord)?;
+        Some(self.table.remove(index).1)
+    }
+
+    #[inline]
+    pub fn get(&self, chord: &Chord) -> Option<u32> {
+        self.find(chord.key, chord.modifiers)
+    }
+
+    /// Finds the command id of the chord which matches the key and modifier keys.
+    #[inline]
+    pub fn find(&self, key: VirtualKey, modifiers: Modifiers) -> Option<u32> {
+        self.table
+            .iter()
+            .find(|(c, _)| c.matches(key, modifiers))
+            .map(|(_, id)| *id)
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&Chord, u32)> {
+        self.table.iter().map(|(c, id)| (c, *id))
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+}
+
+impl FromIterator<(Chord, u32)> for Accelerators {
+    fn from_iter<T: IntoIterator<Item = (Chord, u32)>>(iter: T) -> Self {
+        let mut accels = Self::new();
+        for (chord, id) in iter {
+            accels.insert(chord, id);
+        }
+        accels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_chord() {
+        let chord: Chord = "Ctrl+Shift+S".parse().unwrap();
+        assert_eq!(
+            chord,
+            Chord::new(VirtualKey::S, Modifiers::CTRL | Modifiers::SHIFT)
+        );
+        let chord: Chord = " alt + f4 ".parse().unwrap();
+        assert_eq!(chord, Chord::new(VirtualKey::F4, Modifiers::ALT));
+        let chord: Chord = "Ctrl+Num+".parse().unwrap();
+        assert_eq!(chord, Chord::new(VirtualKey::NumAdd, Modifiers::CTRL));
+        let chord: Chord = "Control+Escape".parse().unwrap();
+        assert_eq!(chord, Chord::new(VirtualKey::Esc, Modifiers::CTRL));
+        assert!("Ctrl+".parse::<Chord>().is_err());
+        assert_eq!(
+            "Ctrl+Foo".parse::<Chord>(),
+            Err(ParseChordError::UnknownKey("Foo".into()))
+        );
+    }
+
+    #[test]
+    fn chord_round_trip() {
+        let mut keys = VIRTUAL_KEYS.to_vec();
+        keys.push(VirtualKey::Other(0xbb));
+        for key in keys {
+            let chord = Chord::new(key, Modifiers::all());
+            assert_eq!(chord.to_string().parse::<Chord>(), Ok(chord), "{chord}");
+        }
+    }
+
+    #[test]
+    fn accelerators_find() {
+        let mut accels = Accelerators::parse([("Ctrl+S", 1), ("Ctrl+Shift+S", 2)]).unwrap();
+        assert_eq!(accels.find(VirtualKey::S, Modifiers::CTRL), Some(1));
+        assert!(accels.find(VirtualKey::S, Modifiers::empty()).is_none());
+        assert_eq!(accels.insert("ctrl+s".parse().unwrap(), 3), Some(1));
+        assert_eq!(accels.remove(&"Ctrl+Shift+S".parse().unwrap()), Some(2));
+        assert_eq!(accels.len(), 1);
+    }
+}
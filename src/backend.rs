@@ -0,0 +1,34 @@
+//! Selects the Win32 backend or the simulated backend of the `headless` feature.
+//!
+//! On Windows, the simulated backend is used only after `headless::enable()` is called.
+//! On other platforms, the simulated backend is always used.
+
+/// Evaluates `$win32` on the Win32 backend and `$headless` on the simulated backend.
+#[cfg(all(windows, feature = "headless"))]
+macro_rules! backend {
+    ($win32:expr, $headless:expr $(,)?) => {
+        if crate::headless::is_enabled() {
+            $headless
+        } else {
+            $win32
+        }
+    };
+}
+
+/// Evaluates `$win32` on the Win32 backend and `$headless` on the simulated backend.
+#[cfg(all(windows, not(feature = "headless")))]
+macro_rules! backend {
+    ($win32:expr, $headless:expr $(,)?) => {
+        $win32
+    };
+}
+
+/// Evaluates `$win32` on the Win32 backend and `$headless` on the simulated backend.
+#[cfg(not(windows))]
+macro_rules! backend {
+    ($win32:expr, $headless:expr $(,)?) => {
+        $headless
+    };
+}
+
+pub(crate) use backend;
@@ -0,0 +1,251 @@
+//! Multi-click and drag detection of mouse buttons.
+//!
+//! [`ClickTracker`] counts clicks of `MouseInput` and detects the start of dragging for
+//! `DragStarted`.
+//!
+//! ```
+//! use std::time::Duration;
+//! use wiard::click::*;
+//! use wiard::{MouseButton, PhysicalPosition};
+//!
+//! let config = ClickConfig::default();
+//! let mut tracker = ClickTracker::new();
+//! let pos = PhysicalPosition::new(10, 10);
+//! assert_eq!(tracker.press(MouseButton::Left, pos, Duration::ZERO, &config), 1);
+//! tracker.release(MouseButton::Left);
+//! assert_eq!(tracker.press(MouseButton::Left, pos, Duration::from_millis(100), &config), 2);
+//! ```
+
+use crate::*;
+use std::time::Duration;
+#[cfg(windows)]
+use windows::Win32::UI::{
+    Input::KeyboardAndMouse::GetDoubleClickTime,
+    WindowsAndMessaging::{GetSystemMetrics, SM_CXDOUBLECLK, SM_CXDRAG, SM_CYDOUBLECLK, SM_CYDRAG},
+};
+
+/// Thresholds of multi-clicks and dragging.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClickConfig {
+    /// The maximum interval between clicks (`GetDoubleClickTime`).
+    pub double_click_time: Duration,
+    /// The rectangle centered on the first click (`SM_CXDOUBLECLK`, `SM_CYDOUBLECLK`).
+    pub double_click_size: PhysicalSize<u32>,
+    /// The rectangle centered on the pressed position (`SM_CXDRAG`, `SM_CYDRAG`).
+    pub drag_size: PhysicalSize<u32>,
+}
+
+impl ClickConfig {
+    /// Gets the settings of the system.
+    ///
+    /// The headless backend always returns the default values.
+    #[inline]
+    pub fn system() -> Self {
+        backend!(Self::query_system(), Self::default())
+    }
+
+    #[cfg(windows)]
+    fn query_system() -> Self {
+        unsafe {
+            Self {
+                double_click_time: Duration::from_millis(GetDoubleClickTime() as u64),
+                double_click_size: PhysicalSize::new(
+                    GetSystemMetrics(SM_CXDOUBLECLK) as u32,
+                    GetSystemMetrics(SM_CYDOUBLECLK) as u32,
+                ),
+                drag_size: PhysicalSize::new(
+                    GetSystemMetrics(SM_CXDRAG) as u32,
+                    GetSystemMetrics(SM_CYDRAG) as u32,
+                ),
+            }
+        }
+    }
+}
+
+impl Default for ClickConfig {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            double_click_time: Duration::from_millis(500),
+            double_click_size: PhysicalSize::new(4, 4),
+            drag_size: PhysicalSize::new(4, 4),
+        }
+    }
+}
+
+fn within(a: PhysicalPosition<i32>, b: PhysicalPosition<i32>, size: PhysicalSize<u32>) -> bool {
+    (a.x - b.x).unsigned_abs() <= size.width / 2 && (a.y - b.y).unsigned_abs() <= size.height / 2
+}
+
+#[derive(Clone, Copy, Debug)]
+struct LastClick {
+    button: MouseButton,
+    position: PhysicalPosition<i32>,
+    time: Duration,
+    count: u32,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Press {
+    button: MouseButton,
+    position: PhysicalPosition<i32>,
+    count: u32,
+    dragging: bool,
+}
+
+/// The start of dragging detected by [`ClickTracker::moved`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Drag {
+    pub button: MouseButton,
+    /// The position where the button was pressed.
+    pub origin: PhysicalPosition<i32>,
+}
+
+/// Counts clicks and detects dragging.
+///
+/// Clicks of a button are counted while they are within `double_click_time` of the previous one
+/// and within `double_click_size` of the first one. Pressing another button or dragging resets
+/// the count.
+#[derive(Clone, Debug, Default)]
+pub struct ClickTracker {
+    last: Option<LastClick>,
+    pressed: Option<Press>,
+}
+
+impl ClickTracker {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the current click count of `button`, or 0.
+    #[inline]
+    pub fn count(&self, button: MouseButton) -> u32 {
+        self.last
+            .filter(|last| last.button == button)
+            .map_or(0, |last| last.count)
+    }
+
+    /// Checks if the pressed button is being dragged.
+    #[inline]
+    pub fn is_dragging(&self) -> bool {
+        self.pressed.is_some_and(|press| press.dragging)
+    }
+
+    /// Processes a pressed button, and returns the click count.
+    pub fn press(
+        &mut self,
+        button: MouseButton,
+        position: PhysicalPosition<i32>,
+        time: Duration,
+        config: &ClickConfig,
+    ) -> u32 {
+        let last = self.last.filter(|last| {
+            last.button == button
+                && time.saturating_sub(last.time) <= config.double_click_time
+                && within(last.position, position, config.double_click_size)
+        });
+        let count = match last {
+            Some(last) => last.count + 1,
+            None => 1,
+        };
+        self.last = Some(LastClick {
+            button,
+            position: last.map_or(position, |last| last.position),
+            time,
+            count,
+        });
+        if self.pressed.is_none() {
+            self.pressed = Some(Press {
+                button,
+                position,
+                count,
+                dragging: false,
+            });
+        }
+        count
+    }
+
+    /// Processes a released button, and returns the click count of the press.
+    pub fn release(&mut self, button: MouseButton) -> u32 {
+        match self.pressed {
+            Some(press) if press.button == button => {
+                self.pressed = None;
+                press.count
+            }
+            _ => self.count(button),
+        }
+    }
+
+    /// Processes a cursor position, and returns `Drag` once the pressed button moves outside of
+    /// `drag_size`.
+    pub fn moved(&mut self, position: PhysicalPosition<i32>, config: &ClickConfig) -> Option<Drag> {
+        let press = self.pressed.as_mut()?;
+        if press.dragging || within(press.position, position, config.drag_size) {
+            return None;
+        }
+        press.dragging = true;
+        self.last = None;
+        Some(Drag {
+            button: press.button,
+            origin: press.position,
+        })
+    }
+
+    /// Forgets the pressed button like when the capture is lost.
+    #[inline]
+    pub fn cancel(&mut self) {
+        self.pressed = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    fn pos(x: i32, y: i32) -> PhysicalPosition<i32> {
+        PhysicalPosition::new(x, y)
+    }
+
+    #[test]
+    fn triple_click_and_timeout() {
+        let config = ClickConfig::default();
+        let mut t = ClickTracker::new();
+        let left = MouseButton::Left;
+        assert_eq!(t.press(left, pos(0, 0), ms(0), &config), 1);
+        assert_eq!(t.release(left), 1);
+        assert_eq!(t.press(left, pos(2, -2), ms(200), &config), 2);
+        assert_eq!(t.release(left), 2);
+        assert_eq!(t.press(left, pos(-2, 2), ms(400), &config), 3);
+        t.release(left);
+        assert_eq!(t.press(left, pos(0, 0), ms(1000), &config), 1);
+        t.release(left);
+        assert_eq!(t.press(left, pos(3, 0), ms(1100), &config), 1);
+        t.release(left);
+        assert_eq!(t.press(MouseButton::Right, pos(3, 0), ms(1200), &config), 1);
+        assert_eq!(t.count(left), 0);
+    }
+
+    #[test]
+    fn drag_resets_count() {
+        let config = ClickConfig::default();
+        let mut t = ClickTracker::new();
+        let left = MouseButton::Left;
+        assert!(t.moved(pos(100, 100), &config).is_none());
+        t.press(left, pos(0, 0), ms(0), &config);
+        assert!(t.moved(pos(2, 2), &config).is_none());
+        let drag = t.moved(pos(0, 3), &config).unwrap();
+        assert_eq!(drag.button, left);
+        assert_eq!(drag.origin, pos(0, 0));
+        assert!(t.is_dragging());
+        assert!(t.moved(pos(10, 10), &config).is_none());
+        assert_eq!(t.release(left), 1);
+        assert!(!t.is_dragging());
+        assert_eq!(t.press(left, pos(0, 0), ms(100), &config), 1);
+    }
+}
@@ -1,12 +1,17 @@
+#[cfg(windows)]
 use super::*;
+#[cfg(windows)]
 use std::sync::LazyLock;
+#[cfg(windows)]
 use windows::Win32::System::Registry::{
     HKEY_CURRENT_USER, REG_DWORD, REG_VALUE_TYPE, RRF_RT_REG_DWORD, RegGetValueW,
 };
+#[cfg(windows)]
 use windows::core::PCSTR;
 
 /// Represents a color mode.
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum ColorMode {
     System,
@@ -16,6 +21,7 @@ pub enum ColorMode {
 
 /// Represents a color mode state.
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum ColorModeState {
     Light = ColorMode::Light as i32,
@@ -23,6 +29,7 @@ pub enum ColorModeState {
 }
 
 /// Check the dark mode in Windows.
+#[cfg(windows)]
 pub fn is_system_dark_mode() -> bool {
     let key =
         windows::core::w!("Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize");
@@ -49,14 +56,20 @@ pub fn is_system_dark_mode() -> bool {
     }
 }
 
+#[cfg(windows)]
 static UXTHEME: LazyLock<Library> = LazyLock::new(|| Library::new("uxtheme.dll").unwrap());
 
+#[cfg(windows)]
 #[allow(dead_code)]
 pub(crate) const APPMODE_DEFAULT: i32 = 0;
+#[cfg(windows)]
 pub(crate) const APPMODE_ALLOWDARK: i32 = 1;
+#[cfg(windows)]
 pub(crate) const APPMODE_FORCEDARK: i32 = 2;
+#[cfg(windows)]
 pub(crate) const APPMODE_FORCELIGHT: i32 = 3;
 
+#[cfg(windows)]
 #[inline]
 pub(crate) fn refresh_immersive_color_policy_state() {
     unsafe {
@@ -66,6 +79,7 @@ pub(crate) fn refresh_immersive_color_policy_state() {
     }
 }
 
+#[cfg(windows)]
 #[inline]
 pub(crate) fn set_preferred_app_mode(app_mode: i32) -> i32 {
     unsafe {
@@ -1,30 +1,43 @@
 use crate::*;
 use std::any::Any;
 use std::collections::HashMap;
+use std::marker::PhantomData;
 use std::sync::{
-    Mutex, OnceLock,
+    Mutex,
     atomic::{self, AtomicU64},
 };
-use windows::Win32::{
-    Foundation::{LPARAM, WPARAM},
-    UI::WindowsAndMessaging::{PostMessageW, WM_CLOSE},
-};
 
-pub(crate) struct Object {
+/// Window properties of a backend, and the hooks of `Context` which differ between backends.
+pub(crate) trait Props: Sized + 'static {
+    fn context() -> &'static ContextImpl<Self>;
+
+    fn parent(&self) -> Option<WindowHandle>;
+
+    /// Called after a window is registered.
+    fn on_register(_handle: WindowHandle) {}
+
+    /// Closes a child window of a removed window.
+    fn close_child(handle: WindowHandle);
+
+    /// Called after all windows are removed when UI thread finishes or panics.
+    fn on_cleanup() {}
+}
+
+pub(crate) struct Object<P> {
     pub kind: WindowKind,
-    pub event_tx: crate::window::Sender<RecvEventOrPanic>,
-    pub props: WindowProps,
+    pub event_tx: crate::receiver::Sender<RecvEventOrPanic>,
+    pub props: P,
     pub children: Vec<WindowHandle>,
 }
 
-pub(crate) struct ContextImpl {
-    pub window_map: Mutex<HashMap<WindowHandle, Object>>,
-    pub event_txs: Mutex<HashMap<u64, crate::window::Sender<RecvEventOrPanic>>>,
+pub(crate) struct ContextImpl<P> {
+    pub window_map: Mutex<HashMap<WindowHandle, Object<P>>>,
+    pub event_txs: Mutex<HashMap<u64, crate::receiver::Sender<RecvEventOrPanic>>>,
     panic_receiver: AtomicU64,
 }
 
-impl ContextImpl {
-    fn new() -> Self {
+impl<P> ContextImpl<P> {
+    pub fn new() -> Self {
         Self {
             window_map: Mutex::new(HashMap::new()),
             event_txs: Mutex::new(HashMap::new()),
@@ -33,28 +46,17 @@ impl ContextImpl {
     }
 }
 
-static CONTEXT: OnceLock<ContextImpl> = OnceLock::new();
-
-fn get_context() -> &'static ContextImpl {
-    CONTEXT.get_or_init(ContextImpl::new)
-}
-
-pub(crate) struct Context;
-
-impl Context {
-    pub fn init() -> Result<()> {
-        window::register_class();
-        ime::init_text_service();
-        Ok(())
-    }
+/// Windows of a backend. Each backend uses an alias such as `Context<WindowProps>`.
+pub(crate) struct Context<P>(PhantomData<P>);
 
+impl<P: Props> Context<P> {
     pub fn is_empty() -> bool {
-        let window_map = get_context().window_map.lock().unwrap();
+        let window_map = P::context().window_map.lock().unwrap();
         window_map.is_empty()
     }
 
-    pub fn register_window(kind: WindowKind, props: WindowProps, event_rx_id: u64) {
-        let ctx = get_context();
+    pub fn register_window(kind: WindowKind, props: P, event_rx_id: u64) {
+        let ctx = P::context();
         let event_tx = {
             let event_txs = ctx.event_txs.lock().unwrap();
             if event_txs.is_empty() {
@@ -65,10 +67,8 @@ impl Context {
         };
         let mut window_map = ctx.window_map.lock().unwrap();
         let window_handle = kind.window_handle();
-        UiThread::send_task(move || {
-            procedure::new_raw_procedure_handler(window_handle);
-        });
-        let parent = props.parent;
+        P::on_register(window_handle);
+        let parent = props.parent();
         window_map.insert(
             window_handle,
             Object {
@@ -85,21 +85,19 @@ impl Context {
         }
     }
 
-    pub fn remove_window(handle: WindowHandle) -> Option<Object> {
-        let mut window_map = get_context().window_map.lock().unwrap();
+    pub fn remove_window(handle: WindowHandle) -> Option<Object<P>> {
+        let mut window_map = P::context().window_map.lock().unwrap();
         let obj = window_map.remove(&handle);
         if let Some(obj) = obj.as_ref() {
-            for child in &obj.children {
-                unsafe {
-                    PostMessageW(Some(child.as_hwnd()), WM_CLOSE, WPARAM(0), LPARAM(0)).ok();
-                }
+            for child in obj.children.iter().copied() {
+                P::close_child(child);
             }
         }
         obj
     }
 
     pub fn send_closed_event_to_all_windows() {
-        let window_map = get_context().window_map.lock().unwrap();
+        let window_map = P::context().window_map.lock().unwrap();
         for (_, obj) in window_map.iter() {
             obj.event_tx
                 .send(RecvEventOrPanic::Event((Event::Closed, obj.kind.clone())))
@@ -108,12 +106,12 @@ impl Context {
     }
 
     pub fn window_is_none(handle: WindowHandle) -> bool {
-        let window_map = get_context().window_map.lock().unwrap();
+        let window_map = P::context().window_map.lock().unwrap();
         !window_map.contains_key(&handle)
     }
 
     pub fn send_event(handle: WindowHandle, event: Event) {
-        let window_map = get_context().window_map.lock().unwrap();
+        let window_map = P::context().window_map.lock().unwrap();
         let Some(object) = window_map.get(&handle) else {
             return;
         };
@@ -125,31 +123,30 @@ impl Context {
 
     pub fn get_window_props<F, T>(handle: WindowHandle, f: F) -> Option<T>
     where
-        F: FnOnce(&WindowProps) -> T,
+        F: FnOnce(&P) -> T,
     {
-        let window_map = get_context().window_map.lock().unwrap();
+        let window_map = P::context().window_map.lock().unwrap();
         let object = window_map.get(&handle)?;
         Some(f(&object.props))
     }
 
     pub fn set_window_props<F>(handle: WindowHandle, f: F)
     where
-        F: FnOnce(&mut WindowProps),
+        F: FnOnce(&mut P),
     {
-        let mut window_map = get_context().window_map.lock().unwrap();
+        let mut window_map = P::context().window_map.lock().unwrap();
         let object = window_map.get_mut(&handle).unwrap();
         f(&mut object.props)
     }
 
-    pub fn register_event_tx(id: u64, tx: crate::window::Sender<RecvEventOrPanic>) {
-        let mut event_txs = get_context().event_txs.lock().unwrap();
+    pub fn register_event_tx(id: u64, tx: crate::receiver::Sender<RecvEventOrPanic>) {
+        let mut event_txs = P::context().event_txs.lock().unwrap();
         event_txs.insert(id, tx);
     }
 
     pub fn send_panic(e: Box<dyn Any + Send>) {
-        Self::send_closed_event_to_all_windows();
-        ime::shutdown_text_service();
-        let ctx = get_context();
+        Self::remove_all_windows();
+        let ctx = P::context();
         let mut event_txs = ctx.event_txs.lock().unwrap();
         if let Some(tx) = event_txs.remove(&ctx.panic_receiver.load(atomic::Ordering::SeqCst)) {
             tx.send(RecvEventOrPanic::Panic(e)).ok();
@@ -158,25 +155,31 @@ impl Context {
     }
 
     pub fn set_panic_receiver(rx: &impl IsReceiver) {
-        get_context()
+        P::context()
             .panic_receiver
             .store(rx.id(), atomic::Ordering::SeqCst);
     }
 
     pub fn cleanup() {
-        Self::send_closed_event_to_all_windows();
-        ime::shutdown_text_service();
-        let mut event_txs = get_context().event_txs.lock().unwrap();
+        Self::remove_all_windows();
+        let mut event_txs = P::context().event_txs.lock().unwrap();
         event_txs.clear();
     }
+
+    fn remove_all_windows() {
+        Self::send_closed_event_to_all_windows();
+        let windows = std::mem::take(&mut *P::context().window_map.lock().unwrap());
+        std::mem::drop(windows);
+        P::on_cleanup();
+    }
 }
 
-#[cfg(test)]
+#[cfg(all(test, windows))]
 mod tests {
     use super::*;
 
     #[test]
     fn window_is_none() {
-        assert!(Context::window_is_none(WindowHandle::default()));
+        assert!(crate::Context::window_is_none(WindowHandle::default()));
     }
 }
@@ -1,9 +1,18 @@
 use crate::*;
+use bitflags::bitflags;
+#[cfg(windows)]
 use windows::Win32::Foundation::{LPARAM, POINT, WPARAM};
+#[cfg(windows)]
 use windows::Win32::Graphics::Gdi::{ClientToScreen, ScreenToClient};
+#[cfg(windows)]
 use windows::Win32::System::{Ole::MK_ALT, SystemServices::*};
+#[cfg(windows)]
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    GetKeyState, VIRTUAL_KEY, VK_CAPITAL, VK_LCONTROL, VK_LMENU, VK_LSHIFT, VK_NUMLOCK,
+    VK_RCONTROL, VK_RMENU, VK_RSHIFT, VK_SCROLL,
+};
+#[cfg(windows)]
 use windows::Win32::UI::WindowsAndMessaging::GetCursorPos;
-use bitflags::bitflags;
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
 #[repr(u32)]
@@ -13,9 +22,21 @@ enum MouseStateVirtualKey {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MouseStateVirtualKeys(u32);
 
 impl MouseStateVirtualKeys {
+    pub(crate) fn new(ctrl: bool, shift: bool) -> Self {
+        let mut value = 0;
+        if ctrl {
+            value |= MouseStateVirtualKey::Ctrl as u32;
+        }
+        if shift {
+            value |= MouseStateVirtualKey::Shift as u32;
+        }
+        Self(value)
+    }
+
     #[inline]
     pub fn contains(&self, key: VirtualKey) -> bool {
         if key == VirtualKey::Ctrl {
@@ -40,6 +61,7 @@ impl MouseStateVirtualKeys {
     }
 }
 
+#[cfg(windows)]
 impl From<WPARAM> for MouseStateVirtualKeys {
     #[inline]
     fn from(value: WPARAM) -> Self {
@@ -69,26 +91,316 @@ impl std::fmt::Display for MouseStateVirtualKeys {
     }
 }
 
+bitflags! {
+    /// A snapshot of modifier keys and toggled lock keys.
+    #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Default)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct ModifierState: u32 {
+        const LEFT_CTRL = 0x0001;
+        const RIGHT_CTRL = 0x0002;
+        const LEFT_SHIFT = 0x0004;
+        const RIGHT_SHIFT = 0x0008;
+        const LEFT_ALT = 0x0010;
+        const RIGHT_ALT = 0x0020;
+        const LEFT_WIN = 0x0040;
+        const RIGHT_WIN = 0x0080;
+        const CAPS_LOCK = 0x0100;
+        const NUM_LOCK = 0x0200;
+        const SCROLL_LOCK = 0x0400;
+    }
+}
+
+impl ModifierState {
+    /// Checks if either Ctrl key is pressed.
+    #[inline]
+    pub fn ctrl(&self) -> bool {
+        self.intersects(Self::LEFT_CTRL | Self::RIGHT_CTRL)
+    }
+
+    /// Checks if either Shift key is pressed.
+    #[inline]
+    pub fn shift(&self) -> bool {
+        self.intersects(Self::LEFT_SHIFT | Self::RIGHT_SHIFT)
+    }
+
+    /// Checks if either Alt key is pressed.
+    #[inline]
+    pub fn alt(&self) -> bool {
+        self.intersects(Self::LEFT_ALT | Self::RIGHT_ALT)
+    }
+
+    /// Checks if either Windows key is pressed.
+    #[inline]
+    pub fn win(&self) -> bool {
+        self.intersects(Self::LEFT_WIN | Self::RIGHT_WIN)
+    }
+
+    /// Converts to `Modifiers` of accelerators without left/right and lock keys.
+    #[inline]
+    pub fn to_modifiers(&self) -> Modifiers {
+        let mut modifiers = Modifiers::empty();
+        modifiers.set(Modifiers::CTRL, self.ctrl());
+        modifiers.set(Modifiers::SHIFT, self.shift());
+        modifiers.set(Modifiers::ALT, self.alt());
+        modifiers.set(Modifiers::WIN, self.win());
+        modifiers
+    }
+}
+
+/// Virtual key codes of the Windows keys, which `VirtualKey` has as `VirtualKey::Other`.
+const WIN_KEYS: [(u32, ModifierState); 2] = [
+    (0x5b, ModifierState::LEFT_WIN),
+    (0x5c, ModifierState::RIGHT_WIN),
+];
+
+impl ModifierState {
+    /// Gets the flag of `vkey` if it is a Windows key.
+    pub(crate) fn win_key(vkey: VirtualKey) -> Option<Self> {
+        WIN_KEYS
+            .into_iter()
+            .find(|&(code, _)| vkey == VirtualKey::Other(code))
+            .map(|(_, flag)| flag)
+    }
+}
+
+#[cfg(windows)]
+impl ModifierState {
+    /// Gets the state when the current message was posted.
+    pub(crate) fn current() -> Self {
+        let state = |vkey: VIRTUAL_KEY| unsafe { GetKeyState(vkey.0 as i32) };
+        let mut ret = Self::empty();
+        for (vkey, flag) in [
+            (VK_LCONTROL, Self::LEFT_CTRL),
+            (VK_RCONTROL, Self::RIGHT_CTRL),
+            (VK_LSHIFT, Self::LEFT_SHIFT),
+            (VK_RSHIFT, Self::RIGHT_SHIFT),
+            (VK_LMENU, Self::LEFT_ALT),
+            (VK_RMENU, Self::RIGHT_ALT),
+        ]
+        .into_iter()
+        .chain(WIN_KEYS.map(|(code, flag)| (VIRTUAL_KEY(code as u16), flag)))
+        {
+            ret.set(flag, state(vkey) < 0);
+        }
+        for (vkey, flag) in [
+            (VK_CAPITAL, Self::CAPS_LOCK),
+            (VK_NUMLOCK, Self::NUM_LOCK),
+            (VK_SCROLL, Self::SCROLL_LOCK),
+        ] {
+            ret.set(flag, state(vkey) & 0x01 != 0);
+        }
+        ret
+    }
+}
+
+/// A key which depends on the keyboard layout.
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LogicalKey {
+    /// A key which produces characters without Ctrl.
+    Character(String),
+    /// A dead key which combines with the next key. This has the spacing character if exists.
+    Dead(Option<char>),
+    /// A key which produces no characters.
+    Named(VirtualKey),
+}
+
+/// All named virtual keys except `VirtualKey::Other`.
+pub(crate) const VIRTUAL_KEYS: [VirtualKey; 98] = [
+    VirtualKey::Esc,
+    VirtualKey::Tab,
+    VirtualKey::CapsLock,
+    VirtualKey::Shift,
+    VirtualKey::Ctrl,
+    VirtualKey::Alt,
+    VirtualKey::BackSpace,
+    VirtualKey::Enter,
+    VirtualKey::Space,
+    VirtualKey::PrintScreen,
+    VirtualKey::ScrollLock,
+    VirtualKey::Pause,
+    VirtualKey::Insert,
+    VirtualKey::Delete,
+    VirtualKey::Home,
+    VirtualKey::End,
+    VirtualKey::PageUp,
+    VirtualKey::PageDown,
+    VirtualKey::Up,
+    VirtualKey::Down,
+    VirtualKey::Left,
+    VirtualKey::Right,
+    VirtualKey::Key1,
+    VirtualKey::Key2,
+    VirtualKey::Key3,
+    VirtualKey::Key4,
+    VirtualKey::Key5,
+    VirtualKey::Key6,
+    VirtualKey::Key7,
+    VirtualKey::Key8,
+    VirtualKey::Key9,
+    VirtualKey::Key0,
+    VirtualKey::A,
+    VirtualKey::B,
+    VirtualKey::C,
+    VirtualKey::D,
+    VirtualKey::E,
+    VirtualKey::F,
+    VirtualKey::G,
+    VirtualKey::H,
+    VirtualKey::I,
+    VirtualKey::J,
+    VirtualKey::K,
+    VirtualKey::L,
+    VirtualKey::M,
+    VirtualKey::N,
+    VirtualKey::O,
+    VirtualKey::P,
+    VirtualKey::Q,
+    VirtualKey::R,
+    VirtualKey::S,
+    VirtualKey::T,
+    VirtualKey::U,
+    VirtualKey::V,
+    VirtualKey::W,
+    VirtualKey::X,
+    VirtualKey::Y,
+    VirtualKey::Z,
+    VirtualKey::F1,
+    VirtualKey::F2,
+    VirtualKey::F3,
+    VirtualKey::F4,
+    VirtualKey::F5,
+    VirtualKey::F6,
+    VirtualKey::F7,
+    VirtualKey::F8,
+    VirtualKey::F9,
+    VirtualKey::F10,
+    VirtualKey::F11,
+    VirtualKey::F12,
+    VirtualKey::F13,
+    VirtualKey::F14,
+    VirtualKey::F15,
+    VirtualKey::F16,
+    VirtualKey::F17,
+    VirtualKey::F18,
+    VirtualKey::F19,
+    VirtualKey::F20,
+    VirtualKey::F21,
+    VirtualKey::F22,
+    VirtualKey::F23,
+    VirtualKey::F24,
+    VirtualKey::NumLock,
+    VirtualKey::Num1,
+    VirtualKey::Num2,
+    VirtualKey::Num3,
+    VirtualKey::Num4,
+    VirtualKey::Num5,
+    VirtualKey::Num6,
+    VirtualKey::Num7,
+    VirtualKey::Num8,
+    VirtualKey::Num9,
+    VirtualKey::Num0,
+    VirtualKey::NumAdd,
+    VirtualKey::NumSub,
+    VirtualKey::NumMul,
+    VirtualKey::NumDiv,
+    VirtualKey::NumDecimal,
+];
+
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MouseState {
     pub position: PhysicalPosition<i32>,
+    #[cfg_attr(feature = "serde", serde(with = "serde_mouse_buttons"))]
     pub buttons: MouseButtons,
     pub keys: MouseStateVirtualKeys,
+    pub modifiers: ModifierState,
 }
 
+#[cfg(windows)]
 impl MouseState {
     pub(crate) fn from_params(wparam: WPARAM, lparam: LPARAM) -> Self {
         Self {
             position: lparam_to_point(lparam),
             buttons: wparam.into(),
             keys: wparam.into(),
+            modifiers: ModifierState::current(),
         }
     }
 }
 
+/// Serializes `KeyCode` which has no serde implementation in gedv.
+#[cfg(feature = "serde")]
+pub(crate) mod serde_key_code {
+    use super::*;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename = "KeyCode")]
+    struct Repr {
+        vkey: VirtualKey,
+        scan_code: ScanCode,
+    }
+
+    pub fn serialize<S: Serializer>(value: &KeyCode, s: S) -> std::result::Result<S::Ok, S::Error> {
+        Repr {
+            vkey: value.vkey,
+            scan_code: value.scan_code,
+        }
+        .serialize(s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> std::result::Result<KeyCode, D::Error> {
+        let repr = Repr::deserialize(d)?;
+        Ok(KeyCode::new(repr.vkey, repr.scan_code))
+    }
+}
+
+/// Serializes `MouseButtons` as a list of `MouseButton`.
+#[cfg(feature = "serde")]
+pub(crate) mod serde_mouse_buttons {
+    use super::*;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        value: &MouseButtons,
+        s: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        value.to_vec().serialize(s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        d: D,
+    ) -> std::result::Result<MouseButtons, D::Error> {
+        Ok(Vec::<MouseButton>::deserialize(d)?.into())
+    }
+}
+
+/// Returns the rectangle in client coordinates which the cursor is confined to.
+///
+/// A locked cursor is confined to the center of the client area.
+pub(crate) fn cursor_clip_rect(
+    size: PhysicalSize<u32>,
+    confine: Option<PhysicalRect<i32>>,
+    locked: bool,
+) -> Option<PhysicalRect<i32>> {
+    if locked {
+        let x = size.width as i32 / 2;
+        let y = size.height as i32 / 2;
+        return Some(PhysicalRect::new(x, y, x + 1, y + 1));
+    }
+    confine
+}
+
 /// This value is a multiple of wheel value.
+#[cfg(windows)]
 pub const WHEEL_DELTA: i32 = windows::Win32::UI::WindowsAndMessaging::WHEEL_DELTA as i32;
 
+/// This value is a multiple of wheel value.
+#[cfg(not(windows))]
+pub const WHEEL_DELTA: i32 = 120;
+
+#[cfg(windows)]
 #[inline]
 pub fn get_cursor_pos() -> ScreenPosition<i32> {
     unsafe {
@@ -98,6 +410,7 @@ pub fn get_cursor_pos() -> ScreenPosition<i32> {
     }
 }
 
+#[cfg(windows)]
 #[inline]
 pub fn client_to_screen(window: &impl IsWindow, src: PhysicalPosition<i32>) -> ScreenPosition<i32> {
     unsafe {
@@ -107,6 +420,7 @@ pub fn client_to_screen(window: &impl IsWindow, src: PhysicalPosition<i32>) -> S
     }
 }
 
+#[cfg(windows)]
 #[inline]
 pub fn screen_to_client(window: &impl IsWindow, src: ScreenPosition<i32>) -> PhysicalPosition<i32> {
     unsafe {
@@ -116,8 +430,10 @@ pub fn screen_to_client(window: &impl IsWindow, src: ScreenPosition<i32>) -> Phy
     }
 }
 
+#[cfg(windows)]
 bitflags! {
     #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct ModifierKey: u32 {
         const CONTROL = MK_CONTROL.0;
         const SHIFT = MK_SHIFT.0;
@@ -130,6 +446,7 @@ bitflags! {
     }
 }
 
+#[cfg(windows)]
 impl ModifierKey {
     #[inline]
     pub const fn as_raw(&self) -> MODIFIERKEYS_FLAGS {
@@ -137,6 +454,7 @@ impl ModifierKey {
     }
 }
 
+#[cfg(windows)]
 impl From<MODIFIERKEYS_FLAGS> for ModifierKey {
     fn from(value: MODIFIERKEYS_FLAGS) -> Self {
         Self::from_bits_retain(value.0)
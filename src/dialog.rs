@@ -1,9 +1,32 @@
 use crate::*;
+use std::cell::RefCell;
 use std::path::PathBuf;
+use std::sync::{Arc, LazyLock, Mutex};
 use tokio::sync::oneshot;
-use windows::Win32::Foundation::ERROR_CANCELLED;
+use windows::Win32::Foundation::{
+    ERROR_CANCELLED, ERROR_PROC_NOT_FOUND, HWND, LPARAM, S_OK, WPARAM,
+};
+use windows::Win32::UI::Controls::{
+    TASKDIALOG_BUTTON, TASKDIALOG_COMMON_BUTTON_FLAGS, TASKDIALOG_NOTIFICATIONS, TASKDIALOGCONFIG,
+    TASKDIALOGCONFIG_0, TASKDIALOGCONFIG_1, TD_ERROR_ICON, TD_INFORMATION_ICON, TD_SHIELD_ICON,
+    TD_WARNING_ICON, TDCBF_ABORT_BUTTON, TDCBF_CANCEL_BUTTON, TDCBF_CLOSE_BUTTON,
+    TDCBF_CONTINUE_BUTTON, TDCBF_IGNORE_BUTTON, TDCBF_NO_BUTTON, TDCBF_OK_BUTTON,
+    TDCBF_RETRY_BUTTON, TDCBF_TRYAGAIN_BUTTON, TDCBF_YES_BUTTON, TDF_ALLOW_DIALOG_CANCELLATION,
+    TDF_CALLBACK_TIMER, TDF_ENABLE_HYPERLINKS, TDF_EXPANDED_BY_DEFAULT,
+    TDF_POSITION_RELATIVE_TO_WINDOW, TDF_SHOW_MARQUEE_PROGRESS_BAR, TDF_SHOW_PROGRESS_BAR,
+    TDF_USE_COMMAND_LINKS, TDF_VERIFICATION_FLAG_CHECKED, TDM_SET_MARQUEE_PROGRESS_BAR,
+    TDM_SET_PROGRESS_BAR_MARQUEE, TDM_SET_PROGRESS_BAR_POS, TDN_CREATED, TDN_HYPERLINK_CLICKED,
+    TDN_TIMER,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    IDABORT, IDCANCEL, IDCLOSE, IDCONTINUE, IDIGNORE, IDNO, IDOK, IDRETRY, IDTRYAGAIN, IDYES,
+    MB_ABORTRETRYIGNORE, MB_CANCELTRYCONTINUE, MB_DEFBUTTON1, MB_DEFBUTTON2, MB_DEFBUTTON3,
+    MB_DEFBUTTON4, MB_ICONERROR, MB_ICONINFORMATION, MB_ICONQUESTION, MB_ICONWARNING, MB_OK,
+    MB_OKCANCEL, MB_RETRYCANCEL, MB_YESNO, MB_YESNOCANCEL, MESSAGEBOX_RESULT, MESSAGEBOX_STYLE,
+    MessageBoxW, SW_SHOWNORMAL, SendMessageW,
+};
 use windows::Win32::{System::Com::*, UI::Shell::Common::*, UI::Shell::*};
-use windows::core::{HSTRING, Interface, PCWSTR, PWSTR};
+use windows::core::{BOOL, HRESULT, HSTRING, Interface, PCSTR, PCWSTR, PWSTR, Ref, implement};
 
 /// Represents options of dialogs.
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -149,19 +172,241 @@ where
     }
 }
 
+/// A value of a custom control in file dialogs.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ControlValue {
+    CheckButton(bool),
+    /// The index of the selected item.
+    ComboBox(usize),
+    EditBox(String),
+}
+
+/// The result of `try_show` of file dialogs.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FileDialogResult<T> {
+    pub selection: T,
+    /// The selected file type, which is one-based like `file_type_index`.
+    pub file_type_index: usize,
+    /// The values of custom controls in the order which they were added.
+    pub controls: Vec<ControlValue>,
+}
+
+/// A folder selected by `FileOpenDialog::new_folder`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Folder(pub PathBuf);
+
+impl AsRef<std::path::Path> for Folder {
+    #[inline]
+    fn as_ref(&self) -> &std::path::Path {
+        &self.0
+    }
+}
+
+impl From<Folder> for PathBuf {
+    #[inline]
+    fn from(src: Folder) -> Self {
+        src.0
+    }
+}
+
+enum CustomControl {
+    CheckButton {
+        label: String,
+        checked: bool,
+    },
+    ComboBox {
+        label: String,
+        items: Vec<String>,
+        selected: usize,
+    },
+    EditBox {
+        label: String,
+        text: String,
+    },
+}
+
+type SelectionChangeHandler = Box<dyn FnMut(PathBuf) + Send>;
+type TypeChangeHandler = Box<dyn FnMut(usize) + Send>;
+
 struct Params<W> {
     title: Option<String>,
     ok_button_label: Option<String>,
     default_directory: Option<PathBuf>,
     default_extension: Option<String>,
+    file_name: Option<String>,
     file_name_label: Option<String>,
     file_types: Vec<FilterSpec>,
     file_type_index: usize,
     options: FileDialogOptions,
+    controls: Vec<CustomControl>,
+    on_selection_change: Option<SelectionChangeHandler>,
+    on_type_change: Option<TypeChangeHandler>,
     owner: W,
 }
 
-unsafe fn show_dialog<W, T>(dialog: &T, params: Params<W>) -> Result<()>
+impl<W> Params<W> {
+    fn new(owner: W, options: FileDialogOptions) -> Self {
+        Self {
+            title: None,
+            ok_button_label: None,
+            default_directory: None,
+            default_extension: None,
+            file_name: None,
+            file_name_label: None,
+            file_types: vec![],
+            file_type_index: 0,
+            options,
+            controls: vec![],
+            on_selection_change: None,
+            on_type_change: None,
+            owner,
+        }
+    }
+}
+
+#[implement(IFileDialogEvents)]
+struct DialogEvents {
+    on_selection_change: RefCell<Option<SelectionChangeHandler>>,
+    on_type_change: RefCell<Option<TypeChangeHandler>>,
+}
+
+impl IFileDialogEvents_Impl for DialogEvents_Impl {
+    fn OnFileOk(&self, _pfd: Ref<IFileDialog>) -> windows::core::Result<()> {
+        Ok(())
+    }
+
+    fn OnFolderChanging(
+        &self,
+        _pfd: Ref<IFileDialog>,
+        _psifolder: Ref<IShellItem>,
+    ) -> windows::core::Result<()> {
+        Ok(())
+    }
+
+    fn OnFolderChange(&self, _pfd: Ref<IFileDialog>) -> windows::core::Result<()> {
+        Ok(())
+    }
+
+    fn OnSelectionChange(&self, pfd: Ref<IFileDialog>) -> windows::core::Result<()> {
+        let mut handler = self.on_selection_change.borrow_mut();
+        let (Some(handler), Some(dialog)) = (handler.as_mut(), pfd.as_ref()) else {
+            return Ok(());
+        };
+        unsafe {
+            let Ok(item) = dialog.GetCurrentSelection() else {
+                return Ok(());
+            };
+            let Ok(name) = item.GetDisplayName(SIGDN_FILESYSPATH) else {
+                return Ok(());
+            };
+            if let Ok(path) = DisplayName(name).to_path_buf() {
+                handler(path);
+            }
+        }
+        Ok(())
+    }
+
+    fn OnShareViolation(
+        &self,
+        _pfd: Ref<IFileDialog>,
+        _psi: Ref<IShellItem>,
+    ) -> windows::core::Result<FDE_SHAREVIOLATION_RESPONSE> {
+        Ok(FDESVR_DEFAULT)
+    }
+
+    fn OnTypeChange(&self, pfd: Ref<IFileDialog>) -> windows::core::Result<()> {
+        let mut handler = self.on_type_change.borrow_mut();
+        let (Some(handler), Some(dialog)) = (handler.as_mut(), pfd.as_ref()) else {
+            return Ok(());
+        };
+        if let Ok(index) = unsafe { dialog.GetFileTypeIndex() } {
+            handler(index as usize);
+        }
+        Ok(())
+    }
+
+    fn OnOverwrite(
+        &self,
+        _pfd: Ref<IFileDialog>,
+        _psi: Ref<IShellItem>,
+    ) -> windows::core::Result<FDE_OVERWRITE_RESPONSE> {
+        Ok(FDEOR_DEFAULT)
+    }
+}
+
+fn control_id(index: usize) -> u32 {
+    index as u32 * 2 + 1
+}
+
+fn group_id(index: usize) -> u32 {
+    control_id(index) + 1
+}
+
+unsafe fn add_control(
+    customize: &IFileDialogCustomize,
+    index: usize,
+    control: &CustomControl,
+) -> Result<()> {
+    unsafe {
+        let id = control_id(index);
+        match control {
+            CustomControl::CheckButton { label, checked } => {
+                customize.AddCheckButton(id, &HSTRING::from(label), *checked)?;
+            }
+            CustomControl::ComboBox {
+                label,
+                items,
+                selected,
+            } => {
+                customize.StartVisualGroup(group_id(index), &HSTRING::from(label))?;
+                customize.AddComboBox(id)?;
+                for (i, item) in items.iter().enumerate() {
+                    customize.AddControlItem(id, i as u32, &HSTRING::from(item))?;
+                }
+                if *selected < items.len() {
+                    customize.SetSelectedControlItem(id, *selected as u32)?;
+                }
+                customize.EndVisualGroup()?;
+            }
+            CustomControl::EditBox { label, text } => {
+                customize.StartVisualGroup(group_id(index), &HSTRING::from(label))?;
+                customize.AddEditBox(id, &HSTRING::from(text))?;
+                customize.EndVisualGroup()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+unsafe fn control_value(
+    customize: &IFileDialogCustomize,
+    index: usize,
+    control: &CustomControl,
+) -> Result<ControlValue> {
+    unsafe {
+        let id = control_id(index);
+        let value = match control {
+            CustomControl::CheckButton { .. } => {
+                ControlValue::CheckButton(customize.GetCheckButtonState(id)?.as_bool())
+            }
+            CustomControl::ComboBox { .. } => {
+                ControlValue::ComboBox(customize.GetSelectedControlItem(id)? as usize)
+            }
+            CustomControl::EditBox { .. } => {
+                let text = DisplayName(PWSTR(customize.GetEditBoxText(id)?));
+                ControlValue::EditBox(text.to_string()?)
+            }
+        };
+        Ok(value)
+    }
+}
+
+struct Choices {
+    file_type_index: usize,
+    controls: Vec<ControlValue>,
+}
+
+unsafe fn show_dialog<W, T>(dialog: &T, params: Params<W>) -> Result<Choices>
 where
     W: IsWindow,
     T: Interface,
@@ -184,6 +429,9 @@ where
         if let Some(ext) = params.default_extension {
             dialog.SetDefaultExtension(&HSTRING::from(ext))?;
         }
+        if let Some(name) = params.file_name {
+            dialog.SetFileName(&HSTRING::from(name))?;
+        }
         if let Some(label) = params.file_name_label {
             dialog.SetFileNameLabel(&HSTRING::from(label))?;
         }
@@ -204,25 +452,79 @@ where
             dialog.SetFileTypes(&file_types)?;
             dialog.SetFileTypeIndex(params.file_type_index as u32)?;
         }
+        let customize = if params.controls.is_empty() {
+            None
+        } else {
+            let customize: IFileDialogCustomize = dialog.cast()?;
+            for (i, control) in params.controls.iter().enumerate() {
+                add_control(&customize, i, control)?;
+            }
+            Some(customize)
+        };
         dialog.SetOptions(params.options.into())?;
-        dialog.Show(Some(params.owner.window_handle().as_hwnd()))?;
-        Ok(())
+        let cookie = if params.on_selection_change.is_some() || params.on_type_change.is_some() {
+            let events: IFileDialogEvents = DialogEvents {
+                on_selection_change: RefCell::new(params.on_selection_change),
+                on_type_change: RefCell::new(params.on_type_change),
+            }
+            .into();
+            Some(dialog.Advise(&events)?)
+        } else {
+            None
+        };
+        let ret = dialog.Show(Some(params.owner.window_handle().as_hwnd()));
+        if let Some(Err(e)) = cookie.map(|cookie| dialog.Unadvise(cookie)) {
+            error!("IFileDialog::Unadvise: {e}");
+        }
+        ret?;
+        let controls = match customize {
+            Some(customize) => params
+                .controls
+                .iter()
+                .enumerate()
+                .map(|(i, control)| control_value(&customize, i, control))
+                .collect::<Result<Vec<_>>>()?,
+            None => vec![],
+        };
+        Ok(Choices {
+            file_type_index: dialog.GetFileTypeIndex().unwrap_or(0) as usize,
+            controls,
+        })
+    }
+}
+
+/// Turns the cancellation of a dialog into `Ok(None)`.
+fn cancelled_as_none<T>(ret: Result<T>) -> Result<Option<T>> {
+    match ret {
+        Ok(ret) => Ok(Some(ret)),
+        Err(Error::Api(e)) if e.code() == ERROR_CANCELLED.into() => Ok(None),
+        Err(e) => Err(e),
     }
 }
 
+fn log_error<T>(ret: Result<Option<T>>) -> Option<T> {
+    ret.unwrap_or_else(|e| {
+        error!("{e}");
+        None
+    })
+}
+
 struct DisplayName(PWSTR);
 
 impl DisplayName {
-    unsafe fn to_path_buf(&self) -> Result<PathBuf> {
+    unsafe fn to_string(&self) -> Result<String> {
         unsafe {
             let len = (0..isize::MAX)
                 .position(|i| *self.0.0.offset(i) == 0)
                 .ok_or(std::io::Error::from(std::io::ErrorKind::InvalidData))?;
             let slice = std::slice::from_raw_parts(self.0.0, len);
-            let path: PathBuf = String::from_utf16_lossy(slice).into();
-            Ok(path)
+            Ok(String::from_utf16_lossy(slice))
         }
     }
+
+    unsafe fn to_path_buf(&self) -> Result<PathBuf> {
+        unsafe { Ok(self.to_string()?.into()) }
+    }
 }
 
 impl Drop for DisplayName {
@@ -274,38 +576,41 @@ impl OpenDialogResult for Vec<PathBuf> {
     }
 }
 
-fn show_open_dialog<W, T>(dialog: FileOpenDialog<W, T>) -> oneshot::Receiver<Option<T>>
+impl OpenDialogResult for Folder {
+    const OPTIONS: FileDialogOptions = FileDialogOptions::PICK_FOLDERS;
+
+    fn get_result(dialog: &IFileOpenDialog) -> Result<Self> {
+        PathBuf::get_result(dialog).map(Folder)
+    }
+}
+
+type DialogReceiver<T> = oneshot::Receiver<Result<Option<FileDialogResult<T>>>>;
+
+fn show_open_dialog<W, T>(dialog: FileOpenDialog<W, T>) -> DialogReceiver<T>
 where
     W: IsWindow + Send + 'static,
     T: OpenDialogResult + 'static,
 {
-    let (tx, rx) = oneshot::channel::<Option<T>>();
+    let (tx, rx) = oneshot::channel();
     let mut params = dialog.params;
     params.options |= T::OPTIONS;
     UiThread::send_task(move || unsafe {
-        let task = || -> Result<T> {
+        let task = || -> Result<FileDialogResult<T>> {
             let dialog: IFileOpenDialog =
                 CoCreateInstance(&FileOpenDialog, None, CLSCTX_INPROC_SERVER)?;
-            show_dialog(&dialog, params)?;
-            T::get_result(&dialog)
+            let choices = show_dialog(&dialog, params)?;
+            Ok(FileDialogResult {
+                selection: T::get_result(&dialog)?,
+                file_type_index: choices.file_type_index,
+                controls: choices.controls,
+            })
         };
-        match task() {
-            Ok(ret) => {
-                tx.send(Some(ret)).ok();
-            }
-            Err(e) => {
-                if !matches!(e, Error::Api(ref e) if e.code() == ERROR_CANCELLED.into()) {
-                    #[cfg(feature = "tracing")]
-                    tracing::error!("{e}");
-                }
-                tx.send(None).ok();
-            }
-        }
+        tx.send(cancelled_as_none(task())).ok();
     });
     rx
 }
 
-/// A dialog for specifying a file (or files) to open.
+/// A dialog for specifying a file (or files, or a folder) to open.
 pub struct FileOpenDialog<W, T = ()> {
     params: Params<W>,
     _t: std::marker::PhantomData<T>,
@@ -318,17 +623,10 @@ where
     #[inline]
     pub fn new(window: &W) -> FileOpenDialog<W, PathBuf> {
         FileOpenDialog {
-            params: Params {
-                title: None,
-                ok_button_label: None,
-                default_directory: None,
-                default_extension: None,
-                file_name_label: None,
-                file_types: vec![],
-                file_type_index: 0,
-                options: FileDialogOptions::PATH_MUST_EXIST | FileDialogOptions::FILE_MUST_EXIST,
-                owner: window.clone(),
-            },
+            params: Params::new(
+                window.clone(),
+                FileDialogOptions::PATH_MUST_EXIST | FileDialogOptions::FILE_MUST_EXIST,
+            ),
             _t: std::marker::PhantomData,
         }
     }
@@ -336,17 +634,19 @@ where
     #[inline]
     pub fn new_multi_select(window: &W) -> FileOpenDialog<W, Vec<PathBuf>> {
         FileOpenDialog {
-            params: Params {
-                title: None,
-                ok_button_label: None,
-                default_directory: None,
-                default_extension: None,
-                file_name_label: None,
-                file_types: vec![],
-                file_type_index: 0,
-                options: FileDialogOptions::PATH_MUST_EXIST | FileDialogOptions::FILE_MUST_EXIST,
-                owner: window.clone(),
-            },
+            params: Params::new(
+                window.clone(),
+                FileDialogOptions::PATH_MUST_EXIST | FileDialogOptions::FILE_MUST_EXIST,
+            ),
+            _t: std::marker::PhantomData,
+        }
+    }
+
+    /// Creates a dialog for picking a folder instead of files.
+    #[inline]
+    pub fn new_folder(window: &W) -> FileOpenDialog<W, Folder> {
+        FileOpenDialog {
+            params: Params::new(window.clone(), FileDialogOptions::PATH_MUST_EXIST),
             _t: std::marker::PhantomData,
         }
     }
@@ -377,6 +677,13 @@ impl<W, T> FileOpenDialog<W, T> {
         self
     }
 
+    /// Sets the file name which is initially entered.
+    #[inline]
+    pub fn file_name(mut self, name: impl Into<String>) -> Self {
+        self.params.file_name = Some(name.into());
+        self
+    }
+
     #[inline]
     pub fn file_name_label(mut self, label: impl Into<String>) -> Self {
         self.params.file_name_label = Some(label.into());
@@ -400,62 +707,167 @@ impl<W, T> FileOpenDialog<W, T> {
         self.params.options = options;
         self
     }
+
+    /// Adds a check box, which is returned as `ControlValue::CheckButton`.
+    #[inline]
+    pub fn check_button(mut self, label: impl Into<String>, checked: bool) -> Self {
+        self.params.controls.push(CustomControl::CheckButton {
+            label: label.into(),
+            checked,
+        });
+        self
+    }
+
+    /// Adds a combo box, which is returned as `ControlValue::ComboBox`.
+    #[inline]
+    pub fn combo_box<I>(mut self, label: impl Into<String>, items: I, selected: usize) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.params.controls.push(CustomControl::ComboBox {
+            label: label.into(),
+            items: items.into_iter().map(|item| item.into()).collect(),
+            selected,
+        });
+        self
+    }
+
+    /// Adds a text box, which is returned as `ControlValue::EditBox`.
+    #[inline]
+    pub fn edit_box(mut self, label: impl Into<String>, text: impl Into<String>) -> Self {
+        self.params.controls.push(CustomControl::EditBox {
+            label: label.into(),
+            text: text.into(),
+        });
+        self
+    }
+
+    /// Sets a function called with the path of the selected item while the dialog is shown.
+    #[inline]
+    pub fn on_selection_change(mut self, f: impl FnMut(PathBuf) + Send + 'static) -> Self {
+        self.params.on_selection_change = Some(Box::new(f));
+        self
+    }
+
+    /// Sets a function called with the one-based index of the file type selected by users.
+    #[inline]
+    pub fn on_type_change(mut self, f: impl FnMut(usize) + Send + 'static) -> Self {
+        self.params.on_type_change = Some(Box::new(f));
+        self
+    }
 }
 
 impl FileOpenDialog<Window, PathBuf> {
     #[inline]
     pub fn show(self) -> Option<PathBuf> {
-        show_open_dialog(self).blocking_recv().ok().flatten()
+        log_error(self.try_show()).map(|ret| ret.selection)
+    }
+
+    /// Shows the dialog, and returns `Ok(None)` if the dialog was cancelled.
+    #[inline]
+    pub fn try_show(self) -> Result<Option<FileDialogResult<PathBuf>>> {
+        show_open_dialog(self)
+            .blocking_recv()
+            .unwrap_or(Err(Error::UiThreadClosed))
     }
 }
 
 impl FileOpenDialog<AsyncWindow, PathBuf> {
     #[inline]
     pub async fn show(self) -> Option<PathBuf> {
-        show_open_dialog(self).await.ok().flatten()
+        log_error(self.try_show().await).map(|ret| ret.selection)
+    }
+
+    /// Shows the dialog, and returns `Ok(None)` if the dialog was cancelled.
+    #[inline]
+    pub async fn try_show(self) -> Result<Option<FileDialogResult<PathBuf>>> {
+        show_open_dialog(self)
+            .await
+            .unwrap_or(Err(Error::UiThreadClosed))
     }
 }
 
 impl FileOpenDialog<Window, Vec<PathBuf>> {
     #[inline]
     pub fn show(self) -> Option<Vec<PathBuf>> {
-        show_open_dialog(self).blocking_recv().ok().flatten()
+        log_error(self.try_show()).map(|ret| ret.selection)
+    }
+
+    /// Shows the dialog, and returns `Ok(None)` if the dialog was cancelled.
+    #[inline]
+    pub fn try_show(self) -> Result<Option<FileDialogResult<Vec<PathBuf>>>> {
+        show_open_dialog(self)
+            .blocking_recv()
+            .unwrap_or(Err(Error::UiThreadClosed))
     }
 }
 
 impl FileOpenDialog<AsyncWindow, Vec<PathBuf>> {
     #[inline]
     pub async fn show(self) -> Option<Vec<PathBuf>> {
-        show_open_dialog(self).await.ok().flatten()
+        log_error(self.try_show().await).map(|ret| ret.selection)
+    }
+
+    /// Shows the dialog, and returns `Ok(None)` if the dialog was cancelled.
+    #[inline]
+    pub async fn try_show(self) -> Result<Option<FileDialogResult<Vec<PathBuf>>>> {
+        show_open_dialog(self)
+            .await
+            .unwrap_or(Err(Error::UiThreadClosed))
+    }
+}
+
+impl FileOpenDialog<Window, Folder> {
+    #[inline]
+    pub fn show(self) -> Option<Folder> {
+        log_error(self.try_show()).map(|ret| ret.selection)
+    }
+
+    /// Shows the dialog, and returns `Ok(None)` if the dialog was cancelled.
+    #[inline]
+    pub fn try_show(self) -> Result<Option<FileDialogResult<Folder>>> {
+        show_open_dialog(self)
+            .blocking_recv()
+            .unwrap_or(Err(Error::UiThreadClosed))
     }
 }
 
-fn show_save_dialog<W>(dialog: FileSaveDialog<W>) -> oneshot::Receiver<Option<PathBuf>>
+impl FileOpenDialog<AsyncWindow, Folder> {
+    #[inline]
+    pub async fn show(self) -> Option<Folder> {
+        log_error(self.try_show().await).map(|ret| ret.selection)
+    }
+
+    /// Shows the dialog, and returns `Ok(None)` if the dialog was cancelled.
+    #[inline]
+    pub async fn try_show(self) -> Result<Option<FileDialogResult<Folder>>> {
+        show_open_dialog(self)
+            .await
+            .unwrap_or(Err(Error::UiThreadClosed))
+    }
+}
+
+fn show_save_dialog<W>(dialog: FileSaveDialog<W>) -> DialogReceiver<PathBuf>
 where
     W: IsWindow + Send + 'static,
 {
-    let (tx, rx) = oneshot::channel::<Option<PathBuf>>();
+    let (tx, rx) = oneshot::channel();
     let params = dialog.params;
     UiThread::send_task(move || unsafe {
-        let task = || -> Result<PathBuf> {
+        let task = || -> Result<FileDialogResult<PathBuf>> {
             let dialog: IFileSaveDialog =
                 CoCreateInstance(&FileSaveDialog, None, CLSCTX_INPROC_SERVER)?;
-            show_dialog(&dialog, params)?;
+            let choices = show_dialog(&dialog, params)?;
             let result = dialog.GetResult()?;
             let result = DisplayName(result.GetDisplayName(SIGDN_FILESYSPATH)?).to_path_buf()?;
-            Ok(result)
+            Ok(FileDialogResult {
+                selection: result,
+                file_type_index: choices.file_type_index,
+                controls: choices.controls,
+            })
         };
-        match task() {
-            Ok(path) => {
-                tx.send(Some(path)).ok();
-            }
-            Err(e) => {
-                if !matches!(e, Error::Api(ref e) if e.code() == ERROR_CANCELLED.into()) {
-                    error!("{e}");
-                }
-                tx.send(None).ok();
-            }
-        }
+        tx.send(cancelled_as_none(task())).ok();
     });
     rx
 }
@@ -472,19 +884,12 @@ impl FileSaveDialog<()> {
         W: IsWindow + Clone,
     {
         FileSaveDialog {
-            params: Params {
-                title: None,
-                ok_button_label: None,
-                default_directory: None,
-                default_extension: None,
-                file_name_label: None,
-                file_types: vec![],
-                file_type_index: 0,
-                options: FileDialogOptions::PATH_MUST_EXIST
+            params: Params::new(
+                window.clone(),
+                FileDialogOptions::PATH_MUST_EXIST
                     | FileDialogOptions::NO_READONLY_RETURN
                     | FileDialogOptions::OVERWRITE_PROMPT,
-                owner: window.clone(),
-            },
+            ),
         }
     }
 }
@@ -514,6 +919,13 @@ impl<W> FileSaveDialog<W> {
         self
     }
 
+    /// Sets the file name which is initially entered.
+    #[inline]
+    pub fn file_name(mut self, name: impl Into<String>) -> Self {
+        self.params.file_name = Some(name.into());
+        self
+    }
+
     #[inline]
     pub fn file_name_label(mut self, label: impl Into<String>) -> Self {
         self.params.file_name_label = Some(label.into());
@@ -537,18 +949,834 @@ impl<W> FileSaveDialog<W> {
         self.params.options = options;
         self
     }
+
+    /// Adds a check box, which is returned as `ControlValue::CheckButton`.
+    #[inline]
+    pub fn check_button(mut self, label: impl Into<String>, checked: bool) -> Self {
+        self.params.controls.push(CustomControl::CheckButton {
+            label: label.into(),
+            checked,
+        });
+        self
+    }
+
+    /// Adds a combo box, which is returned as `ControlValue::ComboBox`.
+    #[inline]
+    pub fn combo_box<I>(mut self, label: impl Into<String>, items: I, selected: usize) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.params.controls.push(CustomControl::ComboBox {
+            label: label.into(),
+            items: items.into_iter().map(|item| item.into()).collect(),
+            selected,
+        });
+        self
+    }
+
+    /// Adds a text box, which is returned as `ControlValue::EditBox`.
+    #[inline]
+    pub fn edit_box(mut self, label: impl Into<String>, text: impl Into<String>) -> Self {
+        self.params.controls.push(CustomControl::EditBox {
+            label: label.into(),
+            text: text.into(),
+        });
+        self
+    }
+
+    /// Sets a function called with the path of the selected item while the dialog is shown.
+    #[inline]
+    pub fn on_selection_change(mut self, f: impl FnMut(PathBuf) + Send + 'static) -> Self {
+        self.params.on_selection_change = Some(Box::new(f));
+        self
+    }
+
+    /// Sets a function called with the one-based index of the file type selected by users.
+    #[inline]
+    pub fn on_type_change(mut self, f: impl FnMut(usize) + Send + 'static) -> Self {
+        self.params.on_type_change = Some(Box::new(f));
+        self
+    }
 }
 
 impl FileSaveDialog<Window> {
     #[inline]
     pub fn show(self) -> Option<PathBuf> {
-        show_save_dialog(self).blocking_recv().ok().flatten()
+        log_error(self.try_show()).map(|ret| ret.selection)
+    }
+
+    /// Shows the dialog, and returns `Ok(None)` if the dialog was cancelled.
+    #[inline]
+    pub fn try_show(self) -> Result<Option<FileDialogResult<PathBuf>>> {
+        show_save_dialog(self)
+            .blocking_recv()
+            .unwrap_or(Err(Error::UiThreadClosed))
     }
 }
 
 impl FileSaveDialog<AsyncWindow> {
     #[inline]
     pub async fn show(self) -> Option<PathBuf> {
-        show_save_dialog(self).await.ok().flatten()
+        log_error(self.try_show().await).map(|ret| ret.selection)
+    }
+
+    /// Shows the dialog, and returns `Ok(None)` if the dialog was cancelled.
+    #[inline]
+    pub async fn try_show(self) -> Result<Option<FileDialogResult<PathBuf>>> {
+        show_save_dialog(self)
+            .await
+            .unwrap_or(Err(Error::UiThreadClosed))
+    }
+}
+
+/// A button of `MessageDialog` and `TaskDialog`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum DialogButton {
+    Ok,
+    Cancel,
+    Yes,
+    No,
+    Retry,
+    Abort,
+    Ignore,
+    TryAgain,
+    Continue,
+    Close,
+}
+
+impl DialogButton {
+    fn id(self) -> MESSAGEBOX_RESULT {
+        match self {
+            Self::Ok => IDOK,
+            Self::Cancel => IDCANCEL,
+            Self::Yes => IDYES,
+            Self::No => IDNO,
+            Self::Retry => IDRETRY,
+            Self::Abort => IDABORT,
+            Self::Ignore => IDIGNORE,
+            Self::TryAgain => IDTRYAGAIN,
+            Self::Continue => IDCONTINUE,
+            Self::Close => IDCLOSE,
+        }
+    }
+
+    fn from_id(id: MESSAGEBOX_RESULT) -> Option<Self> {
+        match id {
+            IDOK => Some(Self::Ok),
+            IDCANCEL => Some(Self::Cancel),
+            IDYES => Some(Self::Yes),
+            IDNO => Some(Self::No),
+            IDRETRY => Some(Self::Retry),
+            IDABORT => Some(Self::Abort),
+            IDIGNORE => Some(Self::Ignore),
+            IDTRYAGAIN => Some(Self::TryAgain),
+            IDCONTINUE => Some(Self::Continue),
+            IDCLOSE => Some(Self::Close),
+            _ => None,
+        }
+    }
+
+    fn common_button_flag(self) -> TASKDIALOG_COMMON_BUTTON_FLAGS {
+        match self {
+            Self::Ok => TDCBF_OK_BUTTON,
+            Self::Cancel => TDCBF_CANCEL_BUTTON,
+            Self::Yes => TDCBF_YES_BUTTON,
+            Self::No => TDCBF_NO_BUTTON,
+            Self::Retry => TDCBF_RETRY_BUTTON,
+            Self::Abort => TDCBF_ABORT_BUTTON,
+            Self::Ignore => TDCBF_IGNORE_BUTTON,
+            Self::TryAgain => TDCBF_TRYAGAIN_BUTTON,
+            Self::Continue => TDCBF_CONTINUE_BUTTON,
+            Self::Close => TDCBF_CLOSE_BUTTON,
+        }
+    }
+}
+
+/// An icon of `MessageDialog`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MessageIcon {
+    Information,
+    Warning,
+    Error,
+    Question,
+}
+
+/// A set of buttons of `MessageDialog`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MessageButtons {
+    Ok,
+    OkCancel,
+    YesNo,
+    YesNoCancel,
+    RetryCancel,
+    AbortRetryIgnore,
+    CancelTryContinue,
+}
+
+impl MessageButtons {
+    fn buttons(self) -> &'static [DialogButton] {
+        use DialogButton::*;
+        match self {
+            Self::Ok => &[Ok],
+            Self::OkCancel => &[Ok, Cancel],
+            Self::YesNo => &[Yes, No],
+            Self::YesNoCancel => &[Yes, No, Cancel],
+            Self::RetryCancel => &[Retry, Cancel],
+            Self::AbortRetryIgnore => &[Abort, Retry, Ignore],
+            Self::CancelTryContinue => &[Cancel, TryAgain, Continue],
+        }
+    }
+
+    fn style(self) -> MESSAGEBOX_STYLE {
+        match self {
+            Self::Ok => MB_OK,
+            Self::OkCancel => MB_OKCANCEL,
+            Self::YesNo => MB_YESNO,
+            Self::YesNoCancel => MB_YESNOCANCEL,
+            Self::RetryCancel => MB_RETRYCANCEL,
+            Self::AbortRetryIgnore => MB_ABORTRETRYIGNORE,
+            Self::CancelTryContinue => MB_CANCELTRYCONTINUE,
+        }
+    }
+}
+
+fn to_pcwstr(s: &Option<HSTRING>) -> PCWSTR {
+    s.as_ref().map_or(PCWSTR::null(), |s| PCWSTR(s.as_ptr()))
+}
+
+fn show_message_dialog<W>(dialog: MessageDialog<W>) -> oneshot::Receiver<Option<DialogButton>>
+where
+    W: IsWindow + Send + 'static,
+{
+    let (tx, rx) = oneshot::channel::<Option<DialogButton>>();
+    UiThread::send_task(move || unsafe {
+        let mut style = dialog.buttons.style();
+        style |= match dialog.icon {
+            Some(MessageIcon::Information) => MB_ICONINFORMATION,
+            Some(MessageIcon::Warning) => MB_ICONWARNING,
+            Some(MessageIcon::Error) => MB_ICONERROR,
+            Some(MessageIcon::Question) => MB_ICONQUESTION,
+            None => MESSAGEBOX_STYLE(0),
+        };
+        let default_button = dialog
+            .default_button
+            .and_then(|button| dialog.buttons.buttons().iter().position(|&b| b == button));
+        if let Some(index) = default_button {
+            style |= [MB_DEFBUTTON1, MB_DEFBUTTON2, MB_DEFBUTTON3, MB_DEFBUTTON4][index];
+        }
+        let title = dialog.title.map(HSTRING::from);
+        let ret = MessageBoxW(
+            Some(dialog.owner.window_handle().as_hwnd()),
+            &HSTRING::from(dialog.text),
+            to_pcwstr(&title),
+            style,
+        );
+        if ret.0 == 0 {
+            error!("MessageBoxW: {}", windows::core::Error::from_thread());
+        }
+        tx.send(DialogButton::from_id(ret)).ok();
+    });
+    rx
+}
+
+/// A dialog for showing a message with an icon and a set of buttons.
+pub struct MessageDialog<W = ()> {
+    owner: W,
+    title: Option<String>,
+    text: String,
+    icon: Option<MessageIcon>,
+    buttons: MessageButtons,
+    default_button: Option<DialogButton>,
+}
+
+impl MessageDialog<()> {
+    #[inline]
+    pub fn new<W>(window: &W, text: impl Into<String>) -> MessageDialog<W>
+    where
+        W: IsWindow + Clone,
+    {
+        MessageDialog {
+            owner: window.clone(),
+            title: None,
+            text: text.into(),
+            icon: None,
+            buttons: MessageButtons::Ok,
+            default_button: None,
+        }
+    }
+}
+
+impl<W> MessageDialog<W> {
+    #[inline]
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    #[inline]
+    pub fn icon(mut self, icon: MessageIcon) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    #[inline]
+    pub fn buttons(mut self, buttons: MessageButtons) -> Self {
+        self.buttons = buttons;
+        self
+    }
+
+    /// Sets the focused button. It is ignored if `buttons` does not contain `button`.
+    #[inline]
+    pub fn default_button(mut self, button: DialogButton) -> Self {
+        self.default_button = Some(button);
+        self
+    }
+}
+
+impl MessageDialog<Window> {
+    /// Shows the dialog, and returns the clicked button.
+    #[inline]
+    pub fn show(self) -> Option<DialogButton> {
+        show_message_dialog(self).blocking_recv().ok().flatten()
+    }
+}
+
+impl MessageDialog<AsyncWindow> {
+    /// Shows the dialog, and returns the clicked button.
+    #[inline]
+    pub async fn show(self) -> Option<DialogButton> {
+        show_message_dialog(self).await.ok().flatten()
+    }
+}
+
+/// An icon of `TaskDialog`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TaskDialogIcon {
+    Information,
+    Warning,
+    Error,
+    Shield,
+}
+
+impl TaskDialogIcon {
+    fn as_pcwstr(self) -> PCWSTR {
+        match self {
+            Self::Information => TD_INFORMATION_ICON,
+            Self::Warning => TD_WARNING_ICON,
+            Self::Error => TD_ERROR_ICON,
+            Self::Shield => TD_SHIELD_ICON,
+        }
+    }
+}
+
+/// A button which closed `TaskDialog`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum TaskDialogButton {
+    Common(DialogButton),
+    /// The index of a button added by `TaskDialog::button`.
+    Custom(usize),
+}
+
+/// The result of `TaskDialog`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TaskDialogResult {
+    pub button: TaskDialogButton,
+    /// The index of the selected radio button.
+    pub radio_button: Option<usize>,
+    pub verification_checked: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+struct ProgressState {
+    position: u32,
+    marquee: bool,
+}
+
+/// A progress bar of `TaskDialog` which can be updated from any thread while the dialog is shown.
+#[derive(Clone, Debug, Default)]
+pub struct TaskDialogProgress(Arc<Mutex<ProgressState>>);
+
+impl TaskDialogProgress {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the position in percent.
+    #[inline]
+    pub fn set_position(&self, position: u32) {
+        self.0.lock().unwrap().position = position.min(100);
+    }
+
+    /// Switches to the marquee style which shows an indeterminate progress.
+    #[inline]
+    pub fn set_marquee(&self, marquee: bool) {
+        self.0.lock().unwrap().marquee = marquee;
+    }
+
+    fn state(&self) -> ProgressState {
+        *self.0.lock().unwrap()
+    }
+}
+
+const CUSTOM_BUTTON_ID: i32 = 1000;
+const RADIO_BUTTON_ID: i32 = 1000;
+
+type TaskDialogIndirectFn =
+    unsafe extern "system" fn(*const TASKDIALOGCONFIG, *mut i32, *mut i32, *mut BOOL) -> HRESULT;
+
+static COMCTL32: LazyLock<Option<Library>> = LazyLock::new(|| Library::new("comctl32.dll").ok());
+
+type HyperlinkHandler = Box<dyn FnMut(String) + Send>;
+
+struct CallbackData {
+    progress: Option<TaskDialogProgress>,
+    prev: Option<ProgressState>,
+    on_hyperlink: Option<HyperlinkHandler>,
+}
+
+impl CallbackData {
+    unsafe fn update_progress(&mut self, hwnd: HWND) {
+        let Some(progress) = self.progress.as_ref() else {
+            return;
+        };
+        let state = progress.state();
+        if self.prev == Some(state) {
+            return;
+        }
+        unsafe {
+            if self.prev.map(|prev| prev.marquee) != Some(state.marquee) {
+                let marquee = WPARAM(state.marquee as usize);
+                SendMessageW(
+                    hwnd,
+                    TDM_SET_MARQUEE_PROGRESS_BAR.0 as u32,
+                    Some(marquee),
+                    None,
+                );
+                SendMessageW(
+                    hwnd,
+                    TDM_SET_PROGRESS_BAR_MARQUEE.0 as u32,
+                    Some(marquee),
+                    None,
+                );
+            }
+            if !state.marquee {
+                SendMessageW(
+                    hwnd,
+                    TDM_SET_PROGRESS_BAR_POS.0 as u32,
+                    Some(WPARAM(state.position as usize)),
+                    None,
+                );
+            }
+        }
+        self.prev = Some(state);
+    }
+}
+
+unsafe extern "system" fn task_dialog_callback(
+    hwnd: HWND,
+    msg: TASKDIALOG_NOTIFICATIONS,
+    _wparam: WPARAM,
+    lparam: LPARAM,
+    data: isize,
+) -> HRESULT {
+    unsafe {
+        let data = (data as *mut CallbackData).as_mut().unwrap();
+        match msg {
+            TDN_CREATED | TDN_TIMER => data.update_progress(hwnd),
+            TDN_HYPERLINK_CLICKED => {
+                let href = PCWSTR(lparam.0 as *const u16);
+                let text = href.to_string().unwrap_or_default();
+                if let Some(f) = data.on_hyperlink.as_mut() {
+                    f(text);
+                } else if is_web_link(&text) {
+                    ShellExecuteW(
+                        Some(hwnd),
+                        windows::core::w!("open"),
+                        href,
+                        PCWSTR::null(),
+                        PCWSTR::null(),
+                        SW_SHOWNORMAL,
+                    );
+                }
+            }
+            _ => {}
+        }
+        S_OK
+    }
+}
+
+/// Checks if `href` is an `http` or `https` URL, which `TaskDialog` opens by default.
+fn is_web_link(href: &str) -> bool {
+    let scheme = href.split_once(':').map_or("", |(scheme, _)| scheme);
+    scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https")
+}
+
+struct TaskDialogParams<W> {
+    owner: W,
+    title: Option<String>,
+    main_instruction: Option<String>,
+    content: Option<String>,
+    icon: Option<TaskDialogIcon>,
+    common_buttons: Vec<DialogButton>,
+    buttons: Vec<String>,
+    command_links: bool,
+    default_button: Option<TaskDialogButton>,
+    radio_buttons: Vec<String>,
+    default_radio_button: Option<usize>,
+    verification: Option<String>,
+    verification_checked: bool,
+    expanded_information: Option<String>,
+    expanded: bool,
+    footer: Option<String>,
+    footer_icon: Option<TaskDialogIcon>,
+    progress: Option<TaskDialogProgress>,
+    hyperlinks: bool,
+    on_hyperlink: Option<HyperlinkHandler>,
+}
+
+unsafe fn task_dialog_indirect<W>(params: TaskDialogParams<W>) -> Result<TaskDialogResult>
+where
+    W: IsWindow,
+{
+    unsafe {
+        let func = COMCTL32.as_ref().map(|lib| {
+            lib.get_proc_address::<TaskDialogIndirectFn>(PCSTR(c"TaskDialogIndirect".as_ptr() as _))
+        });
+        let Some(func) = func.filter(|func| !func.is_null()) else {
+            return Err(windows::core::Error::new(
+                ERROR_PROC_NOT_FOUND.into(),
+                "TaskDialogIndirect needs Common Controls 6.0 enabled by the manifest",
+            )
+            .into());
+        };
+        let title = params.title.map(HSTRING::from);
+        let main_instruction = params.main_instruction.map(HSTRING::from);
+        let content = params.content.map(HSTRING::from);
+        let verification = params.verification.map(HSTRING::from);
+        let expanded_information = params.expanded_information.map(HSTRING::from);
+        let footer = params.footer.map(HSTRING::from);
+        let button_texts = params
+            .buttons
+            .into_iter()
+            .map(HSTRING::from)
+            .collect::<Vec<_>>();
+        let buttons = button_texts
+            .iter()
+            .enumerate()
+            .map(|(i, text)| TASKDIALOG_BUTTON {
+                nButtonID: CUSTOM_BUTTON_ID + i as i32,
+                pszButtonText: PCWSTR(text.as_ptr()),
+            })
+            .collect::<Vec<_>>();
+        let radio_texts = params
+            .radio_buttons
+            .into_iter()
+            .map(HSTRING::from)
+            .collect::<Vec<_>>();
+        let radio_buttons = radio_texts
+            .iter()
+            .enumerate()
+            .map(|(i, text)| TASKDIALOG_BUTTON {
+                nButtonID: RADIO_BUTTON_ID + i as i32,
+                pszButtonText: PCWSTR(text.as_ptr()),
+            })
+            .collect::<Vec<_>>();
+        let mut flags = TDF_POSITION_RELATIVE_TO_WINDOW | TDF_ALLOW_DIALOG_CANCELLATION;
+        if params.command_links && !buttons.is_empty() {
+            flags |= TDF_USE_COMMAND_LINKS;
+        }
+        if params.verification_checked {
+            flags |= TDF_VERIFICATION_FLAG_CHECKED;
+        }
+        if params.expanded {
+            flags |= TDF_EXPANDED_BY_DEFAULT;
+        }
+        if params.hyperlinks || params.on_hyperlink.is_some() {
+            flags |= TDF_ENABLE_HYPERLINKS;
+        }
+        if let Some(progress) = params.progress.as_ref() {
+            flags |= TDF_CALLBACK_TIMER;
+            if progress.state().marquee {
+                flags |= TDF_SHOW_MARQUEE_PROGRESS_BAR;
+            } else {
+                flags |= TDF_SHOW_PROGRESS_BAR;
+            }
+        }
+        let common_buttons = params
+            .common_buttons
+            .iter()
+            .fold(TASKDIALOG_COMMON_BUTTON_FLAGS(0), |flags, button| {
+                flags | button.common_button_flag()
+            });
+        let default_button = match params.default_button {
+            Some(TaskDialogButton::Common(button)) => button.id().0,
+            Some(TaskDialogButton::Custom(index)) => CUSTOM_BUTTON_ID + index as i32,
+            None => 0,
+        };
+        let mut data = CallbackData {
+            progress: params.progress,
+            prev: None,
+            on_hyperlink: params.on_hyperlink,
+        };
+        let config = TASKDIALOGCONFIG {
+            cbSize: std::mem::size_of::<TASKDIALOGCONFIG>() as u32,
+            hwndParent: params.owner.window_handle().as_hwnd(),
+            dwFlags: flags,
+            dwCommonButtons: common_buttons,
+            pszWindowTitle: to_pcwstr(&title),
+            Anonymous1: TASKDIALOGCONFIG_0 {
+                pszMainIcon: params.icon.map_or(PCWSTR::null(), |icon| icon.as_pcwstr()),
+            },
+            pszMainInstruction: to_pcwstr(&main_instruction),
+            pszContent: to_pcwstr(&content),
+            cButtons: buttons.len() as u32,
+            pButtons: buttons.as_ptr(),
+            nDefaultButton: default_button,
+            cRadioButtons: radio_buttons.len() as u32,
+            pRadioButtons: radio_buttons.as_ptr(),
+            nDefaultRadioButton: params
+                .default_radio_button
+                .map_or(0, |index| RADIO_BUTTON_ID + index as i32),
+            pszVerificationText: to_pcwstr(&verification),
+            pszExpandedInformation: to_pcwstr(&expanded_information),
+            Anonymous2: TASKDIALOGCONFIG_1 {
+                pszFooterIcon: params
+                    .footer_icon
+                    .map_or(PCWSTR::null(), |icon| icon.as_pcwstr()),
+            },
+            pszFooter: to_pcwstr(&footer),
+            pfCallback: Some(task_dialog_callback),
+            lpCallbackData: &mut data as *mut CallbackData as isize,
+            ..Default::default()
+        };
+        let mut button = 0;
+        let mut radio_button = 0;
+        let mut verification_checked = BOOL(0);
+        func(
+            &config,
+            &mut button,
+            &mut radio_button,
+            &mut verification_checked,
+        )
+        .ok()?;
+        let button = if button >= CUSTOM_BUTTON_ID {
+            TaskDialogButton::Custom((button - CUSTOM_BUTTON_ID) as usize)
+        } else {
+            TaskDialogButton::Common(
+                DialogButton::from_id(MESSAGEBOX_RESULT(button)).unwrap_or(DialogButton::Cancel),
+            )
+        };
+        Ok(TaskDialogResult {
+            button,
+            radio_button: (radio_button >= RADIO_BUTTON_ID)
+                .then(|| (radio_button - RADIO_BUTTON_ID) as usize),
+            verification_checked: verification_checked.as_bool(),
+        })
+    }
+}
+
+fn show_task_dialog<W>(dialog: TaskDialog<W>) -> oneshot::Receiver<Option<TaskDialogResult>>
+where
+    W: IsWindow + Send + 'static,
+{
+    let (tx, rx) = oneshot::channel::<Option<TaskDialogResult>>();
+    let params = dialog.params;
+    UiThread::send_task(move || unsafe {
+        match task_dialog_indirect(params) {
+            Ok(ret) => {
+                tx.send(Some(ret)).ok();
+            }
+            Err(e) => {
+                error!("{e}");
+                tx.send(None).ok();
+            }
+        }
+    });
+    rx
+}
+
+/// A dialog which has custom buttons, command links, radio buttons, a verification check box,
+/// expandable details, a footer and a progress bar.
+///
+/// `TaskDialog` needs Common Controls 6.0 enabled by the application manifest. Otherwise `show`
+/// returns `None`.
+pub struct TaskDialog<W = ()> {
+    params: TaskDialogParams<W>,
+}
+
+impl TaskDialog<()> {
+    #[inline]
+    pub fn new<W>(window: &W) -> TaskDialog<W>
+    where
+        W: IsWindow + Clone,
+    {
+        TaskDialog {
+            params: TaskDialogParams {
+                owner: window.clone(),
+                title: None,
+                main_instruction: None,
+                content: None,
+                icon: None,
+                common_buttons: vec![],
+                buttons: vec![],
+                command_links: false,
+                default_button: None,
+                radio_buttons: vec![],
+                default_radio_button: None,
+                verification: None,
+                verification_checked: false,
+                expanded_information: None,
+                expanded: false,
+                footer: None,
+                footer_icon: None,
+                progress: None,
+                hyperlinks: false,
+                on_hyperlink: None,
+            },
+        }
+    }
+}
+
+impl<W> TaskDialog<W> {
+    #[inline]
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.params.title = Some(title.into());
+        self
+    }
+
+    #[inline]
+    pub fn main_instruction(mut self, text: impl Into<String>) -> Self {
+        self.params.main_instruction = Some(text.into());
+        self
+    }
+
+    #[inline]
+    pub fn content(mut self, text: impl Into<String>) -> Self {
+        self.params.content = Some(text.into());
+        self
+    }
+
+    #[inline]
+    pub fn icon(mut self, icon: TaskDialogIcon) -> Self {
+        self.params.icon = Some(icon);
+        self
+    }
+
+    /// Sets system-defined buttons. `OK` is shown if no buttons are set.
+    #[inline]
+    pub fn common_buttons(mut self, buttons: &[DialogButton]) -> Self {
+        self.params.common_buttons = buttons.to_vec();
+        self
+    }
+
+    /// Adds a button, which is returned as `TaskDialogButton::Custom` with the index.
+    #[inline]
+    pub fn button(mut self, text: impl Into<String>) -> Self {
+        self.params.buttons.push(text.into());
+        self
+    }
+
+    /// Shows buttons added by `button` as command links.
+    ///
+    /// The text after the first new line is shown as the note of a command link.
+    #[inline]
+    pub fn command_links(mut self, flag: bool) -> Self {
+        self.params.command_links = flag;
+        self
+    }
+
+    #[inline]
+    pub fn default_button(mut self, button: TaskDialogButton) -> Self {
+        self.params.default_button = Some(button);
+        self
+    }
+
+    /// Adds a radio button, which is returned as `TaskDialogResult::radio_button` with the index.
+    #[inline]
+    pub fn radio_button(mut self, text: impl Into<String>) -> Self {
+        self.params.radio_buttons.push(text.into());
+        self
+    }
+
+    #[inline]
+    pub fn default_radio_button(mut self, index: usize) -> Self {
+        self.params.default_radio_button = Some(index);
+        self
+    }
+
+    /// Shows a check box like "Don't show this again".
+    #[inline]
+    pub fn verification(mut self, text: impl Into<String>, checked: bool) -> Self {
+        self.params.verification = Some(text.into());
+        self.params.verification_checked = checked;
+        self
+    }
+
+    /// Sets the details which are shown by the expand button.
+    #[inline]
+    pub fn expanded_information(mut self, text: impl Into<String>) -> Self {
+        self.params.expanded_information = Some(text.into());
+        self
+    }
+
+    #[inline]
+    pub fn expanded_by_default(mut self, flag: bool) -> Self {
+        self.params.expanded = flag;
+        self
+    }
+
+    #[inline]
+    pub fn footer(mut self, text: impl Into<String>) -> Self {
+        self.params.footer = Some(text.into());
+        self
+    }
+
+    #[inline]
+    pub fn footer_icon(mut self, icon: TaskDialogIcon) -> Self {
+        self.params.footer_icon = Some(icon);
+        self
+    }
+
+    /// Shows a progress bar which follows `progress`.
+    #[inline]
+    pub fn progress_bar(mut self, progress: &TaskDialogProgress) -> Self {
+        self.params.progress = Some(progress.clone());
+        self
+    }
+
+    /// Enables `<a href="...">` links in texts.
+    ///
+    /// A clicked link is opened with the default web browser only if it is an `http` or `https`
+    /// URL. Other links are ignored. Use `on_hyperlink` to handle links in another way.
+    #[inline]
+    pub fn hyperlinks(mut self, flag: bool) -> Self {
+        self.params.hyperlinks = flag;
+        self
+    }
+
+    /// Enables `<a href="...">` links in texts, and sets a function called with the `href` of
+    /// a clicked link instead of opening it.
+    #[inline]
+    pub fn on_hyperlink(mut self, f: impl FnMut(String) + Send + 'static) -> Self {
+        self.params.on_hyperlink = Some(Box::new(f));
+        self
+    }
+}
+
+impl TaskDialog<Window> {
+    /// Shows the dialog. Closing the dialog without buttons results in `DialogButton::Cancel`.
+    #[inline]
+    pub fn show(self) -> Option<TaskDialogResult> {
+        show_task_dialog(self).blocking_recv().ok().flatten()
+    }
+}
+
+impl TaskDialog<AsyncWindow> {
+    /// Shows the dialog. Closing the dialog without buttons results in `DialogButton::Cancel`.
+    #[inline]
+    pub async fn show(self) -> Option<TaskDialogResult> {
+        show_task_dialog(self).await.ok().flatten()
     }
 }
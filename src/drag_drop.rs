@@ -50,6 +50,7 @@ impl Data {
 
 bitflags! {
     #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct Effect: u32 {
         const NONE = DROPEFFECT_NONE.0;
         const COPY = DROPEFFECT_COPY.0;
@@ -135,7 +136,7 @@ impl IDropTarget_Impl for DropTarget_Impl {
                 modifier_keys: grfkeystate.into(),
                 data: data.clone(),
                 effect: (*pdweffect).into(),
-                tx: Some(tx),
+                tx: Some(tx.into()),
             };
             Context::send_event(self.handle, Event::DragEnter(ev));
             let effect = rx.blocking_recv().unwrap();
@@ -167,7 +168,7 @@ impl IDropTarget_Impl for DropTarget_Impl {
                 modifier_keys: grfkeystate.into(),
                 data: current.data.clone(),
                 effect: current.effect,
-                tx: Some(tx),
+                tx: Some(tx.into()),
             };
             Context::send_event(self.handle, Event::DragOver(ev));
             let effect = rx.blocking_recv().unwrap();
@@ -205,7 +206,7 @@ impl IDropTarget_Impl for DropTarget_Impl {
                 position: screen_to_client(&self.handle, (pt.x, pt.y).into()),
                 modifier_keys: grfkeystate.into(),
                 effect: current.effect,
-                tx: Some(tx),
+                tx: Some(tx.into()),
             };
             Context::send_event(self.handle, Event::Drop(ev));
             let effect = rx.blocking_recv().unwrap();
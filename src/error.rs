@@ -2,10 +2,13 @@
 #[derive(Debug, thiserror::Error)]
 #[non_exhaustive]
 pub enum Error {
+    #[cfg(windows)]
     #[error(transparent)]
     Api(#[from] windows::core::Error),
     #[error(transparent)]
     Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Image(#[from] crate::image::ImageError),
     #[error("UiThreadClosed")]
     UiThreadClosed,
 }
@@ -25,6 +28,7 @@ pub enum TryRecvError {
 /// This type is `Result<T, wiard::TryRecvError>`.
 pub type TryRecvResult<T> = ::core::result::Result<T, TryRecvError>;
 
+#[cfg_attr(not(windows), allow(unused_macros))]
 macro_rules! error {
     ($($arg:tt)*) => {
         #[cfg(feature = "tracing")]
@@ -34,6 +38,7 @@ macro_rules! error {
     };
 }
 
+#[cfg_attr(not(windows), allow(unused_macros))]
 macro_rules! warning {
     ($($arg:tt)+) => {
         #[cfg(feature = "tracing")]
@@ -52,6 +57,8 @@ macro_rules! info {
     };
 }
 
+#[cfg_attr(not(windows), allow(unused_imports))]
 pub(crate) use error;
 pub(crate) use info;
+#[cfg_attr(not(windows), allow(unused_imports))]
 pub(crate) use warning;
@@ -1,17 +1,85 @@
 use crate::*;
 use std::cell::Cell;
-use tokio::sync::oneshot;
+#[cfg(windows)]
 use std::sync::Arc;
-use windows::Win32::{Foundation::LPARAM, UI::WindowsAndMessaging::*};
+use tokio::sync::oneshot;
+#[cfg(windows)]
+use windows::Win32::UI::WindowsAndMessaging::*;
+
+#[cfg(not(windows))]
+mod hittest {
+    pub const HTERROR: i32 = -2;
+    pub const HTTRANSPARENT: i32 = -1;
+    pub const HTCLIENT: u32 = 1;
+    pub const HTCAPTION: u32 = 2;
+    pub const HTSYSMENU: u32 = 3;
+    pub const HTSIZE: u32 = 4;
+    pub const HTMENU: u32 = 5;
+    pub const HTHSCROLL: u32 = 6;
+    pub const HTVSCROLL: u32 = 7;
+    pub const HTMINBUTTON: u32 = 8;
+    pub const HTMAXBUTTON: u32 = 9;
+    pub const HTLEFT: u32 = 10;
+    pub const HTRIGHT: u32 = 11;
+    pub const HTTOP: u32 = 12;
+    pub const HTTOPLEFT: u32 = 13;
+    pub const HTTOPRIGHT: u32 = 14;
+    pub const HTBOTTOM: u32 = 15;
+    pub const HTBOTTOMLEFT: u32 = 16;
+    pub const HTBOTTOMRIGHT: u32 = 17;
+    pub const HTBORDER: u32 = 18;
+    pub const HTCLOSE: u32 = 20;
+    pub const HTHELP: u32 = 21;
+}
+
+#[cfg(not(windows))]
+use hittest::*;
+
+type ReplyObserver<T> = Box<dyn FnOnce(&T) + Send>;
+
+/// Sends a reply of an event which UiThread waits for.
+pub(crate) struct ReplySender<T> {
+    tx: oneshot::Sender<T>,
+    observer: Option<ReplyObserver<T>>,
+}
+
+impl<T> ReplySender<T> {
+    /// Calls `f` with the reply before sending it to UiThread.
+    pub fn observe(&mut self, f: impl FnOnce(&T) + Send + 'static) {
+        self.observer = Some(Box::new(f));
+    }
+
+    pub fn send(self, value: T) {
+        if let Some(f) = self.observer {
+            f(&value);
+        }
+        self.tx.send(value).ok();
+    }
+}
+
+impl<T> From<oneshot::Sender<T>> for ReplySender<T> {
+    #[inline]
+    fn from(tx: oneshot::Sender<T>) -> Self {
+        Self { tx, observer: None }
+    }
+}
+
+impl<T> std::fmt::Debug for ReplySender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReplySender").finish_non_exhaustive()
+    }
+}
 
 /// An event when a window request to draw.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Draw {
     pub invalidate_rect: PhysicalRect<i32>,
 }
 
 /// An event when window moved.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Moved {
     pub position: ScreenPosition<i32>,
 }
@@ -32,6 +100,7 @@ pub enum ResizingEdge {
 
 /// An event when resizing a window;
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Resizing {
     pub size: PhysicalSize<u32>,
     pub edge: ResizingEdge,
@@ -39,50 +108,105 @@ pub struct Resizing {
 
 /// An event when resized or restored a window from maximized.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Resized {
     pub size: PhysicalSize<u32>,
 }
 
 /// An event when a mouse button pressed or released.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MouseInput {
     pub button: MouseButton,
     pub button_state: ButtonState,
+    /// 1 for a single click, 2 for a double click and so on.
+    ///
+    /// A released button has the count of the press.
+    pub click_count: u32,
     pub mouse_state: MouseState,
 }
 
 /// An event when a mouse cursor moved.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CursorMoved {
     pub mouse_state: MouseState,
 }
 
+/// An event when a mouse cursor moved outside of the drag rectangle while a button is pressed.
+///
+/// The drag rectangle is `SM_CXDRAG` and `SM_CYDRAG` centered on `origin`.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DragStarted {
+    pub button: MouseButton,
+    /// The position where the button was pressed.
+    pub origin: PhysicalPosition<i32>,
+    pub mouse_state: MouseState,
+}
+
 /// An event when a mouse cursor entered a window.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CursorEntered {
     pub mouse_state: MouseState,
 }
 
 /// An event when a mouse cursor left a window.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CursorLeft {
     pub position: PhysicalPosition<i32>,
 }
 
 // An event when a mouse wheel is rotated.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MouseWheel {
     pub axis: MouseWheelAxis,
+    /// The raw distance in multiples or fractions of `WHEEL_DELTA`.
     pub distance: i32,
+    /// `distance` normalized with the system scroll settings.
+    pub delta: ScrollDelta,
+    /// Whole lines or pages which the accumulated distance of the window reached.
+    pub steps: i32,
+    /// The phase of a precise scroll. `None` for notched wheels.
+    pub phase: Option<ScrollPhase>,
     pub mouse_state: MouseState,
 }
 
+/// An event of a mouse, touch or pen pointer (`WM_POINTER*`).
+///
+/// This event is sent when the window enables `pointer_input`.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pointer {
+    pub id: PointerId,
+    pub pointer_type: PointerType,
+    pub phase: PointerPhase,
+    pub position: PhysicalPosition<i32>,
+    pub is_primary: bool,
+    pub in_contact: bool,
+    pub properties: PointerProperties,
+    pub modifiers: ModifierState,
+}
+
 /// An event when keyboard is input.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct KeyInput {
+    /// The physical key. `scan_code` has the `0xe0` prefix like `0xe01d` for extended keys.
+    #[cfg_attr(feature = "serde", serde(with = "crate::device::serde_key_code"))]
     pub key_code: KeyCode,
     pub key_state: KeyState,
     pub prev_pressed: bool,
+    pub modifiers: ModifierState,
+    /// The key in the current keyboard layout.
+    pub logical_key: LogicalKey,
+    /// The text which the key produces with the current modifier keys.
+    ///
+    /// This is `None` for dead keys and keys which produce control characters.
+    pub text: Option<String>,
 }
 
 impl KeyInput {
@@ -90,14 +214,100 @@ impl KeyInput {
     pub fn is(&self, key_code: impl Into<KeyCode>, key_state: KeyState) -> bool {
         self.key_code == key_code.into() && self.key_state == key_state
     }
+
+    /// Checks if the key is an extended key like right Ctrl, numpad Enter and arrow keys.
+    #[inline]
+    pub fn is_extended(&self) -> bool {
+        self.key_code.scan_code.0 & 0xff00 == 0xe000
+    }
+}
+
+/// An event when a key input matched an accelerator of the window.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Accelerator {
+    pub id: u32,
+    pub chord: Chord,
 }
 
 /// An event that receive a keyboard input as the charcter code.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CharInput {
     pub c: char,
 }
 
+/// An event that receive successive characters at once.
+///
+/// This event is sent instead of `CharInput` when the window enables `batch_text_input`.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TextInput {
+    pub text: String,
+}
+
+/// An event that receive a character of a dead key.
+///
+/// The character is combined with the next `CharInput`.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeadCharInput {
+    pub c: char,
+}
+
+/// An event that receive a character with the Alt key.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SysCharInput {
+    pub c: char,
+}
+
+/// An event when a mouse moved by raw input.
+///
+/// `x` and `y` are relative motion without the pointer acceleration unless `absolute` is `true`.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RawMouseMotion {
+    pub device: DeviceId,
+    pub x: i32,
+    pub y: i32,
+    pub absolute: bool,
+}
+
+/// An event when a key is input by raw input.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RawKeyInput {
+    pub device: DeviceId,
+    #[cfg_attr(feature = "serde", serde(with = "crate::device::serde_key_code"))]
+    pub key_code: KeyCode,
+    pub key_state: KeyState,
+}
+
+/// An event when a HID device sent reports by raw input.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RawHidInput {
+    pub device: DeviceId,
+    /// The size of each report.
+    pub size: u32,
+    pub data: Vec<u8>,
+}
+
+/// An event when a raw input device is connected.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceAdded {
+    pub device: DeviceId,
+}
+
+/// An event when a raw input device is disconnected.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceRemoved {
+    pub device: DeviceId,
+}
+
 /// An event of beginning IME composition.
 ///
 /// When this event is dropped, this event send an IME candidate window position to the window.
@@ -106,8 +316,8 @@ pub struct CharInput {
 #[derive(Debug)]
 pub struct ImeBeginComposition {
     position: Cell<PhysicalPosition<i32>>,
-    dpi: i32,
-    tx: Option<oneshot::Sender<PhysicalPosition<i32>>>,
+    pub(crate) dpi: i32,
+    pub(crate) tx: Option<ReplySender<PhysicalPosition<i32>>>,
 }
 
 impl ImeBeginComposition {
@@ -115,7 +325,7 @@ impl ImeBeginComposition {
         Self {
             position: Cell::new(PhysicalPosition::new(0, 0)),
             dpi,
-            tx: Some(tx),
+            tx: Some(tx.into()),
         }
     }
 
@@ -131,12 +341,13 @@ impl ImeBeginComposition {
 impl std::ops::Drop for ImeBeginComposition {
     #[inline]
     fn drop(&mut self) {
-        self.tx.take().unwrap().send(self.position.get()).ok();
+        self.tx.take().unwrap().send(self.position.get());
     }
 }
 
 /// An event when IME composition is updated.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ImeUpdateComposition {
     pub chars: Vec<char>,
     pub clauses: Vec<ime::Clause>,
@@ -145,12 +356,14 @@ pub struct ImeUpdateComposition {
 
 /// An event when IME composition is finished.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ImeEndComposition {
     pub result: Option<String>,
 }
 
 /// An event when IME candidate list is updated.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ImeUpdateCandidateList {
     pub selection: usize,
     pub items: Vec<String>,
@@ -158,24 +371,42 @@ pub struct ImeUpdateCandidateList {
 
 /// An event of maximized a window.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Maximized {
     pub size: PhysicalSize<u32>,
 }
 
 /// An event of restored a window from minimized.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Restored {
     pub size: PhysicalSize<u32>,
 }
 
+/// An event of gained or lost the keyboard focus.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Focused {
+    pub focused: bool,
+}
+
+/// An event of entered or left fullscreen.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FullscreenChanged {
+    pub fullscreen: bool,
+}
+
 /// An event of changed DPI.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DpiChanged {
     pub new_dpi: u32,
 }
 
 /// Values which can return from WM_NCHITTEST
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u32)]
 pub enum NcHitTestValue {
     Border = HTBORDER,
@@ -210,16 +441,18 @@ pub enum NcHitTestValue {
 pub struct NcHitTest {
     pub position: PhysicalPosition<i32>,
     value: Cell<Option<NcHitTestValue>>,
-    tx: Option<oneshot::Sender<Option<NcHitTestValue>>>,
+    pub(crate) tx: Option<ReplySender<Option<NcHitTestValue>>>,
 }
 
 impl NcHitTest {
-    pub(crate) fn new(lparam: LPARAM, tx: oneshot::Sender<Option<NcHitTestValue>>) -> Self {
-        let position = lparam_to_point(lparam);
+    pub(crate) fn new(
+        position: PhysicalPosition<i32>,
+        tx: oneshot::Sender<Option<NcHitTestValue>>,
+    ) -> Self {
         Self {
             position,
             value: Cell::new(None),
-            tx: Some(tx),
+            tx: Some(tx.into()),
         }
     }
 
@@ -236,17 +469,20 @@ impl NcHitTest {
 
 impl std::ops::Drop for NcHitTest {
     fn drop(&mut self) {
-        self.tx.take().unwrap().send(self.value.get()).ok();
+        self.tx.take().unwrap().send(self.value.get());
     }
 }
 
 /// An event of occurred at the notify icon.
+#[cfg(windows)]
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NotifyIcon {
     pub id: super::NotifyIcon,
     pub event: NotifyIconEvent,
 }
 
+#[cfg(windows)]
 impl PartialEq<super::NotifyIcon> for NotifyIcon {
     #[inline]
     fn eq(&self, other: &super::NotifyIcon) -> bool {
@@ -254,6 +490,7 @@ impl PartialEq<super::NotifyIcon> for NotifyIcon {
     }
 }
 
+#[cfg(windows)]
 impl PartialEq<NotifyIcon> for super::NotifyIcon {
     #[inline]
     fn eq(&self, other: &NotifyIcon) -> bool {
@@ -262,7 +499,9 @@ impl PartialEq<NotifyIcon> for super::NotifyIcon {
 }
 
 /// An event of pushed the menu item.
+#[cfg(windows)]
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MenuCommand {
     pub index: usize,
     pub handle: MenuHandle,
@@ -270,6 +509,7 @@ pub struct MenuCommand {
 
 /// An event that requests to show context menu.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ContextMenu {
     pub clicked_window: WindowHandle,
     pub position: ScreenPosition<i32>,
@@ -277,6 +517,7 @@ pub struct ContextMenu {
 
 /// An event of changed the color mode.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ColorModeChanged {
     pub current: ColorModeState,
     pub previous: ColorModeState,
@@ -286,19 +527,21 @@ pub struct ColorModeChanged {
 ///
 /// UiThread wait until this event is dropped.
 ///
+#[cfg(windows)]
 #[derive(Debug)]
 pub struct DragEnter {
     pub position: PhysicalPosition<i32>,
     pub modifier_keys: ModifierKey,
     pub data: Arc<drag_drop::Data>,
     pub effect: drag_drop::Effect,
-    pub(crate) tx: Option<oneshot::Sender<drag_drop::Effect>>,
+    pub(crate) tx: Option<ReplySender<drag_drop::Effect>>,
 }
 
+#[cfg(windows)]
 impl std::ops::Drop for DragEnter {
     fn drop(&mut self) {
         let tx = self.tx.take().unwrap();
-        tx.send(self.effect).ok();
+        tx.send(self.effect);
     }
 }
 
@@ -306,19 +549,21 @@ impl std::ops::Drop for DragEnter {
 ///
 /// UiThread wait until this event is dropped.
 ///
+#[cfg(windows)]
 #[derive(Debug)]
 pub struct DragOver {
     pub position: PhysicalPosition<i32>,
     pub modifier_keys: ModifierKey,
     pub data: Arc<drag_drop::Data>,
     pub effect: drag_drop::Effect,
-    pub(crate) tx: Option<oneshot::Sender<drag_drop::Effect>>,
+    pub(crate) tx: Option<ReplySender<drag_drop::Effect>>,
 }
 
+#[cfg(windows)]
 impl std::ops::Drop for DragOver {
     fn drop(&mut self) {
         let tx = self.tx.take().unwrap();
-        tx.send(self.effect).ok();
+        tx.send(self.effect);
     }
 }
 
@@ -326,19 +571,21 @@ impl std::ops::Drop for DragOver {
 ///
 /// UiThread wait until this event is dropped.
 ///
+#[cfg(windows)]
 #[derive(Debug)]
 pub struct Drop {
     pub position: PhysicalPosition<i32>,
     pub modifier_keys: ModifierKey,
     pub data: Arc<drag_drop::Data>,
     pub effect: drag_drop::Effect,
-    pub(crate) tx: Option<oneshot::Sender<drag_drop::Effect>>,
+    pub(crate) tx: Option<ReplySender<drag_drop::Effect>>,
 }
 
+#[cfg(windows)]
 impl std::ops::Drop for Drop {
     fn drop(&mut self) {
         let tx = self.tx.take().unwrap();
-        tx.send(self.effect).ok();
+        tx.send(self.effect);
     }
 }
 
@@ -358,17 +605,28 @@ impl CloseRequest {
         Self { handle }
     }
 
+    /// Destroys the window. Does nothing for a replayed event.
     #[inline]
     pub fn destroy(&self) {
         let handle = self.handle;
-        UiThread::send_task(move || unsafe {
-            DestroyWindow(handle.as_hwnd()).ok();
-        });
+        if handle.is_replayed() {
+            return;
+        }
+        backend!(
+            UiThread::send_task(move || unsafe {
+                procedure::enable_modal_owner(handle.as_hwnd());
+                DestroyWindow(handle.as_hwnd()).ok();
+            }),
+            UiThread::send_task(move || {
+                headless::procedure::on_destroy(handle);
+            })
+        );
     }
 }
 
 /// An event which defined by an user.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct App {
     pub index: u32,
     pub value0: usize,
@@ -388,6 +646,7 @@ impl App {
 
 /// Other window messages
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Other {
     pub msg: u32,
     pub wparam: usize,
@@ -402,6 +661,8 @@ pub enum Event {
     Activated,
     /// An event when a window was inactive.
     Inactivated,
+    /// An event when a window gained or lost the keyboard focus.
+    Focused(Focused),
     /// An event when a window request to draw.
     Draw(Draw),
     /// An event when a window moved.
@@ -416,16 +677,44 @@ pub enum Event {
     MouseInput(MouseInput),
     /// An event when a mouse cursor moved.
     CursorMoved(CursorMoved),
+    /// An event when a mouse cursor started dragging with a button.
+    DragStarted(DragStarted),
+    /// An event when the window lost the mouse capture which it did not release.
+    CaptureLost,
     /// An event when a mouse cursor entered a window.
     CursorEntered(CursorEntered),
     /// An event when a mouse cursor left a window.
     CursorLeft(CursorLeft),
     /// An event when a mouse wheel is rotated.
     MouseWheel(MouseWheel),
+    /// An event of a mouse, touch or pen pointer.
+    Pointer(Pointer),
+    /// An event when a gesture is recognized.
+    Gesture(gesture::Gesture),
     /// An event when inputed using a keyboard.
     KeyInput(KeyInput),
+    /// An event when a key input matched an accelerator.
+    ///
+    /// The matched key input is not received as `KeyInput`.
+    Accelerator(Accelerator),
     /// An event that received a keyboard input as the charactor.
     CharInput(CharInput),
+    /// An event that received successive characters like pasting.
+    TextInput(TextInput),
+    /// An event when a dead key is input (`WM_DEADCHAR` and `WM_SYSDEADCHAR`).
+    DeadCharInput(DeadCharInput),
+    /// An event when a character is input with the Alt key (`WM_SYSCHAR`).
+    SysCharInput(SysCharInput),
+    /// An event when a mouse moved by raw input (`WM_INPUT`).
+    RawMouseMotion(RawMouseMotion),
+    /// An event when a key is input by raw input (`WM_INPUT`).
+    RawKeyInput(RawKeyInput),
+    /// An event when a HID device sent reports by raw input (`WM_INPUT`).
+    RawHidInput(RawHidInput),
+    /// An event when a raw input device is connected.
+    DeviceAdded(DeviceAdded),
+    /// An event when a raw input device is disconnected.
+    DeviceRemoved(DeviceRemoved),
     /// An event when an IME composition begin.
     ///
     /// **UiThread wait until this event value is dropped.**
@@ -441,6 +730,7 @@ pub enum Event {
     /// An event when an IME candidate list closed.
     ImeEndCandidateList,
     /// An event when pushed a menu item.
+    #[cfg(windows)]
     MenuCommand(MenuCommand),
     /// An event that requests to show a context menu.
     ContextMenu(ContextMenu),
@@ -450,29 +740,38 @@ pub enum Event {
     Maximized(Maximized),
     /// An event when a window restored from minimized.
     Restored(Restored),
+    /// An event when a window entered or left fullscreen.
+    FullscreenChanged(FullscreenChanged),
     /// An event when a display scaling changed.
     DpiChanged(DpiChanged),
+    /// An event when monitors were connected, disconnected or reconfigured.
+    MonitorsChanged,
     /// An event of non client area hit test.
     ///
     /// **UiThread wait until this event value is dropped.**
     NcHitTest(NcHitTest),
     /// An event when a notify icon occurred.
+    #[cfg(windows)]
     NotifyIcon(NotifyIcon),
     /// An event when a color mode changed.
     ColorModeChanged(ColorModeChanged),
     /// An event when a dragging item was entered on the window.
     ///
     /// **UiThread wait until this event value is dropped.**
+    #[cfg(windows)]
     DragEnter(DragEnter),
     /// An event when a dragging item was moved on the window.
     ///
     /// **UiThread wait until this event value is dropped.**
+    #[cfg(windows)]
     DragOver(DragOver),
     /// An event when a dragging item was left on the window.
+    #[cfg(windows)]
     DragLeave,
     /// An event when a item was dropped on the window.
     ///
     /// **UiThread wait until this event value is dropped.**
+    #[cfg(windows)]
     Drop(Drop),
     /// An event of requested to close the window.
     ///
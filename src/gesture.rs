@@ -0,0 +1,843 @@
+//! Gestures recognized from touch inputs.
+//!
+//! A window which enables `WindowBuilder::gestures` receives `Event::Gesture`. With
+//! `pointer_input`, touch pointers are recognized by [`GestureRecognizer`]. Otherwise,
+//! `WM_GESTURE` messages are converted by [`GestureInfoDecoder`].
+//!
+//! ```
+//! use std::time::Duration;
+//! use wiard::gesture::*;
+//! use wiard::{PhysicalPosition, PointerId, PointerPhase};
+//!
+//! let mut recognizer = GestureRecognizer::new();
+//! let sample = |phase, time| GestureSample {
+//!     id: PointerId::new(1),
+//!     phase,
+//!     position: PhysicalPosition::new(10.0, 10.0),
+//!     time: Duration::from_millis(time),
+//! };
+//! assert!(recognizer.process(sample(PointerPhase::Down, 0)).is_empty());
+//! let gestures = recognizer.process(sample(PointerPhase::Up, 100));
+//! assert!(matches!(gestures[0], Gesture::Tap(Tap { fingers: 1, .. })));
+//! ```
+
+use crate::*;
+use std::time::Duration;
+
+/// A 2D vector in physical pixels.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Vector {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Vector {
+    #[inline]
+    pub const fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    #[inline]
+    pub fn length(&self) -> f32 {
+        self.x.hypot(self.y)
+    }
+}
+
+impl std::ops::Add for Vector {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl std::ops::Sub for Vector {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl std::ops::Mul<f32> for Vector {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: f32) -> Self {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GesturePhase {
+    Begin,
+    Update,
+    /// Updates after fingers are lifted.
+    Inertia,
+    End,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pan {
+    pub phase: GesturePhase,
+    pub position: PhysicalPosition<f32>,
+    /// The total translation from `Begin`.
+    pub translation: Vector,
+    pub delta: Vector,
+    /// Pixels per second.
+    pub velocity: Vector,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pinch {
+    pub phase: GesturePhase,
+    pub center: PhysicalPosition<f32>,
+    /// The total scale from `Begin`.
+    pub scale: f32,
+    /// The scale from the previous event.
+    pub delta: f32,
+    /// Scale per second.
+    pub velocity: f32,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rotate {
+    pub phase: GesturePhase,
+    pub center: PhysicalPosition<f32>,
+    /// The total clockwise angle in radians from `Begin`.
+    pub angle: f32,
+    pub delta: f32,
+    /// Radians per second.
+    pub velocity: f32,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tap {
+    pub position: PhysicalPosition<f32>,
+    /// The number of fingers like `2` for a two-finger tap.
+    pub fingers: u32,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LongPress {
+    pub position: PhysicalPosition<f32>,
+}
+
+/// A recognized gesture.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Gesture {
+    Pan(Pan),
+    Pinch(Pinch),
+    Rotate(Rotate),
+    Tap(Tap),
+    LongPress(LongPress),
+}
+
+/// A timestamped touch pointer.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct GestureSample {
+    pub id: PointerId,
+    pub phase: PointerPhase,
+    pub position: PhysicalPosition<f32>,
+    /// The elapsed time from any origin which is shared by all samples.
+    pub time: Duration,
+}
+
+impl GestureSample {
+    #[inline]
+    pub fn from_pointer(ev: &event::Pointer, time: Duration) -> Self {
+        Self {
+            id: ev.id,
+            phase: ev.phase,
+            position: PhysicalPosition::new(ev.position.x as f32, ev.position.y as f32),
+            time,
+        }
+    }
+}
+
+/// Thresholds of `GestureRecognizer`.
+#[derive(Clone, Debug)]
+pub struct GestureConfig {
+    /// A distance in pixels which fingers can move without starting pan.
+    pub slop: f32,
+    /// Fingers must be lifted within this duration for `Tap`.
+    pub tap_timeout: Duration,
+    pub long_press: Duration,
+    /// An angle in radians which starts `Rotate`.
+    pub rotate_threshold: f32,
+    /// Pan continues with `Inertia` when the velocity in pixels per second is over this value.
+    pub min_inertia_velocity: f32,
+    /// The rate of decay of the inertia velocity per second.
+    pub deceleration: f32,
+}
+
+impl Default for GestureConfig {
+    fn default() -> Self {
+        Self {
+            slop: 10.0,
+            tap_timeout: Duration::from_millis(300),
+            long_press: Duration::from_millis(500),
+            rotate_threshold: 0.1,
+            min_inertia_velocity: 100.0,
+            deceleration: 4.0,
+        }
+    }
+}
+
+/// Fingers which rest longer than this duration before lifted do not start inertia.
+const INERTIA_TIMEOUT: Duration = Duration::from_millis(100);
+
+#[derive(Clone, Copy, Debug)]
+struct Contact {
+    id: PointerId,
+    start: Vector,
+    position: Vector,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct TwoFingers {
+    distance: f32,
+    angle: f32,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Active {
+    total: f32,
+    base: f32,
+    last: f32,
+    velocity: f32,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct PanState {
+    translation: Vector,
+    velocity: Vector,
+    position: Vector,
+    time: Duration,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Candidate {
+    start: Duration,
+    fingers: u32,
+    position: Vector,
+}
+
+/// Recognizes gestures from touch pointers.
+///
+/// Fingers which move further than `slop` start `Pan`, and two fingers also start `Pinch` and
+/// `Rotate`. `Tap` is recognized when all fingers are lifted within `tap_timeout` without
+/// moving, and `LongPress` when a finger stays `long_press` without moving.
+///
+/// `LongPress` and `Pan` inertia are recognized by [`poll`](Self::poll), so call it periodically
+/// while [`needs_poll`](Self::needs_poll) returns `true`.
+#[derive(Clone, Debug, Default)]
+pub struct GestureRecognizer {
+    config: GestureConfig,
+    contacts: Vec<Contact>,
+    last_time: Duration,
+    moved: bool,
+    candidate: Option<Candidate>,
+    long_press: bool,
+    anchor: Option<(Vector, Option<TwoFingers>)>,
+    pan: Option<PanState>,
+    pinch: Option<Active>,
+    rotate: Option<Active>,
+    inertia: Option<PanState>,
+}
+
+fn to_vector(position: PhysicalPosition<f32>) -> Vector {
+    Vector::new(position.x, position.y)
+}
+
+fn to_position(v: Vector) -> PhysicalPosition<f32> {
+    PhysicalPosition::new(v.x, v.y)
+}
+
+fn normalize_angle(mut angle: f32) -> f32 {
+    use std::f32::consts::PI;
+
+    while angle > PI {
+        angle -= PI * 2.0;
+    }
+    while angle < -PI {
+        angle += PI * 2.0;
+    }
+    angle
+}
+
+impl GestureRecognizer {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn with_config(config: GestureConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    #[inline]
+    pub fn config(&self) -> &GestureConfig {
+        &self.config
+    }
+
+    /// Checks if `poll` may recognize gestures.
+    #[inline]
+    pub fn needs_poll(&self) -> bool {
+        self.inertia.is_some()
+            || (!self.long_press && self.candidate.is_some_and(|c| c.fingers == 1))
+    }
+
+    /// Cancels all gestures without `End`.
+    pub fn reset(&mut self) {
+        *self = Self::with_config(self.config.clone());
+    }
+
+    fn centroid(&self) -> Vector {
+        let n = self.contacts.len().max(1) as f32;
+        self.contacts
+            .iter()
+            .fold(Vector::default(), |sum, c| sum + c.position)
+            * (1.0 / n)
+    }
+
+    fn two_fingers(&self) -> Option<TwoFingers> {
+        let [a, b, ..] = self.contacts.as_slice() else {
+            return None;
+        };
+        let d = b.position - a.position;
+        Some(TwoFingers {
+            distance: d.length(),
+            angle: d.y.atan2(d.x),
+        })
+    }
+
+    fn rebase(&mut self) {
+        self.anchor = Some((self.centroid(), self.two_fingers()));
+        for active in [&mut self.pinch, &mut self.rotate].into_iter().flatten() {
+            active.base = active.total;
+        }
+    }
+
+    fn dt(&self, time: Duration) -> f32 {
+        time.saturating_sub(self.last_time).as_secs_f32()
+    }
+
+    fn end_two_fingers(&mut self, gestures: &mut Vec<Gesture>) {
+        let center = to_position(self.centroid());
+        if let Some(pinch) = self.pinch.take() {
+            gestures.push(Gesture::Pinch(Pinch {
+                phase: GesturePhase::End,
+                center,
+                scale: pinch.total,
+                delta: 1.0,
+                velocity: pinch.velocity,
+            }));
+        }
+        if let Some(rotate) = self.rotate.take() {
+            gestures.push(Gesture::Rotate(Rotate {
+                phase: GesturePhase::End,
+                center,
+                angle: rotate.total,
+                delta: 0.0,
+                velocity: rotate.velocity,
+            }));
+        }
+    }
+
+    fn pan_event(phase: GesturePhase, pan: &PanState, delta: Vector) -> Gesture {
+        Gesture::Pan(Pan {
+            phase,
+            position: to_position(pan.position),
+            translation: pan.translation,
+            delta,
+            velocity: pan.velocity,
+        })
+    }
+
+    fn on_move(&mut self, time: Duration, gestures: &mut Vec<Gesture>) {
+        let dt = self.dt(time);
+        let slop = self.config.slop;
+        if !self.moved
+            && self
+                .contacts
+                .iter()
+                .any(|c| (c.position - c.start).length() > slop)
+        {
+            self.moved = true;
+            self.candidate = None;
+        }
+        if !self.moved {
+            return;
+        }
+        let Some((anchor, base)) = self.anchor else {
+            return;
+        };
+        let centroid = self.centroid();
+        let delta = centroid - anchor;
+        self.anchor = Some((centroid, base));
+        let velocity = if dt > 0.0 {
+            delta * (1.0 / dt)
+        } else {
+            Vector::default()
+        };
+        match &mut self.pan {
+            Some(pan) => {
+                pan.translation = pan.translation + delta;
+                pan.position = centroid;
+                pan.time = time;
+                if dt > 0.0 {
+                    pan.velocity = velocity;
+                }
+                let pan = *pan;
+                gestures.push(Self::pan_event(GesturePhase::Update, &pan, delta));
+            }
+            None => {
+                let pan = PanState {
+                    translation: delta,
+                    velocity,
+                    position: centroid,
+                    time,
+                };
+                self.pan = Some(pan);
+                gestures.push(Self::pan_event(GesturePhase::Begin, &pan, delta));
+            }
+        }
+        let (Some(base), Some(current)) = (base, self.two_fingers()) else {
+            return;
+        };
+        let center = to_position(centroid);
+        if base.distance > 0.0 {
+            let scale = current.distance / base.distance;
+            let started = self.pinch.is_some();
+            let pinch = self.pinch.get_or_insert(Active {
+                total: 1.0,
+                base: 1.0,
+                last: 1.0,
+                velocity: 0.0,
+            });
+            if started || (current.distance - base.distance).abs() > slop {
+                pinch.total = pinch.base * scale;
+                let delta = pinch.total / pinch.last;
+                if dt > 0.0 {
+                    pinch.velocity = (pinch.total - pinch.last) / dt;
+                }
+                pinch.last = pinch.total;
+                gestures.push(Gesture::Pinch(Pinch {
+                    phase: if started {
+                        GesturePhase::Update
+                    } else {
+                        GesturePhase::Begin
+                    },
+                    center,
+                    scale: pinch.total,
+                    delta,
+                    velocity: pinch.velocity,
+                }));
+            } else {
+                self.pinch = None;
+            }
+        }
+        let angle = normalize_angle(current.angle - base.angle);
+        let started = self.rotate.is_some();
+        let rotate = self.rotate.get_or_insert(Active {
+            total: 0.0,
+            base: 0.0,
+            last: 0.0,
+            velocity: 0.0,
+        });
+        if started || angle.abs() > self.config.rotate_threshold {
+            rotate.total = rotate.base + angle;
+            let delta = rotate.total - rotate.last;
+            if dt > 0.0 {
+                rotate.velocity = delta / dt;
+            }
+            rotate.last = rotate.total;
+            gestures.push(Gesture::Rotate(Rotate {
+                phase: if started {
+                    GesturePhase::Update
+                } else {
+                    GesturePhase::Begin
+                },
+                center,
+                angle: rotate.total,
+                delta,
+                velocity: rotate.velocity,
+            }));
+        } else {
+            self.rotate = None;
+        }
+    }
+
+    fn on_up(&mut self, time: Duration, canceled: bool, gestures: &mut Vec<Gesture>) {
+        if canceled {
+            self.candidate = None;
+        }
+        if !self.contacts.is_empty() {
+            self.rebase();
+            return;
+        }
+        self.end_two_fingers(gestures);
+        if let Some(candidate) = self.candidate.take()
+            && !self.long_press
+            && time.saturating_sub(candidate.start) <= self.config.tap_timeout
+        {
+            gestures.push(Gesture::Tap(Tap {
+                position: to_position(candidate.position),
+                fingers: candidate.fingers,
+            }));
+        }
+        if let Some(pan) = self.pan.take() {
+            let resting = time.saturating_sub(pan.time) > INERTIA_TIMEOUT;
+            if !canceled && !resting && pan.velocity.length() > self.config.min_inertia_velocity {
+                self.inertia = Some(pan);
+            } else {
+                gestures.push(Self::pan_event(GesturePhase::End, &pan, Vector::default()));
+            }
+        }
+        self.moved = false;
+        self.long_press = false;
+        self.anchor = None;
+    }
+
+    /// Processes a sample, and returns recognized gestures.
+    pub fn process(&mut self, sample: GestureSample) -> Vec<Gesture> {
+        let mut gestures = self.poll(sample.time);
+        let position = to_vector(sample.position);
+        match sample.phase {
+            PointerPhase::Down => {
+                if let Some(pan) = self.inertia.take() {
+                    gestures.push(Self::pan_event(GesturePhase::End, &pan, Vector::default()));
+                }
+                self.contacts.retain(|c| c.id != sample.id);
+                self.contacts.push(Contact {
+                    id: sample.id,
+                    start: position,
+                    position,
+                });
+                let fingers = self.contacts.len() as u32;
+                if fingers == 1 {
+                    self.candidate = Some(Candidate {
+                        start: sample.time,
+                        fingers,
+                        position,
+                    });
+                } else {
+                    let centroid = self.centroid();
+                    if let Some(candidate) = &mut self.candidate {
+                        candidate.fingers = candidate.fingers.max(fingers);
+                        candidate.position = centroid;
+                    }
+                }
+                self.rebase();
+            }
+            PointerPhase::Move => {
+                if let Some(c) = self.contacts.iter_mut().find(|c| c.id == sample.id) {
+                    c.position = position;
+                    self.on_move(sample.time, &mut gestures);
+                }
+            }
+            PointerPhase::Up | PointerPhase::Cancel => {
+                let before = self.contacts.len();
+                self.contacts.retain(|c| c.id != sample.id);
+                if self.contacts.len() < before {
+                    if before >= 2 && self.contacts.len() < 2 {
+                        self.end_two_fingers(&mut gestures);
+                    }
+                    let canceled = sample.phase == PointerPhase::Cancel;
+                    self.on_up(sample.time, canceled, &mut gestures);
+                }
+            }
+        }
+        self.last_time = sample.time;
+        gestures
+    }
+
+    /// Recognizes time-based gestures: `LongPress` and `Pan` with `Inertia`.
+    pub fn poll(&mut self, time: Duration) -> Vec<Gesture> {
+        let mut gestures = vec![];
+        if let Some(candidate) = self.candidate
+            && !self.long_press
+            && !self.moved
+            && candidate.fingers == 1
+            && self.contacts.len() == 1
+            && time.saturating_sub(candidate.start) >= self.config.long_press
+        {
+            self.long_press = true;
+            gestures.push(Gesture::LongPress(LongPress {
+                position: to_position(candidate.position),
+            }));
+        }
+        if let Some(mut pan) = self.inertia {
+            let dt = self.dt(time);
+            if dt > 0.0 {
+                pan.velocity = pan.velocity * (-self.config.deceleration * dt).exp();
+                let delta = pan.velocity * dt;
+                pan.translation = pan.translation + delta;
+                pan.position = pan.position + delta;
+                if pan.velocity.length() > self.config.min_inertia_velocity {
+                    gestures.push(Self::pan_event(GesturePhase::Inertia, &pan, delta));
+                    self.inertia = Some(pan);
+                } else {
+                    gestures.push(Self::pan_event(GesturePhase::End, &pan, delta));
+                    self.inertia = None;
+                }
+                self.last_time = time;
+            }
+        }
+        gestures
+    }
+}
+
+const GID_ZOOM: u32 = 3;
+const GID_PAN: u32 = 4;
+const GID_ROTATE: u32 = 5;
+const GID_TWOFINGERTAP: u32 = 6;
+const GF_BEGIN: u32 = 0x01;
+const GF_INERTIA: u32 = 0x02;
+const GF_END: u32 = 0x04;
+
+/// Converts `GESTUREINFO` of `WM_GESTURE` to gestures.
+#[derive(Clone, Debug, Default)]
+pub struct GestureInfoDecoder {
+    zoom: Option<(u64, f32)>,
+    pan: Option<(PhysicalPosition<i32>, Vector, Duration)>,
+    rotate: Option<(f32, f32)>,
+}
+
+impl GestureInfoDecoder {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decodes `dwID`, `dwFlags`, `ptsLocation` in client coordinates and `ullArguments`.
+    pub fn decode(
+        &mut self,
+        id: u32,
+        flags: u32,
+        position: PhysicalPosition<i32>,
+        arguments: u64,
+        time: Duration,
+    ) -> Option<Gesture> {
+        let phase = if flags & GF_BEGIN != 0 {
+            GesturePhase::Begin
+        } else if flags & GF_END != 0 {
+            GesturePhase::End
+        } else if flags & GF_INERTIA != 0 {
+            GesturePhase::Inertia
+        } else {
+            GesturePhase::Update
+        };
+        let center = PhysicalPosition::new(position.x as f32, position.y as f32);
+        match id {
+            GID_ZOOM => {
+                let (first, last) = match (phase, self.zoom) {
+                    (GesturePhase::Begin, _) | (_, None) => (arguments, 1.0),
+                    (_, Some(zoom)) => zoom,
+                };
+                let scale = if first == 0 {
+                    1.0
+                } else {
+                    arguments as f32 / first as f32
+                };
+                self.zoom = (phase != GesturePhase::End).then_some((first, scale));
+                Some(Gesture::Pinch(Pinch {
+                    phase,
+                    center,
+                    scale,
+                    delta: if last == 0.0 { 1.0 } else { scale / last },
+                    velocity: 0.0,
+                }))
+            }
+            GID_PAN => {
+                let (start, translation, last_time) = match (phase, self.pan) {
+                    (GesturePhase::Begin, _) | (_, None) => (position, Vector::default(), time),
+                    (_, Some(pan)) => pan,
+                };
+                let total =
+                    Vector::new((position.x - start.x) as f32, (position.y - start.y) as f32);
+                let delta = total - translation;
+                let dt = time.saturating_sub(last_time).as_secs_f32();
+                self.pan = (phase != GesturePhase::End).then_some((start, total, time));
+                Some(Gesture::Pan(Pan {
+                    phase,
+                    position: center,
+                    translation: total,
+                    delta,
+                    velocity: if dt > 0.0 {
+                        delta * (1.0 / dt)
+                    } else {
+                        Vector::default()
+                    },
+                }))
+            }
+            GID_ROTATE => {
+                // GID_ROTATE_ANGLE_FROM_ARGUMENT
+                let angle = (arguments & 0xffff) as f32 / 65535.0 * 4.0 * std::f32::consts::PI
+                    - 2.0 * std::f32::consts::PI;
+                let (first, last) = match (phase, self.rotate) {
+                    (GesturePhase::Begin, _) | (_, None) => (angle, 0.0),
+                    (_, Some(rotate)) => rotate,
+                };
+                // WM_GESTURE reports counterclockwise angles.
+                let total = -(angle - first);
+                self.rotate = (phase != GesturePhase::End).then_some((first, total));
+                Some(Gesture::Rotate(Rotate {
+                    phase,
+                    center,
+                    angle: total,
+                    delta: total - last,
+                    velocity: 0.0,
+                }))
+            }
+            GID_TWOFINGERTAP => Some(Gesture::Tap(Tap {
+                position: center,
+                fingers: 2,
+            })),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(id: u32, phase: PointerPhase, x: f32, y: f32, ms: u64) -> GestureSample {
+        GestureSample {
+            id: PointerId::new(id),
+            phase,
+            position: PhysicalPosition::new(x, y),
+            time: Duration::from_millis(ms),
+        }
+    }
+
+    fn names(gestures: &[Gesture]) -> Vec<String> {
+        gestures
+            .iter()
+            .map(|g| match g {
+                Gesture::Pan(g) => format!("Pan:{:?}", g.phase),
+                Gesture::Pinch(g) => format!("Pinch:{:?}", g.phase),
+                Gesture::Rotate(g) => format!("Rotate:{:?}", g.phase),
+                Gesture::Tap(g) => format!("Tap:{}", g.fingers),
+                Gesture::LongPress(_) => "LongPress".to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn two_finger_tap_and_long_press() {
+        let mut r = GestureRecognizer::new();
+        r.process(sample(1, PointerPhase::Down, 0.0, 0.0, 0));
+        r.process(sample(2, PointerPhase::Down, 50.0, 0.0, 20));
+        r.process(sample(1, PointerPhase::Move, 2.0, 0.0, 40));
+        assert!(
+            r.process(sample(1, PointerPhase::Up, 2.0, 0.0, 100))
+                .is_empty()
+        );
+        let g = r.process(sample(2, PointerPhase::Up, 50.0, 0.0, 120));
+        assert_eq!(names(&g), ["Tap:2"]);
+        r.process(sample(3, PointerPhase::Down, 0.0, 0.0, 1000));
+        assert!(r.needs_poll());
+        assert!(r.poll(Duration::from_millis(1400)).is_empty());
+        assert_eq!(names(&r.poll(Duration::from_millis(1500))), ["LongPress"]);
+        assert!(!r.needs_poll());
+        assert!(
+            r.process(sample(3, PointerPhase::Up, 0.0, 0.0, 1600))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn pinch_and_rotate() {
+        let mut r = GestureRecognizer::new();
+        r.process(sample(1, PointerPhase::Down, 0.0, 0.0, 0));
+        r.process(sample(2, PointerPhase::Down, 100.0, 0.0, 0));
+        let g = r.process(sample(2, PointerPhase::Move, 200.0, 0.0, 100));
+        assert_eq!(names(&g), ["Pan:Begin", "Pinch:Begin"]);
+        let Gesture::Pinch(pinch) = &g[1] else {
+            unreachable!()
+        };
+        assert!((pinch.scale - 2.0).abs() < 1e-4);
+        let g = r.process(sample(2, PointerPhase::Move, 0.0, 200.0, 200));
+        assert_eq!(names(&g), ["Pan:Update", "Pinch:Update", "Rotate:Begin"]);
+        let Gesture::Rotate(rotate) = &g[2] else {
+            unreachable!()
+        };
+        assert!((rotate.angle - std::f32::consts::FRAC_PI_2).abs() < 1e-4);
+        let g = r.process(sample(1, PointerPhase::Cancel, 0.0, 0.0, 300));
+        assert_eq!(names(&g), ["Pinch:End", "Rotate:End"]);
+        let g = r.process(sample(2, PointerPhase::Up, 0.0, 200.0, 5000));
+        assert_eq!(names(&g), ["Pan:End"]);
+    }
+
+    #[test]
+    fn pan_inertia() {
+        let mut r = GestureRecognizer::new();
+        r.process(sample(1, PointerPhase::Down, 0.0, 0.0, 0));
+        let g = r.process(sample(1, PointerPhase::Move, 20.0, 0.0, 10));
+        assert_eq!(names(&g), ["Pan:Begin"]);
+        let g = r.process(sample(1, PointerPhase::Move, 40.0, 0.0, 20));
+        let Gesture::Pan(pan) = &g[0] else {
+            unreachable!()
+        };
+        assert!((pan.velocity.x - 2000.0).abs() < 1.0);
+        assert!(
+            r.process(sample(1, PointerPhase::Up, 40.0, 0.0, 20))
+                .is_empty()
+        );
+        assert!(r.needs_poll());
+        let mut time = 20;
+        let mut phases = vec![];
+        while r.needs_poll() {
+            time += 16;
+            phases.extend(names(&r.poll(Duration::from_millis(time))));
+        }
+        assert!(phases.iter().all(|p| p == "Pan:Inertia" || p == "Pan:End"));
+        assert_eq!(phases.last().unwrap(), "Pan:End");
+        assert!(phases.len() > 10);
+    }
+
+    #[test]
+    fn gesture_info() {
+        let mut d = GestureInfoDecoder::new();
+        let pos = PhysicalPosition::new(10, 10);
+        let ms = Duration::from_millis;
+        let g = d.decode(GID_ZOOM, GF_BEGIN, pos, 100, ms(0)).unwrap();
+        assert_eq!(names(std::slice::from_ref(&g)), ["Pinch:Begin"]);
+        let Some(Gesture::Pinch(pinch)) = d.decode(GID_ZOOM, 0, pos, 150, ms(10)) else {
+            unreachable!()
+        };
+        assert!((pinch.scale - 1.5).abs() < 1e-4);
+        d.decode(GID_PAN, GF_BEGIN, pos, 0, ms(0));
+        let Some(Gesture::Pan(pan)) = d.decode(
+            GID_PAN,
+            GF_INERTIA,
+            PhysicalPosition::new(30, 10),
+            0,
+            ms(100),
+        ) else {
+            unreachable!()
+        };
+        assert_eq!(pan.phase, GesturePhase::Inertia);
+        assert_eq!(pan.translation, Vector::new(20.0, 0.0));
+        assert!((pan.velocity.x - 200.0).abs() < 1e-2);
+        let g = d.decode(GID_TWOFINGERTAP, 0, pos, 0, ms(0));
+        assert!(matches!(g, Some(Gesture::Tap(Tap { fingers: 2, .. }))));
+    }
+}
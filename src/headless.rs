@@ -0,0 +1,104 @@
+//! A simulated backend for running window and event handling code without Win32.
+//!
+//! On the simulated backend, [`Window`] and the other window types create virtual windows
+//! instead of Win32 windows. A virtual window tracks its position, size, DPI, visibility and
+//! color mode, and [`Driver`] injects synthetic input which is delivered through the same
+//! [`Event`] enum, `EventReceiver` and `AsyncEventReceiver` as the Win32 backend.
+//!
+//! On Windows, the simulated backend is used after [`enable`] is called. On other platforms, it
+//! is always used.
+//!
+//! On Windows, menus, notify icons, cursors, icons, styles and drag and drop are ignored by the
+//! simulated backend. On other platforms, they are not available: the `menu`, `notify_icon`,
+//! `style`, `drag_drop` and `utility` modules, the cursor, icon and dialog types, and the
+//! methods which use them such as `WindowBuilder::style`, `WindowBuilder::icon`,
+//! `WindowBuilder::cursor`, `WindowBuilder::menu`, `WindowBuilder::drop_target`,
+//! `Window::set_cursor`, `Window::set_icon`, `Window::set_style` and `Window::set_menu` exist
+//! only on Windows. Code which uses them needs `#[cfg(windows)]`.
+//!
+//! ```no_run
+//! wiard::headless::enable();
+//! let mut event_rx = wiard::EventReceiver::new();
+//! let window = wiard::Window::builder(&event_rx)
+//!     .visible(false)
+//!     .build()
+//!     .unwrap();
+//! let driver = wiard::headless::Driver::new(&window);
+//! driver.char_input('a');
+//! driver.close_request();
+//! loop {
+//!     let Some((event, _)) = event_rx.recv() else {
+//!         break;
+//!     };
+//!     println!("{event:?}");
+//! }
+//! ```
+//!
+//! [`Window`]: crate::Window
+//! [`Event`]: crate::Event
+
+pub(crate) mod context;
+mod driver;
+pub(crate) mod procedure;
+pub(crate) mod window;
+
+pub use driver::*;
+
+use crate::UiThread;
+use std::sync::OnceLock;
+use std::sync::atomic::{self, AtomicBool};
+
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+static ENABLED: OnceLock<bool> = OnceLock::new();
+
+/// Uses the simulated backend instead of Win32.
+///
+/// This must be called before any window or `UiThread` is created. On platforms other than
+/// Windows, the simulated backend is always used and this function does nothing.
+///
+/// # Panics
+///
+/// Panics if the Win32 backend has already been selected.
+///
+#[inline]
+pub fn enable() {
+    REQUESTED.store(true, atomic::Ordering::SeqCst);
+    assert!(is_enabled(), "the Win32 backend has already been selected");
+}
+
+/// Checks if the simulated backend is used.
+///
+/// The backend is selected when this function is called first, and does not change after that.
+#[inline]
+pub fn is_enabled() -> bool {
+    *ENABLED.get_or_init(|| !cfg!(windows) || REQUESTED.load(atomic::Ordering::SeqCst))
+}
+
+/// Changes the simulated system color mode.
+///
+/// Windows of `ColorMode::System` receive `ColorModeChanged` like `WM_SETTINGCHANGE` on Windows.
+///
+#[inline]
+pub fn set_system_dark_mode(dark_mode: bool) {
+    UiThread::send_task(move || {
+        procedure::on_setting_change(dark_mode);
+    });
+}
+
+/// Replaces the simulated monitors.
+///
+/// All windows receive `MonitorsChanged` like `WM_DISPLAYCHANGE` on Windows. An empty list
+/// restores the default monitor.
+///
+#[inline]
+pub fn set_monitors(monitors: Vec<crate::MonitorInfo>) {
+    UiThread::send_task(move || {
+        procedure::on_display_change(monitors);
+    });
+}
+
+/// Checks the simulated system color mode.
+#[inline]
+pub fn is_system_dark_mode() -> bool {
+    procedure::is_system_dark_mode()
+}
@@ -0,0 +1,55 @@
+use super::{procedure, window::WindowProps};
+use crate::context::{self, Object, Props};
+use crate::*;
+use std::sync::OnceLock;
+
+/// Simulated windows.
+pub(crate) type Context = context::Context<WindowProps>;
+
+static CONTEXT: OnceLock<context::ContextImpl<WindowProps>> = OnceLock::new();
+
+impl Props for WindowProps {
+    fn context() -> &'static context::ContextImpl<Self> {
+        CONTEXT.get_or_init(context::ContextImpl::new)
+    }
+
+    fn parent(&self) -> Option<WindowHandle> {
+        self.parent
+    }
+
+    fn close_child(handle: WindowHandle) {
+        UiThread::send_task(move || {
+            procedure::on_close(handle);
+        });
+    }
+}
+
+impl Context {
+    pub fn window_handles() -> Vec<WindowHandle> {
+        let window_map = WindowProps::context().window_map.lock().unwrap();
+        window_map.keys().copied().collect()
+    }
+
+    pub fn root_window(handle: WindowHandle) -> WindowHandle {
+        let window_map = WindowProps::context().window_map.lock().unwrap();
+        let mut handle = handle;
+        while let Some(Object {
+            kind: WindowKind::InnerWindow(w),
+            ..
+        }) = window_map.get(&handle)
+        {
+            handle = w.parent;
+        }
+        handle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_is_none() {
+        assert!(Context::window_is_none(WindowHandle::default()));
+    }
+}
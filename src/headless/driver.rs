@@ -0,0 +1,398 @@
+use super::{context::Context, procedure};
+use crate::*;
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+/// Injects synthetic input to a simulated window.
+///
+/// Each method sends a task to UI thread like a window message, so that events are received in
+/// the order of calling methods.
+///
+#[derive(Clone, Debug)]
+pub struct Driver {
+    handle: WindowHandle,
+}
+
+impl Driver {
+    #[inline]
+    pub fn new(window: &impl IsWindow) -> Self {
+        Self {
+            handle: window.window_handle(),
+        }
+    }
+
+    #[inline]
+    pub fn window_handle(&self) -> WindowHandle {
+        self.handle
+    }
+
+    /// Gets the title of the simulated window.
+    #[inline]
+    pub fn title(&self) -> Option<String> {
+        Context::get_window_props(self.handle, |props| props.title.clone())
+    }
+
+    /// Checks if the simulated window has a resizable frame.
+    #[inline]
+    pub fn is_resizable(&self) -> Option<bool> {
+        Context::get_window_props(self.handle, |props| props.resizable)
+    }
+
+    /// Checks if the simulated window shows IME candidate windows.
+    #[inline]
+    pub fn is_visible_ime_candidate_window(&self) -> Option<bool> {
+        Context::get_window_props(self.handle, |props| props.visible_ime_candidate_window)
+    }
+
+    /// Checks if the simulated window captures the mouse.
+    #[inline]
+    pub fn has_capture(&self) -> Option<bool> {
+        Context::get_window_props(self.handle, |props| props.capturing)
+    }
+
+    /// Gets the rectangle in client coordinates which the cursor is confined to.
+    ///
+    /// A locked cursor is confined to the center of the client area.
+    #[inline]
+    pub fn cursor_clip(&self) -> Option<PhysicalRect<i32>> {
+        Context::get_window_props(self.handle, |props| {
+            cursor_clip_rect(props.size, props.cursor_confine, props.cursor_locked)
+        })
+        .flatten()
+    }
+
+    /// Checks if the cursor is hidden on the simulated window.
+    #[inline]
+    pub fn is_cursor_hidden(&self) -> Option<bool> {
+        Context::get_window_props(self.handle, |props| {
+            props.cursor_hidden || props.cursor_locked
+        })
+    }
+
+    /// Moves the mouse cursor on the window like `WM_MOUSEMOVE`.
+    ///
+    /// The first call after entering the window sends `CursorEntered`. `DragStarted` is sent
+    /// when the cursor moves outside of the drag rectangle while a button is pressed.
+    ///
+    #[inline]
+    pub fn cursor_moved(&self, position: PhysicalPosition<i32>) {
+        let handle = self.handle;
+        UiThread::send_task(move || procedure::on_mouse_move(handle, position));
+    }
+
+    #[inline]
+    pub fn cursor_left(&self, position: PhysicalPosition<i32>) {
+        let handle = self.handle;
+        UiThread::send_task(move || procedure::on_mouse_leave(handle, position));
+    }
+
+    #[inline]
+    pub fn mouse_input(
+        &self,
+        button: MouseButton,
+        button_state: ButtonState,
+        position: PhysicalPosition<i32>,
+    ) {
+        let handle = self.handle;
+        UiThread::send_task(move || {
+            procedure::on_mouse_input(handle, button, button_state, position, None)
+        });
+    }
+
+    /// Inputs a mouse button like `mouse_input` at `time`.
+    ///
+    /// `time` is the elapsed time which counts clicks with `ClickConfig::double_click_time`.
+    ///
+    #[inline]
+    pub fn mouse_input_at(
+        &self,
+        button: MouseButton,
+        button_state: ButtonState,
+        position: PhysicalPosition<i32>,
+        time: Duration,
+    ) {
+        let handle = self.handle;
+        UiThread::send_task(move || {
+            procedure::on_mouse_input(handle, button, button_state, position, Some(time))
+        });
+    }
+
+    /// Takes the mouse capture away like `WM_CAPTURECHANGED`.
+    ///
+    /// The window receives `Event::CaptureLost` if it captures the mouse.
+    ///
+    #[inline]
+    pub fn capture_lost(&self) {
+        let handle = self.handle;
+        UiThread::send_task(move || procedure::on_capture_lost(handle));
+    }
+
+    #[inline]
+    pub fn mouse_wheel(
+        &self,
+        axis: MouseWheelAxis,
+        distance: i32,
+        position: PhysicalPosition<i32>,
+    ) {
+        let handle = self.handle;
+        UiThread::send_task(move || {
+            procedure::on_mouse_wheel(handle, axis, distance, position, None)
+        });
+    }
+
+    /// Inputs a wheel distance like `mouse_wheel` at `time`.
+    ///
+    /// `time` is the elapsed time which infers `ScrollPhase` of precise scrolls.
+    ///
+    #[inline]
+    pub fn mouse_wheel_at(
+        &self,
+        axis: MouseWheelAxis,
+        distance: i32,
+        position: PhysicalPosition<i32>,
+        time: Duration,
+    ) {
+        let handle = self.handle;
+        UiThread::send_task(move || {
+            procedure::on_mouse_wheel(handle, axis, distance, position, Some(time))
+        });
+    }
+
+    /// Fires the timer which ends a precise scroll like `WM_TIMER`.
+    ///
+    /// The window receives `ScrollPhase::End` when no wheel event came within
+    /// `SCROLL_END_TIMEOUT` before `time`.
+    ///
+    #[inline]
+    pub fn wheel_timer(&self, time: Duration) {
+        let handle = self.handle;
+        UiThread::send_task(move || procedure::on_wheel_timer(handle, time));
+    }
+
+    /// Inputs a pointer state like `WM_POINTERDOWN`, `WM_POINTERUPDATE` and `WM_POINTERUP`.
+    ///
+    /// The sample is dropped when the window does not enable `pointer_input`. `modifiers` of
+    /// the sample is replaced with modifier keys of the window.
+    ///
+    #[inline]
+    pub fn pointer_input(&self, sample: pointer::PointerSample) {
+        let handle = self.handle;
+        UiThread::send_task(move || procedure::on_pointer(handle, sample, None));
+    }
+
+    /// Inputs a pointer state at `time` for gestures.
+    ///
+    /// `time` is the elapsed time which is shared with `gesture_timer`. `pointer_input` uses
+    /// the elapsed time from creating the window.
+    ///
+    #[inline]
+    pub fn pointer_input_at(&self, sample: pointer::PointerSample, time: Duration) {
+        let handle = self.handle;
+        UiThread::send_task(move || procedure::on_pointer(handle, sample, Some(time)));
+    }
+
+    /// Fires the timer of the gesture recognizer like `WM_TIMER`.
+    ///
+    /// This recognizes `LongPress` and `Pan` with `Inertia`.
+    ///
+    #[inline]
+    pub fn gesture_timer(&self, time: Duration) {
+        let handle = self.handle;
+        UiThread::send_task(move || procedure::on_gesture_timer(handle, time));
+    }
+
+    /// Removes a pointer like `WM_POINTERLEAVE` and `WM_POINTERCAPTURECHANGED`.
+    ///
+    /// The window receives `PointerPhase::Cancel` if the pointer is in contact.
+    ///
+    #[inline]
+    pub fn pointer_leave(&self, id: PointerId) {
+        let handle = self.handle;
+        UiThread::send_task(move || procedure::on_pointer_leave(handle, id));
+    }
+
+    /// Inputs a key like `WM_KEYDOWN` and `WM_KEYUP`.
+    ///
+    /// `prev_pressed` of `KeyInput` is set when the key is already pressed.
+    ///
+    /// Modifier keys are tracked for each window. Right Ctrl and right Alt are distinguished by
+    /// the extended scan codes `0xe01d` and `0xe038`, and right Shift by `0x36`. Pressing
+    /// CapsLock, NumLock and ScrollLock toggles the lock state of the UI thread.
+    ///
+    /// `logical_key` and `text` of `KeyInput` are translated with the US keyboard layout.
+    ///
+    #[inline]
+    pub fn key_input(&self, key_code: impl Into<KeyCode>, key_state: KeyState) {
+        let handle = self.handle;
+        let key_code = key_code.into();
+        UiThread::send_task(move || procedure::on_key_input(handle, key_code, key_state));
+    }
+
+    #[inline]
+    pub fn char_input(&self, c: char) {
+        let handle = self.handle;
+        let units = c.encode_utf16(&mut [0; 2]).to_vec();
+        UiThread::send_task(move || procedure::on_char(handle, units));
+    }
+
+    /// Inputs a UTF-16 code unit like `WM_CHAR`. Surrogate pairs are reassembled per window.
+    #[inline]
+    pub fn utf16_char_input(&self, unit: u16) {
+        let handle = self.handle;
+        UiThread::send_task(move || procedure::on_char(handle, vec![unit]));
+    }
+
+    /// Inputs `text` as successive `WM_CHAR` at once like pasting.
+    #[inline]
+    pub fn text_input(&self, text: &str) {
+        let handle = self.handle;
+        let units = text.encode_utf16().collect::<Vec<_>>();
+        UiThread::send_task(move || procedure::on_char(handle, units));
+    }
+
+    /// Inputs a character of a dead key like `WM_DEADCHAR`.
+    #[inline]
+    pub fn dead_char_input(&self, c: char) {
+        let handle = self.handle;
+        UiThread::send_task(move || procedure::on_dead_char(handle, c));
+    }
+
+    /// Inputs a character with the Alt key like `WM_SYSCHAR`.
+    #[inline]
+    pub fn sys_char_input(&self, c: char) {
+        let handle = self.handle;
+        UiThread::send_task(move || procedure::on_sys_char(handle, c));
+    }
+
+    /// Inputs a `RAWINPUT` buffer like `WM_INPUT`.
+    ///
+    /// The input is dropped when the window does not register the device type, or the input is
+    /// a background input and the window does not enable `background`.
+    ///
+    pub fn raw_input(&self, data: &[u8]) -> std::result::Result<(), raw_input::DecodeError> {
+        let input = raw_input::decode(data)?;
+        let handle = self.handle;
+        UiThread::send_task(move || procedure::on_input(handle, input));
+        Ok(())
+    }
+
+    /// Connects a raw input device like `WM_INPUT_DEVICE_CHANGE` with `GIDC_ARRIVAL`.
+    #[inline]
+    pub fn device_added(&self, device: DeviceId) {
+        let handle = self.handle;
+        UiThread::send_task(move || procedure::on_input_device_change(handle, device, true));
+    }
+
+    /// Disconnects a raw input device like `WM_INPUT_DEVICE_CHANGE` with `GIDC_REMOVAL`.
+    #[inline]
+    pub fn device_removed(&self, device: DeviceId) {
+        let handle = self.handle;
+        UiThread::send_task(move || procedure::on_input_device_change(handle, device, false));
+    }
+
+    /// Begins an IME composition.
+    ///
+    /// The returned receiver receives the candidate window position when `ImeBeginComposition` is
+    /// dropped. When IME is disabled, the receiver is closed without a value.
+    ///
+    #[inline]
+    pub fn ime_begin_composition(&self) -> oneshot::Receiver<PhysicalPosition<i32>> {
+        let handle = self.handle;
+        let (tx, rx) = oneshot::channel();
+        UiThread::send_task(move || procedure::on_ime_start_composition(handle, tx));
+        rx
+    }
+
+    #[inline]
+    pub fn ime_update_composition(
+        &self,
+        s: impl Into<String>,
+        clauses: Vec<ime::Clause>,
+        cursor_position: usize,
+    ) {
+        let handle = self.handle;
+        let chars = s.into().chars().collect();
+        UiThread::send_task(move || {
+            procedure::on_ime_composition(handle, chars, clauses, cursor_position)
+        });
+    }
+
+    #[inline]
+    pub fn ime_end_composition(&self, result: Option<String>) {
+        let handle = self.handle;
+        UiThread::send_task(move || procedure::on_ime_end_composition(handle, result));
+    }
+
+    /// Resizes the window by a user like `WM_SIZE`.
+    #[inline]
+    pub fn resize(&self, size: PhysicalSize<u32>) {
+        let handle = self.handle;
+        UiThread::send_task(move || procedure::on_size(handle, size));
+    }
+
+    /// Drags the frame of the window from `edge` like `WM_SIZING`.
+    ///
+    /// `size` is constrained by the size constraints of the window, and `Resized` follows
+    /// `Resizing` when the size changes.
+    #[inline]
+    pub fn sizing(&self, edge: ResizingEdge, size: PhysicalSize<u32>) {
+        let handle = self.handle;
+        UiThread::send_task(move || procedure::on_sizing(handle, size, edge));
+    }
+
+    /// Moves the window by a user like `WM_WINDOWPOSCHANGED`.
+    #[inline]
+    pub fn move_to(&self, position: ScreenPosition<i32>) {
+        let handle = self.handle;
+        UiThread::send_task(move || {
+            procedure::on_move(handle, PhysicalPosition::new(position.x, position.y))
+        });
+    }
+
+    /// Changes DPI of the window like `WM_DPICHANGED`.
+    ///
+    /// The window size is scaled by the new DPI, and `Resized` is sent before `DpiChanged`.
+    ///
+    #[inline]
+    pub fn change_dpi(&self, new_dpi: u32) {
+        let handle = self.handle;
+        UiThread::send_task(move || procedure::on_dpi_changed(handle, new_dpi));
+    }
+
+    #[inline]
+    pub fn activate(&self, active: bool) {
+        let handle = self.handle;
+        UiThread::send_task(move || procedure::on_activate(handle, active));
+    }
+
+    /// Gives or takes the keyboard focus without changing the activation like `WM_SETFOCUS` and
+    /// `WM_KILLFOCUS`.
+    #[inline]
+    pub fn focus(&self, focused: bool) {
+        let handle = self.handle;
+        UiThread::send_task(move || procedure::on_focus(handle, focused));
+    }
+
+    /// Requests to close the window by a user like `WM_CLOSE`.
+    #[inline]
+    pub fn close_request(&self) {
+        let handle = self.handle;
+        UiThread::send_task(move || procedure::on_close(handle));
+    }
+
+    /// Hit-tests the non client area like `WM_NCHITTEST`.
+    ///
+    /// The returned receiver receives the value set to `NcHitTest`.
+    /// When the window does not hook it, the receiver receives `None`.
+    ///
+    #[inline]
+    pub fn nc_hittest(
+        &self,
+        position: PhysicalPosition<i32>,
+    ) -> oneshot::Receiver<Option<NcHitTestValue>> {
+        let handle = self.handle;
+        let (tx, rx) = oneshot::channel();
+        UiThread::send_task(move || procedure::on_nc_hittest(handle, position, tx));
+        rx
+    }
+}
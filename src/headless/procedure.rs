@@ -0,0 +1,767 @@
+use super::context::Context;
+use crate::*;
+use std::sync::atomic::{self, AtomicBool, AtomicU32};
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+static SYSTEM_DARK_MODE: AtomicBool = AtomicBool::new(false);
+static LOCK_KEYS: AtomicU32 = AtomicU32::new(0);
+
+pub(crate) fn is_system_dark_mode() -> bool {
+    SYSTEM_DARK_MODE.load(atomic::Ordering::SeqCst)
+}
+
+pub(crate) fn lock_keys() -> ModifierState {
+    ModifierState::from_bits_retain(LOCK_KEYS.load(atomic::Ordering::SeqCst))
+}
+
+/// Right Ctrl and right Alt have the extended scan code `0xe0xx`, and right Shift is `0x36`.
+fn modifier_key(key_code: KeyCode) -> ModifierState {
+    let extended = key_code.scan_code.0 & 0xff00 == 0xe000;
+    match key_code.vkey {
+        VirtualKey::Ctrl if extended => ModifierState::RIGHT_CTRL,
+        VirtualKey::Ctrl => ModifierState::LEFT_CTRL,
+        VirtualKey::Shift if key_code.scan_code.0 == 0x36 => ModifierState::RIGHT_SHIFT,
+        VirtualKey::Shift => ModifierState::LEFT_SHIFT,
+        VirtualKey::Alt if extended => ModifierState::RIGHT_ALT,
+        VirtualKey::Alt => ModifierState::LEFT_ALT,
+        vkey => ModifierState::win_key(vkey).unwrap_or_else(ModifierState::empty),
+    }
+}
+
+/// Unshifted and shifted characters of the US keyboard layout.
+const US_LAYOUT: [(VirtualKey, char, char); 48] = [
+    (VirtualKey::A, 'a', 'A'),
+    (VirtualKey::B, 'b', 'B'),
+    (VirtualKey::C, 'c', 'C'),
+    (VirtualKey::D, 'd', 'D'),
+    (VirtualKey::E, 'e', 'E'),
+    (VirtualKey::F, 'f', 'F'),
+    (VirtualKey::G, 'g', 'G'),
+    (VirtualKey::H, 'h', 'H'),
+    (VirtualKey::I, 'i', 'I'),
+    (VirtualKey::J, 'j', 'J'),
+    (VirtualKey::K, 'k', 'K'),
+    (VirtualKey::L, 'l', 'L'),
+    (VirtualKey::M, 'm', 'M'),
+    (VirtualKey::N, 'n', 'N'),
+    (VirtualKey::O, 'o', 'O'),
+    (VirtualKey::P, 'p', 'P'),
+    (VirtualKey::Q, 'q', 'Q'),
+    (VirtualKey::R, 'r', 'R'),
+    (VirtualKey::S, 's', 'S'),
+    (VirtualKey::T, 't', 'T'),
+    (VirtualKey::U, 'u', 'U'),
+    (VirtualKey::V, 'v', 'V'),
+    (VirtualKey::W, 'w', 'W'),
+    (VirtualKey::X, 'x', 'X'),
+    (VirtualKey::Y, 'y', 'Y'),
+    (VirtualKey::Z, 'z', 'Z'),
+    (VirtualKey::Key0, '0', ')'),
+    (VirtualKey::Key1, '1', '!'),
+    (VirtualKey::Key2, '2', '@'),
+    (VirtualKey::Key3, '3', '#'),
+    (VirtualKey::Key4, '4', '$'),
+    (VirtualKey::Key5, '5', '%'),
+    (VirtualKey::Key6, '6', '^'),
+    (VirtualKey::Key7, '7', '&'),
+    (VirtualKey::Key8, '8', '*'),
+    (VirtualKey::Key9, '9', '('),
+    (VirtualKey::Space, ' ', ' '),
+    (VirtualKey::Other(0xba), ';', ':'),
+    (VirtualKey::Other(0xbb), '=', '+'),
+    (VirtualKey::Other(0xbc), ',', '<'),
+    (VirtualKey::Other(0xbd), '-', '_'),
+    (VirtualKey::Other(0xbe), '.', '>'),
+    (VirtualKey::Other(0xbf), '/', '?'),
+    (VirtualKey::Other(0xc0), '`', '~'),
+    (VirtualKey::Other(0xdb), '[', '{'),
+    (VirtualKey::Other(0xdc), '\\', '|'),
+    (VirtualKey::Other(0xdd), ']', '}'),
+    (VirtualKey::Other(0xde), '\'', '"'),
+];
+
+const NUMPAD: [(VirtualKey, char); 15] = [
+    (VirtualKey::Num0, '0'),
+    (VirtualKey::Num1, '1'),
+    (VirtualKey::Num2, '2'),
+    (VirtualKey::Num3, '3'),
+    (VirtualKey::Num4, '4'),
+    (VirtualKey::Num5, '5'),
+    (VirtualKey::Num6, '6'),
+    (VirtualKey::Num7, '7'),
+    (VirtualKey::Num8, '8'),
+    (VirtualKey::Num9, '9'),
+    (VirtualKey::NumAdd, '+'),
+    (VirtualKey::NumSub, '-'),
+    (VirtualKey::NumMul, '*'),
+    (VirtualKey::NumDiv, '/'),
+    (VirtualKey::NumDecimal, '.'),
+];
+
+/// Translates a key with the US keyboard layout.
+fn translate_key(vkey: VirtualKey, modifiers: ModifierState) -> (LogicalKey, Option<String>) {
+    let c = US_LAYOUT
+        .iter()
+        .find(|(k, _, _)| *k == vkey)
+        .map(|&(_, lower, upper)| {
+            let shift = if lower.is_ascii_alphabetic() {
+                modifiers.shift() != modifiers.contains(ModifierState::CAPS_LOCK)
+            } else {
+                modifiers.shift()
+            };
+            if shift { upper } else { lower }
+        })
+        .or_else(|| NUMPAD.iter().find(|(k, _)| *k == vkey).map(|&(_, c)| c));
+    let Some(c) = c else {
+        return (LogicalKey::Named(vkey), None);
+    };
+    // Ctrl without Alt produces control characters.
+    let text = (!modifiers.ctrl() || modifiers.alt()).then(|| c.to_string());
+    (LogicalKey::Character(c.to_string()), text)
+}
+
+fn lock_key(vkey: VirtualKey) -> ModifierState {
+    match vkey {
+        VirtualKey::CapsLock => ModifierState::CAPS_LOCK,
+        VirtualKey::NumLock => ModifierState::NUM_LOCK,
+        VirtualKey::ScrollLock => ModifierState::SCROLL_LOCK,
+        _ => ModifierState::empty(),
+    }
+}
+
+fn mouse_state(handle: WindowHandle, position: PhysicalPosition<i32>) -> Option<MouseState> {
+    Context::get_window_props(handle, |props| props.mouse_state(position))
+}
+
+pub(crate) fn on_paint(handle: WindowHandle, invalidate_rect: PhysicalRect<i32>) {
+    if Context::window_is_none(handle) {
+        return;
+    }
+    Context::set_window_props(handle, |props| props.redrawing = false);
+    Context::send_event(handle, Event::Draw(event::Draw { invalidate_rect }));
+}
+
+pub(crate) fn on_mouse_move(handle: WindowHandle, position: PhysicalPosition<i32>) {
+    let Some(entered) = Context::get_window_props(handle, |props| props.entered) else {
+        return;
+    };
+    let mouse_state = mouse_state(handle, position).unwrap();
+    let config = click::ClickConfig::system();
+    let mut drag = None;
+    Context::set_window_props(handle, |props| drag = props.clicks.moved(position, &config));
+    if !entered {
+        Context::set_window_props(handle, |props| props.entered = true);
+        Context::send_event(
+            handle,
+            Event::CursorEntered(event::CursorEntered {
+                mouse_state: mouse_state.clone(),
+            }),
+        );
+    } else {
+        Context::send_event(
+            handle,
+            Event::CursorMoved(event::CursorMoved {
+                mouse_state: mouse_state.clone(),
+            }),
+        );
+    }
+    if let Some(drag) = drag {
+        Context::send_event(
+            handle,
+            Event::DragStarted(event::DragStarted {
+                button: drag.button,
+                origin: drag.origin,
+                mouse_state,
+            }),
+        );
+    }
+}
+
+pub(crate) fn on_mouse_leave(handle: WindowHandle, position: PhysicalPosition<i32>) {
+    if Context::window_is_none(handle) {
+        return;
+    }
+    Context::set_window_props(handle, |props| props.entered = false);
+    Context::send_event(handle, Event::CursorLeft(event::CursorLeft { position }));
+}
+
+pub(crate) fn on_mouse_input(
+    handle: WindowHandle,
+    button: MouseButton,
+    button_state: ButtonState,
+    position: PhysicalPosition<i32>,
+    time: Option<Duration>,
+) {
+    if Context::window_is_none(handle) {
+        return;
+    }
+    let config = click::ClickConfig::system();
+    let mut click_count = 0;
+    Context::set_window_props(handle, |props| {
+        props.buttons.retain(|b| *b != button);
+        click_count = match button_state {
+            ButtonState::Pressed => {
+                props.buttons.push(button);
+                let time = time.unwrap_or_else(|| props.input_clock.elapsed());
+                props.clicks.press(button, position, time, &config)
+            }
+            ButtonState::Released => props.clicks.release(button),
+        };
+        match button_state {
+            ButtonState::Pressed if props.auto_capture && !props.capturing => {
+                props.capturing = true;
+                props.auto_captured = true;
+            }
+            ButtonState::Released if props.auto_captured && props.buttons.is_empty() => {
+                props.capturing = false;
+                props.auto_captured = false;
+            }
+            _ => {}
+        }
+    });
+    let mouse_state = mouse_state(handle, position).unwrap();
+    Context::send_event(
+        handle,
+        Event::MouseInput(event::MouseInput {
+            button,
+            button_state,
+            click_count,
+            mouse_state,
+        }),
+    );
+}
+
+pub(crate) fn on_capture_lost(handle: WindowHandle) {
+    let mut lost = false;
+    Context::set_window_props(handle, |props| {
+        lost = props.capturing;
+        props.capturing = false;
+        props.auto_captured = false;
+        props.clicks.cancel();
+    });
+    if lost {
+        Context::send_event(handle, Event::CaptureLost);
+    }
+}
+
+pub(crate) fn on_mouse_wheel(
+    handle: WindowHandle,
+    axis: MouseWheelAxis,
+    distance: i32,
+    position: PhysicalPosition<i32>,
+    time: Option<Duration>,
+) {
+    let Some(mouse_state) = mouse_state(handle, position) else {
+        return;
+    };
+    let settings = wheel::ScrollSettings::system();
+    let mut steps = vec![];
+    let mut prev = None;
+    Context::set_window_props(handle, |props| {
+        let time = time.unwrap_or_else(|| props.input_clock.elapsed());
+        steps = props.wheel.process(axis, distance, time, &settings);
+        prev = props.wheel_mouse_state.replace(mouse_state.clone());
+    });
+    for step in steps {
+        let mouse_state = match step.phase {
+            Some(ScrollPhase::End) => prev.clone().unwrap_or_else(|| mouse_state.clone()),
+            _ => mouse_state.clone(),
+        };
+        Context::send_event(
+            handle,
+            Event::MouseWheel(wheel::to_event(&step, distance, mouse_state)),
+        );
+    }
+}
+
+pub(crate) fn on_wheel_timer(handle: WindowHandle, time: Duration) {
+    let mut end = None;
+    Context::set_window_props(handle, |props| {
+        end = props.wheel.poll(time).zip(props.wheel_mouse_state.clone());
+    });
+    if let Some((step, mouse_state)) = end {
+        Context::send_event(
+            handle,
+            Event::MouseWheel(wheel::to_event(&step, 0, mouse_state)),
+        );
+    }
+}
+
+fn update_gestures(
+    handle: WindowHandle,
+    time: Option<Duration>,
+    f: impl FnOnce(&mut gesture::GestureRecognizer, Duration) -> Vec<gesture::Gesture>,
+) {
+    let mut gestures = vec![];
+    Context::set_window_props(handle, |props| {
+        let time = time.unwrap_or_else(|| props.input_clock.elapsed());
+        gestures = f(&mut props.gesture_recognizer, time);
+    });
+    for gesture in gestures {
+        Context::send_event(handle, Event::Gesture(gesture));
+    }
+}
+
+pub(crate) fn on_pointer(
+    handle: WindowHandle,
+    sample: pointer::PointerSample,
+    time: Option<Duration>,
+) {
+    let props = Context::get_window_props(handle, |props| (props.pointer_input, props.gestures));
+    let Some((true, gestures)) = props else {
+        return;
+    };
+    let mut ev = None;
+    Context::set_window_props(handle, |props| {
+        let sample = sample.modifiers(props.modifier_state());
+        ev = props.pointers.process(sample);
+    });
+    if let Some(ev) = ev {
+        Context::send_event(handle, Event::Pointer(ev.clone()));
+        if gestures && ev.pointer_type == PointerType::Touch {
+            update_gestures(handle, time, |recognizer, time| {
+                recognizer.process(gesture::GestureSample::from_pointer(&ev, time))
+            });
+        }
+    }
+}
+
+pub(crate) fn on_gesture_timer(handle: WindowHandle, time: Duration) {
+    if Context::get_window_props(handle, |props| props.gestures) != Some(true) {
+        return;
+    }
+    update_gestures(handle, Some(time), |recognizer, time| recognizer.poll(time));
+}
+
+pub(crate) fn on_pointer_leave(handle: WindowHandle, id: PointerId) {
+    let Some(true) = Context::get_window_props(handle, |props| props.pointer_input) else {
+        return;
+    };
+    let mut ev = None;
+    Context::set_window_props(handle, |props| {
+        ev = props.pointers.leave(id);
+    });
+    if let Some(ev) = ev {
+        Context::send_event(handle, Event::Pointer(ev));
+    }
+}
+
+pub(crate) fn on_key_input(handle: WindowHandle, key_code: KeyCode, key_state: KeyState) {
+    let prev_pressed =
+        Context::get_window_props(handle, |props| props.keys.contains(&key_code.vkey));
+    let Some(prev_pressed) = prev_pressed else {
+        return;
+    };
+    if key_state == KeyState::Pressed && !prev_pressed {
+        LOCK_KEYS.fetch_xor(lock_key(key_code.vkey).bits(), atomic::Ordering::SeqCst);
+    }
+    let modifier = modifier_key(key_code);
+    Context::set_window_props(handle, |props| {
+        match key_state {
+            KeyState::Pressed => {
+                props.keys.insert(key_code.vkey);
+            }
+            KeyState::Released => {
+                props.keys.remove(&key_code.vkey);
+            }
+        }
+        props
+            .modifiers
+            .set(modifier, key_state == KeyState::Pressed);
+    });
+    let modifiers = Context::get_window_props(handle, |props| props.modifier_state()).unwrap();
+    if key_state == KeyState::Pressed && translate_accelerator(handle, key_code.vkey, modifiers) {
+        return;
+    }
+    let (logical_key, text) = translate_key(key_code.vkey, modifiers);
+    Context::send_event(
+        handle,
+        Event::KeyInput(event::KeyInput {
+            key_code,
+            key_state,
+            prev_pressed,
+            modifiers,
+            logical_key,
+            text: text.filter(|_| key_state == KeyState::Pressed),
+        }),
+    );
+}
+
+/// Sends `Event::Accelerator` instead of `KeyInput` if the key matches accelerators of the window
+/// or its root window.
+fn translate_accelerator(handle: WindowHandle, key: VirtualKey, modifiers: ModifierState) -> bool {
+    let modifiers = modifiers.to_modifiers();
+    for handle in [handle, Context::root_window(handle)] {
+        let id = Context::get_window_props(handle, |props| props.accelerators.find(key, modifiers));
+        if let Some(Some(id)) = id {
+            Context::send_event(
+                handle,
+                Event::Accelerator(event::Accelerator {
+                    id,
+                    chord: Chord::new(key, modifiers),
+                }),
+            );
+            return true;
+        }
+    }
+    false
+}
+
+/// Decodes UTF-16 code units like successive `WM_CHAR`.
+pub(crate) fn on_char(handle: WindowHandle, units: Vec<u16>) {
+    let Some(batch) = Context::get_window_props(handle, |props| props.batch_text_input) else {
+        return;
+    };
+    let mut chars = vec![];
+    Context::set_window_props(handle, |props| {
+        for unit in units {
+            chars.extend(props.utf16.push(unit));
+        }
+    });
+    if batch {
+        if !chars.is_empty() {
+            Context::send_event(
+                handle,
+                Event::TextInput(event::TextInput {
+                    text: chars.into_iter().collect(),
+                }),
+            );
+        }
+    } else {
+        for c in chars {
+            Context::send_event(handle, Event::CharInput(event::CharInput { c }));
+        }
+    }
+}
+
+pub(crate) fn on_dead_char(handle: WindowHandle, c: char) {
+    Context::send_event(handle, Event::DeadCharInput(event::DeadCharInput { c }));
+}
+
+pub(crate) fn on_sys_char(handle: WindowHandle, c: char) {
+    Context::send_event(handle, Event::SysCharInput(event::SysCharInput { c }));
+}
+
+pub(crate) fn on_input(handle: WindowHandle, input: raw_input::RawInput) {
+    let accepted = Context::get_window_props(handle, |props| props.raw_input.accepts(&input));
+    if accepted != Some(true) {
+        return;
+    }
+    if let Some(ev) = raw_input::to_event(input) {
+        Context::send_event(handle, ev);
+    }
+}
+
+pub(crate) fn on_input_device_change(handle: WindowHandle, device: DeviceId, arrival: bool) {
+    let registered = Context::get_window_props(handle, |props| !props.raw_input.is_empty());
+    if registered != Some(true) {
+        return;
+    }
+    let ev = if arrival {
+        Event::DeviceAdded(event::DeviceAdded { device })
+    } else {
+        Event::DeviceRemoved(event::DeviceRemoved { device })
+    };
+    Context::send_event(handle, ev);
+}
+
+pub(crate) fn on_ime_start_composition(
+    handle: WindowHandle,
+    reply: oneshot::Sender<PhysicalPosition<i32>>,
+) {
+    let props = Context::get_window_props(handle, |props| (props.ime_enabled, props.dpi));
+    let Some((true, dpi)) = props else {
+        return;
+    };
+    let (tx, rx) = oneshot::channel::<PhysicalPosition<i32>>();
+    Context::send_event(
+        handle,
+        Event::ImeBeginComposition(event::ImeBeginComposition::new(dpi as i32, tx)),
+    );
+    if let Ok(position) = rx.blocking_recv() {
+        reply.send(position).ok();
+    }
+}
+
+pub(crate) fn on_ime_composition(
+    handle: WindowHandle,
+    chars: Vec<char>,
+    clauses: Vec<ime::Clause>,
+    cursor_position: usize,
+) {
+    let enabled = Context::get_window_props(handle, |props| props.ime_enabled);
+    if !enabled.unwrap_or(false) {
+        return;
+    }
+    Context::send_event(
+        handle,
+        Event::ImeUpdateComposition(event::ImeUpdateComposition {
+            chars,
+            clauses,
+            cursor_position,
+        }),
+    );
+}
+
+pub(crate) fn on_ime_end_composition(handle: WindowHandle, result: Option<String>) {
+    let enabled = Context::get_window_props(handle, |props| props.ime_enabled);
+    if !enabled.unwrap_or(false) {
+        return;
+    }
+    Context::send_event(
+        handle,
+        Event::ImeEndComposition(event::ImeEndComposition { result }),
+    );
+}
+
+pub(crate) fn on_move(handle: WindowHandle, position: PhysicalPosition<i32>) {
+    if Context::window_is_none(handle) {
+        return;
+    }
+    Context::set_window_props(handle, |props| props.position = position);
+    Context::send_event(
+        handle,
+        Event::Moved(event::Moved {
+            position: ScreenPosition::new(position.x, position.y),
+        }),
+    );
+}
+
+pub(crate) fn on_sizing(handle: WindowHandle, size: PhysicalSize<u32>, edge: ResizingEdge) {
+    let props = Context::get_window_props(handle, |props| {
+        (props.size_constraints, props.dpi, props.size)
+    });
+    let Some((constraints, dpi, prev)) = props else {
+        return;
+    };
+    let size = constraints.constrain(size, edge, dpi);
+    Context::send_event(handle, Event::Resizing(event::Resizing { size, edge }));
+    if size != prev {
+        on_size(handle, size);
+    }
+}
+
+pub(crate) fn on_size(handle: WindowHandle, size: PhysicalSize<u32>) {
+    if Context::window_is_none(handle) {
+        return;
+    }
+    Context::set_window_props(handle, |props| {
+        props.size = size;
+        props.minimized = false;
+        props.maximized = false;
+    });
+    Context::send_event(handle, Event::Resized(event::Resized { size }));
+}
+
+pub(crate) fn on_show(handle: WindowHandle) {
+    let Some(size) = Context::get_window_props(handle, |props| props.size) else {
+        return;
+    };
+    Context::set_window_props(handle, |props| props.visible = true);
+    on_activate(handle, true);
+    on_paint(
+        handle,
+        PhysicalRect::new(0, 0, size.width as i32, size.height as i32),
+    );
+}
+
+pub(crate) fn on_show_without_activation(handle: WindowHandle) {
+    let Some(size) = Context::get_window_props(handle, |props| props.size) else {
+        return;
+    };
+    Context::set_window_props(handle, |props| props.visible = true);
+    on_paint(
+        handle,
+        PhysicalRect::new(0, 0, size.width as i32, size.height as i32),
+    );
+}
+
+pub(crate) fn on_hide(handle: WindowHandle) {
+    if Context::window_is_none(handle) {
+        return;
+    }
+    Context::set_window_props(handle, |props| props.visible = false);
+    on_activate(handle, false);
+}
+
+pub(crate) fn on_minimize(handle: WindowHandle) {
+    let Some(minimized) = Context::get_window_props(handle, |props| props.minimized) else {
+        return;
+    };
+    if minimized {
+        return;
+    }
+    Context::set_window_props(handle, |props| props.minimized = true);
+    Context::send_event(handle, Event::Minizmized);
+}
+
+pub(crate) fn on_maximize(handle: WindowHandle) {
+    let Some(size) = Context::get_window_props(handle, |props| props.size) else {
+        return;
+    };
+    Context::set_window_props(handle, |props| {
+        props.minimized = false;
+        props.maximized = true;
+    });
+    Context::send_event(handle, Event::Maximized(event::Maximized { size }));
+}
+
+pub(crate) fn on_restore(handle: WindowHandle) {
+    let props = Context::get_window_props(handle, |props| (props.minimized, props.size));
+    let Some((minimized, size)) = props else {
+        return;
+    };
+    Context::set_window_props(handle, |props| {
+        props.minimized = false;
+        props.maximized = false;
+    });
+    if minimized {
+        Context::send_event(handle, Event::Restored(event::Restored { size }));
+    } else {
+        Context::send_event(handle, Event::Resized(event::Resized { size }));
+    }
+}
+
+pub(crate) fn on_activate(handle: WindowHandle, active: bool) {
+    let Some(prev) = Context::get_window_props(handle, |props| props.active) else {
+        return;
+    };
+    if prev == active {
+        return;
+    }
+    Context::set_window_props(handle, |props| props.active = active);
+    if active {
+        Context::send_event(handle, Event::Activated);
+    } else {
+        Context::send_event(handle, Event::Inactivated);
+    }
+    on_focus(handle, active);
+}
+
+pub(crate) fn on_focus(handle: WindowHandle, focused: bool) {
+    let Some(prev) = Context::get_window_props(handle, |props| props.focused) else {
+        return;
+    };
+    if prev == focused {
+        return;
+    }
+    Context::set_window_props(handle, |props| props.focused = focused);
+    Context::send_event(handle, Event::Focused(event::Focused { focused }));
+}
+
+pub(crate) fn on_dpi_changed(handle: WindowHandle, new_dpi: u32) {
+    let props = Context::get_window_props(handle, |props| (props.dpi, props.size));
+    let Some((prev_dpi, size)) = props else {
+        return;
+    };
+    let size = PhysicalSize::new(
+        size.width * new_dpi / prev_dpi,
+        size.height * new_dpi / prev_dpi,
+    );
+    Context::set_window_props(handle, |props| props.dpi = new_dpi);
+    on_size(handle, size);
+    Context::send_event(handle, Event::DpiChanged(event::DpiChanged { new_dpi }));
+}
+
+pub(crate) fn on_nc_hittest(
+    handle: WindowHandle,
+    position: PhysicalPosition<i32>,
+    reply: oneshot::Sender<Option<NcHitTestValue>>,
+) {
+    let hook = Context::get_window_props(handle, |props| props.nc_hittest);
+    if !hook.unwrap_or(false) {
+        reply.send(None).ok();
+        return;
+    }
+    let (tx, rx) = oneshot::channel();
+    Context::send_event(
+        handle,
+        Event::NcHitTest(event::NcHitTest::new(position, tx)),
+    );
+    reply.send(rx.blocking_recv().ok().flatten()).ok();
+}
+
+pub(crate) fn on_close(handle: WindowHandle) {
+    let Some(auto_close) = Context::get_window_props(handle, |props| props.auto_close) else {
+        return;
+    };
+    if auto_close {
+        on_destroy(handle);
+        return;
+    }
+    Context::send_event(
+        handle,
+        Event::CloseRequest(event::CloseRequest::new(handle)),
+    );
+}
+
+pub(crate) fn on_destroy(handle: WindowHandle) {
+    if Context::window_is_none(handle) {
+        return;
+    }
+    Context::send_event(handle, Event::Closed);
+    let modal = Context::remove_window(handle).and_then(|obj| obj.props.modal);
+    if let Some(modal) = modal {
+        on_enable(modal.owner, true);
+        on_activate(modal.owner, true);
+        modal.finish();
+    }
+    if Context::is_empty() {
+        ui_thread::post_quit_message(0);
+    }
+}
+
+pub(crate) fn on_enable(handle: WindowHandle, enabled: bool) {
+    if Context::window_is_none(handle) {
+        return;
+    }
+    Context::set_window_props(handle, |props| props.enabled = enabled);
+}
+
+pub(crate) fn on_app(handle: WindowHandle, app: event::App) {
+    Context::send_event(handle, Event::App(app));
+}
+
+pub(crate) fn change_color_mode(handle: WindowHandle, color_mode: ColorMode) {
+    let Some(prev_color_mode_state) =
+        Context::get_window_props(handle, |props| props.color_mode_state)
+    else {
+        return;
+    };
+    let color_mode_state = match color_mode {
+        ColorMode::Dark => ColorModeState::Dark,
+        ColorMode::System if is_system_dark_mode() => ColorModeState::Dark,
+        _ => ColorModeState::Light,
+    };
+    Context::set_window_props(handle, |props| {
+        props.color_mode = color_mode;
+        props.color_mode_state = color_mode_state;
+    });
+    if color_mode_state == prev_color_mode_state {
+        return;
+    }
+    Context::send_event(
+        handle,
+        Event::ColorModeChanged(event::ColorModeChanged {
+            current: color_mode_state,
+            previous: prev_color_mode_state,
+        }),
+    );
+}
+
+pub(crate) fn on_setting_change(dark_mode: bool) {
+    SYSTEM_DARK_MODE.store(dark_mode, atomic::Ordering::SeqCst);
+    for handle in Context::window_handles() {
+        if let Some(color_mode) = Context::get_window_props(handle, |props| props.color_mode) {
+            change_color_mode(handle, color_mode);
+        }
+    }
+}
+
+pub(crate) fn on_display_change(monitors: Vec<MonitorInfo>) {
+    monitor::set_simulated_monitors(monitors);
+    for handle in Context::window_handles() {
+        Context::send_event(handle, Event::MonitorsChanged);
+    }
+}
@@ -0,0 +1,692 @@
+use super::{context::Context, procedure};
+use crate::*;
+use std::collections::HashSet;
+use std::sync::atomic::{self, AtomicU64};
+use tokio::sync::oneshot;
+
+/// The state of a window saved when entering fullscreen.
+pub(crate) struct FullscreenState {
+    pub fullscreen: Fullscreen,
+    pub position: PhysicalPosition<i32>,
+    pub size: PhysicalSize<u32>,
+    pub maximized: bool,
+}
+
+pub(crate) struct WindowProps {
+    pub title: String,
+    pub resizable: bool,
+    pub position: PhysicalPosition<i32>,
+    pub size: PhysicalSize<u32>,
+    pub dpi: u32,
+    pub visible: bool,
+    pub active: bool,
+    pub focused: bool,
+    pub topmost: bool,
+    pub ime_enabled: bool,
+    pub visible_ime_candidate_window: bool,
+    pub auto_close: bool,
+    pub parent: Option<WindowHandle>,
+    pub enabled: bool,
+    pub modal: Option<ModalState>,
+    pub nc_hittest: bool,
+    pub size_constraints: SizeConstraints,
+    pub auto_capture: bool,
+    pub capturing: bool,
+    pub auto_captured: bool,
+    pub cursor_confine: Option<PhysicalRect<i32>>,
+    pub cursor_hidden: bool,
+    pub cursor_locked: bool,
+    pub redrawing: bool,
+    pub minimized: bool,
+    pub maximized: bool,
+    pub fullscreen: Option<FullscreenState>,
+    pub entered: bool,
+    pub buttons: Vec<MouseButton>,
+    pub keys: HashSet<VirtualKey>,
+    pub modifiers: ModifierState,
+    pub accelerators: Accelerators,
+    pub batch_text_input: bool,
+    pub pointer_input: bool,
+    pub pointers: pointer::PointerTracker,
+    pub gestures: bool,
+    pub gesture_recognizer: gesture::GestureRecognizer,
+    pub input_clock: std::time::Instant,
+    pub wheel: wheel::WheelAccumulator,
+    pub wheel_mouse_state: Option<MouseState>,
+    pub clicks: click::ClickTracker,
+    pub raw_input: RawInputDevices,
+    pub utf16: text::Utf16Decoder,
+    pub color_mode: ColorMode,
+    pub color_mode_state: ColorModeState,
+}
+
+impl WindowProps {
+    pub fn mouse_state(&self, position: PhysicalPosition<i32>) -> MouseState {
+        MouseState {
+            position,
+            buttons: MouseButtons::from(&self.buttons),
+            keys: MouseStateVirtualKeys::new(
+                self.keys.contains(&VirtualKey::Ctrl),
+                self.keys.contains(&VirtualKey::Shift),
+            ),
+            modifiers: self.modifier_state(),
+        }
+    }
+
+    /// Pressed modifier keys of the window and toggled lock keys of the UI thread.
+    pub fn modifier_state(&self) -> ModifierState {
+        self.modifiers | procedure::lock_keys()
+    }
+}
+
+pub fn create_window<Pos, Sz, Dt>(
+    props: BuilderProps<Pos, Sz, Dt>,
+    f: impl FnOnce(WindowHandle) -> WindowKind,
+) -> Result<WindowHandle>
+where
+    Pos: ToPhysical<i32, Output<i32> = PhysicalPosition<i32>> + Send + 'static,
+    Sz: ToPhysical<u32, Output<u32> = PhysicalSize<u32>> + Send + 'static,
+{
+    let parent = props.parent_inner.or(props.parent);
+    let dpi = props
+        .dpi
+        .or_else(|| parent.and_then(|p| Context::get_window_props(p, |props| props.dpi)))
+        .unwrap_or(DEFAULT_DPI);
+    let mut position = props.position.to_physical(dpi as i32);
+    let mut size = props.inner_size.to_physical(dpi);
+    let mut dpi = dpi;
+    if let Some(placement) = props.placement.as_ref() {
+        let monitors = Monitor::all()
+            .iter()
+            .filter_map(|m| m.info())
+            .collect::<Vec<_>>();
+        let rc = placement.resolve(&monitors);
+        position = PhysicalPosition::new(rc.left, rc.top);
+        size = PhysicalSize::new((rc.right - rc.left) as u32, (rc.bottom - rc.top) as u32);
+        if let Some(monitor) = placement.target_monitor(&monitors) {
+            dpi = monitor.dpi;
+        }
+    }
+    let state = props.placement.as_ref().map(|placement| placement.state);
+    let modal_owner = props
+        .parent
+        .filter(|owner| props.owned && props.modal && !Context::window_is_none(*owner));
+    let handle = {
+        static ID: AtomicU64 = AtomicU64::new(1);
+        WindowHandle::from_raw_id(ID.fetch_add(1, atomic::Ordering::SeqCst))
+    };
+    let dark_mode = procedure::is_system_dark_mode();
+    if dark_mode {
+        info!("Dark mode");
+    } else {
+        info!("Light mode");
+    }
+    let window_props = WindowProps {
+        title: props.title,
+        resizable: true,
+        position,
+        size,
+        dpi,
+        visible: props.visiblity,
+        active: false,
+        focused: false,
+        topmost: props.always_on_top,
+        ime_enabled: props.enable_ime,
+        visible_ime_candidate_window: props.visible_ime_candidate_window,
+        auto_close: props.auto_close,
+        parent,
+        enabled: true,
+        modal: modal_owner.map(ModalState::new),
+        nc_hittest: props.nc_hittest,
+        size_constraints: props.size_constraints,
+        auto_capture: props.auto_capture,
+        capturing: false,
+        auto_captured: false,
+        cursor_confine: None,
+        cursor_hidden: false,
+        cursor_locked: false,
+        redrawing: false,
+        minimized: state == Some(PlacementState::Minimized),
+        maximized: state == Some(PlacementState::Maximized),
+        fullscreen: None,
+        entered: false,
+        buttons: vec![],
+        keys: HashSet::new(),
+        modifiers: ModifierState::empty(),
+        accelerators: props.accelerators,
+        batch_text_input: props.batch_text_input,
+        pointer_input: props.pointer_input,
+        pointers: pointer::PointerTracker::new(),
+        gestures: props.gestures,
+        gesture_recognizer: gesture::GestureRecognizer::new(),
+        input_clock: std::time::Instant::now(),
+        wheel: wheel::WheelAccumulator::new(),
+        wheel_mouse_state: None,
+        clicks: click::ClickTracker::new(),
+        raw_input: props.raw_input,
+        utf16: text::Utf16Decoder::new(),
+        color_mode: props.color_mode,
+        color_mode_state: if dark_mode {
+            ColorModeState::Dark
+        } else {
+            ColorModeState::Light
+        },
+    };
+    Context::register_window(f(handle), window_props, props.event_rx_id);
+    if let Some(owner) = modal_owner {
+        procedure::on_enable(owner, false);
+    }
+    Context::send_event(handle, Event::Resized(event::Resized { size }));
+    if props.visiblity {
+        procedure::on_show(handle);
+    }
+    Ok(handle)
+}
+
+#[inline]
+pub fn position(handle: WindowHandle) -> oneshot::Receiver<ScreenPosition<i32>> {
+    let (tx, rx) = oneshot::channel::<ScreenPosition<i32>>();
+    UiThread::send_task(move || {
+        if let Some(position) = Context::get_window_props(handle, |props| props.position) {
+            tx.send(ScreenPosition::new(position.x, position.y)).ok();
+        }
+    });
+    rx
+}
+
+/// The position of an inner window is kept relative to the parent.
+#[inline]
+pub fn inner_position(
+    handle: WindowHandle,
+    _parent: WindowHandle,
+) -> oneshot::Receiver<PhysicalPosition<i32>> {
+    let (tx, rx) = oneshot::channel::<PhysicalPosition<i32>>();
+    UiThread::send_task(move || {
+        if let Some(position) = Context::get_window_props(handle, |props| props.position) {
+            tx.send(position).ok();
+        }
+    });
+    rx
+}
+
+#[inline]
+pub fn is_closed(handle: WindowHandle) -> bool {
+    Context::window_is_none(handle)
+}
+
+#[inline]
+pub fn inner_size(handle: WindowHandle) -> oneshot::Receiver<PhysicalSize<u32>> {
+    let (tx, rx) = oneshot::channel::<PhysicalSize<u32>>();
+    UiThread::send_task(move || {
+        if let Some(size) = Context::get_window_props(handle, |props| props.size) {
+            tx.send(size).ok();
+        }
+    });
+    rx
+}
+
+#[inline]
+pub fn dpi(handle: WindowHandle) -> oneshot::Receiver<u32> {
+    let (tx, rx) = oneshot::channel::<u32>();
+    UiThread::send_task(move || {
+        if let Some(dpi) = Context::get_window_props(handle, |props| props.dpi) {
+            tx.send(dpi).ok();
+        }
+    });
+    rx
+}
+
+#[inline]
+pub fn enable_ime(handle: WindowHandle, enabled: bool) -> oneshot::Receiver<()> {
+    let (tx, rx) = oneshot::channel::<()>();
+    UiThread::send_task(move || {
+        if Context::window_is_none(handle) {
+            return;
+        }
+        Context::set_window_props(handle, |props| props.ime_enabled = enabled);
+        tx.send(()).ok();
+    });
+    rx
+}
+
+#[inline]
+pub fn set_position<T>(handle: WindowHandle, position: T)
+where
+    T: ToPhysical<i32, Output<i32> = PhysicalPosition<i32>> + Send + 'static,
+{
+    UiThread::send_task(move || {
+        let Some(dpi) = Context::get_window_props(handle, |props| props.dpi) else {
+            return;
+        };
+        procedure::on_move(handle, position.to_physical(dpi as i32));
+    });
+}
+
+#[inline]
+pub fn set_size<T>(handle: WindowHandle, size: T)
+where
+    T: ToPhysical<u32, Output<u32> = PhysicalSize<u32>> + Send + 'static,
+{
+    UiThread::send_task(move || {
+        let Some(dpi) = Context::get_window_props(handle, |props| props.dpi) else {
+            return;
+        };
+        procedure::on_size(handle, size.to_physical(dpi));
+    });
+}
+
+#[inline]
+pub fn close(handle: WindowHandle) {
+    UiThread::send_task(move || procedure::on_close(handle));
+}
+
+#[inline]
+pub fn show(handle: WindowHandle) {
+    UiThread::send_task(move || procedure::on_show(handle));
+}
+
+#[inline]
+pub fn hide(handle: WindowHandle) {
+    UiThread::send_task(move || procedure::on_hide(handle));
+}
+
+#[inline]
+pub fn minimize(handle: WindowHandle) {
+    UiThread::send_task(move || procedure::on_minimize(handle));
+}
+
+#[inline]
+pub fn maximize(handle: WindowHandle) {
+    UiThread::send_task(move || procedure::on_maximize(handle));
+}
+
+#[inline]
+pub fn restore(handle: WindowHandle) {
+    UiThread::send_task(move || procedure::on_restore(handle));
+}
+
+#[inline]
+pub fn set_capture(handle: WindowHandle) {
+    UiThread::send_task(move || {
+        Context::set_window_props(handle, |props| {
+            props.capturing = true;
+            props.auto_captured = false;
+        });
+    });
+}
+
+#[inline]
+pub fn release_capture(handle: WindowHandle) {
+    UiThread::send_task(move || {
+        Context::set_window_props(handle, |props| {
+            props.capturing = false;
+            props.auto_captured = false;
+        });
+    });
+}
+
+#[inline]
+pub fn confine_cursor(handle: WindowHandle, rect: Option<PhysicalRect<i32>>) {
+    UiThread::send_task(move || {
+        Context::set_window_props(handle, |props| props.cursor_confine = rect);
+    });
+}
+
+#[inline]
+pub fn hide_cursor(handle: WindowHandle, hidden: bool) {
+    UiThread::send_task(move || {
+        Context::set_window_props(handle, |props| props.cursor_hidden = hidden);
+    });
+}
+
+#[inline]
+pub fn lock_cursor(handle: WindowHandle, locked: bool) {
+    UiThread::send_task(move || {
+        Context::set_window_props(handle, |props| props.cursor_locked = locked);
+    });
+}
+
+#[inline]
+pub fn accelerators(handle: WindowHandle) -> Option<Accelerators> {
+    Context::get_window_props(handle, |props| props.accelerators.clone())
+}
+
+#[inline]
+pub fn set_accelerators(handle: WindowHandle, accelerators: Accelerators) {
+    UiThread::send_task(move || {
+        Context::set_window_props(handle, |props| {
+            props.accelerators = accelerators;
+        });
+    });
+}
+
+#[inline]
+pub fn raw_input(handle: WindowHandle) -> Option<RawInputDevices> {
+    Context::get_window_props(handle, |props| props.raw_input.clone())
+}
+
+#[inline]
+pub fn set_raw_input(handle: WindowHandle, devices: RawInputDevices) {
+    UiThread::send_task(move || {
+        Context::set_window_props(handle, |props| {
+            props.raw_input = devices;
+        });
+    });
+}
+
+#[inline]
+pub fn redraw(handle: WindowHandle, invalidate_rect: Option<PhysicalRect<i32>>) {
+    UiThread::send_task(move || {
+        let props = Context::get_window_props(handle, |props| (props.redrawing, props.size));
+        let Some((redrawing, size)) = props else {
+            return;
+        };
+        if !redrawing {
+            Context::set_window_props(handle, |props| props.redrawing = true);
+            let invalidate_rect = invalidate_rect.unwrap_or(PhysicalRect::new(
+                0,
+                0,
+                size.width as i32,
+                size.height as i32,
+            ));
+            UiThread::send_task(move || procedure::on_paint(handle, invalidate_rect));
+        }
+    });
+}
+
+#[inline]
+pub fn title(handle: WindowHandle) -> oneshot::Receiver<String> {
+    let (tx, rx) = oneshot::channel::<String>();
+    UiThread::send_task(move || {
+        if let Some(title) = Context::get_window_props(handle, |props| props.title.clone()) {
+            tx.send(title).ok();
+        }
+    });
+    rx
+}
+
+#[inline]
+pub fn set_title(handle: WindowHandle, title: String) {
+    UiThread::send_task(move || {
+        Context::set_window_props(handle, |props| props.title = title);
+    });
+}
+
+#[inline]
+pub fn set_resizable(handle: WindowHandle, flag: bool) {
+    UiThread::send_task(move || {
+        Context::set_window_props(handle, |props| props.resizable = flag);
+    });
+}
+
+#[inline]
+pub fn size_constraints(handle: WindowHandle) -> Option<SizeConstraints> {
+    Context::get_window_props(handle, |props| props.size_constraints)
+}
+
+#[inline]
+pub fn set_size_constraints(handle: WindowHandle, constraints: SizeConstraints) {
+    UiThread::send_task(move || {
+        Context::set_window_props(handle, |props| props.size_constraints = constraints);
+        let props = Context::get_window_props(handle, |props| {
+            (props.size, props.dpi, props.minimized || props.maximized)
+        });
+        let Some((size, dpi, zoomed)) = props else {
+            return;
+        };
+        let constrained = constraints.constrain(size, ResizingEdge::BottomRight, dpi);
+        if !zoomed && constrained != size {
+            procedure::on_size(handle, constrained);
+        }
+    });
+}
+
+#[inline]
+pub fn current_monitor(handle: WindowHandle) -> Option<Monitor> {
+    Context::get_window_props(handle, |props| {
+        Monitor::from_point(ScreenPosition::new(
+            props.position.x + props.size.width as i32 / 2,
+            props.position.y + props.size.height as i32 / 2,
+        ))
+    })
+}
+
+pub fn placement(handle: WindowHandle) -> Option<WindowPlacement> {
+    let props = Context::get_window_props(handle, |props| {
+        let (position, size, maximized) = match props.fullscreen.as_ref() {
+            Some(state) => (state.position, state.size, state.maximized),
+            None => (props.position, props.size, props.maximized),
+        };
+        let state = if props.minimized {
+            PlacementState::Minimized
+        } else if maximized {
+            PlacementState::Maximized
+        } else {
+            PlacementState::Normal
+        };
+        (position, size, state, props.dpi)
+    });
+    let (position, size, state, dpi) = props?;
+    let monitor = current_monitor(handle)?;
+    Some(WindowPlacement {
+        rect: ScreenRect::new(
+            position.x,
+            position.y,
+            position.x + size.width as i32,
+            position.y + size.height as i32,
+        ),
+        state,
+        monitor_id: monitor
+            .info()
+            .map(|info| info.device_id)
+            .unwrap_or_default(),
+        dpi,
+    })
+}
+
+#[inline]
+pub fn fullscreen(handle: WindowHandle) -> Option<Fullscreen> {
+    Context::get_window_props(handle, |props| {
+        props.fullscreen.as_ref().map(|state| state.fullscreen)
+    })
+    .flatten()
+}
+
+fn enter_fullscreen(handle: WindowHandle, fullscreen: Fullscreen) {
+    let props = Context::get_window_props(handle, |props| {
+        (
+            props.fullscreen.is_some(),
+            props.position,
+            props.size,
+            props.maximized,
+        )
+    });
+    let Some((entered, position, size, maximized)) = props else {
+        return;
+    };
+    let monitor = match fullscreen {
+        Fullscreen::Borderless(monitor) => {
+            monitor.unwrap_or_else(|| current_monitor(handle).unwrap_or(Monitor::primary()))
+        }
+    };
+    let Some(rc) = monitor.bounds() else {
+        error!("the monitor for fullscreen was not found");
+        return;
+    };
+    Context::set_window_props(handle, |props| match props.fullscreen.as_mut() {
+        Some(state) => state.fullscreen = fullscreen,
+        None => {
+            props.fullscreen = Some(FullscreenState {
+                fullscreen,
+                position,
+                size,
+                maximized,
+            });
+        }
+    });
+    procedure::on_move(handle, PhysicalPosition::new(rc.left, rc.top));
+    procedure::on_size(
+        handle,
+        PhysicalSize::new((rc.right - rc.left) as u32, (rc.bottom - rc.top) as u32),
+    );
+    if !entered {
+        Context::send_event(
+            handle,
+            Event::FullscreenChanged(event::FullscreenChanged { fullscreen: true }),
+        );
+    }
+}
+
+fn leave_fullscreen(handle: WindowHandle) {
+    if Context::window_is_none(handle) {
+        return;
+    }
+    let mut state = None;
+    Context::set_window_props(handle, |props| state = props.fullscreen.take());
+    let Some(state) = state else {
+        return;
+    };
+    procedure::on_move(handle, state.position);
+    procedure::on_size(handle, state.size);
+    if state.maximized {
+        procedure::on_maximize(handle);
+    }
+    Context::send_event(
+        handle,
+        Event::FullscreenChanged(event::FullscreenChanged { fullscreen: false }),
+    );
+}
+
+#[inline]
+pub fn set_fullscreen(handle: WindowHandle, fullscreen: Option<Fullscreen>) {
+    UiThread::send_task(move || match fullscreen {
+        Some(fullscreen) => enter_fullscreen(handle, fullscreen),
+        None => leave_fullscreen(handle),
+    });
+}
+
+fn query(handle: WindowHandle, f: fn(&WindowProps) -> bool) -> oneshot::Receiver<bool> {
+    let (tx, rx) = oneshot::channel::<bool>();
+    UiThread::send_task(move || {
+        if let Some(value) = Context::get_window_props(handle, f) {
+            tx.send(value).ok();
+        }
+    });
+    rx
+}
+
+#[inline]
+pub fn is_visible(handle: WindowHandle) -> oneshot::Receiver<bool> {
+    query(handle, |props| props.visible)
+}
+
+#[inline]
+pub fn is_minimized(handle: WindowHandle) -> oneshot::Receiver<bool> {
+    query(handle, |props| props.minimized)
+}
+
+#[inline]
+pub fn is_maximized(handle: WindowHandle) -> oneshot::Receiver<bool> {
+    query(handle, |props| props.maximized)
+}
+
+#[inline]
+pub fn has_focus(handle: WindowHandle) -> oneshot::Receiver<bool> {
+    query(handle, |props| props.focused)
+}
+
+#[inline]
+pub fn is_active(handle: WindowHandle) -> oneshot::Receiver<bool> {
+    query(handle, |props| props.active)
+}
+
+#[inline]
+pub fn is_topmost(handle: WindowHandle) -> oneshot::Receiver<bool> {
+    query(handle, |props| props.topmost)
+}
+
+#[inline]
+pub fn set_topmost(handle: WindowHandle, topmost: bool) {
+    UiThread::send_task(move || {
+        if Context::window_is_none(handle) {
+            return;
+        }
+        Context::set_window_props(handle, |props| props.topmost = topmost);
+    });
+}
+
+#[inline]
+pub fn show_without_activation(handle: WindowHandle) {
+    UiThread::send_task(move || procedure::on_show_without_activation(handle));
+}
+
+#[inline]
+pub fn minimize_without_activation(handle: WindowHandle) {
+    UiThread::send_task(move || {
+        procedure::on_show_without_activation(handle);
+        procedure::on_minimize(handle);
+    });
+}
+
+#[inline]
+pub fn is_enabled(handle: WindowHandle) -> oneshot::Receiver<bool> {
+    query(handle, |props| props.enabled)
+}
+
+#[inline]
+pub fn modal_result(handle: WindowHandle) -> ModalResult {
+    let (tx, rx) = oneshot::channel::<Option<usize>>();
+    UiThread::send_task(move || {
+        if Context::window_is_none(handle) {
+            return;
+        }
+        Context::set_window_props(handle, |props| {
+            if let Some(modal) = props.modal.as_mut() {
+                modal.waiters.push(tx);
+            }
+        });
+    });
+    ModalResult::new(rx)
+}
+
+#[inline]
+pub fn end_modal(handle: WindowHandle, result: usize) {
+    UiThread::send_task(move || {
+        if Context::window_is_none(handle) {
+            return;
+        }
+        Context::set_window_props(handle, |props| {
+            if let Some(modal) = props.modal.as_mut() {
+                modal.result = Some(result);
+            }
+        });
+        procedure::on_destroy(handle);
+    });
+}
+
+#[inline]
+pub fn post_app_event(handle: WindowHandle, app: event::App) {
+    UiThread::send_task(move || procedure::on_app(handle, app));
+}
+
+#[inline]
+pub fn set_foreground(handle: WindowHandle) {
+    UiThread::send_task(move || procedure::on_activate(handle, true));
+}
+
+#[inline]
+pub fn set_focus(handle: WindowHandle) {
+    UiThread::send_task(move || procedure::on_activate(handle, true));
+}
+
+#[inline]
+pub fn color_mode(handle: WindowHandle) -> Option<ColorMode> {
+    Context::get_window_props(handle, |props| props.color_mode)
+}
+
+#[inline]
+pub fn set_color_mode(handle: WindowHandle, mode: ColorMode) {
+    UiThread::send_task(move || {
+        procedure::change_color_mode(handle, mode);
+    });
+}
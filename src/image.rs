@@ -0,0 +1,517 @@
+//! Images of cursors and icons.
+//!
+//! [`IconDir`] and [`AniCursor`] decode the containers of `.ico`, `.cur` and `.ani` files in pure
+//! Rust. The images in the entries are kept as DIB or PNG bytes, which
+//! `CreateIconFromResourceEx` accepts as is. [`RgbaImage`] holds pixels passed by applications
+//! and scales them for DPIs.
+//!
+//! ```
+//! use wiard::image::*;
+//!
+//! let image = RgbaImage::new(2, 2, vec![255; 2 * 2 * 4]).unwrap();
+//! let scaled = image.resize(wiard::PhysicalSize::new(3, 3));
+//! assert_eq!(scaled.pixels().len(), 3 * 3 * 4);
+//! ```
+
+use crate::*;
+use std::time::Duration;
+
+/// The error type for decoding images.
+#[derive(Debug, thiserror::Error)]
+pub enum ImageError {
+    #[error("TooShort")]
+    TooShort,
+    #[error("InvalidHeader")]
+    InvalidHeader,
+    #[error("UnknownType: {0}")]
+    UnknownType(u16),
+    #[error("NoImages")]
+    NoImages,
+    #[error("EmptyImage")]
+    EmptyImage,
+    #[error("InvalidPixels: expected {expected} bytes, actual {actual} bytes")]
+    InvalidPixels { expected: usize, actual: usize },
+}
+
+type Result<T> = std::result::Result<T, ImageError>;
+
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&self, offset: usize, len: usize) -> Result<&'a [u8]> {
+        offset
+            .checked_add(len)
+            .and_then(|end| self.data.get(offset..end))
+            .ok_or(ImageError::TooShort)
+    }
+
+    fn u16(&self, offset: usize) -> Result<u16> {
+        self.bytes(offset, 2)
+            .map(|b| u16::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    fn u32(&self, offset: usize) -> Result<u32> {
+        self.bytes(offset, 4)
+            .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+    }
+}
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// The type of an ICO directory.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum IconKind {
+    Icon,
+    Cursor,
+}
+
+/// An image in an ICO directory.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct IconEntry {
+    pub size: PhysicalSize<u32>,
+    /// The hotspot of a cursor. It is always `(0, 0)` for icons.
+    pub hotspot: PhysicalPosition<u32>,
+    pub bit_count: u16,
+    /// DIB or PNG bytes.
+    pub data: Vec<u8>,
+}
+
+impl IconEntry {
+    #[inline]
+    pub fn is_png(&self) -> bool {
+        self.data.starts_with(PNG_SIGNATURE)
+    }
+}
+
+/// The directory of `.ico` and `.cur` files.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct IconDir {
+    pub kind: IconKind,
+    pub entries: Vec<IconEntry>,
+}
+
+impl IconDir {
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let r = Reader { data };
+        if r.u16(0)? != 0 {
+            return Err(ImageError::InvalidHeader);
+        }
+        let kind = match r.u16(2)? {
+            1 => IconKind::Icon,
+            2 => IconKind::Cursor,
+            t => return Err(ImageError::UnknownType(t)),
+        };
+        let count = r.u16(4)? as usize;
+        if count == 0 {
+            return Err(ImageError::NoImages);
+        }
+        let entries = (0..count)
+            .map(|i| {
+                let e = 6 + i * 16;
+                let entry = r.bytes(e, 16)?;
+                let dir_size = |v: u8| if v == 0 { 256 } else { v as u32 };
+                let len = r.u32(e + 8)? as usize;
+                let offset = r.u32(e + 12)? as usize;
+                let data = r.bytes(offset, len)?.to_vec();
+                let (size, bit_count) = image_header(&data)?.unwrap_or((
+                    PhysicalSize::new(dir_size(entry[0]), dir_size(entry[1])),
+                    r.u16(e + 6)?,
+                ));
+                let hotspot = match kind {
+                    IconKind::Icon => PhysicalPosition::new(0, 0),
+                    IconKind::Cursor => {
+                        PhysicalPosition::new(r.u16(e + 4)? as u32, r.u16(e + 6)? as u32)
+                    }
+                };
+                Ok(IconEntry {
+                    size,
+                    hotspot,
+                    bit_count,
+                    data,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { kind, entries })
+    }
+
+    /// Returns the smallest entry not smaller than `size`, or the largest entry.
+    ///
+    /// Entries with more colors are preferred among entries of the same size.
+    pub fn best_entry(&self, size: u32) -> Option<&IconEntry> {
+        let key = |e: &&IconEntry| (e.size.width.max(e.size.height), e.bit_count);
+        let larger = self
+            .entries
+            .iter()
+            .filter(|e| e.size.width.max(e.size.height) >= size)
+            .min_by_key(|e| (key(e).0, std::cmp::Reverse(e.bit_count)));
+        larger.or_else(|| self.entries.iter().max_by_key(key))
+    }
+}
+
+/// Reads the size and the bit count from the header of DIB or PNG bytes.
+fn image_header(data: &[u8]) -> Result<Option<(PhysicalSize<u32>, u16)>> {
+    let r = Reader { data };
+    if data.starts_with(PNG_SIGNATURE) {
+        let ihdr = r.bytes(16, 8)?;
+        let width = u32::from_be_bytes(ihdr[0..4].try_into().unwrap());
+        let height = u32::from_be_bytes(ihdr[4..8].try_into().unwrap());
+        return Ok(Some((PhysicalSize::new(width, height), 32)));
+    }
+    if r.u32(0)? < 40 {
+        return Ok(None);
+    }
+    let width = r.u32(4)? as i32;
+    let height = r.u32(8)? as i32;
+    if width <= 0 || height == 0 {
+        return Err(ImageError::InvalidHeader);
+    }
+    let size = PhysicalSize::new(width as u32, height.unsigned_abs() / 2);
+    Ok(Some((size, r.u16(14)?)))
+}
+
+/// A step of an animated cursor.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct AniStep {
+    /// The index of `AniCursor::frames`.
+    pub frame: usize,
+    pub duration: Duration,
+}
+
+/// An animated cursor of `.ani` files.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AniCursor {
+    pub frames: Vec<IconDir>,
+    pub steps: Vec<AniStep>,
+}
+
+const AF_ICON: u32 = 0x1;
+
+fn jiffies(n: u32) -> Duration {
+    Duration::from_micros(n as u64 * 1_000_000 / 60)
+}
+
+fn chunks(data: &[u8]) -> impl Iterator<Item = Result<(&[u8], &[u8])>> {
+    let mut offset = 0;
+    std::iter::from_fn(move || {
+        if offset >= data.len() {
+            return None;
+        }
+        let r = Reader { data };
+        let chunk = r.bytes(offset, 4).and_then(|id| {
+            let len = r.u32(offset + 4)? as usize;
+            let body = r.bytes(offset + 8, len)?;
+            offset += 8 + len + (len & 1);
+            Ok((id, body))
+        });
+        if chunk.is_err() {
+            offset = data.len();
+        }
+        Some(chunk)
+    })
+}
+
+impl AniCursor {
+    /// Checks if `data` starts with the RIFF header of `.ani` files.
+    #[inline]
+    pub fn is_ani(data: &[u8]) -> bool {
+        data.len() >= 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"ACON"
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        if !Self::is_ani(data) {
+            return Err(ImageError::InvalidHeader);
+        }
+        let r = Reader { data };
+        let len = (r.u32(4)? as usize).min(data.len() - 8);
+        let mut header = None;
+        let mut rates = None;
+        let mut seq = None;
+        let mut frames = vec![];
+        let body = data.get(12..8 + len).ok_or(ImageError::TooShort)?;
+        for chunk in chunks(body) {
+            let (id, body) = chunk?;
+            let r = Reader { data: body };
+            let list = || {
+                (0..body.len() / 4)
+                    .map(|i| r.u32(i * 4))
+                    .collect::<Result<Vec<_>>>()
+            };
+            match id {
+                b"anih" => header = Some((r.u32(4)?, r.u32(8)?, r.u32(28)?, r.u32(32)?)),
+                b"rate" => rates = Some(list()?),
+                b"seq " => seq = Some(list()?),
+                b"LIST" if r.bytes(0, 4)? == b"fram" => {
+                    for chunk in chunks(&body[4..]) {
+                        let (id, body) = chunk?;
+                        if id == b"icon" {
+                            frames.push(IconDir::decode(body)?);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        let Some((frame_count, step_count, rate, flags)) = header else {
+            return Err(ImageError::InvalidHeader);
+        };
+        if flags & AF_ICON == 0 {
+            return Err(ImageError::InvalidHeader);
+        }
+        if frames.is_empty() || frames.len() != frame_count as usize {
+            return Err(ImageError::NoImages);
+        }
+        let steps = (0..step_count as usize)
+            .map(|i| {
+                let frame = match &seq {
+                    Some(seq) => *seq.get(i).ok_or(ImageError::TooShort)? as usize,
+                    None => i % frames.len(),
+                };
+                if frame >= frames.len() {
+                    return Err(ImageError::InvalidHeader);
+                }
+                let rate = match &rates {
+                    Some(rates) => *rates.get(i).ok_or(ImageError::TooShort)?,
+                    None => rate,
+                };
+                Ok(AniStep {
+                    frame,
+                    duration: jiffies(rate),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if steps.is_empty() {
+            return Err(ImageError::NoImages);
+        }
+        Ok(Self { frames, steps })
+    }
+}
+
+/// Non-premultiplied RGBA pixels.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RgbaImage {
+    size: PhysicalSize<u32>,
+    pixels: Vec<u8>,
+}
+
+impl RgbaImage {
+    /// `pixels` must have `width * height * 4` bytes in rows from the top.
+    pub fn new(width: u32, height: u32, pixels: Vec<u8>) -> Result<Self> {
+        if width == 0 || height == 0 {
+            return Err(ImageError::EmptyImage);
+        }
+        let expected = width as usize * height as usize * 4;
+        if pixels.len() != expected {
+            return Err(ImageError::InvalidPixels {
+                expected,
+                actual: pixels.len(),
+            });
+        }
+        Ok(Self {
+            size: PhysicalSize::new(width, height),
+            pixels,
+        })
+    }
+
+    #[inline]
+    pub fn size(&self) -> PhysicalSize<u32> {
+        self.size
+    }
+
+    #[inline]
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Scales the image with bilinear filtering in the premultiplied space.
+    pub fn resize(&self, size: PhysicalSize<u32>) -> Self {
+        let size = PhysicalSize::new(size.width.max(1), size.height.max(1));
+        if size == self.size {
+            return self.clone();
+        }
+        let (sw, sh) = (self.size.width as usize, self.size.height as usize);
+        let (dw, dh) = (size.width as usize, size.height as usize);
+        let sample = |d: usize, dn: usize, sn: usize| {
+            let f = ((d as f32 + 0.5) * sn as f32 / dn as f32 - 0.5).clamp(0.0, (sn - 1) as f32);
+            let i = f as usize;
+            (i, (i + 1).min(sn - 1), f - i as f32)
+        };
+        let mut pixels = vec![0; dw * dh * 4];
+        for y in 0..dh {
+            let (y0, y1, ty) = sample(y, dh, sh);
+            for x in 0..dw {
+                let (x0, x1, tx) = sample(x, dw, sw);
+                let mut acc = [0.0f32; 4];
+                for (sx, sy, w) in [
+                    (x0, y0, (1.0 - tx) * (1.0 - ty)),
+                    (x1, y0, tx * (1.0 - ty)),
+                    (x0, y1, (1.0 - tx) * ty),
+                    (x1, y1, tx * ty),
+                ] {
+                    let p = &self.pixels[(sy * sw + sx) * 4..][..4];
+                    let a = p[3] as f32 * w;
+                    for i in 0..3 {
+                        acc[i] += p[i] as f32 * a;
+                    }
+                    acc[3] += a;
+                }
+                if acc[3] > 0.0 {
+                    let out = &mut pixels[(y * dw + x) * 4..][..4];
+                    for i in 0..3 {
+                        out[i] = (acc[i] / acc[3]).round() as u8;
+                    }
+                    out[3] = acc[3].round() as u8;
+                }
+            }
+        }
+        Self { size, pixels }
+    }
+
+    /// Returns BGRA pixels for `CreateBitmap`.
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub(crate) fn to_bgra(&self) -> Vec<u8> {
+        self.pixels
+            .chunks_exact(4)
+            .flat_map(|p| [p[2], p[1], p[0], p[3]])
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_cur() {
+        let dir = IconDir::decode(include_bytes!("../tests/data/arrow.cur")).unwrap();
+        assert_eq!(dir.kind, IconKind::Cursor);
+        assert_eq!(dir.entries.len(), 2);
+        let small = &dir.entries[0];
+        assert_eq!(small.size, PhysicalSize::new(16, 16));
+        assert_eq!(small.hotspot, PhysicalPosition::new(1, 2));
+        assert_eq!(small.bit_count, 32);
+        assert!(!small.is_png());
+        assert_eq!(
+            dir.best_entry(16).unwrap().hotspot,
+            PhysicalPosition::new(1, 2)
+        );
+        assert_eq!(
+            dir.best_entry(24).unwrap().hotspot,
+            PhysicalPosition::new(2, 4)
+        );
+        assert_eq!(dir.best_entry(48).unwrap().size, PhysicalSize::new(32, 32));
+        assert!(matches!(
+            IconDir::decode(&[0, 0, 3, 0, 1, 0]),
+            Err(ImageError::UnknownType(3))
+        ));
+        assert!(matches!(
+            IconDir::decode(&include_bytes!("../tests/data/arrow.cur")[..100]),
+            Err(ImageError::TooShort)
+        ));
+    }
+
+    fn dib(width: u32, bit_count: u16) -> Vec<u8> {
+        let mut data = vec![];
+        data.extend(40u32.to_le_bytes());
+        data.extend(width.to_le_bytes());
+        data.extend((width * 2).to_le_bytes());
+        data.extend(1u16.to_le_bytes());
+        data.extend(bit_count.to_le_bytes());
+        data.resize(40, 0);
+        data
+    }
+
+    fn ico(images: &[Vec<u8>]) -> Vec<u8> {
+        let mut data = vec![0, 0, 1, 0, images.len() as u8, 0];
+        let mut offset = 6 + images.len() * 16;
+        for image in images {
+            data.extend([0; 8]);
+            data.extend((image.len() as u32).to_le_bytes());
+            data.extend((offset as u32).to_le_bytes());
+            offset += image.len();
+        }
+        data.extend(images.concat());
+        data
+    }
+
+    #[test]
+    fn best_icon_entry() {
+        let data = ico(&[
+            dib(32, 8),
+            dib(16, 32),
+            dib(48, 32),
+            dib(32, 32),
+            dib(256, 32),
+        ]);
+        let dir = IconDir::decode(&data).unwrap();
+        assert_eq!(dir.kind, IconKind::Icon);
+        let best = |size| {
+            let entry = dir.best_entry(size).unwrap();
+            (entry.size.width, entry.bit_count)
+        };
+        assert_eq!(best(16), (16, 32));
+        assert_eq!(best(20), (32, 32));
+        assert_eq!(best(32), (32, 32));
+        assert_eq!(best(40), (48, 32));
+        assert_eq!(best(512), (256, 32));
+    }
+
+    #[test]
+    fn png_entry() {
+        let mut png = PNG_SIGNATURE.to_vec();
+        png.extend([0, 0, 0, 13]);
+        png.extend(b"IHDR");
+        png.extend(48u32.to_be_bytes());
+        png.extend(48u32.to_be_bytes());
+        let mut data = vec![0, 0, 1, 0, 1, 0, 0, 0, 0, 0, 1, 0, 32, 0];
+        data.extend((png.len() as u32).to_le_bytes());
+        data.extend(22u32.to_le_bytes());
+        data.extend(&png);
+        let dir = IconDir::decode(&data).unwrap();
+        assert_eq!(dir.kind, IconKind::Icon);
+        assert!(dir.entries[0].is_png());
+        assert_eq!(dir.entries[0].size, PhysicalSize::new(48, 48));
+    }
+
+    #[test]
+    fn decode_ani() {
+        let data = include_bytes!("../tests/data/busy.ani");
+        assert!(AniCursor::is_ani(data));
+        let ani = AniCursor::decode(data).unwrap();
+        assert_eq!(ani.frames.len(), 2);
+        assert_eq!(
+            ani.frames[0].entries[0].hotspot,
+            PhysicalPosition::new(16, 16)
+        );
+        assert_ne!(ani.frames[0], ani.frames[1]);
+        let steps = ani.steps.iter().map(|s| (s.frame, s.duration.as_millis()));
+        assert_eq!(steps.collect::<Vec<_>>(), [(0, 100), (1, 200), (0, 100)]);
+        assert!(matches!(
+            AniCursor::decode(include_bytes!("../tests/data/arrow.cur")),
+            Err(ImageError::InvalidHeader)
+        ));
+    }
+
+    #[test]
+    fn rgba_image() {
+        assert!(matches!(
+            RgbaImage::new(2, 2, vec![0; 15]),
+            Err(ImageError::InvalidPixels {
+                expected: 16,
+                actual: 15
+            })
+        ));
+        assert!(matches!(
+            RgbaImage::new(0, 2, vec![]),
+            Err(ImageError::EmptyImage)
+        ));
+        let image = RgbaImage::new(2, 1, vec![255, 0, 0, 255, 0, 0, 255, 0]).unwrap();
+        let scaled = image.resize(PhysicalSize::new(4, 2));
+        assert_eq!(scaled.size(), PhysicalSize::new(4, 2));
+        assert_eq!(scaled.pixels()[0..4], [255, 0, 0, 255]);
+        assert_eq!(scaled.pixels()[4..8], [255, 0, 0, 191]);
+        assert_eq!(scaled.pixels()[12..16], [0, 0, 0, 0]);
+        assert_eq!(image.to_bgra(), [0, 0, 255, 255, 255, 0, 0, 0]);
+    }
+}
@@ -1,5 +1,8 @@
+#[cfg(windows)]
 use crate::*;
+#[cfg(windows)]
 use std::cell::{Cell, OnceCell, RefCell};
+#[cfg(windows)]
 use windows::Win32::{
     Foundation::{HWND, POINT, RECT},
     System::Com::*,
@@ -7,11 +10,14 @@ use windows::Win32::{
     UI::Input::KeyboardAndMouse::GetFocus,
     UI::TextServices::*,
 };
+#[cfg(windows)]
 use windows::core::{BOOL, Interface};
 
+#[cfg(windows)]
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 struct ImcHandle(isize);
 
+#[cfg(windows)]
 impl ImcHandle {
     fn new(himc: HIMC) -> Self {
         Self(himc.0 as isize)
@@ -22,12 +28,14 @@ impl ImcHandle {
     }
 }
 
+#[cfg(windows)]
 pub(crate) struct ImmContext {
     window_handle: WindowHandle,
     imc_handle: ImcHandle,
     enabled: Cell<bool>,
 }
 
+#[cfg(windows)]
 impl ImmContext {
     pub fn new(window_handle: WindowHandle) -> Self {
         unsafe {
@@ -68,6 +76,7 @@ impl ImmContext {
     }
 }
 
+#[cfg(windows)]
 impl Drop for ImmContext {
     fn drop(&mut self) {
         unsafe {
@@ -80,6 +89,7 @@ impl Drop for ImmContext {
 
 /// Represents a clause when an IME composition.
 #[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Clause {
     pub range: std::ops::Range<usize>,
     pub targeted: bool,
@@ -99,11 +109,13 @@ impl Ord for Clause {
     }
 }
 
+#[cfg(windows)]
 pub(crate) struct Imc {
     hwnd: HWND,
     himc: HIMC,
 }
 
+#[cfg(windows)]
 impl Imc {
     pub fn get(hwnd: HWND) -> Self {
         let himc = unsafe { ImmGetContext(hwnd) };
@@ -227,6 +239,7 @@ impl Imc {
     }
 }
 
+#[cfg(windows)]
 impl Drop for Imc {
     fn drop(&mut self) {
         unsafe {
@@ -235,11 +248,13 @@ impl Drop for Imc {
     }
 }
 
+#[cfg(windows)]
 struct TextService {
     thread_mgr: RefCell<Option<ITfThreadMgr>>,
     cookie: u32,
 }
 
+#[cfg(windows)]
 impl TextService {
     fn shutdown(&self) {
         let thread_mgr = self.thread_mgr.take().unwrap();
@@ -251,10 +266,12 @@ impl TextService {
     }
 }
 
+#[cfg(windows)]
 thread_local! {
     static TEXT_SERVICE: OnceCell<TextService> = const { OnceCell::new() };
 }
 
+#[cfg(windows)]
 fn thread_mgr() -> ITfThreadMgr {
     TEXT_SERVICE.with(|ts| {
         ts.get()
@@ -267,13 +284,16 @@ fn thread_mgr() -> ITfThreadMgr {
     })
 }
 
+#[cfg(windows)]
 fn ui_element_mgr() -> ITfUIElementMgr {
     thread_mgr().cast().unwrap()
 }
 
+#[cfg(windows)]
 #[windows_core::implement(ITfUIElementSink)]
 struct UiElementSink;
 
+#[cfg(windows)]
 impl UiElementSink {
     #[allow(clippy::new_ret_no_self)]
     fn new() -> ITfUIElementSink {
@@ -281,6 +301,7 @@ impl UiElementSink {
     }
 }
 
+#[cfg(windows)]
 #[allow(non_snake_case)]
 impl ITfUIElementSink_Impl for UiElementSink_Impl {
     fn BeginUIElement(&self, _id: u32, show: *mut BOOL) -> windows::core::Result<()> {
@@ -338,6 +359,7 @@ impl ITfUIElementSink_Impl for UiElementSink_Impl {
     }
 }
 
+#[cfg(windows)]
 pub(crate) fn init_text_service() {
     let thread_mgr: ITfThreadMgr =
         unsafe { CoCreateInstance(&CLSID_TF_ThreadMgr, None, CLSCTX_INPROC_SERVER).unwrap() };
@@ -366,6 +388,7 @@ pub(crate) fn init_text_service() {
     });
 }
 
+#[cfg(windows)]
 pub(crate) fn shutdown_text_service() {
     TEXT_SERVICE.with(|tm| tm.get().unwrap().shutdown());
 }
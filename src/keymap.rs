@@ -0,0 +1,541 @@
+//! Multi-key chord sequences.
+//!
+//! A [`KeySequence`] is a list of chords like `"Ctrl+K Ctrl+C"`. [`Keymap`] binds sequences to
+//! command names, and [`Resolver`] resolves `event::KeyInput` to the commands.
+//! `Resolver` does not depend on a window, so it can be used without the UI thread.
+//!
+//! The keymap file has a binding per line. `#` begins a comment.
+//!
+//! ```text
+//! # sequence = command
+//! Ctrl+S = file.save
+//! Ctrl+K Ctrl+C = editor.comment
+//! ```
+//!
+//! ```
+//! use std::time::Instant;
+//! use wiard::keymap::{Keymap, Resolution, Resolver};
+//!
+//! let keymap = Keymap::parse("Ctrl+K Ctrl+C = editor.comment").unwrap();
+//! let mut resolver = Resolver::new(keymap);
+//! let now = Instant::now();
+//! let ret = resolver.feed("Ctrl+K".parse().unwrap(), now);
+//! assert!(matches!(ret, Resolution::Pending(_)));
+//! let ret = resolver.feed("Ctrl+C".parse().unwrap(), now);
+//! assert!(matches!(ret, Resolution::Matched { command, .. } if command == "editor.comment"));
+//! ```
+
+use crate::accelerator::ParseChordError;
+use crate::*;
+use std::io::BufRead;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// A sequence of chords.
+#[derive(Clone, PartialEq, Eq, Debug, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeySequence(Vec<Chord>);
+
+impl KeySequence {
+    #[inline]
+    pub fn new(chords: impl Into<Vec<Chord>>) -> Self {
+        Self(chords.into())
+    }
+
+    #[inline]
+    pub fn chords(&self) -> &[Chord] {
+        &self.0
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Checks if `chords` is a prefix of this sequence.
+    #[inline]
+    pub fn starts_with(&self, chords: &[Chord]) -> bool {
+        self.0.starts_with(chords)
+    }
+}
+
+impl From<Chord> for KeySequence {
+    #[inline]
+    fn from(value: Chord) -> Self {
+        Self(vec![value])
+    }
+}
+
+impl FromStr for KeySequence {
+    type Err = ParseChordError;
+
+    /// Parses chords separated by whitespaces like `"Ctrl+K Ctrl+C"`.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let chords = s
+            .split_whitespace()
+            .map(|chord| chord.parse())
+            .collect::<std::result::Result<Vec<Chord>, _>>()?;
+        if chords.is_empty() {
+            return Err(ParseChordError::Empty);
+        }
+        Ok(Self(chords))
+    }
+}
+
+impl std::fmt::Display for KeySequence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, chord) in self.0.iter().enumerate() {
+            if i != 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{chord}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A binding which conflicts with a new binding.
+///
+/// Sequences conflict when they are same or one is a prefix of the other.
+#[derive(Clone, PartialEq, Eq, Debug, thiserror::Error)]
+#[error("`{sequence}` conflicts with `{existing}` ({command})")]
+pub struct Conflict {
+    /// The new sequence.
+    pub sequence: KeySequence,
+    /// The sequence which is already bound.
+    pub existing: KeySequence,
+    /// The command of `existing`.
+    pub command: String,
+}
+
+/// The error type for loading `Keymap`.
+#[derive(Debug, thiserror::Error)]
+pub enum KeymapError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("line {line}: {error}")]
+    Chord { line: usize, error: ParseChordError },
+    #[error("line {line}: MissingCommand")]
+    MissingCommand { line: usize },
+    #[error("line {line}: {conflict}")]
+    Conflict { line: usize, conflict: Conflict },
+}
+
+/// A table which binds key sequences to command names.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Keymap {
+    table: Vec<(KeySequence, String)>,
+}
+
+impl Keymap {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a keymap text. Line numbers of errors start from 1.
+    pub fn parse(s: &str) -> std::result::Result<Self, KeymapError> {
+        Self::from_reader(s.as_bytes())
+    }
+
+    pub fn from_reader(reader: impl BufRead) -> std::result::Result<Self, KeymapError> {
+        let mut keymap = Self::new();
+        for (i, line) in reader.lines().enumerate() {
+            let line_number = i + 1;
+            let line = line?;
+            let line = line
+                .split_once('#')
+                .map_or(line.as_str(), |(l, _)| l)
+                .trim();
+            if line.is_empty() {
+                continue;
+            }
+            let Some((sequence, command)) = line.split_once('=') else {
+                return Err(KeymapError::MissingCommand { line: line_number });
+            };
+            let command = command.trim();
+            if command.is_empty() {
+                return Err(KeymapError::MissingCommand { line: line_number });
+            }
+            let sequence = sequence.parse().map_err(|error| KeymapError::Chord {
+                line: line_number,
+                error,
+            })?;
+            keymap
+                .insert(sequence, command)
+                .map_err(|conflict| KeymapError::Conflict {
+                    line: line_number,
+                    conflict,
+                })?;
+        }
+        Ok(keymap)
+    }
+
+    /// Loads a keymap file.
+    pub fn load(path: impl AsRef<Path>) -> std::result::Result<Self, KeymapError> {
+        let file = std::fs::File::open(path)?;
+        Self::from_reader(std::io::BufReader::new(file))
+    }
+
+    /// Finds the binding which conflicts with `sequence`.
+    pub fn conflict(&self, sequence: &KeySequence) -> Option<Conflict> {
+        self.table
+            .iter()
+            .find(|(seq, _)| {
+                seq.starts_with(sequence.chords()) || sequence.starts_with(seq.chords())
+            })
+            .map(|(seq, command)| Conflict {
+                sequence: sequence.clone(),
+                existing: seq.clone(),
+                command: command.clone(),
+            })
+    }
+
+    /// Binds `sequence` to `command`. Fails when `sequence` conflicts with another binding.
+    pub fn insert(
+        &mut self,
+        sequence: KeySequence,
+        command: impl Into<String>,
+    ) -> std::result::Result<(), Conflict> {
+        if let Some(conflict) = self.conflict(&sequence) {
+            return Err(conflict);
+        }
+        self.table.push((sequence, command.into()));
+        Ok(())
+    }
+
+    /// Unbinds `sequence`, and returns the command.
+    pub fn remove(&mut self, sequence: &KeySequence) -> Option<String> {
+        let index = self.table.iter().position(|(seq, _)| seq == sequence)?;
+        Some(self.table.remove(index).1)
+    }
+
+    #[inline]
+    pub fn get(&self, sequence: &KeySequence) -> Option<&str> {
+        self.table
+            .iter()
+            .find(|(seq, _)| seq == sequence)
+            .map(|(_, command)| command.as_str())
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&KeySequence, &str)> {
+        self.table
+            .iter()
+            .map(|(seq, command)| (seq, command.as_str()))
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+}
+
+/// A result of `Resolver`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Resolution {
+    /// A modifier key or a released key. Nothing changed.
+    Ignored,
+    /// The chord is not bound. The key input should be handled as usual.
+    Unbound(Chord),
+    /// The sequence is a prefix of bindings, and waiting for the next chord.
+    Pending(KeySequence),
+    /// The sequence is bound to `command`.
+    Matched {
+        command: String,
+        sequence: KeySequence,
+    },
+    /// The pending sequence was broken by a chord which is not bound.
+    Cancelled(KeySequence),
+    /// The pending sequence timed out before the chord arrived.
+    ///
+    /// `next` is the result of the chord resolved as the first chord of a new sequence.
+    TimedOut {
+        sequence: KeySequence,
+        next: Box<Resolution>,
+    },
+}
+
+/// A state machine which resolves key inputs to commands of `Keymap`.
+///
+/// Modifier keys are taken from `KeyInput::modifiers`.
+#[derive(Clone, Debug)]
+pub struct Resolver {
+    keymap: Keymap,
+    timeout: Option<Duration>,
+    pending: Vec<Chord>,
+    last: Option<Instant>,
+}
+
+impl Resolver {
+    #[inline]
+    pub fn new(keymap: Keymap) -> Self {
+        Self {
+            keymap,
+            timeout: None,
+            pending: vec![],
+            last: None,
+        }
+    }
+
+    #[inline]
+    pub fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    #[inline]
+    pub fn set_keymap(&mut self, keymap: Keymap) {
+        self.keymap = keymap;
+        self.pending.clear();
+    }
+
+    #[inline]
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Sets the time to wait for the next chord. `None` waits forever. The default is `None`.
+    #[inline]
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// The chords which are waiting for the next chord.
+    #[inline]
+    pub fn pending(&self) -> &[Chord] {
+        &self.pending
+    }
+
+    /// Clears the pending sequence.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.pending.clear();
+    }
+
+    #[inline]
+    pub fn input(&mut self, ev: &event::KeyInput) -> Resolution {
+        self.input_at(ev, Instant::now())
+    }
+
+    /// Same as `input` with the time of the key input.
+    pub fn input_at(&mut self, ev: &event::KeyInput, now: Instant) -> Resolution {
+        let modifier = matches!(
+            ev.key_code.vkey,
+            VirtualKey::Ctrl | VirtualKey::Shift | VirtualKey::Alt
+        ) || ModifierState::win_key(ev.key_code.vkey).is_some();
+        if modifier || ev.key_state != KeyState::Pressed {
+            return Resolution::Ignored;
+        }
+        self.feed(
+            Chord::new(ev.key_code.vkey, ev.modifiers.to_modifiers()),
+            now,
+        )
+    }
+
+    /// Resolves a chord directly.
+    pub fn feed(&mut self, chord: Chord, now: Instant) -> Resolution {
+        match self.poll(now) {
+            Some(sequence) => Resolution::TimedOut {
+                sequence,
+                next: Box::new(self.resolve(chord, now)),
+            },
+            None => self.resolve(chord, now),
+        }
+    }
+
+    fn resolve(&mut self, chord: Chord, now: Instant) -> Resolution {
+        self.pending.push(chord);
+        let mut prefix = false;
+        for (seq, command) in self.keymap.iter() {
+            if seq.chords() == self.pending.as_slice() {
+                let sequence = KeySequence(std::mem::take(&mut self.pending));
+                return Resolution::Matched {
+                    command: command.to_string(),
+                    sequence,
+                };
+            }
+            prefix |= seq.starts_with(&self.pending);
+        }
+        if prefix {
+            self.last = Some(now);
+            return Resolution::Pending(KeySequence(self.pending.clone()));
+        }
+        let pending = std::mem::take(&mut self.pending);
+        if pending.len() == 1 {
+            Resolution::Unbound(chord)
+        } else {
+            Resolution::Cancelled(KeySequence(pending))
+        }
+    }
+
+    /// Clears the pending sequence if it timed out, and returns it.
+    pub fn poll(&mut self, now: Instant) -> Option<KeySequence> {
+        let timeout = self.timeout?;
+        let last = self.last?;
+        if self.pending.is_empty() || now.saturating_duration_since(last) < timeout {
+            return None;
+        }
+        Some(KeySequence(std::mem::take(&mut self.pending)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key_input(
+        vkey: VirtualKey,
+        key_state: KeyState,
+        modifiers: ModifierState,
+    ) -> event::KeyInput {
+        event::KeyInput {
+            key_code: KeyCode::new(vkey, ScanCode(0)),
+            key_state,
+            prev_pressed: false,
+            modifiers,
+            logical_key: LogicalKey::Named(vkey),
+            text: None,
+        }
+    }
+
+    fn chord(s: &str) -> Chord {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_keymap() {
+        let keymap = Keymap::parse(
+            "# comment\n\nCtrl+S = file.save\nCtrl+K  Ctrl+C = editor.comment # comment\n",
+        )
+        .unwrap();
+        assert_eq!(keymap.len(), 2);
+        assert_eq!(
+            keymap.get(&"Ctrl+K Ctrl+C".parse().unwrap()),
+            Some("editor.comment")
+        );
+        assert!(matches!(
+            Keymap::parse("Ctrl+S = a\nCtrl+S Ctrl+A = b"),
+            Err(KeymapError::Conflict { line: 2, .. })
+        ));
+        assert!(matches!(
+            Keymap::parse("Ctrl+K"),
+            Err(KeymapError::MissingCommand { line: 1 })
+        ));
+        assert!(matches!(
+            Keymap::parse("Ctrl+Foo = a"),
+            Err(KeymapError::Chord { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn conflict() {
+        let mut keymap = Keymap::new();
+        keymap
+            .insert("Ctrl+K Ctrl+C".parse().unwrap(), "a")
+            .unwrap();
+        let conflict = keymap.insert("Ctrl+K".parse().unwrap(), "b").unwrap_err();
+        assert_eq!(conflict.existing.to_string(), "Ctrl+K Ctrl+C");
+        assert_eq!(conflict.command, "a");
+        assert!(
+            keymap
+                .insert("Ctrl+K Ctrl+C".parse().unwrap(), "c")
+                .is_err()
+        );
+        assert!(keymap.insert("Ctrl+K Ctrl+U".parse().unwrap(), "d").is_ok());
+    }
+
+    #[test]
+    fn resolve_key_input() {
+        let keymap = Keymap::parse("Ctrl+K Ctrl+C = comment\nCtrl+S = save").unwrap();
+        let mut resolver = Resolver::new(keymap);
+        let now = Instant::now();
+        let ctrl = ModifierState::LEFT_CTRL | ModifierState::NUM_LOCK;
+        let ret = resolver.input_at(&key_input(VirtualKey::Ctrl, KeyState::Pressed, ctrl), now);
+        assert_eq!(ret, Resolution::Ignored);
+        let ret = resolver.input_at(&key_input(VirtualKey::K, KeyState::Pressed, ctrl), now);
+        assert_eq!(ret, Resolution::Pending("Ctrl+K".parse().unwrap()));
+        let ret = resolver.input_at(&key_input(VirtualKey::K, KeyState::Released, ctrl), now);
+        assert_eq!(ret, Resolution::Ignored);
+        let ctrl = ModifierState::RIGHT_CTRL;
+        let ret = resolver.input_at(&key_input(VirtualKey::C, KeyState::Pressed, ctrl), now);
+        assert_eq!(
+            ret,
+            Resolution::Matched {
+                command: "comment".into(),
+                sequence: "Ctrl+K Ctrl+C".parse().unwrap(),
+            }
+        );
+        let none = ModifierState::empty();
+        let ret = resolver.input_at(&key_input(VirtualKey::S, KeyState::Pressed, none), now);
+        assert_eq!(ret, Resolution::Unbound(Chord::from(VirtualKey::S)));
+    }
+
+    #[test]
+    fn cancel_and_timeout() {
+        let keymap = Keymap::parse("Ctrl+K Ctrl+C = comment").unwrap();
+        let mut resolver = Resolver::new(keymap);
+        resolver.set_timeout(Some(Duration::from_secs(1)));
+        let now = Instant::now();
+        resolver.feed(chord("Ctrl+K"), now);
+        let ret = resolver.feed(chord("Ctrl+X"), now);
+        assert_eq!(ret, Resolution::Cancelled("Ctrl+K Ctrl+X".parse().unwrap()));
+        assert!(resolver.pending().is_empty());
+
+        resolver.feed(chord("Ctrl+K"), now);
+        assert!(resolver.poll(now + Duration::from_millis(500)).is_none());
+        assert_eq!(
+            resolver.poll(now + Duration::from_secs(1)),
+            Some(chord("Ctrl+K").into())
+        );
+        assert!(resolver.pending().is_empty());
+
+        resolver.feed(chord("Ctrl+K"), now);
+        let ret = resolver.feed(chord("Ctrl+C"), now + Duration::from_secs(2));
+        assert_eq!(
+            ret,
+            Resolution::TimedOut {
+                sequence: chord("Ctrl+K").into(),
+                next: Box::new(Resolution::Unbound(chord("Ctrl+C"))),
+            }
+        );
+        assert!(resolver.pending().is_empty());
+    }
+
+    #[test]
+    fn feed_after_timeout() {
+        let keymap = Keymap::parse("Ctrl+K Ctrl+C = comment").unwrap();
+        let mut resolver = Resolver::new(keymap);
+        resolver.set_timeout(Some(Duration::from_secs(1)));
+        let now = Instant::now();
+        resolver.feed(chord("Ctrl+K"), now);
+        let later = now + Duration::from_secs(2);
+        let ret = resolver.feed(chord("Ctrl+K"), later);
+        assert_eq!(
+            ret,
+            Resolution::TimedOut {
+                sequence: chord("Ctrl+K").into(),
+                next: Box::new(Resolution::Pending(chord("Ctrl+K").into())),
+            }
+        );
+        let ret = resolver.feed(chord("Ctrl+C"), later + Duration::from_millis(500));
+        assert_eq!(
+            ret,
+            Resolution::Matched {
+                command: "comment".into(),
+                sequence: "Ctrl+K Ctrl+C".parse().unwrap(),
+            }
+        );
+        assert!(resolver.poll(later + Duration::from_secs(5)).is_none());
+    }
+}
@@ -40,50 +40,111 @@
 //! # Note
 //! wiard use `WM_APP`. Don't post directly `WM_APP` to wiard's UI thread.
 //!
+//! # Headless
+//! With the `headless` feature, wiard can replace Win32 windows with simulated ones so that
+//! event handling code can run on any platform. See the `headless` module for details.
+//!
 
 pub use gedv::*;
 
+#[cfg(all(not(windows), not(feature = "headless")))]
+compile_error!("wiard needs Windows; enable the `headless` feature on other platforms");
+
+pub mod accelerator;
+mod backend;
+pub mod click;
 mod color;
 mod context;
 mod device;
+#[cfg(windows)]
 mod dialog;
+#[cfg(windows)]
 pub mod drag_drop;
 mod error;
 pub mod event;
+pub mod gesture;
+#[cfg(feature = "headless")]
+pub mod headless;
+pub mod image;
 pub mod ime;
+pub mod keymap;
+#[cfg(windows)]
 pub mod menu;
+pub mod monitor;
+#[cfg(windows)]
 mod messages;
+mod modal;
+#[cfg(windows)]
 pub mod notify_icon;
+pub mod placement;
+pub mod pointer;
+#[cfg(windows)]
 mod procedure;
+pub mod raw_input;
+mod receiver;
+pub mod record;
+#[cfg(windows)]
 mod resource;
+pub mod sizing;
+#[cfg(windows)]
 pub mod style;
+pub mod text;
 mod ui_thread;
+#[cfg(windows)]
 pub mod utility;
+pub mod wheel;
 mod window;
 
+#[doc(inline)]
+pub use accelerator::{Accelerators, Chord, Modifiers};
+use backend::*;
 pub use color::*;
-use context::*;
 pub use device::*;
+#[cfg(windows)]
 pub use dialog::*;
+#[cfg(windows)]
 pub use dialog::{FileDialogOptions, FileOpenDialog};
 pub use error::*;
 #[doc(inline)]
 pub use event::{Event, NcHitTestValue, ResizingEdge};
 #[doc(inline)]
+pub use keymap::{KeySequence, Keymap};
+#[cfg(windows)]
+#[doc(inline)]
 pub use menu::{Menu, MenuBar, MenuBarItem, MenuHandle, MenuItem};
+#[cfg(windows)]
 use messages::*;
+pub use modal::ModalResult;
+use modal::ModalState;
+#[doc(inline)]
+pub use monitor::{Fullscreen, Monitor, MonitorInfo, Orientation, ScreenRect};
+#[cfg(windows)]
 #[doc(inline)]
 pub use notify_icon::{NotifyIcon, NotifyIconEvent};
+#[doc(inline)]
+pub use placement::{PlacementState, WindowPlacement};
+#[doc(inline)]
+pub use pointer::{PenButtons, PointerId, PointerPhase, PointerProperties, PointerType};
+#[doc(inline)]
+pub use raw_input::{DeviceId, RawInputDevices};
+pub use receiver::*;
+#[cfg(windows)]
 pub use resource::*;
 #[doc(inline)]
+pub use sizing::{DpiSize, SizeConstraints};
+#[cfg(windows)]
+#[doc(inline)]
 pub use style::*;
 pub use ui_thread::UiThread;
+#[doc(inline)]
+pub use ui_thread::shutdown;
+#[cfg(windows)]
 use utility::*;
+#[doc(inline)]
+pub use wheel::{ScrollDelta, ScrollPhase};
 pub use window::*;
 
+#[cfg(windows)]
 pub mod api {
     pub use windows::*;
 }
-
-#[doc(inline)]
-pub use utility::shutdown;
@@ -211,11 +211,34 @@ impl MenuHandle {
     pub(crate) fn from_raw(handle: HMENU) -> Self {
         Self { handle }
     }
+
+    pub(crate) fn from_raw_id(id: u64) -> Self {
+        Self::from_raw(HMENU(id as usize as *mut _))
+    }
+
+    pub(crate) fn raw_id(&self) -> u64 {
+        self.handle.0 as usize as u64
+    }
 }
 
 unsafe impl Send for MenuHandle {}
 unsafe impl Sync for MenuHandle {}
 
+/// Serializes the raw `HMENU` value, which means nothing in another process.
+#[cfg(feature = "serde")]
+impl serde::Serialize for MenuHandle {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> std::result::Result<S::Ok, S::Error> {
+        s.serialize_u64(self.raw_id())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for MenuHandle {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> std::result::Result<Self, D::Error> {
+        <u64 as serde::Deserialize>::deserialize(d).map(Self::from_raw_id)
+    }
+}
+
 #[derive(Debug)]
 struct Object {
     handle: RawHandle,
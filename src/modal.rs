@@ -0,0 +1,48 @@
+use crate::*;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::Poll;
+use tokio::sync::oneshot;
+
+/// The state of a modal window which disables its owner.
+pub(crate) struct ModalState {
+    pub owner: WindowHandle,
+    pub result: Option<usize>,
+    pub waiters: Vec<oneshot::Sender<Option<usize>>>,
+}
+
+impl ModalState {
+    pub fn new(owner: WindowHandle) -> Self {
+        Self {
+            owner,
+            result: None,
+            waiters: vec![],
+        }
+    }
+
+    pub fn finish(self) {
+        for tx in self.waiters {
+            tx.send(self.result).ok();
+        }
+    }
+}
+
+/// A future which resolves when a modal window is closed.
+///
+/// The output is the value passed to `end_modal`, or `None` if the window was closed in another
+/// way.
+pub struct ModalResult(oneshot::Receiver<Option<usize>>);
+
+impl ModalResult {
+    pub(crate) fn new(rx: oneshot::Receiver<Option<usize>>) -> Self {
+        Self(rx)
+    }
+}
+
+impl Future for ModalResult {
+    type Output = Option<usize>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(cx).map(|ret| ret.ok().flatten())
+    }
+}
@@ -0,0 +1,297 @@
+//! Displays connected to the system.
+//!
+//! [`Monitor`] identifies a display, [`MonitorInfo`] describes it, and [`Fullscreen`] selects
+//! the display which a window covers. Windows receive `MonitorsChanged` when displays are
+//! connected, disconnected or reconfigured.
+
+use crate::*;
+#[cfg(feature = "headless")]
+use std::sync::Mutex;
+#[cfg(windows)]
+use windows::Win32::{
+    Foundation::{HWND, LPARAM, POINT, RECT},
+    Graphics::Gdi::*,
+    UI::HiDpi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI},
+    UI::WindowsAndMessaging::{EDD_GET_DEVICE_INTERFACE_NAME, MONITORINFOF_PRIMARY},
+};
+#[cfg(windows)]
+use windows::core::{BOOL, PCWSTR};
+
+/// A rectangle in screen coordinates.
+pub type ScreenRect<T> = Rect<T, coord::Screen>;
+
+/// The rotation of a display.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Orientation {
+    Landscape,
+    Portrait,
+    LandscapeFlipped,
+    PortraitFlipped,
+}
+
+/// Properties of a display.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MonitorInfo {
+    /// The description of the display like `Generic PnP Monitor`.
+    pub name: String,
+    /// The device interface path which identifies the display across sessions.
+    pub device_id: String,
+    pub bounds: ScreenRect<i32>,
+    /// The area which excludes the taskbar and docked toolbars.
+    pub work_area: ScreenRect<i32>,
+    pub dpi: u32,
+    /// The refresh rate in Hz, or `None` if the hardware default is used.
+    pub refresh_rate: Option<u32>,
+    pub is_primary: bool,
+    pub orientation: Orientation,
+}
+
+impl MonitorInfo {
+    /// Returns the ratio of `dpi` to 96.
+    #[inline]
+    pub fn scale_factor(&self) -> f32 {
+        self.dpi as f32 / DEFAULT_DPI as f32
+    }
+}
+
+#[cfg(feature = "headless")]
+static MONITORS: Mutex<Vec<MonitorInfo>> = Mutex::new(vec![]);
+
+#[cfg(feature = "headless")]
+fn simulated_monitors() -> Vec<MonitorInfo> {
+    let monitors = MONITORS.lock().unwrap();
+    if !monitors.is_empty() {
+        return monitors.clone();
+    }
+    let bounds = ScreenRect::new(0, 0, 1920, 1080);
+    vec![MonitorInfo {
+        name: "Headless Monitor".into(),
+        device_id: "headless-0".into(),
+        bounds,
+        work_area: bounds,
+        dpi: DEFAULT_DPI,
+        refresh_rate: Some(60),
+        is_primary: true,
+        orientation: Orientation::Landscape,
+    }]
+}
+
+#[cfg(feature = "headless")]
+pub(crate) fn set_simulated_monitors(monitors: Vec<MonitorInfo>) {
+    *MONITORS.lock().unwrap() = monitors;
+}
+
+#[cfg(windows)]
+fn wide_to_string(s: &[u16]) -> String {
+    let len = s.iter().position(|&c| c == 0).unwrap_or(s.len());
+    String::from_utf16_lossy(&s[..len])
+}
+
+#[cfg(windows)]
+fn to_screen_rect(rc: RECT) -> ScreenRect<i32> {
+    ScreenRect::new(rc.left, rc.top, rc.right, rc.bottom)
+}
+
+/// A display connected to the system.
+///
+/// The headless backend simulates a primary monitor of 1920x1080 until
+/// `headless::set_monitors` is called.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Monitor(isize);
+
+impl Monitor {
+    #[cfg(windows)]
+    fn new(handle: HMONITOR) -> Self {
+        Self(handle.0 as isize)
+    }
+
+    #[cfg(windows)]
+    pub(crate) fn as_hmonitor(&self) -> HMONITOR {
+        HMONITOR(self.0 as *mut _)
+    }
+
+    /// Returns all monitors.
+    #[inline]
+    pub fn all() -> Vec<Self> {
+        backend!(
+            Self::enum_display_monitors(),
+            (0..simulated_monitors().len())
+                .map(|i| Self(i as isize))
+                .collect()
+        )
+    }
+
+    #[cfg(windows)]
+    fn enum_display_monitors() -> Vec<Self> {
+        unsafe extern "system" fn callback(
+            monitor: HMONITOR,
+            _: HDC,
+            _: *mut RECT,
+            lparam: LPARAM,
+        ) -> BOOL {
+            unsafe {
+                let monitors = (lparam.0 as *mut Vec<Monitor>).as_mut().unwrap();
+                monitors.push(Monitor::new(monitor));
+            }
+            true.into()
+        }
+
+        let mut monitors: Vec<Self> = vec![];
+        unsafe {
+            let _ = EnumDisplayMonitors(
+                None,
+                None,
+                Some(callback),
+                LPARAM(&mut monitors as *mut _ as isize),
+            );
+        }
+        monitors
+    }
+
+    /// Returns the primary monitor.
+    #[inline]
+    pub fn primary() -> Self {
+        backend!(
+            unsafe {
+                Self::new(MonitorFromPoint(
+                    POINT { x: 0, y: 0 },
+                    MONITOR_DEFAULTTOPRIMARY,
+                ))
+            },
+            {
+                let i = simulated_monitors()
+                    .iter()
+                    .position(|info| info.is_primary)
+                    .unwrap_or(0);
+                Self(i as isize)
+            }
+        )
+    }
+
+    /// Returns the monitor which contains `position`, or the nearest one.
+    #[inline]
+    pub fn from_point(position: ScreenPosition<i32>) -> Self {
+        backend!(
+            {
+                let pt = POINT {
+                    x: position.x,
+                    y: position.y,
+                };
+                unsafe { Self::new(MonitorFromPoint(pt, MONITOR_DEFAULTTONEAREST)) }
+            },
+            Self::simulated_from_point(position)
+        )
+    }
+
+    #[cfg(feature = "headless")]
+    fn simulated_from_point(position: ScreenPosition<i32>) -> Self {
+        let distance = |rc: &ScreenRect<i32>| {
+            let dx = (rc.left - position.x).max(position.x - rc.right + 1).max(0) as i64;
+            let dy = (rc.top - position.y).max(position.y - rc.bottom + 1).max(0) as i64;
+            dx * dx + dy * dy
+        };
+        simulated_monitors()
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, info)| distance(&info.bounds))
+            .map_or(Self::primary(), |(i, _)| Self(i as isize))
+    }
+
+    #[cfg(windows)]
+    pub(crate) fn from_window(hwnd: HWND) -> Self {
+        unsafe { Self::new(MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST)) }
+    }
+
+    /// Returns the rectangle of the monitor, or `None` if the monitor was disconnected.
+    #[inline]
+    pub fn bounds(&self) -> Option<ScreenRect<i32>> {
+        self.info().map(|info| info.bounds)
+    }
+
+    /// Returns the work area of the monitor, or `None` if the monitor was disconnected.
+    #[inline]
+    pub fn work_area(&self) -> Option<ScreenRect<i32>> {
+        self.info().map(|info| info.work_area)
+    }
+
+    /// Returns the properties of the monitor, or `None` if the monitor was disconnected.
+    #[inline]
+    pub fn info(&self) -> Option<MonitorInfo> {
+        backend!(
+            self.monitor_info(),
+            simulated_monitors().get(self.0 as usize).cloned()
+        )
+    }
+
+    #[cfg(windows)]
+    fn monitor_info(&self) -> Option<MonitorInfo> {
+        unsafe {
+            let mut info = MONITORINFOEXW::default();
+            info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+            if !GetMonitorInfoW(self.as_hmonitor(), &mut info.monitorInfo).as_bool() {
+                return None;
+            }
+            let device = PCWSTR(info.szDevice.as_ptr());
+            let mut display = DISPLAY_DEVICEW {
+                cb: std::mem::size_of::<DISPLAY_DEVICEW>() as u32,
+                ..Default::default()
+            };
+            let (name, device_id) =
+                if EnumDisplayDevicesW(device, 0, &mut display, EDD_GET_DEVICE_INTERFACE_NAME)
+                    .as_bool()
+                {
+                    (
+                        wide_to_string(&display.DeviceString),
+                        wide_to_string(&display.DeviceID),
+                    )
+                } else {
+                    (wide_to_string(&info.szDevice), String::new())
+                };
+            let mut mode = DEVMODEW {
+                dmSize: std::mem::size_of::<DEVMODEW>() as u16,
+                ..Default::default()
+            };
+            let (refresh_rate, orientation) =
+                if EnumDisplaySettingsW(device, ENUM_CURRENT_SETTINGS, &mut mode).as_bool() {
+                    let orientation = match mode.Anonymous1.Anonymous2.dmDisplayOrientation {
+                        DMDO_90 => Orientation::Portrait,
+                        DMDO_180 => Orientation::LandscapeFlipped,
+                        DMDO_270 => Orientation::PortraitFlipped,
+                        _ => Orientation::Landscape,
+                    };
+                    let rate = mode.dmDisplayFrequency;
+                    ((rate > 1).then_some(rate), orientation)
+                } else {
+                    (None, Orientation::Landscape)
+                };
+            let mut dpi = DEFAULT_DPI;
+            let mut dpi_y = 0;
+            if let Err(e) =
+                GetDpiForMonitor(self.as_hmonitor(), MDT_EFFECTIVE_DPI, &mut dpi, &mut dpi_y)
+            {
+                error!("GetDpiForMonitor: {e}");
+            }
+            Some(MonitorInfo {
+                name,
+                device_id,
+                bounds: to_screen_rect(info.monitorInfo.rcMonitor),
+                work_area: to_screen_rect(info.monitorInfo.rcWork),
+                dpi,
+                refresh_rate,
+                is_primary: info.monitorInfo.dwFlags & MONITORINFOF_PRIMARY != 0,
+                orientation,
+            })
+        }
+    }
+}
+
+/// A fullscreen mode of a window.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Fullscreen {
+    /// Removes the frame and the menu bar, and covers the whole monitor including the taskbar.
+    ///
+    /// `None` selects the monitor which the window is on.
+    Borderless(Option<Monitor>),
+}
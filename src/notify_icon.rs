@@ -2,9 +2,11 @@ use super::*;
 use std::sync::atomic::{self, AtomicU32};
 use windows::Win32::Foundation::HINSTANCE;
 use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::HiDpi::GetDpiForWindow;
 use windows::Win32::UI::Shell::*;
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct Id(u32);
 
 impl Id {
@@ -62,7 +64,7 @@ impl Builder<'_> {
             if let Some(icon) = self.icon {
                 let hinstance: Option<HINSTANCE> = Some(GetModuleHandleW(None).unwrap().into());
                 data.uFlags |= NIF_ICON;
-                data.hIcon = icon.load(hinstance)?;
+                data.hIcon = icon.load(hinstance, GetDpiForWindow(self.window.as_hwnd()))?;
             }
             if let Some(tip) = self.tip {
                 data.uFlags |= NIF_TIP | NIF_SHOWTIP;
@@ -82,6 +84,7 @@ impl Builder<'_> {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NotifyIcon {
     id: Id,
 }
@@ -97,6 +100,10 @@ impl NotifyIcon {
         Self { id: Id(id) }
     }
 
+    pub(crate) fn id(&self) -> u32 {
+        self.id.0
+    }
+
     #[inline]
     pub fn delete(self) -> bool {
         unsafe {
@@ -115,6 +122,7 @@ pub mod event {
     use super::*;
 
     #[derive(Clone, Debug)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct MouseInput {
         pub button: MouseButton,
         pub button_state: ButtonState,
@@ -123,6 +131,7 @@ pub mod event {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum NotifyIconEvent {
     MouseInput(event::MouseInput),
     CursorMoved(ScreenPosition<i32>),
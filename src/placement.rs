@@ -0,0 +1,170 @@
+//! Saving and restoring positions of windows.
+//!
+//! [`WindowPlacement`] records the normal rectangle, the state and the monitor of a window.
+//! [`WindowPlacement::resolve`] fits it onto the current monitors, so that a placement saved on
+//! a disconnected monitor or at another DPI is restored onto a visible work area.
+//!
+//! ```
+//! use wiard::placement::*;
+//! use wiard::{MonitorInfo, Orientation, ScreenRect};
+//!
+//! let placement = WindowPlacement {
+//!     rect: ScreenRect::new(-1500, 100, -700, 700),
+//!     state: PlacementState::Normal,
+//!     monitor_id: "left".into(),
+//!     dpi: 96,
+//! };
+//! let primary = MonitorInfo {
+//!     name: "primary".into(),
+//!     device_id: "primary".into(),
+//!     bounds: ScreenRect::new(0, 0, 1920, 1080),
+//!     work_area: ScreenRect::new(0, 0, 1920, 1040),
+//!     dpi: 96,
+//!     refresh_rate: None,
+//!     is_primary: true,
+//!     orientation: Orientation::Landscape,
+//! };
+//! assert_eq!(placement.resolve(&[primary]), ScreenRect::new(0, 100, 800, 700));
+//! ```
+
+use crate::*;
+
+/// The state of a window in [`WindowPlacement`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PlacementState {
+    Normal,
+    Maximized,
+    Minimized,
+}
+
+/// A saved position, size and state of a window.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WindowPlacement {
+    /// The rectangle of the window when it is neither maximized nor minimized.
+    pub rect: ScreenRect<i32>,
+    pub state: PlacementState,
+    /// [`MonitorInfo::device_id`] of the monitor which the window was on.
+    pub monitor_id: String,
+    /// The DPI of the window when the placement was saved.
+    pub dpi: u32,
+}
+
+fn intersection_area(a: &ScreenRect<i32>, b: &ScreenRect<i32>) -> i64 {
+    let width = (a.right.min(b.right) - a.left.max(b.left)).max(0) as i64;
+    let height = (a.bottom.min(b.bottom) - a.top.max(b.top)).max(0) as i64;
+    width * height
+}
+
+fn fit(value: i32, len: i32, min: i32, max: i32) -> i32 {
+    value.min(max - len).max(min)
+}
+
+impl WindowPlacement {
+    /// Selects the monitor to restore the window in `monitors`.
+    ///
+    /// The monitor of `monitor_id` is preferred, then the one which overlaps `rect` most, then
+    /// the primary monitor.
+    pub fn target_monitor<'a>(&self, monitors: &'a [MonitorInfo]) -> Option<&'a MonitorInfo> {
+        let saved = monitors
+            .iter()
+            .find(|m| !self.monitor_id.is_empty() && m.device_id == self.monitor_id);
+        saved
+            .or_else(|| {
+                monitors
+                    .iter()
+                    .map(|m| (m, intersection_area(&m.work_area, &self.rect)))
+                    .filter(|(_, area)| *area > 0)
+                    .max_by_key(|(_, area)| *area)
+                    .map(|(m, _)| m)
+            })
+            .or_else(|| monitors.iter().find(|m| m.is_primary))
+            .or_else(|| monitors.first())
+    }
+
+    /// Returns `rect` rescaled for the DPI of the target monitor and moved into its work area.
+    ///
+    /// The size is scaled by the ratio of the DPIs keeping the left-top corner, and shrunk to
+    /// the work area if it is larger. `rect` is returned as it is when `monitors` is empty.
+    pub fn resolve(&self, monitors: &[MonitorInfo]) -> ScreenRect<i32> {
+        let Some(monitor) = self.target_monitor(monitors) else {
+            return self.rect;
+        };
+        let scale = |len: i32| {
+            if self.dpi == 0 || self.dpi == monitor.dpi {
+                len
+            } else {
+                (len as i64 * monitor.dpi as i64 / self.dpi as i64) as i32
+            }
+        };
+        let work = monitor.work_area;
+        let width = scale(self.rect.right - self.rect.left).min(work.right - work.left);
+        let height = scale(self.rect.bottom - self.rect.top).min(work.bottom - work.top);
+        let left = fit(self.rect.left, width, work.left, work.right);
+        let top = fit(self.rect.top, height, work.top, work.bottom);
+        ScreenRect::new(left, top, left + width, top + height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(id: &str, work_area: ScreenRect<i32>, dpi: u32, is_primary: bool) -> MonitorInfo {
+        MonitorInfo {
+            name: id.into(),
+            device_id: id.into(),
+            bounds: work_area,
+            work_area,
+            dpi,
+            refresh_rate: None,
+            is_primary,
+            orientation: Orientation::Landscape,
+        }
+    }
+
+    fn placement(rect: ScreenRect<i32>, monitor_id: &str, dpi: u32) -> WindowPlacement {
+        WindowPlacement {
+            rect,
+            state: PlacementState::Maximized,
+            monitor_id: monitor_id.into(),
+            dpi,
+        }
+    }
+
+    #[test]
+    fn same_monitor() {
+        let monitors = [
+            monitor("a", ScreenRect::new(0, 0, 1920, 1040), 96, true),
+            monitor("b", ScreenRect::new(1920, 0, 3840, 1080), 96, false),
+        ];
+        let p = placement(ScreenRect::new(2000, 100, 2800, 700), "b", 96);
+        assert_eq!(p.target_monitor(&monitors).unwrap().device_id, "b");
+        assert_eq!(p.resolve(&monitors), p.rect);
+    }
+
+    #[test]
+    fn missing_monitor() {
+        let monitors = [
+            monitor("a", ScreenRect::new(0, 0, 1920, 1040), 96, false),
+            monitor("b", ScreenRect::new(1920, 0, 3840, 1040), 96, true),
+        ];
+        let p = placement(ScreenRect::new(1800, 100, 2200, 400), "gone", 96);
+        assert_eq!(p.target_monitor(&monitors).unwrap().device_id, "b");
+        assert_eq!(p.resolve(&monitors), ScreenRect::new(1920, 100, 2320, 400));
+        let p = placement(ScreenRect::new(5000, 2000, 5400, 2300), "gone", 96);
+        assert_eq!(p.target_monitor(&monitors).unwrap().device_id, "b");
+        assert_eq!(p.resolve(&monitors), ScreenRect::new(3440, 740, 3840, 1040));
+        assert_eq!(p.resolve(&[]), p.rect);
+    }
+
+    #[test]
+    fn rescale_and_shrink() {
+        let monitors = [monitor("a", ScreenRect::new(0, 0, 1920, 1040), 192, true)];
+        let p = placement(ScreenRect::new(100, 100, 500, 400), "a", 96);
+        assert_eq!(p.resolve(&monitors), ScreenRect::new(100, 100, 900, 700));
+        let p = placement(ScreenRect::new(-100, -50, 2900, 1950), "a", 192);
+        assert_eq!(p.resolve(&monitors), ScreenRect::new(0, 0, 1920, 1040));
+    }
+}
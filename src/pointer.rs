@@ -0,0 +1,403 @@
+//! Pointer inputs of mouse, touch and pen.
+//!
+//! A window which enables `WindowBuilder::pointer_input` receives `WM_POINTER*` messages as
+//! `Event::Pointer`. [`PointerTracker`] keeps states of each pointer, and converts
+//! [`PointerSample`] to the event.
+//!
+//! ```
+//! use wiard::pointer::*;
+//! use wiard::PhysicalPosition;
+//!
+//! let mut tracker = PointerTracker::new();
+//! let id = PointerId::new(3);
+//! let position = PhysicalPosition::new(10, 20);
+//! let ev = tracker
+//!     .process(PointerSample::new(id, PointerType::Touch, PointerFlags::DOWN, position))
+//!     .unwrap();
+//! assert_eq!(ev.phase, PointerPhase::Down);
+//! assert!(ev.is_primary);
+//! ```
+
+use crate::*;
+use std::collections::HashMap;
+
+/// The identifier of a pointer.
+///
+/// The identifier may be reused after the pointer is up or canceled.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PointerId(u32);
+
+impl PointerId {
+    #[inline]
+    pub const fn new(id: u32) -> Self {
+        Self(id)
+    }
+
+    #[inline]
+    pub const fn raw(&self) -> u32 {
+        self.0
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PointerType {
+    Mouse,
+    Touch,
+    Pen,
+    Touchpad,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PointerPhase {
+    /// The pointer comes in contact.
+    Down,
+    /// The pointer moved in contact or hovering.
+    Move,
+    /// The pointer leaves contact.
+    Up,
+    /// The pointer was canceled like a palm rejection or losing the capture.
+    ///
+    /// `Up` is not sent after this phase.
+    Cancel,
+}
+
+bitflags::bitflags! {
+    /// `POINTER_FLAGS`.
+    #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Default)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct PointerFlags: u32 {
+        const IN_RANGE = 0x0000_0002;
+        const IN_CONTACT = 0x0000_0004;
+        const PRIMARY = 0x0000_2000;
+        const CANCELED = 0x0000_8000;
+        const DOWN = 0x0001_0000;
+        const UPDATE = 0x0002_0000;
+        const UP = 0x0004_0000;
+        const CAPTURE_CHANGED = 0x0020_0000;
+    }
+}
+
+bitflags::bitflags! {
+    /// Buttons of a pen (`PEN_FLAGS`).
+    #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Default)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct PenButtons: u32 {
+        const BARREL = 0x0001;
+        const INVERTED = 0x0002;
+        const ERASER = 0x0004;
+    }
+}
+
+/// Properties of a pointer which depend on the device.
+#[derive(Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PointerProperties {
+    /// Normalized to `0.0..=1.0`. `None` when the device does not report pressure.
+    pub pressure: Option<f32>,
+    /// Degrees in `-90..=90` along the x axis.
+    pub tilt_x: i32,
+    /// Degrees in `-90..=90` along the y axis.
+    pub tilt_y: i32,
+    /// Clockwise rotation in degrees `0..360`.
+    pub twist: u32,
+    /// The size of the touch contact area.
+    pub contact_size: Option<PhysicalSize<u32>>,
+    pub pen_buttons: PenButtons,
+}
+
+/// A state of a pointer from `WM_POINTER*` messages.
+#[derive(Clone, PartialEq, Debug)]
+pub struct PointerSample {
+    pub id: PointerId,
+    pub pointer_type: PointerType,
+    pub flags: PointerFlags,
+    pub position: PhysicalPosition<i32>,
+    pub properties: PointerProperties,
+    pub modifiers: ModifierState,
+}
+
+impl PointerSample {
+    #[inline]
+    pub fn new(
+        id: PointerId,
+        pointer_type: PointerType,
+        flags: PointerFlags,
+        position: PhysicalPosition<i32>,
+    ) -> Self {
+        Self {
+            id,
+            pointer_type,
+            flags,
+            position,
+            properties: PointerProperties::default(),
+            modifiers: ModifierState::empty(),
+        }
+    }
+
+    #[inline]
+    pub fn properties(mut self, properties: PointerProperties) -> Self {
+        self.properties = properties;
+        self
+    }
+
+    #[inline]
+    pub fn modifiers(mut self, modifiers: ModifierState) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+}
+
+#[derive(Clone, Debug)]
+struct PointerState {
+    pointer_type: PointerType,
+    in_contact: bool,
+    position: PhysicalPosition<i32>,
+    properties: PointerProperties,
+    modifiers: ModifierState,
+}
+
+/// Tracks pointers and converts samples to `event::Pointer`.
+///
+/// The first pointer which comes in contact while no pointer is in contact becomes the primary
+/// pointer until it is up or canceled. A mouse is always the primary pointer.
+#[derive(Clone, Debug, Default)]
+pub struct PointerTracker {
+    pointers: HashMap<PointerId, PointerState>,
+    primary: Option<PointerId>,
+}
+
+impl PointerTracker {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the current primary pointer in contact.
+    #[inline]
+    pub fn primary(&self) -> Option<PointerId> {
+        self.primary
+    }
+
+    /// Returns pointers in contact.
+    pub fn contacts(&self) -> Vec<PointerId> {
+        let mut ids = self
+            .pointers
+            .iter()
+            .filter(|(_, state)| state.in_contact)
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        ids.sort();
+        ids
+    }
+
+    #[inline]
+    pub fn is_tracked(&self, id: PointerId) -> bool {
+        self.pointers.contains_key(&id)
+    }
+
+    fn is_primary(&self, id: PointerId, pointer_type: PointerType) -> bool {
+        pointer_type == PointerType::Mouse
+            || self.primary == Some(id)
+            || (self.primary.is_none() && self.contacts().is_empty())
+    }
+
+    fn to_event(&self, id: PointerId, phase: PointerPhase, state: &PointerState) -> event::Pointer {
+        event::Pointer {
+            id,
+            pointer_type: state.pointer_type,
+            phase,
+            position: state.position,
+            is_primary: self.is_primary(id, state.pointer_type),
+            in_contact: state.in_contact,
+            properties: state.properties.clone(),
+            modifiers: state.modifiers,
+        }
+    }
+
+    fn end(&mut self, id: PointerId, phase: PointerPhase, sample: PointerSample) -> event::Pointer {
+        let state = PointerState {
+            pointer_type: sample.pointer_type,
+            in_contact: false,
+            position: sample.position,
+            properties: sample.properties,
+            modifiers: sample.modifiers,
+        };
+        let ev = self.to_event(id, phase, &state);
+        if self.primary == Some(id) {
+            self.primary = None;
+        }
+        if phase == PointerPhase::Cancel || !sample.flags.contains(PointerFlags::IN_RANGE) {
+            self.pointers.remove(&id);
+        } else {
+            self.pointers.insert(id, state);
+        }
+        ev
+    }
+
+    /// Updates the pointer state, and returns the event.
+    ///
+    /// This returns `None` when the sample does not change the state like an update of
+    /// a canceled pointer.
+    pub fn process(&mut self, sample: PointerSample) -> Option<event::Pointer> {
+        let id = sample.id;
+        let flags = sample.flags;
+        let prev = self.pointers.get(&id).map(|state| state.in_contact);
+        if flags.contains(PointerFlags::CANCELED) {
+            return prev.map(|_| self.end(id, PointerPhase::Cancel, sample));
+        }
+        let in_contact = flags.intersects(PointerFlags::IN_CONTACT | PointerFlags::DOWN)
+            && !flags.contains(PointerFlags::UP);
+        let phase = match (prev, in_contact) {
+            (Some(true), false) => return Some(self.end(id, PointerPhase::Up, sample)),
+            (None, false) if flags.contains(PointerFlags::UP) => return None,
+            (Some(true), true) => PointerPhase::Move,
+            (_, true) => {
+                if self.primary.is_none() && self.contacts().is_empty() {
+                    self.primary = Some(id);
+                }
+                PointerPhase::Down
+            }
+            (_, false) => PointerPhase::Move,
+        };
+        let state = PointerState {
+            pointer_type: sample.pointer_type,
+            in_contact,
+            position: sample.position,
+            properties: sample.properties,
+            modifiers: sample.modifiers,
+        };
+        let ev = self.to_event(id, phase, &state);
+        self.pointers.insert(id, state);
+        Some(ev)
+    }
+
+    /// Forgets a pointer which left the window (`WM_POINTERLEAVE`).
+    ///
+    /// This returns `Cancel` if the pointer is in contact.
+    pub fn leave(&mut self, id: PointerId) -> Option<event::Pointer> {
+        let state = self.pointers.get(&id)?;
+        let ev = state
+            .in_contact
+            .then(|| self.to_event(id, PointerPhase::Cancel, state));
+        self.pointers.remove(&id);
+        if self.primary == Some(id) {
+            self.primary = None;
+        }
+        ev.map(|ev| event::Pointer {
+            in_contact: false,
+            ..ev
+        })
+    }
+
+    /// Cancels all pointers in contact like losing the capture.
+    pub fn cancel_all(&mut self) -> Vec<event::Pointer> {
+        let contacts = self.contacts();
+        let evs = contacts
+            .iter()
+            .map(|id| event::Pointer {
+                in_contact: false,
+                ..self.to_event(*id, PointerPhase::Cancel, &self.pointers[id])
+            })
+            .collect();
+        for id in contacts {
+            self.pointers.remove(&id);
+        }
+        self.primary = None;
+        evs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(id: u32, pointer_type: PointerType, flags: PointerFlags) -> PointerSample {
+        PointerSample::new(
+            PointerId::new(id),
+            pointer_type,
+            flags,
+            PhysicalPosition::new(id as i32, 0),
+        )
+    }
+
+    #[test]
+    fn multi_touch() {
+        let mut tracker = PointerTracker::new();
+        let down = PointerFlags::DOWN | PointerFlags::IN_CONTACT | PointerFlags::IN_RANGE;
+        let update = PointerFlags::UPDATE | PointerFlags::IN_CONTACT | PointerFlags::IN_RANGE;
+        let ev = tracker
+            .process(sample(1, PointerType::Touch, down))
+            .unwrap();
+        assert_eq!(ev.phase, PointerPhase::Down);
+        assert!(ev.is_primary);
+        let ev = tracker
+            .process(sample(2, PointerType::Touch, down))
+            .unwrap();
+        assert_eq!(ev.phase, PointerPhase::Down);
+        assert!(!ev.is_primary);
+        assert_eq!(tracker.contacts(), [PointerId::new(1), PointerId::new(2)]);
+        let ev = tracker
+            .process(sample(2, PointerType::Touch, update))
+            .unwrap();
+        assert_eq!(ev.phase, PointerPhase::Move);
+        assert!(ev.in_contact);
+        let ev = tracker
+            .process(sample(1, PointerType::Touch, PointerFlags::UP))
+            .unwrap();
+        assert_eq!(ev.phase, PointerPhase::Up);
+        assert!(ev.is_primary);
+        assert!(!ev.in_contact);
+        assert!(!tracker.is_tracked(PointerId::new(1)));
+        assert!(tracker.primary().is_none());
+        let ev = tracker
+            .process(sample(3, PointerType::Touch, down))
+            .unwrap();
+        assert_eq!(ev.phase, PointerPhase::Down);
+        assert!(!ev.is_primary);
+        let evs = tracker.cancel_all();
+        assert_eq!(evs.len(), 2);
+        assert!(evs.iter().all(|ev| ev.phase == PointerPhase::Cancel));
+        assert!(tracker.contacts().is_empty());
+        assert!(
+            tracker
+                .process(sample(2, PointerType::Touch, PointerFlags::UP))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn pen_hover_and_cancel() {
+        let mut tracker = PointerTracker::new();
+        let hover = PointerFlags::UPDATE | PointerFlags::IN_RANGE;
+        let ev = tracker.process(sample(5, PointerType::Pen, hover)).unwrap();
+        assert_eq!(ev.phase, PointerPhase::Move);
+        assert!(!ev.in_contact);
+        assert!(ev.is_primary);
+        let down = PointerFlags::DOWN | PointerFlags::IN_CONTACT | PointerFlags::IN_RANGE;
+        let ev = tracker.process(sample(5, PointerType::Pen, down)).unwrap();
+        assert_eq!(ev.phase, PointerPhase::Down);
+        let ev = tracker
+            .process(sample(
+                5,
+                PointerType::Pen,
+                PointerFlags::UP | PointerFlags::IN_RANGE,
+            ))
+            .unwrap();
+        assert_eq!(ev.phase, PointerPhase::Up);
+        assert!(tracker.is_tracked(PointerId::new(5)));
+        assert!(tracker.leave(PointerId::new(5)).is_none());
+        tracker.process(sample(6, PointerType::Pen, down)).unwrap();
+        let ev = tracker
+            .process(sample(
+                6,
+                PointerType::Pen,
+                PointerFlags::CANCELED | PointerFlags::UP,
+            ))
+            .unwrap();
+        assert_eq!(ev.phase, PointerPhase::Cancel);
+        assert!(!tracker.is_tracked(PointerId::new(6)));
+    }
+}
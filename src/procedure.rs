@@ -5,15 +5,23 @@ use std::collections::HashMap;
 use tokio::sync::oneshot;
 use windows::Win32::System::Ole::RevokeDragDrop;
 use windows::Win32::{
-    Foundation::{COLORREF, HWND, LPARAM, LRESULT, POINT, RECT, SIZE, WPARAM},
+    Foundation::{COLORREF, HINSTANCE, HWND, LPARAM, LRESULT, POINT, RECT, SIZE, WPARAM},
     Graphics::Dwm::*,
     Graphics::Gdi::*,
+    System::LibraryLoader::GetModuleHandleW,
     UI::Controls::*,
     UI::HiDpi::{EnableNonClientDpiScaling, GetDpiForWindow},
     UI::Input::Ime::{ISC_SHOWUIALLCANDIDATEWINDOW, ISC_SHOWUICOMPOSITIONWINDOW},
     UI::Input::KeyboardAndMouse::{
-        ReleaseCapture, SetCapture, TME_LEAVE, TRACKMOUSEEVENT, TrackMouseEvent, VIRTUAL_KEY,
+        EnableWindow, GetCapture, GetKeyboardLayout, GetKeyboardState, ReleaseCapture, SetCapture,
+        TME_LEAVE, TRACKMOUSEEVENT, ToUnicodeEx, TrackMouseEvent, VIRTUAL_KEY, VK_CONTROL,
+        VK_LCONTROL, VK_MENU, VK_RCONTROL,
     },
+    UI::Input::Pointer::{
+        GetPointerInfo, GetPointerPenInfo, GetPointerTouchInfo, GetPointerType, POINTER_INFO,
+        POINTER_PEN_INFO, POINTER_TOUCH_INFO,
+    },
+    UI::Input::Touch::{CloseGestureInfoHandle, GESTUREINFO, GetGestureInfo, HGESTUREINFO},
     UI::WindowsAndMessaging::*,
 };
 use windows::core::{BOOL, PWSTR};
@@ -102,6 +110,12 @@ unsafe fn on_mouse_move(hwnd: HWND, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
     unsafe {
         let entered = ENTERED.with(|entered| *entered.borrow());
         let handle = WindowHandle::new(hwnd);
+        let mouse_state = MouseState::from_params(wparam, lparam);
+        let config = click::ClickConfig::system();
+        let mut drag = None;
+        Context::set_window_props(handle, |props| {
+            drag = props.clicks.moved(mouse_state.position, &config);
+        });
         if entered.is_none() {
             TrackMouseEvent(&mut TRACKMOUSEEVENT {
                 cbSize: std::mem::size_of::<TRACKMOUSEEVENT>() as u32,
@@ -114,14 +128,24 @@ unsafe fn on_mouse_move(hwnd: HWND, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
             Context::send_event(
                 handle,
                 event::Event::CursorEntered(event::CursorEntered {
-                    mouse_state: MouseState::from_params(wparam, lparam),
+                    mouse_state: mouse_state.clone(),
                 }),
             );
         } else {
             Context::send_event(
                 handle,
                 event::Event::CursorMoved(event::CursorMoved {
-                    mouse_state: MouseState::from_params(wparam, lparam),
+                    mouse_state: mouse_state.clone(),
+                }),
+            );
+        }
+        if let Some(drag) = drag {
+            Context::send_event(
+                handle,
+                event::Event::DragStarted(event::DragStarted {
+                    button: drag.button,
+                    origin: drag.origin,
+                    mouse_state,
                 }),
             );
         }
@@ -134,10 +158,9 @@ unsafe fn on_set_cursor(hwnd: HWND, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
         if loword(lparam.0 as i32) != HTCLIENT as i16 {
             return DefWindowProcW(hwnd, WM_SETCURSOR, wparam, lparam);
         }
-        let cursor =
-            Context::get_window_props(WindowHandle::new(hwnd), |props| props.cursor.clone())
-                .unwrap();
-        cursor.set();
+        let handle = WindowHandle::new(hwnd);
+        set_window_cursor(handle);
+        update_cursor_clip(handle);
         LRESULT(0)
     }
 }
@@ -166,27 +189,191 @@ unsafe fn on_mouse_input(
     wparam: WPARAM,
     lparam: LPARAM,
 ) -> LRESULT {
-    unsafe {
-        match button_state {
+    let handle = WindowHandle::new(hwnd);
+    let mouse_state = MouseState::from_params(wparam, lparam);
+    let config = click::ClickConfig::system();
+    let mut click_count = 0;
+    Context::set_window_props(handle, |props| {
+        click_count = match button_state {
             ButtonState::Pressed => {
-                SetCapture(hwnd);
+                let time = props.input_clock.elapsed();
+                props
+                    .clicks
+                    .press(button, mouse_state.position, time, &config)
             }
-            ButtonState::Released => {
-                ReleaseCapture().ok();
+            ButtonState::Released => props.clicks.release(button),
+        };
+    });
+    match button_state {
+        ButtonState::Pressed => {
+            let auto_capture =
+                Context::get_window_props(handle, |props| props.auto_capture && !props.capturing);
+            if auto_capture == Some(true) {
+                set_capture(handle);
+                Context::set_window_props(handle, |props| props.auto_captured = true);
+            }
+        }
+        ButtonState::Released => {
+            let auto_captured = Context::get_window_props(handle, |props| props.auto_captured);
+            if auto_captured == Some(true) && mouse_state.buttons.is_empty() {
+                release_capture(handle);
+            }
+        }
+    }
+    Context::send_event(
+        handle,
+        Event::MouseInput(event::MouseInput {
+            button,
+            button_state,
+            click_count,
+            mouse_state,
+        }),
+    );
+    LRESULT(0)
+}
+
+pub(crate) fn set_capture(handle: WindowHandle) {
+    unsafe {
+        SetCapture(handle.as_hwnd());
+    }
+    Context::set_window_props(handle, |props| {
+        props.capturing = true;
+        props.auto_captured = false;
+    });
+}
+
+pub(crate) fn release_capture(handle: WindowHandle) {
+    Context::set_window_props(handle, |props| {
+        props.capturing = false;
+        props.auto_captured = false;
+    });
+    unsafe {
+        if GetCapture() == handle.as_hwnd() {
+            ReleaseCapture().ok();
+        }
+    }
+}
+
+unsafe fn on_capture_changed(hwnd: HWND, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    unsafe {
+        let handle = WindowHandle::new(hwnd);
+        let mut lost = false;
+        if HWND(lparam.0 as _) != hwnd {
+            Context::set_window_props(handle, |props| {
+                lost = props.capturing;
+                props.capturing = false;
+                props.auto_captured = false;
+                props.clicks.cancel();
+            });
+        }
+        if lost {
+            Context::send_event(handle, Event::CaptureLost);
+        }
+        DefWindowProcW(hwnd, WM_CAPTURECHANGED, wparam, lparam)
+    }
+}
+
+/// Confines the cursor with `ClipCursor` while the root window is in the foreground.
+pub(crate) fn update_cursor_clip(handle: WindowHandle) {
+    let Some((confine, locked)) =
+        Context::get_window_props(handle, |props| (props.cursor_confine, props.cursor_locked))
+    else {
+        return;
+    };
+    unsafe {
+        let hwnd = handle.as_hwnd();
+        if GetAncestor(hwnd, GA_ROOT) != GetForegroundWindow() {
+            return;
+        }
+        let rc = get_client_rect(hwnd);
+        let size = PhysicalSize::new((rc.right - rc.left) as u32, (rc.bottom - rc.top) as u32);
+        let Some(rc) = cursor_clip_rect(size, confine, locked) else {
+            return;
+        };
+        let mut lt = POINT {
+            x: rc.left,
+            y: rc.top,
+        };
+        let mut rb = POINT {
+            x: rc.right,
+            y: rc.bottom,
+        };
+        let _ = ClientToScreen(hwnd, &mut lt);
+        let _ = ClientToScreen(hwnd, &mut rb);
+        let rc = RECT {
+            left: lt.x,
+            top: lt.y,
+            right: rb.x,
+            bottom: rb.y,
+        };
+        ClipCursor(Some(&rc as *const RECT)).ok();
+    }
+}
+
+/// Applies `confine_cursor`, `hide_cursor` and `lock_cursor` of the window.
+pub(crate) fn update_cursor_mode(handle: WindowHandle) {
+    if Context::window_is_none(handle) {
+        return;
+    }
+    unsafe {
+        let hwnd = handle.as_hwnd();
+        if GetAncestor(hwnd, GA_ROOT) == GetForegroundWindow() {
+            ClipCursor(None).ok();
+        }
+        update_cursor_clip(handle);
+        if is_cursor_over(hwnd) {
+            set_window_cursor(handle);
+        }
+    }
+}
+
+fn is_cursor_over(hwnd: HWND) -> bool {
+    let mut pt = POINT::default();
+    unsafe { GetCursorPos(&mut pt).is_ok() && WindowFromPoint(pt) == hwnd }
+}
+
+/// Sets the cursor of the window, and schedules the next step of an animated cursor.
+fn set_window_cursor(handle: WindowHandle) {
+    let Some((cursor, hidden, step)) = Context::get_window_props(handle, |props| {
+        (
+            props.cursor.clone(),
+            props.cursor_hidden || props.cursor_locked,
+            props.cursor_step,
+        )
+    }) else {
+        return;
+    };
+    unsafe {
+        let hwnd = handle.as_hwnd();
+        let delay = if hidden {
+            SetCursor(None);
+            None
+        } else {
+            cursor.set(GetDpiForWindow(hwnd), step)
+        };
+        match delay {
+            Some(delay) => {
+                SetTimer(
+                    Some(hwnd),
+                    CURSOR_TIMER_ID,
+                    delay.as_millis().max(1) as u32,
+                    None,
+                );
+            }
+            None => {
+                KillTimer(Some(hwnd), CURSOR_TIMER_ID).ok();
             }
         }
-        Context::send_event(
-            WindowHandle::new(hwnd),
-            Event::MouseInput(event::MouseInput {
-                button,
-                button_state,
-                mouse_state: MouseState::from_params(wparam, lparam),
-            }),
-        );
-        LRESULT(0)
     }
 }
 
+fn has_cursor_clip(handle: WindowHandle) -> bool {
+    Context::get_window_props(handle, |props| {
+        props.cursor_confine.is_some() || props.cursor_locked
+    })
+    .unwrap_or(false)
+}
+
 unsafe fn on_mouse_wheel(
     hwnd: HWND,
     axis: MouseWheelAxis,
@@ -194,50 +381,494 @@ unsafe fn on_mouse_wheel(
     lparam: LPARAM,
 ) -> LRESULT {
     unsafe {
-        let delta = hiword(wparam.0 as i32);
+        let handle = WindowHandle::new(hwnd);
+        let distance = hiword(wparam.0 as i32) as i32;
         let mouse_state = MouseState::from_params(wparam, lparam);
         let mut pt = POINT {
             x: mouse_state.position.x,
             y: mouse_state.position.y,
         };
         let _ = ScreenToClient(hwnd, &mut pt);
-        Context::send_event(
-            WindowHandle::new(hwnd),
-            Event::MouseWheel(event::MouseWheel {
-                axis,
-                distance: delta as i32,
-                mouse_state: MouseState {
-                    position: PhysicalPosition::new(pt.x, pt.y),
-                    buttons: mouse_state.buttons,
-                    keys: mouse_state.keys,
-                },
-            }),
+        let mouse_state = MouseState {
+            position: PhysicalPosition::new(pt.x, pt.y),
+            buttons: mouse_state.buttons,
+            keys: mouse_state.keys,
+            modifiers: mouse_state.modifiers,
+        };
+        let settings = wheel::ScrollSettings::system();
+        let mut steps = vec![];
+        let mut prev = None;
+        let mut needs_poll = false;
+        Context::set_window_props(handle, |props| {
+            let time = props.input_clock.elapsed();
+            steps = props.wheel.process(axis, distance, time, &settings);
+            prev = props.wheel_mouse_state.replace(mouse_state.clone());
+            needs_poll = props.wheel.needs_poll();
+        });
+        for step in steps {
+            let mouse_state = match step.phase {
+                Some(ScrollPhase::End) => prev.clone().unwrap_or_else(|| mouse_state.clone()),
+                _ => mouse_state.clone(),
+            };
+            Context::send_event(
+                handle,
+                Event::MouseWheel(wheel::to_event(&step, distance, mouse_state)),
+            );
+        }
+        if needs_poll {
+            SetTimer(
+                Some(hwnd),
+                WHEEL_TIMER_ID,
+                wheel::SCROLL_END_TIMEOUT.as_millis() as u32,
+                None,
+            );
+        }
+        LRESULT(0)
+    }
+}
+
+/// Reads the position, the buttons and the pen or touch details of a pointer.
+fn pointer_sample(hwnd: HWND, id: u32) -> Option<pointer::PointerSample> {
+    unsafe {
+        let mut ty = POINTER_INPUT_TYPE::default();
+        GetPointerType(id, &mut ty).ok()?;
+        let mut properties = PointerProperties::default();
+        let info = match ty {
+            PT_PEN => {
+                let mut pen = POINTER_PEN_INFO::default();
+                GetPointerPenInfo(id, &mut pen).ok()?;
+                if pen.penMask & PEN_MASK_PRESSURE != 0 {
+                    properties.pressure = Some(pen.pressure as f32 / 1024.0);
+                }
+                if pen.penMask & PEN_MASK_ROTATION != 0 {
+                    properties.twist = pen.rotation;
+                }
+                if pen.penMask & PEN_MASK_TILT_X != 0 {
+                    properties.tilt_x = pen.tiltX;
+                }
+                if pen.penMask & PEN_MASK_TILT_Y != 0 {
+                    properties.tilt_y = pen.tiltY;
+                }
+                properties.pen_buttons = PenButtons::from_bits_truncate(pen.penFlags);
+                pen.pointerInfo
+            }
+            PT_TOUCH => {
+                let mut touch = POINTER_TOUCH_INFO::default();
+                GetPointerTouchInfo(id, &mut touch).ok()?;
+                if touch.touchMask & TOUCH_MASK_PRESSURE != 0 {
+                    properties.pressure = Some(touch.pressure as f32 / 1024.0);
+                }
+                if touch.touchMask & TOUCH_MASK_ORIENTATION != 0 {
+                    properties.twist = touch.orientation;
+                }
+                if touch.touchMask & TOUCH_MASK_CONTACTAREA != 0 {
+                    let rc = touch.rcContact;
+                    properties.contact_size = Some(PhysicalSize::new(
+                        (rc.right - rc.left).max(0) as u32,
+                        (rc.bottom - rc.top).max(0) as u32,
+                    ));
+                }
+                touch.pointerInfo
+            }
+            _ => {
+                let mut info = POINTER_INFO::default();
+                GetPointerInfo(id, &mut info).ok()?;
+                info
+            }
+        };
+        let pointer_type = match ty {
+            PT_TOUCH => PointerType::Touch,
+            PT_PEN => PointerType::Pen,
+            PT_TOUCHPAD => PointerType::Touchpad,
+            _ => PointerType::Mouse,
+        };
+        let mut pt = info.ptPixelLocation;
+        let _ = ScreenToClient(hwnd, &mut pt);
+        let sample = pointer::PointerSample::new(
+            PointerId::new(id),
+            pointer_type,
+            pointer::PointerFlags::from_bits_truncate(info.pointerFlags.0),
+            PhysicalPosition::new(pt.x, pt.y),
         );
+        Some(
+            sample
+                .properties(properties)
+                .modifiers(ModifierState::current()),
+        )
+    }
+}
+
+unsafe fn on_pointer(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    unsafe {
+        let handle = WindowHandle::new(hwnd);
+        let enabled = Context::get_window_props(handle, |props| props.pointer_input);
+        if enabled != Some(true) {
+            return DefWindowProcW(hwnd, msg, wparam, lparam);
+        }
+        let id = (wparam.0 & 0xffff) as u32;
+        let mut evs = vec![];
+        let mut mouse = true;
+        match msg {
+            WM_POINTERLEAVE | WM_POINTERCAPTURECHANGED => {
+                Context::set_window_props(handle, |props| {
+                    evs.extend(props.pointers.leave(PointerId::new(id)));
+                });
+            }
+            _ => {
+                if let Some(sample) = pointer_sample(hwnd, id) {
+                    mouse = sample.pointer_type == PointerType::Mouse;
+                    Context::set_window_props(handle, |props| {
+                        evs.extend(props.pointers.process(sample));
+                    });
+                }
+            }
+        }
+        let gestures = Context::get_window_props(handle, |props| props.gestures);
+        for ev in evs {
+            Context::send_event(handle, Event::Pointer(ev.clone()));
+            if gestures == Some(true) && ev.pointer_type == PointerType::Touch {
+                update_gestures(hwnd, |recognizer, time| {
+                    recognizer.process(gesture::GestureSample::from_pointer(&ev, time))
+                });
+            }
+        }
+        if mouse {
+            DefWindowProcW(hwnd, msg, wparam, lparam)
+        } else {
+            LRESULT(0)
+        }
+    }
+}
+
+const GESTURE_TIMER_ID: usize = 1;
+const WHEEL_TIMER_ID: usize = 2;
+const CURSOR_TIMER_ID: usize = 3;
+
+fn update_gestures(
+    hwnd: HWND,
+    f: impl FnOnce(&mut gesture::GestureRecognizer, std::time::Duration) -> Vec<gesture::Gesture>,
+) {
+    let handle = WindowHandle::new(hwnd);
+    let mut gestures = vec![];
+    let mut needs_poll = false;
+    Context::set_window_props(handle, |props| {
+        let time = props.input_clock.elapsed();
+        gestures = f(&mut props.gesture_recognizer, time);
+        needs_poll = props.gesture_recognizer.needs_poll();
+    });
+    for gesture in gestures {
+        Context::send_event(handle, Event::Gesture(gesture));
+    }
+    unsafe {
+        if needs_poll {
+            SetTimer(Some(hwnd), GESTURE_TIMER_ID, 16, None);
+        } else {
+            KillTimer(Some(hwnd), GESTURE_TIMER_ID).ok();
+        }
+    }
+}
+
+unsafe fn on_timer(hwnd: HWND, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    unsafe {
+        match wparam.0 {
+            GESTURE_TIMER_ID => update_gestures(hwnd, |recognizer, time| recognizer.poll(time)),
+            WHEEL_TIMER_ID => {
+                let handle = WindowHandle::new(hwnd);
+                let mut end = None;
+                let mut needs_poll = false;
+                Context::set_window_props(handle, |props| {
+                    let time = props.input_clock.elapsed();
+                    end = props.wheel.poll(time).zip(props.wheel_mouse_state.clone());
+                    needs_poll = props.wheel.needs_poll();
+                });
+                if !needs_poll {
+                    KillTimer(Some(hwnd), WHEEL_TIMER_ID).ok();
+                }
+                if let Some((step, mouse_state)) = end {
+                    Context::send_event(
+                        handle,
+                        Event::MouseWheel(wheel::to_event(&step, 0, mouse_state)),
+                    );
+                }
+            }
+            CURSOR_TIMER_ID => {
+                let handle = WindowHandle::new(hwnd);
+                Context::set_window_props(handle, |props| {
+                    props.cursor_step = props.cursor_step.wrapping_add(1);
+                });
+                if is_cursor_over(hwnd) {
+                    set_window_cursor(handle);
+                } else {
+                    KillTimer(Some(hwnd), CURSOR_TIMER_ID).ok();
+                }
+            }
+            _ => return DefWindowProcW(hwnd, WM_TIMER, wparam, lparam),
+        }
         LRESULT(0)
     }
 }
 
+unsafe fn on_gesture(hwnd: HWND, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    unsafe {
+        let handle = WindowHandle::new(hwnd);
+        let enabled =
+            Context::get_window_props(handle, |props| props.gestures && !props.pointer_input);
+        if enabled != Some(true) {
+            return DefWindowProcW(hwnd, WM_GESTURE, wparam, lparam);
+        }
+        let hinfo = HGESTUREINFO(lparam.0 as _);
+        let mut info = GESTUREINFO {
+            cbSize: std::mem::size_of::<GESTUREINFO>() as u32,
+            ..Default::default()
+        };
+        if GetGestureInfo(hinfo, &mut info).is_err() {
+            return DefWindowProcW(hwnd, WM_GESTURE, wparam, lparam);
+        }
+        let mut pt = POINT {
+            x: info.ptsLocation.x as i32,
+            y: info.ptsLocation.y as i32,
+        };
+        let _ = ScreenToClient(hwnd, &mut pt);
+        let mut gesture = None;
+        Context::set_window_props(handle, |props| {
+            let time = props.input_clock.elapsed();
+            gesture = props.gesture_info.decode(
+                info.dwID,
+                info.dwFlags,
+                PhysicalPosition::new(pt.x, pt.y),
+                info.ullArguments,
+                time,
+            );
+        });
+        let Some(gesture) = gesture else {
+            return DefWindowProcW(hwnd, WM_GESTURE, wparam, lparam);
+        };
+        Context::send_event(handle, Event::Gesture(gesture));
+        CloseGestureInfoHandle(hinfo).ok();
+        LRESULT(0)
+    }
+}
+
+/// Translates a key with the keyboard state without changing the dead key state of the thread.
+fn to_unicode(vkey: u32, scan_code: u32, key_state: &[u8; 256]) -> Option<(String, bool)> {
+    const DONT_CHANGE_KEYBOARD_STATE: u32 = 0x04;
+    let mut buffer = [0u16; 8];
+    let len = unsafe {
+        ToUnicodeEx(
+            vkey,
+            scan_code,
+            key_state,
+            &mut buffer,
+            DONT_CHANGE_KEYBOARD_STATE,
+            Some(GetKeyboardLayout(0)),
+        )
+    };
+    match len {
+        0 => None,
+        len if len < 0 => Some((String::from_utf16_lossy(&buffer[..1]), true)),
+        len => Some((String::from_utf16_lossy(&buffer[..len as usize]), false)),
+    }
+}
+
+fn translate_key(vkey: VIRTUAL_KEY, scan_code: u32) -> (LogicalKey, Option<String>) {
+    let mut key_state = [0u8; 256];
+    if unsafe { GetKeyboardState(&mut key_state) }.is_err() {
+        return (LogicalKey::Named(vkey.into()), None);
+    }
+    let printable = |s: &String| !s.chars().any(|c| c.is_control());
+    let text = to_unicode(vkey.0 as u32, scan_code, &key_state)
+        .filter(|(s, dead)| !dead && printable(s))
+        .map(|(s, _)| s);
+    // AltGr is Ctrl+Alt, so Ctrl is removed only without Alt.
+    if key_state[VK_MENU.0 as usize] & 0x80 == 0 {
+        for vkey in [VK_CONTROL, VK_LCONTROL, VK_RCONTROL] {
+            key_state[vkey.0 as usize] = 0;
+        }
+    }
+    let logical_key = match to_unicode(vkey.0 as u32, scan_code, &key_state) {
+        Some((s, true)) => LogicalKey::Dead(s.chars().next()),
+        Some((s, false)) if printable(&s) => LogicalKey::Character(s),
+        _ => LogicalKey::Named(vkey.into()),
+    };
+    (logical_key, text)
+}
+
 unsafe fn on_key_input(hwnd: HWND, key_state: KeyState, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
-    let vkey = VirtualKey::from(VIRTUAL_KEY(wparam.0 as u16));
-    let scan_code = ScanCode(((lparam.0 >> 16) & 0x7f) as u32);
+    let vkey = VIRTUAL_KEY(wparam.0 as u16);
+    let extended = (lparam.0 >> 24) & 0x01 != 0;
+    let raw_scan_code = ((lparam.0 >> 16) & 0xff) as u32;
+    let scan_code = if extended {
+        0xe000 | raw_scan_code
+    } else {
+        raw_scan_code
+    };
     let prev_pressed = (lparam.0 >> 30) & 0x01 != 0;
+    let (logical_key, text) = translate_key(vkey, raw_scan_code);
+    let text = text.filter(|_| key_state == KeyState::Pressed);
     Context::send_event(
         WindowHandle::new(hwnd),
         Event::KeyInput(event::KeyInput {
-            key_code: KeyCode::new(vkey, scan_code),
+            key_code: KeyCode::new(vkey.into(), ScanCode(scan_code)),
             key_state,
             prev_pressed,
+            modifiers: ModifierState::current(),
+            logical_key,
+            text,
         }),
     );
     LRESULT(0)
 }
 
+unsafe fn on_sys_key_input(
+    hwnd: HWND,
+    msg: u32,
+    key_state: KeyState,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    unsafe {
+        on_key_input(hwnd, key_state, wparam, lparam);
+        DefWindowProcW(hwnd, msg, wparam, lparam)
+    }
+}
+
+/// Sends `Event::Accelerator` instead of `WM_KEYDOWN` and `WM_SYSKEYDOWN` if the key matches
+/// accelerators of the window or its root window.
+pub(crate) fn translate_accelerator(msg: &MSG) -> bool {
+    if msg.message != WM_KEYDOWN && msg.message != WM_SYSKEYDOWN {
+        return false;
+    }
+    let key = VirtualKey::from(VIRTUAL_KEY(msg.wParam.0 as u16));
+    let modifiers = ModifierState::current().to_modifiers();
+    let root = unsafe { GetAncestor(msg.hwnd, GA_ROOT) };
+    for handle in [WindowHandle::new(msg.hwnd), WindowHandle::new(root)] {
+        let id = Context::get_window_props(handle, |props| props.accelerators.find(key, modifiers));
+        if let Some(Some(id)) = id {
+            Context::send_event(
+                handle,
+                Event::Accelerator(event::Accelerator {
+                    id,
+                    chord: Chord::new(key, modifiers),
+                }),
+            );
+            return true;
+        }
+    }
+    false
+}
+
+fn send_chars(handle: WindowHandle, chars: Vec<char>, batch: bool) {
+    if batch {
+        if !chars.is_empty() {
+            Context::send_event(
+                handle,
+                Event::TextInput(event::TextInput {
+                    text: chars.into_iter().collect(),
+                }),
+            );
+        }
+    } else {
+        for c in chars {
+            Context::send_event(handle, Event::CharInput(event::CharInput { c }));
+        }
+    }
+}
+
 unsafe fn on_char(hwnd: HWND, wparam: WPARAM, _lparam: LPARAM) -> LRESULT {
-    if let Some(c) = char::from_u32(wparam.0 as u32) {
-        Context::send_event(
-            WindowHandle::new(hwnd),
-            Event::CharInput(event::CharInput { c }),
-        );
+    unsafe {
+        let handle = WindowHandle::new(hwnd);
+        let Some(batch) = Context::get_window_props(handle, |props| props.batch_text_input) else {
+            return LRESULT(0);
+        };
+        let mut chars = vec![];
+        let mut decode = |unit: u16| {
+            Context::set_window_props(handle, |props| chars.extend(props.utf16.push(unit)));
+        };
+        decode(wparam.0 as u16);
+        if batch {
+            let mut msg = MSG::default();
+            while PeekMessageW(&mut msg, Some(hwnd), WM_CHAR, WM_CHAR, PM_REMOVE).as_bool() {
+                decode(msg.wParam.0 as u16);
+            }
+        }
+        send_chars(handle, chars, batch);
+        LRESULT(0)
+    }
+}
+
+unsafe fn on_unichar(hwnd: HWND, wparam: WPARAM, _lparam: LPARAM) -> LRESULT {
+    if wparam.0 as u32 == UNICODE_NOCHAR {
+        return LRESULT(1);
+    }
+    let handle = WindowHandle::new(hwnd);
+    let Some(batch) = Context::get_window_props(handle, |props| props.batch_text_input) else {
+        return LRESULT(0);
+    };
+    let chars = char::from_u32(wparam.0 as u32).into_iter().collect();
+    send_chars(handle, chars, batch);
+    LRESULT(0)
+}
+
+unsafe fn on_dead_char(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    unsafe {
+        if let Some(c) = char::from_u32(wparam.0 as u32) {
+            Context::send_event(
+                WindowHandle::new(hwnd),
+                Event::DeadCharInput(event::DeadCharInput { c }),
+            );
+        }
+        if msg == WM_SYSDEADCHAR {
+            return DefWindowProcW(hwnd, msg, wparam, lparam);
+        }
+        LRESULT(0)
+    }
+}
+
+unsafe fn on_sys_char(hwnd: HWND, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    unsafe {
+        if let Some(c) = char::from_u32(wparam.0 as u32) {
+            Context::send_event(
+                WindowHandle::new(hwnd),
+                Event::SysCharInput(event::SysCharInput { c }),
+            );
+        }
+        DefWindowProcW(hwnd, WM_SYSCHAR, wparam, lparam)
+    }
+}
+
+unsafe fn on_input(hwnd: HWND, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    unsafe {
+        let handle = WindowHandle::new(hwnd);
+        if let Some(buffer) = raw_input::read(lparam) {
+            match raw_input::decode(&buffer) {
+                Ok(input) => {
+                    if let Some(ev) = raw_input::to_event(input) {
+                        Context::send_event(handle, ev);
+                    }
+                }
+                Err(e) => {
+                    error!("raw_input::decode: {e}");
+                }
+            }
+        }
+        DefWindowProcW(hwnd, WM_INPUT, wparam, lparam)
+    }
+}
+
+unsafe fn on_input_device_change(hwnd: HWND, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    let handle = WindowHandle::new(hwnd);
+    let device = DeviceId::from_raw(lparam.0 as u64);
+    match wparam.0 as u32 {
+        GIDC_ARRIVAL => {
+            Context::send_event(handle, Event::DeviceAdded(event::DeviceAdded { device }));
+        }
+        GIDC_REMOVAL => {
+            Context::send_event(
+                handle,
+                Event::DeviceRemoved(event::DeviceRemoved { device }),
+            );
+        }
+        _ => {}
     }
     LRESULT(0)
 }
@@ -308,21 +939,45 @@ unsafe fn on_ime_end_composition(hwnd: HWND, wparam: WPARAM, lparam: LPARAM) ->
     }
 }
 
-unsafe fn on_sizing(hwnd: HWND, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+/// Returns the difference between the window size and the client size.
+fn frame_size(hwnd: HWND) -> PhysicalSize<i32> {
+    let window_rc = get_window_rect(hwnd);
+    let client_rc = get_client_rect(hwnd);
+    PhysicalSize::new(
+        (window_rc.right - window_rc.left) - (client_rc.right - client_rc.left),
+        (window_rc.bottom - window_rc.top) - (client_rc.bottom - client_rc.top),
+    )
+}
+
+unsafe fn on_get_min_max_info(hwnd: HWND, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
     unsafe {
-        let d = {
-            let window_rc = get_window_rect(hwnd);
-            let client_rc = get_client_rect(hwnd);
-            PhysicalSize::new(
-                (window_rc.right - window_rc.left) - (client_rc.right - client_rc.left),
-                (window_rc.bottom - window_rc.top) - (client_rc.bottom - client_rc.top),
-            )
+        let constraints = Context::get_window_props(WindowHandle::new(hwnd), |props| {
+            props.fullscreen.is_none().then_some(props.size_constraints)
+        });
+        let constraints = constraints.flatten();
+        let Some(constraints) = constraints.filter(|c| c.min.is_some() || c.max.is_some()) else {
+            return DefWindowProcW(hwnd, WM_GETMINMAXINFO, wparam, lparam);
         };
-        let rc = (lparam.0 as *mut RECT).as_mut().unwrap();
-        let size = PhysicalSize::new(
-            (rc.right - rc.left - d.width) as u32,
-            (rc.bottom - rc.top - d.height) as u32,
-        );
+        let (min, max) = constraints.min_max(GetDpiForWindow(hwnd));
+        let frame = frame_size(hwnd);
+        let to_point = |size: PhysicalSize<u32>| POINT {
+            x: (size.width.min(i32::MAX as u32) as i32).saturating_add(frame.width),
+            y: (size.height.min(i32::MAX as u32) as i32).saturating_add(frame.height),
+        };
+        let info = (lparam.0 as *mut MINMAXINFO).as_mut().unwrap();
+        if constraints.min.is_some() {
+            info.ptMinTrackSize = to_point(min);
+        }
+        if constraints.max.is_some() {
+            info.ptMaxTrackSize = to_point(max);
+        }
+        LRESULT(0)
+    }
+}
+
+unsafe fn on_sizing(hwnd: HWND, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    unsafe {
+        let handle = WindowHandle::new(hwnd);
         let edge = match wparam.0 as u32 {
             WMSZ_LEFT => ResizingEdge::Left,
             WMSZ_RIGHT => ResizingEdge::Right,
@@ -334,10 +989,18 @@ unsafe fn on_sizing(hwnd: HWND, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
             WMSZ_BOTTOMRIGHT => ResizingEdge::BottomRight,
             _ => unreachable!(),
         };
-        Context::send_event(
-            WindowHandle::new(hwnd),
-            Event::Resizing(event::Resizing { size, edge }),
+        let d = frame_size(hwnd);
+        let rc = (lparam.0 as *mut RECT).as_mut().unwrap();
+        let constraints = Context::get_window_props(handle, |props| props.size_constraints);
+        if let Some(constraints) = constraints.filter(|c| !c.is_empty()) {
+            let adjusted = constraints.adjust_rect((*rc).into(), edge, d, GetDpiForWindow(hwnd));
+            *rc = adjusted.into();
+        }
+        let size = PhysicalSize::new(
+            (rc.right - rc.left - d.width).max(0) as u32,
+            (rc.bottom - rc.top - d.height).max(0) as u32,
         );
+        Context::send_event(handle, Event::Resizing(event::Resizing { size, edge }));
         DefWindowProcW(hwnd, WM_SIZING, wparam, lparam)
     }
 }
@@ -381,6 +1044,7 @@ unsafe fn on_window_pos_changed(hwnd: HWND, wparam: WPARAM, lparam: LPARAM) -> L
                 }),
             );
         }
+        update_cursor_clip(WindowHandle::new(hwnd));
         DefWindowProcW(hwnd, WM_WINDOWPOSCHANGED, wparam, lparam)
     }
 }
@@ -416,27 +1080,61 @@ unsafe fn on_activate(hwnd: HWND, wparam: WPARAM, _lparam: LPARAM) -> LRESULT {
     let active = wparam.0 as u32 & (WA_ACTIVE | WA_CLICKACTIVE) != 0;
     let handle = WindowHandle::new(hwnd);
     if active {
+        update_cursor_clip(handle);
         Context::send_event(handle, Event::Activated);
     } else {
+        if has_cursor_clip(handle) {
+            unsafe {
+                ClipCursor(None).ok();
+            }
+        }
         Context::send_event(handle, Event::Inactivated);
     }
     LRESULT(0)
 }
 
+unsafe fn on_set_focus(hwnd: HWND, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    unsafe {
+        Context::send_event(
+            WindowHandle::new(hwnd),
+            Event::Focused(event::Focused { focused: true }),
+        );
+        DefWindowProcW(hwnd, WM_SETFOCUS, wparam, lparam)
+    }
+}
+
+unsafe fn on_kill_focus(hwnd: HWND, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    unsafe {
+        Context::send_event(
+            WindowHandle::new(hwnd),
+            Event::Focused(event::Focused { focused: false }),
+        );
+        DefWindowProcW(hwnd, WM_KILLFOCUS, wparam, lparam)
+    }
+}
+
 unsafe fn on_dpi_changed(hwnd: HWND, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
     unsafe {
         let rc = *(lparam.0 as *const RECT);
-        SetWindowPos(
-            hwnd,
-            None,
-            rc.left,
-            rc.top,
-            rc.right - rc.left,
-            rc.bottom - rc.top,
-            SWP_NOZORDER | SWP_NOACTIVATE,
-        )
-        .ok();
+        let fullscreen =
+            Context::get_window_props(WindowHandle::new(hwnd), |props| props.fullscreen.is_some());
+        if !fullscreen.unwrap_or(false) {
+            SetWindowPos(
+                hwnd,
+                None,
+                rc.left,
+                rc.top,
+                rc.right - rc.left,
+                rc.bottom - rc.top,
+                SWP_NOZORDER | SWP_NOACTIVATE,
+            )
+            .ok();
+        }
         let new_dpi = hiword(wparam.0 as i32) as u32;
+        let icon = Context::get_window_props(WindowHandle::new(hwnd), |props| props.icon.clone());
+        if let Some(icon) = icon.flatten() {
+            set_window_icon(hwnd, &icon, new_dpi);
+        }
         Context::send_event(
             WindowHandle::new(hwnd),
             Event::DpiChanged(event::DpiChanged { new_dpi }),
@@ -445,6 +1143,31 @@ unsafe fn on_dpi_changed(hwnd: HWND, wparam: WPARAM, lparam: LPARAM) -> LRESULT
     }
 }
 
+/// Sets the big and small icons selected for `dpi` to the window.
+pub(crate) fn set_window_icon(hwnd: HWND, icon: &Icon, dpi: u32) {
+    unsafe {
+        let hinstance: Option<HINSTANCE> = Some(GetModuleHandleW(None).unwrap().into());
+        for (kind, loaded) in [
+            (ICON_BIG, icon.load(hinstance, dpi)),
+            (ICON_SMALL, icon.load_small(hinstance, dpi)),
+        ] {
+            match loaded {
+                Ok(handle) => {
+                    SendMessageW(
+                        hwnd,
+                        WM_SETICON,
+                        Some(WPARAM(kind as usize)),
+                        Some(LPARAM(handle.0 as isize)),
+                    );
+                }
+                Err(e) => {
+                    error!("{e}");
+                }
+            }
+        }
+    }
+}
+
 unsafe fn on_get_dpi_scaled_size(hwnd: HWND, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
     unsafe {
         let prev_dpi = GetDpiForWindow(hwnd) as i32;
@@ -486,7 +1209,7 @@ unsafe fn on_nc_hittest(hwnd: HWND, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
         let (tx, rx) = oneshot::channel();
         Context::send_event(
             WindowHandle::new(hwnd),
-            Event::NcHitTest(event::NcHitTest::new(lparam, tx)),
+            Event::NcHitTest(event::NcHitTest::new(lparam_to_point(lparam), tx)),
         );
         match rx.blocking_recv() {
             Ok(Some(value)) => LRESULT(value as isize),
@@ -500,6 +1223,7 @@ unsafe fn on_close(hwnd: HWND, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
         let handle = WindowHandle::new(hwnd);
         let auto_close = Context::get_window_props(handle, |props| props.auto_close).unwrap();
         if auto_close {
+            enable_modal_owner(hwnd);
             return DefWindowProcW(hwnd, WM_CLOSE, wparam, lparam);
         }
         Context::send_event(
@@ -515,8 +1239,16 @@ unsafe fn on_destroy(hwnd: HWND) -> LRESULT {
         let handle = WindowHandle::new(hwnd);
         remove_raw_procedure_handler(hwnd);
         RevokeDragDrop(hwnd).ok();
+        let raw_input = Context::get_window_props(handle, |props| props.raw_input.clone());
+        if let Some(raw_input) = raw_input.filter(|r| !r.is_empty()) {
+            raw_input::register(hwnd, &raw_input, &RawInputDevices::new()).ok();
+        }
         Context::send_event(handle, Event::Closed);
-        Context::remove_window(handle);
+        let modal = Context::remove_window(handle).and_then(|obj| obj.props.modal);
+        if let Some(modal) = modal {
+            let _ = EnableWindow(modal.owner.as_hwnd(), true);
+            modal.finish();
+        }
         if Context::is_empty() {
             PostQuitMessage(0);
         }
@@ -524,6 +1256,20 @@ unsafe fn on_destroy(hwnd: HWND) -> LRESULT {
     }
 }
 
+/// Enables the owner of a modal window before the window is destroyed, so that the owner is
+/// activated instead of a window of another application.
+pub(crate) fn enable_modal_owner(hwnd: HWND) {
+    let owner = Context::get_window_props(WindowHandle::new(hwnd), |props| {
+        props.modal.as_ref().map(|modal| modal.owner)
+    })
+    .flatten();
+    if let Some(owner) = owner {
+        unsafe {
+            let _ = EnableWindow(owner.as_hwnd(), true);
+        }
+    }
+}
+
 unsafe fn on_app(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
     let handle = WindowHandle::new(hwnd);
     Context::send_event(
@@ -904,6 +1650,13 @@ unsafe fn on_setting_change(hwnd: HWND, wparam: WPARAM, lparam: LPARAM) -> LRESU
     }
 }
 
+unsafe fn on_display_change(hwnd: HWND, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    unsafe {
+        Context::send_event(WindowHandle::new(hwnd), Event::MonitorsChanged);
+        DefWindowProcW(hwnd, WM_DISPLAYCHANGE, wparam, lparam)
+    }
+}
+
 fn wparam_to_button(wparam: WPARAM) -> MouseButton {
     match get_xbutton_wparam(wparam) {
         0x0001 => MouseButton::Ex(0),
@@ -928,7 +1681,7 @@ pub(crate) extern "system" fn window_proc(
             WM_MOUSEMOVE => on_mouse_move(hwnd, wparam, lparam),
             WM_SETCURSOR => on_set_cursor(hwnd, wparam, lparam),
             WM_MOUSELEAVE => on_mouse_leave(hwnd, wparam, lparam),
-            WM_LBUTTONDOWN => {
+            WM_LBUTTONDOWN | WM_LBUTTONDBLCLK => {
                 on_mouse_input(
                     hwnd,
                     MouseButton::Left,
@@ -936,23 +1689,23 @@ pub(crate) extern "system" fn window_proc(
                     wparam,
                     lparam,
                 );
-                DefWindowProcW(hwnd, WM_LBUTTONDOWN, wparam, lparam)
+                DefWindowProcW(hwnd, msg, wparam, lparam)
             }
-            WM_RBUTTONDOWN => on_mouse_input(
+            WM_RBUTTONDOWN | WM_RBUTTONDBLCLK => on_mouse_input(
                 hwnd,
                 MouseButton::Right,
                 ButtonState::Pressed,
                 wparam,
                 lparam,
             ),
-            WM_MBUTTONDOWN => on_mouse_input(
+            WM_MBUTTONDOWN | WM_MBUTTONDBLCLK => on_mouse_input(
                 hwnd,
                 MouseButton::Middle,
                 ButtonState::Pressed,
                 wparam,
                 lparam,
             ),
-            WM_XBUTTONDOWN => on_mouse_input(
+            WM_XBUTTONDOWN | WM_XBUTTONDBLCLK => on_mouse_input(
                 hwnd,
                 wparam_to_button(wparam),
                 ButtonState::Pressed,
@@ -993,23 +1746,41 @@ pub(crate) extern "system" fn window_proc(
                 wparam,
                 lparam,
             ),
+            WM_CAPTURECHANGED => on_capture_changed(hwnd, wparam, lparam),
             WM_MOUSEWHEEL => on_mouse_wheel(hwnd, MouseWheelAxis::Vertical, wparam, lparam),
             WM_MOUSEHWHEEL => on_mouse_wheel(hwnd, MouseWheelAxis::Horizontal, wparam, lparam),
+            WM_POINTERDOWN
+            | WM_POINTERUPDATE
+            | WM_POINTERUP
+            | WM_POINTERLEAVE
+            | WM_POINTERCAPTURECHANGED => on_pointer(hwnd, msg, wparam, lparam),
+            WM_GESTURE => on_gesture(hwnd, wparam, lparam),
+            WM_TIMER => on_timer(hwnd, wparam, lparam),
             OFFSET_WM_APP..0xbfff => on_app(hwnd, msg, wparam, lparam),
             WM_KEYDOWN => on_key_input(hwnd, KeyState::Pressed, wparam, lparam),
             WM_KEYUP => on_key_input(hwnd, KeyState::Released, wparam, lparam),
+            WM_SYSKEYDOWN => on_sys_key_input(hwnd, msg, KeyState::Pressed, wparam, lparam),
+            WM_SYSKEYUP => on_sys_key_input(hwnd, msg, KeyState::Released, wparam, lparam),
             WM_CHAR => on_char(hwnd, wparam, lparam),
+            WM_UNICHAR => on_unichar(hwnd, wparam, lparam),
+            WM_DEADCHAR | WM_SYSDEADCHAR => on_dead_char(hwnd, msg, wparam, lparam),
+            WM_SYSCHAR => on_sys_char(hwnd, wparam, lparam),
+            WM_INPUT => on_input(hwnd, wparam, lparam),
+            WM_INPUT_DEVICE_CHANGE => on_input_device_change(hwnd, wparam, lparam),
             WM_APP_NOTIFY_ICON => on_notify_icon(hwnd, wparam, lparam),
             WM_IME_SETCONTEXT => on_ime_set_context(hwnd, wparam, lparam),
             WM_IME_STARTCOMPOSITION => on_ime_start_composition(hwnd, wparam, lparam),
             WM_IME_COMPOSITION => on_ime_composition(hwnd, wparam, lparam),
             WM_IME_ENDCOMPOSITION => on_ime_end_composition(hwnd, wparam, lparam),
+            WM_GETMINMAXINFO => on_get_min_max_info(hwnd, wparam, lparam),
             WM_SIZING => on_sizing(hwnd, wparam, lparam),
             WM_SIZE => on_size(hwnd, wparam, lparam),
             WM_WINDOWPOSCHANGED => on_window_pos_changed(hwnd, wparam, lparam),
             WM_ENTERSIZEMOVE => on_enter_size_move(hwnd, wparam, lparam),
             WM_EXITSIZEMOVE => on_exit_size_move(hwnd, wparam, lparam),
             WM_ACTIVATE => on_activate(hwnd, wparam, lparam),
+            WM_SETFOCUS => on_set_focus(hwnd, wparam, lparam),
+            WM_KILLFOCUS => on_kill_focus(hwnd, wparam, lparam),
             WM_NCACTIVATE => on_nc_activate(hwnd, wparam, lparam),
             WM_DPICHANGED => on_dpi_changed(hwnd, wparam, lparam),
             WM_GETDPISCALEDSIZE => on_get_dpi_scaled_size(hwnd, wparam, lparam),
@@ -1018,6 +1789,7 @@ pub(crate) extern "system" fn window_proc(
             WM_MENUCOMMAND => on_menu_command(hwnd, wparam, lparam),
             WM_CONTEXTMENU => on_context_menu(hwnd, wparam, lparam),
             WM_SETTINGCHANGE => on_setting_change(hwnd, wparam, lparam),
+            WM_DISPLAYCHANGE => on_display_change(hwnd, wparam, lparam),
             WM_CLOSE => on_close(hwnd, wparam, lparam),
             WM_DESTROY => on_destroy(hwnd),
             _ => {
@@ -0,0 +1,537 @@
+//! Raw input.
+//!
+//! Raw input delivers unaccelerated mouse motion and key inputs with the device which produced
+//! them. Register devices by `WindowBuilder::raw_input` or `Window::set_raw_input`, and receive
+//! `Event::RawMouseMotion`, `Event::RawKeyInput`, `Event::RawHidInput`, `Event::DeviceAdded` and
+//! `Event::DeviceRemoved`.
+//!
+//! Raw input devices are registered for each process. When multiple windows register the same
+//! device, the last window receives inputs.
+//!
+//! [`decode`] decodes a `RAWINPUT` buffer which `GetRawInputData` returns.
+
+use crate::*;
+#[cfg(windows)]
+use windows::Win32::{Foundation::HWND, UI::Input::*};
+
+const GENERIC_DESKTOP: u16 = 0x01;
+const USAGE_MOUSE: u16 = 0x02;
+const USAGE_KEYBOARD: u16 = 0x06;
+
+const RIM_TYPEMOUSE: u32 = 0;
+const RIM_TYPEKEYBOARD: u32 = 1;
+const RIM_TYPEHID: u32 = 2;
+const RIM_INPUTSINK: usize = 1;
+const MOUSE_MOVE_ABSOLUTE: u16 = 0x01;
+const MOUSE_VIRTUAL_DESKTOP: u16 = 0x02;
+const RI_KEY_BREAK: u16 = 0x01;
+const RI_KEY_E0: u16 = 0x02;
+const RI_KEY_E1: u16 = 0x04;
+
+/// A HID usage page and usage.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HidUsage {
+    pub usage_page: u16,
+    pub usage: u16,
+}
+
+impl HidUsage {
+    #[inline]
+    pub const fn new(usage_page: u16, usage: u16) -> Self {
+        Self { usage_page, usage }
+    }
+}
+
+/// Devices to receive raw inputs.
+///
+/// ```
+/// use wiard::raw_input::RawInputDevices;
+///
+/// let devices = RawInputDevices::new().mouse(true).keyboard(true).background(true);
+/// assert_eq!(devices.usages().len(), 2);
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct RawInputDevices {
+    mouse: bool,
+    keyboard: bool,
+    hid: Vec<HidUsage>,
+    background: bool,
+}
+
+impl RawInputDevices {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn mouse(mut self, flag: bool) -> Self {
+        self.mouse = flag;
+        self
+    }
+
+    #[inline]
+    pub fn keyboard(mut self, flag: bool) -> Self {
+        self.keyboard = flag;
+        self
+    }
+
+    /// Adds a HID usage like gamepads (`0x01`, `0x05`).
+    #[inline]
+    pub fn hid(mut self, usage_page: u16, usage: u16) -> Self {
+        let usage = HidUsage::new(usage_page, usage);
+        if !self.hid.contains(&usage) {
+            self.hid.push(usage);
+        }
+        self
+    }
+
+    /// Receives inputs even when the window is not in the foreground.
+    #[inline]
+    pub fn background(mut self, flag: bool) -> Self {
+        self.background = flag;
+        self
+    }
+
+    #[inline]
+    pub fn is_background(&self) -> bool {
+        self.background
+    }
+
+    /// All usages including mouse and keyboard.
+    pub fn usages(&self) -> Vec<HidUsage> {
+        let mut usages = vec![];
+        if self.mouse {
+            usages.push(HidUsage::new(GENERIC_DESKTOP, USAGE_MOUSE));
+        }
+        if self.keyboard {
+            usages.push(HidUsage::new(GENERIC_DESKTOP, USAGE_KEYBOARD));
+        }
+        usages.extend(self.hid.iter().copied());
+        usages
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        !self.mouse && !self.keyboard && self.hid.is_empty()
+    }
+
+    /// Checks if the decoded input is from registered devices.
+    pub fn accepts(&self, input: &RawInput) -> bool {
+        if input.background && !self.background {
+            return false;
+        }
+        match input.data {
+            RawInputData::Mouse(_) => self.mouse,
+            RawInputData::Keyboard(_) => self.keyboard,
+            RawInputData::Hid(_) => !self.hid.is_empty(),
+        }
+    }
+}
+
+/// Represents a raw input device.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceId(u64);
+
+impl DeviceId {
+    /// Creates from a device handle.
+    #[inline]
+    pub const fn from_raw(handle: u64) -> Self {
+        Self(handle)
+    }
+
+    #[inline]
+    pub const fn raw(&self) -> u64 {
+        self.0
+    }
+}
+
+/// `RAWMOUSE`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RawMouse {
+    /// `x` and `y` are absolute positions normalized to `0..=65535` instead of relative motion.
+    pub absolute: bool,
+    pub virtual_desktop: bool,
+    pub x: i32,
+    pub y: i32,
+    pub button_flags: u16,
+    pub button_data: u16,
+}
+
+/// `RAWKEYBOARD`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RawKeyboard {
+    /// `scan_code` has the `0xe0` or `0xe1` prefix like `KeyInput`.
+    pub key_code: KeyCode,
+    pub key_state: KeyState,
+}
+
+/// `RAWHID`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RawHid {
+    /// The size of each report.
+    pub size: u32,
+    pub count: u32,
+    /// `count` reports.
+    pub data: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum RawInputData {
+    Mouse(RawMouse),
+    Keyboard(RawKeyboard),
+    Hid(RawHid),
+}
+
+/// A decoded `RAWINPUT`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RawInput {
+    pub device: DeviceId,
+    /// The input was received when the window is not in the foreground.
+    pub background: bool,
+    pub data: RawInputData,
+}
+
+/// The error type for `decode`.
+#[derive(Clone, PartialEq, Eq, Debug, thiserror::Error)]
+pub enum DecodeError {
+    #[error("TooShort")]
+    TooShort,
+    #[error("UnknownType: {0}")]
+    UnknownType(u32),
+}
+
+#[cfg(windows)]
+fn virtual_key_from_raw(vkey: u16) -> VirtualKey {
+    windows::Win32::UI::Input::KeyboardAndMouse::VIRTUAL_KEY(vkey).into()
+}
+
+#[cfg(not(windows))]
+fn virtual_key_from_raw(vkey: u16) -> VirtualKey {
+    use VirtualKey::*;
+
+    const DIGITS: [VirtualKey; 10] = [Key0, Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9];
+    const LETTERS: [VirtualKey; 26] = [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    ];
+    const NUMPAD: [VirtualKey; 10] = [Num0, Num1, Num2, Num3, Num4, Num5, Num6, Num7, Num8, Num9];
+    const FUNCTIONS: [VirtualKey; 24] = [
+        F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12, F13, F14, F15, F16, F17, F18, F19, F20,
+        F21, F22, F23, F24,
+    ];
+    const NAMED: [(u16, VirtualKey); 28] = [
+        (0x08, BackSpace),
+        (0x09, Tab),
+        (0x0d, Enter),
+        (0x10, Shift),
+        (0x11, Ctrl),
+        (0x12, Alt),
+        (0x13, Pause),
+        (0x14, CapsLock),
+        (0x1b, Esc),
+        (0x20, Space),
+        (0x21, PageUp),
+        (0x22, PageDown),
+        (0x23, End),
+        (0x24, Home),
+        (0x25, Left),
+        (0x26, Up),
+        (0x27, Right),
+        (0x28, Down),
+        (0x2c, PrintScreen),
+        (0x2d, Insert),
+        (0x2e, Delete),
+        (0x6a, NumMul),
+        (0x6b, NumAdd),
+        (0x6d, NumSub),
+        (0x6e, NumDecimal),
+        (0x6f, NumDiv),
+        (0x90, NumLock),
+        (0x91, ScrollLock),
+    ];
+    let i = vkey as usize;
+    match vkey {
+        0x30..=0x39 => DIGITS[i - 0x30],
+        0x41..=0x5a => LETTERS[i - 0x41],
+        0x60..=0x69 => NUMPAD[i - 0x60],
+        0x70..=0x87 => FUNCTIONS[i - 0x70],
+        _ => NAMED
+            .iter()
+            .find(|(v, _)| *v == vkey)
+            .map_or(Other(vkey as u32), |&(_, key)| key),
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl Reader<'_> {
+    fn bytes<const N: usize>(&self, offset: usize) -> std::result::Result<[u8; N], DecodeError> {
+        self.data
+            .get(offset..offset + N)
+            .and_then(|b| b.try_into().ok())
+            .ok_or(DecodeError::TooShort)
+    }
+
+    fn u16(&self, offset: usize) -> std::result::Result<u16, DecodeError> {
+        self.bytes(offset).map(u16::from_le_bytes)
+    }
+
+    fn u32(&self, offset: usize) -> std::result::Result<u32, DecodeError> {
+        self.bytes(offset).map(u32::from_le_bytes)
+    }
+
+    fn i32(&self, offset: usize) -> std::result::Result<i32, DecodeError> {
+        self.bytes(offset).map(i32::from_le_bytes)
+    }
+
+    fn usize(&self, offset: usize) -> std::result::Result<usize, DecodeError> {
+        const N: usize = std::mem::size_of::<usize>();
+        self.bytes::<N>(offset).map(usize::from_le_bytes)
+    }
+}
+
+/// The size of `RAWINPUTHEADER` on the current platform.
+pub const HEADER_SIZE: usize = 8 + std::mem::size_of::<usize>() * 2;
+
+/// Decodes a `RAWINPUT` buffer with the layout of the current platform.
+pub fn decode(data: &[u8]) -> std::result::Result<RawInput, DecodeError> {
+    let reader = Reader { data };
+    let ty = reader.u32(0)?;
+    let device = DeviceId(reader.usize(8)? as u64);
+    let background = reader.usize(8 + std::mem::size_of::<usize>())? == RIM_INPUTSINK;
+    let body = HEADER_SIZE;
+    let data = match ty {
+        RIM_TYPEMOUSE => {
+            let flags = reader.u16(body)?;
+            RawInputData::Mouse(RawMouse {
+                absolute: flags & MOUSE_MOVE_ABSOLUTE != 0,
+                virtual_desktop: flags & MOUSE_VIRTUAL_DESKTOP != 0,
+                button_flags: reader.u16(body + 4)?,
+                button_data: reader.u16(body + 6)?,
+                x: reader.i32(body + 12)?,
+                y: reader.i32(body + 16)?,
+            })
+        }
+        RIM_TYPEKEYBOARD => {
+            let make_code = reader.u16(body)? as u32;
+            let flags = reader.u16(body + 2)?;
+            let vkey = reader.u16(body + 6)?;
+            let scan_code = if flags & RI_KEY_E0 != 0 {
+                0xe000 | make_code
+            } else if flags & RI_KEY_E1 != 0 {
+                0xe100 | make_code
+            } else {
+                make_code
+            };
+            RawInputData::Keyboard(RawKeyboard {
+                key_code: KeyCode::new(virtual_key_from_raw(vkey), ScanCode(scan_code)),
+                key_state: if flags & RI_KEY_BREAK != 0 {
+                    KeyState::Released
+                } else {
+                    KeyState::Pressed
+                },
+            })
+        }
+        RIM_TYPEHID => {
+            let size = reader.u32(body)?;
+            let count = reader.u32(body + 4)?;
+            let len = size as usize * count as usize;
+            let data = data
+                .get(body + 8..body + 8 + len)
+                .ok_or(DecodeError::TooShort)?;
+            RawInputData::Hid(RawHid {
+                size,
+                count,
+                data: data.to_vec(),
+            })
+        }
+        _ => return Err(DecodeError::UnknownType(ty)),
+    };
+    Ok(RawInput {
+        device,
+        background,
+        data,
+    })
+}
+
+/// Converts a decoded input to the event.
+pub(crate) fn to_event(input: RawInput) -> Option<Event> {
+    let device = input.device;
+    match input.data {
+        RawInputData::Mouse(m) => (m.absolute || m.x != 0 || m.y != 0).then_some(
+            Event::RawMouseMotion(event::RawMouseMotion {
+                device,
+                x: m.x,
+                y: m.y,
+                absolute: m.absolute,
+            }),
+        ),
+        RawInputData::Keyboard(k) => Some(Event::RawKeyInput(event::RawKeyInput {
+            device,
+            key_code: k.key_code,
+            key_state: k.key_state,
+        })),
+        RawInputData::Hid(h) => Some(Event::RawHidInput(event::RawHidInput {
+            device,
+            size: h.size,
+            data: h.data,
+        })),
+    }
+}
+
+#[cfg(windows)]
+fn to_raw_devices(
+    usages: &[HidUsage],
+    flags: RAWINPUTDEVICE_FLAGS,
+    hwnd: HWND,
+) -> Vec<RAWINPUTDEVICE> {
+    usages
+        .iter()
+        .map(|usage| RAWINPUTDEVICE {
+            usUsagePage: usage.usage_page,
+            usUsage: usage.usage,
+            dwFlags: flags,
+            hwndTarget: hwnd,
+        })
+        .collect()
+}
+
+/// Registers `devices`, and removes usages in `prev` which are not in `devices`.
+#[cfg(windows)]
+pub(crate) fn register(
+    hwnd: HWND,
+    prev: &RawInputDevices,
+    devices: &RawInputDevices,
+) -> Result<()> {
+    let usages = devices.usages();
+    let removed = prev
+        .usages()
+        .into_iter()
+        .filter(|u| !usages.contains(u))
+        .collect::<Vec<_>>();
+    let size = std::mem::size_of::<RAWINPUTDEVICE>() as u32;
+    unsafe {
+        if !removed.is_empty() {
+            RegisterRawInputDevices(
+                &to_raw_devices(&removed, RIDEV_REMOVE, HWND::default()),
+                size,
+            )?;
+        }
+        if !usages.is_empty() {
+            let mut flags = RIDEV_DEVNOTIFY;
+            if devices.background {
+                flags |= RIDEV_INPUTSINK;
+            }
+            RegisterRawInputDevices(&to_raw_devices(&usages, flags, hwnd), size)?;
+        }
+    }
+    Ok(())
+}
+
+/// Reads a `RAWINPUT` buffer of `WM_INPUT`.
+#[cfg(windows)]
+pub(crate) fn read(lparam: windows::Win32::Foundation::LPARAM) -> Option<Vec<u8>> {
+    unsafe {
+        let handle = HRAWINPUT(lparam.0 as _);
+        let header_size = std::mem::size_of::<RAWINPUTHEADER>() as u32;
+        let mut size = 0;
+        GetRawInputData(handle, RID_INPUT, None, &mut size, header_size);
+        if size == 0 {
+            return None;
+        }
+        let mut buffer = vec![0u8; size as usize];
+        let ret = GetRawInputData(
+            handle,
+            RID_INPUT,
+            Some(buffer.as_mut_ptr() as _),
+            &mut size,
+            header_size,
+        );
+        (ret != u32::MAX).then_some(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(ty: u32, body_size: usize, device: usize, wparam: usize) -> Vec<u8> {
+        let mut data = vec![];
+        data.extend(ty.to_le_bytes());
+        data.extend(((HEADER_SIZE + body_size) as u32).to_le_bytes());
+        data.extend(device.to_le_bytes());
+        data.extend(wparam.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn decode_mouse() {
+        let mut data = header(RIM_TYPEMOUSE, 24, 0x1234, 0);
+        data.extend(0u16.to_le_bytes());
+        data.extend([0; 2]);
+        data.extend(0x0400u16.to_le_bytes());
+        data.extend(120u16.to_le_bytes());
+        data.extend(0u32.to_le_bytes());
+        data.extend((-3i32).to_le_bytes());
+        data.extend(5i32.to_le_bytes());
+        data.extend(0u32.to_le_bytes());
+        let input = decode(&data).unwrap();
+        assert_eq!(input.device, DeviceId::from_raw(0x1234));
+        assert!(!input.background);
+        assert_eq!(
+            input.data,
+            RawInputData::Mouse(RawMouse {
+                absolute: false,
+                virtual_desktop: false,
+                x: -3,
+                y: 5,
+                button_flags: 0x0400,
+                button_data: 120,
+            })
+        );
+        assert_eq!(decode(&data[..data.len() - 8]), Err(DecodeError::TooShort));
+    }
+
+    #[test]
+    fn decode_keyboard() {
+        let mut data = header(RIM_TYPEKEYBOARD, 16, 7, RIM_INPUTSINK);
+        data.extend(0x1du16.to_le_bytes());
+        data.extend((RI_KEY_E0 | RI_KEY_BREAK).to_le_bytes());
+        data.extend(0u16.to_le_bytes());
+        data.extend(0x11u16.to_le_bytes());
+        data.extend(0x0101u32.to_le_bytes());
+        data.extend(0u32.to_le_bytes());
+        let input = decode(&data).unwrap();
+        assert!(input.background);
+        let RawInputData::Keyboard(keyboard) = input.data else {
+            panic!("not keyboard");
+        };
+        assert_eq!(keyboard.key_code.vkey, VirtualKey::Ctrl);
+        assert_eq!(keyboard.key_code.scan_code, ScanCode(0xe01d));
+        assert_eq!(keyboard.key_state, KeyState::Released);
+    }
+
+    #[test]
+    fn decode_hid() {
+        let mut data = header(RIM_TYPEHID, 14, 9, 0);
+        data.extend(3u32.to_le_bytes());
+        data.extend(2u32.to_le_bytes());
+        data.extend([1, 2, 3, 4, 5, 6]);
+        let input = decode(&data).unwrap();
+        assert_eq!(
+            input.data,
+            RawInputData::Hid(RawHid {
+                size: 3,
+                count: 2,
+                data: vec![1, 2, 3, 4, 5, 6],
+            })
+        );
+        assert_eq!(decode(&data[..data.len() - 1]), Err(DecodeError::TooShort));
+        let data = header(3, 0, 9, 0);
+        assert_eq!(decode(&data), Err(DecodeError::UnknownType(3)));
+    }
+}
@@ -0,0 +1,268 @@
+use crate::*;
+use std::any::Any;
+use std::sync::atomic::{self, AtomicU64};
+
+/// `EventReceive` and `AsyncEventReceiver` are implement this trait.
+pub trait IsReceiver {
+    fn id(&self) -> u64;
+}
+
+/// `Window` and `AsyncWindow` are implement this trait.
+pub trait IsWindow {
+    fn window_handle(&self) -> WindowHandle;
+}
+
+/// Represents `Window` or `InnerWindow`
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum WindowKind {
+    Window(Window),
+    InnerWindow(InnerWindow),
+}
+
+impl IsWindow for WindowKind {
+    fn window_handle(&self) -> WindowHandle {
+        match self {
+            Self::Window(w) => w.handle,
+            Self::InnerWindow(w) => w.handle,
+        }
+    }
+}
+
+/// Represents `AsyncWindow` or `AsyncInnerWindow`
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum AsyncWindowKind {
+    Window(AsyncWindow),
+    InnerWindow(AsyncInnerWindow),
+}
+
+impl IsWindow for AsyncWindowKind {
+    fn window_handle(&self) -> WindowHandle {
+        match self {
+            Self::Window(w) => w.handle,
+            Self::InnerWindow(w) => w.handle,
+        }
+    }
+}
+
+type Receiver<T> = tokio::sync::mpsc::UnboundedReceiver<T>;
+pub(crate) type Sender<T> = tokio::sync::mpsc::UnboundedSender<T>;
+
+pub(crate) enum RecvEventOrPanic {
+    Event(RecvEvent),
+    Panic(Box<dyn Any + Send>),
+}
+
+/// The type of receiving event from UI thread.
+pub type RecvEvent = (Event, WindowKind);
+
+/// The async version type of receiving event from UI thread.
+pub type AsyncRecvEvent = (Event, AsyncWindowKind);
+
+fn gen_id() -> u64 {
+    static ID: AtomicU64 = AtomicU64::new(0);
+    ID.fetch_add(1, atomic::Ordering::SeqCst)
+}
+
+/// This object which receives an event from UI thread.
+pub struct EventReceiver {
+    id: u64,
+    rx: Receiver<RecvEventOrPanic>,
+}
+
+impl EventReceiver {
+    /// Creates a new event receiver.
+    #[inline]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        let id = gen_id();
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        backend!(
+            Context::register_event_tx(id, tx),
+            headless::context::Context::register_event_tx(id, tx)
+        );
+        Self { id, rx }
+    }
+
+    /// Attempts to wait for an event from UI thread.
+    ///
+    /// When UI thread shutdowns, this function returns `None`.
+    ///
+    #[inline]
+    pub fn recv(&mut self) -> Option<RecvEvent> {
+        match self.rx.blocking_recv()? {
+            RecvEventOrPanic::Event(ret) => Some(ret),
+            RecvEventOrPanic::Panic(e) => std::panic::resume_unwind(e),
+        }
+    }
+
+    /// Attempts to return a pending event from UI Thread.
+    ///
+    /// This function do not block.
+    /// When UI thread shutdowns, this function returns `Err(TryRecvError::Disconnected)`.
+    ///
+    #[inline]
+    pub fn try_recv(&mut self) -> TryRecvResult<RecvEvent> {
+        use tokio::sync::mpsc;
+
+        match self.rx.try_recv() {
+            Ok(ret) => match ret {
+                RecvEventOrPanic::Event(re) => Ok(re),
+                RecvEventOrPanic::Panic(e) => std::panic::resume_unwind(e),
+            },
+            Err(mpsc::error::TryRecvError::Empty) => Err(TryRecvError::Empty),
+            Err(mpsc::error::TryRecvError::Disconnected) => Err(TryRecvError::Disconnected),
+        }
+    }
+}
+
+impl IsReceiver for EventReceiver {
+    #[inline]
+    fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+/// This object which receives an event from UI thread.
+pub struct AsyncEventReceiver {
+    id: u64,
+    rx: Receiver<RecvEventOrPanic>,
+}
+
+impl AsyncEventReceiver {
+    /// Creates a new event receiver.
+    #[inline]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        let id = gen_id();
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        backend!(
+            Context::register_event_tx(id, tx),
+            headless::context::Context::register_event_tx(id, tx)
+        );
+        Self { id, rx }
+    }
+
+    /// Attempts to wait for an event from UI thread.
+    ///
+    /// When UI thread shutdowns, this function returns `None`.
+    ///
+    #[inline]
+    pub async fn recv(&mut self) -> Option<AsyncRecvEvent> {
+        let ret = {
+            match self.rx.recv().await? {
+                RecvEventOrPanic::Event(re) => re,
+                RecvEventOrPanic::Panic(e) => std::panic::resume_unwind(e),
+            }
+        };
+        match ret.1 {
+            WindowKind::Window(w) => Some((
+                ret.0,
+                AsyncWindowKind::Window(AsyncWindow { handle: w.handle }),
+            )),
+            WindowKind::InnerWindow(w) => Some((
+                ret.0,
+                AsyncWindowKind::InnerWindow(AsyncInnerWindow {
+                    parent: w.parent,
+                    handle: w.handle,
+                }),
+            )),
+        }
+    }
+
+    /// Attempts to return a pending event from UI Thread.
+    ///
+    /// This function do not block.
+    /// When UI thread shutdowns, this function returns `Err(TryRecvError::Disconnected)`.
+    ///
+    #[inline]
+    pub fn try_recv(&mut self) -> TryRecvResult<AsyncRecvEvent> {
+        use tokio::sync::mpsc;
+
+        match self.rx.try_recv() {
+            Ok(ret) => {
+                let ret = match ret {
+                    RecvEventOrPanic::Event(re) => re,
+                    RecvEventOrPanic::Panic(e) => std::panic::resume_unwind(e),
+                };
+                match ret.1 {
+                    WindowKind::Window(w) => Ok((
+                        ret.0,
+                        AsyncWindowKind::Window(AsyncWindow { handle: w.handle }),
+                    )),
+                    WindowKind::InnerWindow(w) => Ok((
+                        ret.0,
+                        AsyncWindowKind::InnerWindow(AsyncInnerWindow {
+                            parent: w.parent,
+                            handle: w.handle,
+                        }),
+                    )),
+                }
+            }
+            Err(mpsc::error::TryRecvError::Empty) => Err(TryRecvError::Empty),
+            Err(mpsc::error::TryRecvError::Disconnected) => Err(TryRecvError::Disconnected),
+        }
+    }
+}
+
+impl IsReceiver for AsyncEventReceiver {
+    #[inline]
+    fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+impl PartialEq<Window> for WindowKind {
+    #[inline]
+    fn eq(&self, other: &Window) -> bool {
+        self.window_handle() == other.window_handle()
+    }
+}
+
+impl PartialEq<InnerWindow> for WindowKind {
+    #[inline]
+    fn eq(&self, other: &InnerWindow) -> bool {
+        self.window_handle() == other.handle
+    }
+}
+
+impl PartialEq<WindowKind> for Window {
+    #[inline]
+    fn eq(&self, other: &WindowKind) -> bool {
+        self.window_handle() == other.window_handle()
+    }
+}
+
+impl PartialEq<WindowKind> for InnerWindow {
+    #[inline]
+    fn eq(&self, other: &WindowKind) -> bool {
+        self.handle == other.window_handle()
+    }
+}
+
+impl PartialEq<AsyncWindowKind> for AsyncWindow {
+    #[inline]
+    fn eq(&self, other: &AsyncWindowKind) -> bool {
+        self.window_handle() == other.window_handle()
+    }
+}
+
+impl PartialEq<AsyncWindowKind> for AsyncInnerWindow {
+    #[inline]
+    fn eq(&self, other: &AsyncWindowKind) -> bool {
+        self.handle == other.window_handle()
+    }
+}
+
+impl PartialEq<AsyncWindow> for AsyncWindowKind {
+    #[inline]
+    fn eq(&self, other: &AsyncWindow) -> bool {
+        self.window_handle() == other.window_handle()
+    }
+}
+
+impl PartialEq<AsyncInnerWindow> for AsyncWindowKind {
+    #[inline]
+    fn eq(&self, other: &AsyncInnerWindow) -> bool {
+        self.window_handle() == other.handle
+    }
+}
@@ -0,0 +1,2232 @@
+//! Records events and replays them without UI thread.
+//!
+//! [`Recorder`] wraps `EventReceiver` and writes every received event with the elapsed time and
+//! the window which received it. [`Replayer`] reads the recording and returns the same events
+//! as `RecvEvent`.
+//!
+//! Windows of replayed events do not refer to any live window. Their methods and
+//! `CloseRequest::destroy` do nothing, and queries return nothing.
+//!
+//! Events which UiThread waits for (`NcHitTest`, `ImeBeginComposition`, `DragEnter`, `DragOver`
+//! and `Drop`) are recorded with the reply of the app. `Replayer` compares replies of the replayed
+//! events with recorded ones, and keeps differences as [`ReplyMismatch`].
+//!
+//! # Format
+//! A recording is a UTF-8 text. The first line is `wiard-record <FORMAT_VERSION>`.
+//! Each following line is an event (`E <seq> <nanoseconds> <window> <event>`) or
+//! a reply of the event (`R <seq> <reply>`). Strings and characters are quoted.
+//!
+//! # Example
+//! ```no_run
+//! use wiard::record::{Recorder, Replayer};
+//!
+//! let event_rx = wiard::EventReceiver::new();
+//! let _window = wiard::Window::builder(&event_rx).build().unwrap();
+//! let mut recorder = Recorder::new(event_rx, std::fs::File::create("events.txt").unwrap());
+//! while let Some((event, _)) = recorder.recv() {
+//!     println!("{event:?}");
+//! }
+//! recorder.finish().unwrap();
+//!
+//! let file = std::io::BufReader::new(std::fs::File::open("events.txt").unwrap());
+//! let mut replayer = Replayer::new(file).unwrap();
+//! while let Some((event, _)) = replayer.recv() {
+//!     println!("{event:?}");
+//! }
+//! assert!(replayer.mismatches().is_empty());
+//! ```
+
+use crate::*;
+use std::collections::HashMap;
+use std::fmt::{Debug, Display, Write as _};
+use std::io::{self, BufRead, Write};
+#[cfg(windows)]
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+
+/// The version of the recording format.
+pub const FORMAT_VERSION: u32 = 1;
+
+const MAGIC: &str = "wiard-record";
+
+/// The window which received a recorded event.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RecordedWindow {
+    Window(u64),
+    InnerWindow { parent: u64, handle: u64 },
+}
+
+impl RecordedWindow {
+    fn new(handle: WindowHandle, parent: Option<WindowHandle>) -> Self {
+        match parent {
+            Some(parent) => Self::InnerWindow {
+                parent: parent.raw_id(),
+                handle: handle.raw_id(),
+            },
+            None => Self::Window(handle.raw_id()),
+        }
+    }
+
+    fn handle(&self) -> WindowHandle {
+        match *self {
+            Self::Window(handle) => WindowHandle::replayed(handle),
+            Self::InnerWindow { handle, .. } => WindowHandle::replayed(handle),
+        }
+    }
+
+    fn to_window_kind(self) -> WindowKind {
+        match self {
+            Self::Window(handle) => WindowKind::Window(Window {
+                handle: WindowHandle::replayed(handle),
+            }),
+            Self::InnerWindow { parent, handle } => WindowKind::InnerWindow(InnerWindow {
+                parent: WindowHandle::replayed(parent),
+                handle: WindowHandle::replayed(handle),
+            }),
+        }
+    }
+
+    fn to_async_window_kind(self) -> AsyncWindowKind {
+        match self {
+            Self::Window(handle) => AsyncWindowKind::Window(AsyncWindow {
+                handle: WindowHandle::replayed(handle),
+            }),
+            Self::InnerWindow { parent, handle } => {
+                AsyncWindowKind::InnerWindow(AsyncInnerWindow {
+                    parent: WindowHandle::replayed(parent),
+                    handle: WindowHandle::replayed(handle),
+                })
+            }
+        }
+    }
+}
+
+impl From<&WindowKind> for RecordedWindow {
+    #[inline]
+    fn from(value: &WindowKind) -> Self {
+        match value {
+            WindowKind::Window(w) => Self::new(w.handle, None),
+            WindowKind::InnerWindow(w) => Self::new(w.handle, Some(w.parent)),
+        }
+    }
+}
+
+impl From<&AsyncWindowKind> for RecordedWindow {
+    #[inline]
+    fn from(value: &AsyncWindowKind) -> Self {
+        match value {
+            AsyncWindowKind::Window(w) => Self::new(w.handle, None),
+            AsyncWindowKind::InnerWindow(w) => Self::new(w.handle, Some(w.parent)),
+        }
+    }
+}
+
+/// `event::ImeBeginComposition` without the reply.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ImeBeginComposition {
+    pub dpi: i32,
+}
+
+/// `event::NcHitTest` without the reply.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NcHitTest {
+    pub position: PhysicalPosition<i32>,
+}
+
+/// `event::DragEnter`, `event::DragOver` and `event::Drop` without the reply.
+#[cfg(windows)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DragDrop {
+    pub position: PhysicalPosition<i32>,
+    pub modifier_keys: ModifierKey,
+    pub files: Vec<PathBuf>,
+    pub effect: drag_drop::Effect,
+}
+
+#[cfg(windows)]
+impl DragDrop {
+    fn new(
+        position: PhysicalPosition<i32>,
+        modifier_keys: ModifierKey,
+        data: &drag_drop::Data,
+        effect: drag_drop::Effect,
+    ) -> Self {
+        let drag_drop::Data::Files(files) = data;
+        Self {
+            position,
+            modifier_keys,
+            files: files.clone(),
+            effect,
+        }
+    }
+
+    fn data(&self) -> Arc<drag_drop::Data> {
+        Arc::new(drag_drop::Data::Files(self.files.clone()))
+    }
+}
+
+macro_rules! recorded_event {
+    (
+        units { $($(#[cfg($ucfg:meta)])? $unit:ident,)* }
+        values { $($(#[cfg($vcfg:meta)])? $value:ident($ty:ty),)* }
+    ) => {
+        /// An owned copy of `Event`.
+        ///
+        /// Events which UiThread waits for have no reply channels.
+        /// With the `serde` feature, this type can be serialized to send events to other processes.
+        /// `WindowHandle` and `MenuHandle` are serialized as raw `HWND` and `HMENU` values. These
+        /// values mean nothing in another process, so use them only to tell windows and menus apart.
+        ///
+        #[derive(Clone, Debug)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[non_exhaustive]
+        pub enum RecordedEvent {
+            $($(#[cfg($ucfg)])? $unit,)*
+            $($(#[cfg($vcfg)])? $value($ty),)*
+        }
+
+        impl Field for RecordedEvent {
+            fn write(&self, line: &mut Line) {
+                match self {
+                    $($(#[cfg($ucfg)])? Self::$unit => line.push(stringify!($unit)),)*
+                    $($(#[cfg($vcfg)])? Self::$value(v) => {
+                        line.push(stringify!($value));
+                        v.write(line);
+                    })*
+                }
+            }
+
+            fn read(tokens: &mut Tokens) -> io::Result<Self> {
+                let name = tokens.bare()?;
+                $(
+                    $(#[cfg($ucfg)])?
+                    if name == stringify!($unit) {
+                        return Ok(Self::$unit);
+                    }
+                )*
+                $(
+                    $(#[cfg($vcfg)])?
+                    if name == stringify!($value) {
+                        return Ok(Self::$value(Field::read(tokens)?));
+                    }
+                )*
+                Err(invalid_data(format!("unknown event: {name}")))
+            }
+        }
+    };
+}
+
+recorded_event! {
+    units {
+        Activated,
+        Inactivated,
+        EnterResizing,
+        CaptureLost,
+        ImeBeginCandidateList,
+        ImeEndCandidateList,
+        Minizmized,
+        MonitorsChanged,
+        #[cfg(windows)]
+        DragLeave,
+        CloseRequest,
+        Closed,
+    }
+    values {
+        Focused(event::Focused),
+        Draw(event::Draw),
+        Moved(event::Moved),
+        Resizing(event::Resizing),
+        Resized(event::Resized),
+        MouseInput(event::MouseInput),
+        CursorMoved(event::CursorMoved),
+        DragStarted(event::DragStarted),
+        CursorEntered(event::CursorEntered),
+        CursorLeft(event::CursorLeft),
+        MouseWheel(event::MouseWheel),
+        Pointer(event::Pointer),
+        Gesture(gesture::Gesture),
+        KeyInput(event::KeyInput),
+        Accelerator(event::Accelerator),
+        CharInput(event::CharInput),
+        TextInput(event::TextInput),
+        DeadCharInput(event::DeadCharInput),
+        SysCharInput(event::SysCharInput),
+        RawMouseMotion(event::RawMouseMotion),
+        RawKeyInput(event::RawKeyInput),
+        RawHidInput(event::RawHidInput),
+        DeviceAdded(event::DeviceAdded),
+        DeviceRemoved(event::DeviceRemoved),
+        ImeBeginComposition(ImeBeginComposition),
+        ImeUpdateComposition(event::ImeUpdateComposition),
+        ImeEndComposition(event::ImeEndComposition),
+        ImeUpdateCandidateList(event::ImeUpdateCandidateList),
+        #[cfg(windows)]
+        MenuCommand(event::MenuCommand),
+        ContextMenu(event::ContextMenu),
+        Maximized(event::Maximized),
+        Restored(event::Restored),
+        FullscreenChanged(event::FullscreenChanged),
+        DpiChanged(event::DpiChanged),
+        NcHitTest(NcHitTest),
+        #[cfg(windows)]
+        NotifyIcon(event::NotifyIcon),
+        ColorModeChanged(event::ColorModeChanged),
+        #[cfg(windows)]
+        DragEnter(DragDrop),
+        #[cfg(windows)]
+        DragOver(DragDrop),
+        #[cfg(windows)]
+        Drop(DragDrop),
+        App(event::App),
+        Other(event::Other),
+    }
+}
+
+impl RecordedEvent {
+    /// Copies `event` without the reply channel.
+    pub fn new(event: &Event) -> Self {
+        match event {
+            Event::Activated => Self::Activated,
+            Event::Inactivated => Self::Inactivated,
+            Event::Focused(ev) => Self::Focused(ev.clone()),
+            Event::Draw(ev) => Self::Draw(ev.clone()),
+            Event::Moved(ev) => Self::Moved(ev.clone()),
+            Event::EnterResizing => Self::EnterResizing,
+            Event::CaptureLost => Self::CaptureLost,
+            Event::Resizing(ev) => Self::Resizing(ev.clone()),
+            Event::Resized(ev) => Self::Resized(ev.clone()),
+            Event::MouseInput(ev) => Self::MouseInput(ev.clone()),
+            Event::CursorMoved(ev) => Self::CursorMoved(ev.clone()),
+            Event::DragStarted(ev) => Self::DragStarted(ev.clone()),
+            Event::CursorEntered(ev) => Self::CursorEntered(ev.clone()),
+            Event::CursorLeft(ev) => Self::CursorLeft(ev.clone()),
+            Event::MouseWheel(ev) => Self::MouseWheel(ev.clone()),
+            Event::Pointer(ev) => Self::Pointer(ev.clone()),
+            Event::Gesture(ev) => Self::Gesture(ev.clone()),
+            Event::KeyInput(ev) => Self::KeyInput(ev.clone()),
+            Event::Accelerator(ev) => Self::Accelerator(ev.clone()),
+            Event::CharInput(ev) => Self::CharInput(ev.clone()),
+            Event::TextInput(ev) => Self::TextInput(ev.clone()),
+            Event::DeadCharInput(ev) => Self::DeadCharInput(ev.clone()),
+            Event::SysCharInput(ev) => Self::SysCharInput(ev.clone()),
+            Event::RawMouseMotion(ev) => Self::RawMouseMotion(ev.clone()),
+            Event::RawKeyInput(ev) => Self::RawKeyInput(ev.clone()),
+            Event::RawHidInput(ev) => Self::RawHidInput(ev.clone()),
+            Event::DeviceAdded(ev) => Self::DeviceAdded(ev.clone()),
+            Event::DeviceRemoved(ev) => Self::DeviceRemoved(ev.clone()),
+            Event::ImeBeginComposition(ev) => {
+                Self::ImeBeginComposition(ImeBeginComposition { dpi: ev.dpi })
+            }
+            Event::ImeUpdateComposition(ev) => Self::ImeUpdateComposition(ev.clone()),
+            Event::ImeEndComposition(ev) => Self::ImeEndComposition(ev.clone()),
+            Event::ImeBeginCandidateList => Self::ImeBeginCandidateList,
+            Event::ImeUpdateCandidateList(ev) => Self::ImeUpdateCandidateList(ev.clone()),
+            Event::ImeEndCandidateList => Self::ImeEndCandidateList,
+            #[cfg(windows)]
+            Event::MenuCommand(ev) => Self::MenuCommand(ev.clone()),
+            Event::ContextMenu(ev) => Self::ContextMenu(ev.clone()),
+            Event::Minizmized => Self::Minizmized,
+            Event::Maximized(ev) => Self::Maximized(ev.clone()),
+            Event::Restored(ev) => Self::Restored(ev.clone()),
+            Event::FullscreenChanged(ev) => Self::FullscreenChanged(ev.clone()),
+            Event::DpiChanged(ev) => Self::DpiChanged(ev.clone()),
+            Event::MonitorsChanged => Self::MonitorsChanged,
+            Event::NcHitTest(ev) => Self::NcHitTest(NcHitTest {
+                position: ev.position,
+            }),
+            #[cfg(windows)]
+            Event::NotifyIcon(ev) => Self::NotifyIcon(ev.clone()),
+            Event::ColorModeChanged(ev) => Self::ColorModeChanged(ev.clone()),
+            #[cfg(windows)]
+            Event::DragEnter(ev) => Self::DragEnter(DragDrop::new(
+                ev.position,
+                ev.modifier_keys,
+                &ev.data,
+                ev.effect,
+            )),
+            #[cfg(windows)]
+            Event::DragOver(ev) => Self::DragOver(DragDrop::new(
+                ev.position,
+                ev.modifier_keys,
+                &ev.data,
+                ev.effect,
+            )),
+            #[cfg(windows)]
+            Event::DragLeave => Self::DragLeave,
+            #[cfg(windows)]
+            Event::Drop(ev) => Self::Drop(DragDrop::new(
+                ev.position,
+                ev.modifier_keys,
+                &ev.data,
+                ev.effect,
+            )),
+            Event::CloseRequest(_) => Self::CloseRequest,
+            Event::Closed => Self::Closed,
+            Event::App(ev) => Self::App(ev.clone()),
+            Event::Other(ev) => Self::Other(ev.clone()),
+        }
+    }
+
+    /// Builds `Event` with a new reply channel which nobody waits for.
+    fn into_event(self, handle: WindowHandle) -> (Event, Option<PendingReply>) {
+        let event = match self {
+            Self::Activated => Event::Activated,
+            Self::Inactivated => Event::Inactivated,
+            Self::Focused(ev) => Event::Focused(ev),
+            Self::Draw(ev) => Event::Draw(ev),
+            Self::Moved(ev) => Event::Moved(ev),
+            Self::EnterResizing => Event::EnterResizing,
+            Self::CaptureLost => Event::CaptureLost,
+            Self::Resizing(ev) => Event::Resizing(ev),
+            Self::Resized(ev) => Event::Resized(ev),
+            Self::MouseInput(ev) => Event::MouseInput(ev),
+            Self::CursorMoved(ev) => Event::CursorMoved(ev),
+            Self::DragStarted(ev) => Event::DragStarted(ev),
+            Self::CursorEntered(ev) => Event::CursorEntered(ev),
+            Self::CursorLeft(ev) => Event::CursorLeft(ev),
+            Self::MouseWheel(ev) => Event::MouseWheel(ev),
+            Self::Pointer(ev) => Event::Pointer(ev),
+            Self::Gesture(ev) => Event::Gesture(ev),
+            Self::KeyInput(ev) => Event::KeyInput(ev),
+            Self::Accelerator(ev) => Event::Accelerator(ev),
+            Self::CharInput(ev) => Event::CharInput(ev),
+            Self::TextInput(ev) => Event::TextInput(ev),
+            Self::DeadCharInput(ev) => Event::DeadCharInput(ev),
+            Self::SysCharInput(ev) => Event::SysCharInput(ev),
+            Self::RawMouseMotion(ev) => Event::RawMouseMotion(ev),
+            Self::RawKeyInput(ev) => Event::RawKeyInput(ev),
+            Self::RawHidInput(ev) => Event::RawHidInput(ev),
+            Self::DeviceAdded(ev) => Event::DeviceAdded(ev),
+            Self::DeviceRemoved(ev) => Event::DeviceRemoved(ev),
+            Self::ImeBeginComposition(ev) => {
+                let (tx, rx) = oneshot::channel();
+                return (
+                    Event::ImeBeginComposition(event::ImeBeginComposition::new(ev.dpi, tx)),
+                    Some(PendingReply::ImeBeginComposition(rx)),
+                );
+            }
+            Self::ImeUpdateComposition(ev) => Event::ImeUpdateComposition(ev),
+            Self::ImeEndComposition(ev) => Event::ImeEndComposition(ev),
+            Self::ImeBeginCandidateList => Event::ImeBeginCandidateList,
+            Self::ImeUpdateCandidateList(ev) => Event::ImeUpdateCandidateList(ev),
+            Self::ImeEndCandidateList => Event::ImeEndCandidateList,
+            #[cfg(windows)]
+            Self::MenuCommand(ev) => Event::MenuCommand(ev),
+            Self::ContextMenu(ev) => Event::ContextMenu(ev),
+            Self::Minizmized => Event::Minizmized,
+            Self::Maximized(ev) => Event::Maximized(ev),
+            Self::Restored(ev) => Event::Restored(ev),
+            Self::FullscreenChanged(ev) => Event::FullscreenChanged(ev),
+            Self::DpiChanged(ev) => Event::DpiChanged(ev),
+            Self::MonitorsChanged => Event::MonitorsChanged,
+            Self::NcHitTest(ev) => {
+                let (tx, rx) = oneshot::channel();
+                return (
+                    Event::NcHitTest(event::NcHitTest::new(ev.position, tx)),
+                    Some(PendingReply::NcHitTest(rx)),
+                );
+            }
+            #[cfg(windows)]
+            Self::NotifyIcon(ev) => Event::NotifyIcon(ev),
+            Self::ColorModeChanged(ev) => Event::ColorModeChanged(ev),
+            #[cfg(windows)]
+            Self::DragEnter(ev) => {
+                let (tx, rx) = oneshot::channel();
+                return (
+                    Event::DragEnter(event::DragEnter {
+                        position: ev.position,
+                        modifier_keys: ev.modifier_keys,
+                        data: ev.data(),
+                        effect: ev.effect,
+                        tx: Some(tx.into()),
+                    }),
+                    Some(PendingReply::Drag(rx)),
+                );
+            }
+            #[cfg(windows)]
+            Self::DragOver(ev) => {
+                let (tx, rx) = oneshot::channel();
+                return (
+                    Event::DragOver(event::DragOver {
+                        position: ev.position,
+                        modifier_keys: ev.modifier_keys,
+                        data: ev.data(),
+                        effect: ev.effect,
+                        tx: Some(tx.into()),
+                    }),
+                    Some(PendingReply::Drag(rx)),
+                );
+            }
+            #[cfg(windows)]
+            Self::DragLeave => Event::DragLeave,
+            #[cfg(windows)]
+            Self::Drop(ev) => {
+                let (tx, rx) = oneshot::channel();
+                return (
+                    Event::Drop(event::Drop {
+                        position: ev.position,
+                        modifier_keys: ev.modifier_keys,
+                        data: ev.data(),
+                        effect: ev.effect,
+                        tx: Some(tx.into()),
+                    }),
+                    Some(PendingReply::Drag(rx)),
+                );
+            }
+            Self::CloseRequest => Event::CloseRequest(event::CloseRequest::new(handle)),
+            Self::Closed => Event::Closed,
+            Self::App(ev) => Event::App(ev),
+            Self::Other(ev) => Event::Other(ev),
+        };
+        (event, None)
+    }
+}
+
+impl From<&Event> for RecordedEvent {
+    #[inline]
+    fn from(value: &Event) -> Self {
+        Self::new(value)
+    }
+}
+
+/// A reply of the app to an event which UiThread waits for.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RecordedReply {
+    NcHitTest(Option<NcHitTestValue>),
+    ImeBeginComposition(PhysicalPosition<i32>),
+    #[cfg(windows)]
+    Drag(drag_drop::Effect),
+}
+
+/// A recorded event.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Entry {
+    /// The sequence number from 0.
+    pub seq: u64,
+    /// The elapsed time from starting the recording.
+    pub timestamp: Duration,
+    pub window: RecordedWindow,
+    pub event: RecordedEvent,
+    pub reply: Option<RecordedReply>,
+}
+
+/// A difference between a recorded reply and a replayed reply.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReplyMismatch {
+    pub seq: u64,
+    pub expected: Option<RecordedReply>,
+    pub actual: RecordedReply,
+}
+
+type Replies = Arc<Mutex<Vec<(u64, RecordedReply)>>>;
+
+fn observe<T: 'static>(
+    tx: &mut Option<event::ReplySender<T>>,
+    replies: &Replies,
+    seq: u64,
+    f: fn(&T) -> RecordedReply,
+) {
+    let Some(tx) = tx.as_mut() else {
+        return;
+    };
+    let replies = replies.clone();
+    tx.observe(move |value| replies.lock().unwrap().push((seq, f(value))));
+}
+
+struct RecordState<W: Write> {
+    writer: W,
+    start: Instant,
+    seq: u64,
+    replies: Replies,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> RecordState<W> {
+    fn new(mut writer: W) -> Self {
+        let error = writeln!(writer, "{MAGIC} {FORMAT_VERSION}").err();
+        Self {
+            writer,
+            start: Instant::now(),
+            seq: 0,
+            replies: Arc::new(Mutex::new(vec![])),
+            error,
+        }
+    }
+
+    fn write_line(&mut self, line: Line) {
+        if self.error.is_some() {
+            return;
+        }
+        if let Err(e) = writeln!(self.writer, "{}", line.0) {
+            self.error = Some(e);
+        }
+    }
+
+    fn write_replies(&mut self) {
+        let replies = std::mem::take(&mut *self.replies.lock().unwrap());
+        for (seq, reply) in replies {
+            let mut line = Line::new("R");
+            line.push(seq);
+            reply.write(&mut line);
+            self.write_line(line);
+        }
+    }
+
+    fn record(&mut self, event: &mut Event, window: RecordedWindow) {
+        self.write_replies();
+        let seq = self.seq;
+        self.seq += 1;
+        let timestamp = self.start.elapsed();
+        let mut line = Line::new("E");
+        line.push(seq);
+        line.push(timestamp.as_nanos());
+        window.write(&mut line);
+        RecordedEvent::new(event).write(&mut line);
+        self.write_line(line);
+        match event {
+            Event::NcHitTest(ev) => observe(&mut ev.tx, &self.replies, seq, |v| {
+                RecordedReply::NcHitTest(*v)
+            }),
+            Event::ImeBeginComposition(ev) => observe(&mut ev.tx, &self.replies, seq, |v| {
+                RecordedReply::ImeBeginComposition(*v)
+            }),
+            #[cfg(windows)]
+            Event::DragEnter(ev) => {
+                observe(&mut ev.tx, &self.replies, seq, |v| RecordedReply::Drag(*v))
+            }
+            #[cfg(windows)]
+            Event::DragOver(ev) => {
+                observe(&mut ev.tx, &self.replies, seq, |v| RecordedReply::Drag(*v))
+            }
+            #[cfg(windows)]
+            Event::Drop(ev) => observe(&mut ev.tx, &self.replies, seq, |v| RecordedReply::Drag(*v)),
+            _ => {}
+        }
+    }
+
+    fn finish(mut self) -> Result<W> {
+        self.write_replies();
+        if let Some(e) = self.error {
+            return Err(e.into());
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Records events from `EventReceiver`.
+///
+/// A reply of an event is written when the event is dropped and `recv` or `finish` is called.
+/// Drop events which UiThread waits for before calling `finish`.
+///
+pub struct Recorder<W: Write> {
+    rx: EventReceiver,
+    state: RecordState<W>,
+}
+
+impl<W: Write> Recorder<W> {
+    /// Creates a new recorder and writes the header to `writer`.
+    #[inline]
+    pub fn new(rx: EventReceiver, writer: W) -> Self {
+        Self {
+            rx,
+            state: RecordState::new(writer),
+        }
+    }
+
+    /// Gets the wrapped receiver to create windows.
+    #[inline]
+    pub fn receiver(&self) -> &EventReceiver {
+        &self.rx
+    }
+
+    /// Attempts to wait for an event from UI thread and records it.
+    ///
+    /// IO errors do not stop receiving events. They are returned from `finish`.
+    ///
+    pub fn recv(&mut self) -> Option<RecvEvent> {
+        let (mut event, window) = self.rx.recv()?;
+        self.state.record(&mut event, (&window).into());
+        Some((event, window))
+    }
+
+    /// Writes remaining replies and returns the writer.
+    #[inline]
+    pub fn finish(self) -> Result<W> {
+        self.state.finish()
+    }
+}
+
+/// Records events from `AsyncEventReceiver`.
+///
+/// A reply of an event is written when the event is dropped and `recv` or `finish` is called.
+/// Drop events which UiThread waits for before calling `finish`.
+///
+pub struct AsyncRecorder<W: Write> {
+    rx: AsyncEventReceiver,
+    state: RecordState<W>,
+}
+
+impl<W: Write> AsyncRecorder<W> {
+    /// Creates a new recorder and writes the header to `writer`.
+    #[inline]
+    pub fn new(rx: AsyncEventReceiver, writer: W) -> Self {
+        Self {
+            rx,
+            state: RecordState::new(writer),
+        }
+    }
+
+    /// Gets the wrapped receiver to create windows.
+    #[inline]
+    pub fn receiver(&self) -> &AsyncEventReceiver {
+        &self.rx
+    }
+
+    /// Attempts to wait for an event from UI thread and records it.
+    ///
+    /// IO errors do not stop receiving events. They are returned from `finish`.
+    ///
+    pub async fn recv(&mut self) -> Option<AsyncRecvEvent> {
+        let (mut event, window) = self.rx.recv().await?;
+        self.state.record(&mut event, (&window).into());
+        Some((event, window))
+    }
+
+    /// Writes remaining replies and returns the writer.
+    #[inline]
+    pub fn finish(self) -> Result<W> {
+        self.state.finish()
+    }
+}
+
+enum PendingReply {
+    NcHitTest(oneshot::Receiver<Option<NcHitTestValue>>),
+    ImeBeginComposition(oneshot::Receiver<PhysicalPosition<i32>>),
+    #[cfg(windows)]
+    Drag(oneshot::Receiver<drag_drop::Effect>),
+}
+
+impl PendingReply {
+    fn try_recv(&mut self) -> Option<RecordedReply> {
+        match self {
+            Self::NcHitTest(rx) => rx.try_recv().ok().map(RecordedReply::NcHitTest),
+            Self::ImeBeginComposition(rx) => {
+                rx.try_recv().ok().map(RecordedReply::ImeBeginComposition)
+            }
+            #[cfg(windows)]
+            Self::Drag(rx) => rx.try_recv().ok().map(RecordedReply::Drag),
+        }
+    }
+}
+
+struct ReplayState {
+    entries: Vec<Entry>,
+    next: usize,
+    pending: Vec<(usize, PendingReply)>,
+    mismatches: Vec<ReplyMismatch>,
+}
+
+impl ReplayState {
+    fn new(reader: impl BufRead) -> Result<Self> {
+        let mut lines = reader.lines();
+        let header = lines.next().transpose()?.unwrap_or_default();
+        let mut tokens = Tokens::new(&header);
+        if tokens.bare()? != MAGIC {
+            return Err(invalid_data("not a wiard recording").into());
+        }
+        let version: u32 = tokens.parse()?;
+        if version != FORMAT_VERSION {
+            return Err(invalid_data(format!("unsupported format version: {version}")).into());
+        }
+        let mut entries = vec![];
+        let mut indices = HashMap::new();
+        for line in lines {
+            let line = line?;
+            if line.is_empty() {
+                continue;
+            }
+            let mut tokens = Tokens::new(&line);
+            match tokens.bare()? {
+                "E" => {
+                    let seq: u64 = tokens.parse()?;
+                    let timestamp = Duration::from_nanos(tokens.parse()?);
+                    let window = RecordedWindow::read(&mut tokens)?;
+                    let event = RecordedEvent::read(&mut tokens)?;
+                    indices.insert(seq, entries.len());
+                    entries.push(Entry {
+                        seq,
+                        timestamp,
+                        window,
+                        event,
+                        reply: None,
+                    });
+                }
+                "R" => {
+                    let seq: u64 = tokens.parse()?;
+                    let reply = RecordedReply::read(&mut tokens)?;
+                    let Some(&index) = indices.get(&seq) else {
+                        return Err(invalid_data(format!("no event for the reply: {seq}")).into());
+                    };
+                    entries[index].reply = Some(reply);
+                }
+                kind => return Err(invalid_data(format!("unknown line: {kind}")).into()),
+            }
+            tokens.end()?;
+        }
+        Ok(Self {
+            entries,
+            next: 0,
+            pending: vec![],
+            mismatches: vec![],
+        })
+    }
+
+    fn check_replies(&mut self) {
+        let entries = &self.entries;
+        let mismatches = &mut self.mismatches;
+        self.pending.retain_mut(|(index, reply)| {
+            let Some(actual) = reply.try_recv() else {
+                return true;
+            };
+            let entry = &entries[*index];
+            if entry.reply != Some(actual) {
+                mismatches.push(ReplyMismatch {
+                    seq: entry.seq,
+                    expected: entry.reply,
+                    actual,
+                });
+            }
+            false
+        });
+    }
+
+    fn next(&mut self) -> Option<(Event, RecordedWindow)> {
+        self.check_replies();
+        let index = self.next;
+        let entry = self.entries.get(index)?.clone();
+        self.next += 1;
+        let (event, reply) = entry.event.into_event(entry.window.handle());
+        if let Some(reply) = reply {
+            self.pending.push((index, reply));
+        }
+        Some((event, entry.window))
+    }
+
+    fn timestamp(&self) -> Option<Duration> {
+        let index = self.next.checked_sub(1)?;
+        Some(self.entries[index].timestamp)
+    }
+
+    fn mismatches(&mut self) -> &[ReplyMismatch] {
+        self.check_replies();
+        &self.mismatches
+    }
+}
+
+/// Replays recorded events as `RecvEvent` without UI thread.
+pub struct Replayer(ReplayState);
+
+impl Replayer {
+    /// Reads a recording which `Recorder` or `AsyncRecorder` wrote.
+    #[inline]
+    pub fn new(reader: impl BufRead) -> Result<Self> {
+        Ok(Self(ReplayState::new(reader)?))
+    }
+
+    /// Gets all recorded events.
+    #[inline]
+    pub fn entries(&self) -> &[Entry] {
+        &self.0.entries
+    }
+
+    /// Gets the timestamp of the last returned event.
+    #[inline]
+    pub fn timestamp(&self) -> Option<Duration> {
+        self.0.timestamp()
+    }
+
+    /// Returns the next recorded event.
+    ///
+    /// When all events are returned, this function returns `None`.
+    ///
+    #[inline]
+    pub fn recv(&mut self) -> Option<RecvEvent> {
+        self.0
+            .next()
+            .map(|(event, window)| (event, window.to_window_kind()))
+    }
+
+    /// Gets replies which differ from recorded ones.
+    ///
+    /// Replies of events which are not dropped yet are not checked.
+    ///
+    #[inline]
+    pub fn mismatches(&mut self) -> &[ReplyMismatch] {
+        self.0.mismatches()
+    }
+}
+
+/// Replays recorded events as `AsyncRecvEvent` without UI thread.
+pub struct AsyncReplayer(ReplayState);
+
+impl AsyncReplayer {
+    /// Reads a recording which `Recorder` or `AsyncRecorder` wrote.
+    #[inline]
+    pub fn new(reader: impl BufRead) -> Result<Self> {
+        Ok(Self(ReplayState::new(reader)?))
+    }
+
+    /// Gets all recorded events.
+    #[inline]
+    pub fn entries(&self) -> &[Entry] {
+        &self.0.entries
+    }
+
+    /// Gets the timestamp of the last returned event.
+    #[inline]
+    pub fn timestamp(&self) -> Option<Duration> {
+        self.0.timestamp()
+    }
+
+    /// Returns the next recorded event.
+    ///
+    /// When all events are returned, this function returns `None`.
+    ///
+    #[inline]
+    pub async fn recv(&mut self) -> Option<AsyncRecvEvent> {
+        self.0
+            .next()
+            .map(|(event, window)| (event, window.to_async_window_kind()))
+    }
+
+    /// Gets replies which differ from recorded ones.
+    ///
+    /// Replies of events which are not dropped yet are not checked.
+    ///
+    #[inline]
+    pub fn mismatches(&mut self) -> &[ReplyMismatch] {
+        self.0.mismatches()
+    }
+}
+
+fn invalid_data(msg: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
+
+struct Line(String);
+
+impl Line {
+    fn new(kind: &str) -> Self {
+        Self(kind.to_string())
+    }
+
+    fn push(&mut self, token: impl Display) {
+        write!(self.0, " {token}").unwrap();
+    }
+}
+
+struct Tokens<'a> {
+    s: &'a str,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(s: &'a str) -> Self {
+        Self { s }
+    }
+
+    fn next_token(&mut self) -> io::Result<&'a str> {
+        let s = self.s.trim_start();
+        let Some(first) = s.chars().next() else {
+            return Err(invalid_data("unexpected end of line"));
+        };
+        let len = if first == '"' || first == '\'' {
+            let mut escaped = false;
+            let end = s.char_indices().skip(1).find(|&(_, c)| {
+                if escaped {
+                    escaped = false;
+                    return false;
+                }
+                escaped = c == '\\';
+                c == first
+            });
+            let Some((end, _)) = end else {
+                return Err(invalid_data("unterminated quote"));
+            };
+            end + 1
+        } else {
+            s.find(char::is_whitespace).unwrap_or(s.len())
+        };
+        let (token, rest) = s.split_at(len);
+        self.s = rest;
+        Ok(token)
+    }
+
+    fn bare(&mut self) -> io::Result<&'a str> {
+        let token = self.next_token()?;
+        if token.starts_with(['"', '\'']) {
+            return Err(invalid_data(format!("unexpected quote: {token}")));
+        }
+        Ok(token)
+    }
+
+    fn quoted(&mut self) -> io::Result<String> {
+        let token = self.next_token()?;
+        if token.len() < 2 || !token.starts_with(['"', '\'']) {
+            return Err(invalid_data(format!("expected a quote: {token}")));
+        }
+        unescape(&token[1..token.len() - 1])
+    }
+
+    fn parse<T: std::str::FromStr>(&mut self) -> io::Result<T> {
+        let token = self.bare()?;
+        token
+            .parse()
+            .map_err(|_| invalid_data(format!("invalid value: {token}")))
+    }
+
+    fn none(&mut self) -> bool {
+        let s = self.s.trim_start();
+        if s == "-" || s.starts_with("- ") {
+            self.s = &s[1..];
+            true
+        } else {
+            false
+        }
+    }
+
+    fn end(&self) -> io::Result<()> {
+        if self.s.trim().is_empty() {
+            Ok(())
+        } else {
+            Err(invalid_data(format!(
+                "unexpected tokens: {}",
+                self.s.trim()
+            )))
+        }
+    }
+}
+
+fn unescape(s: &str) -> io::Result<String> {
+    let mut ret = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            ret.push(c);
+            continue;
+        }
+        let c = match chars.next() {
+            Some('t') => '\t',
+            Some('r') => '\r',
+            Some('n') => '\n',
+            Some('0') => '\0',
+            Some(c @ ('\\' | '"' | '\'')) => c,
+            Some('u') => {
+                let code = chars
+                    .by_ref()
+                    .skip_while(|&c| c == '{')
+                    .take_while(|&c| c != '}')
+                    .collect::<String>();
+                u32::from_str_radix(&code, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| invalid_data(format!("invalid escape: \\u{{{code}}}")))?
+            }
+            c => return Err(invalid_data(format!("invalid escape: {c:?}"))),
+        };
+        ret.push(c);
+    }
+    Ok(ret)
+}
+
+fn read_name<T: Copy + Debug>(tokens: &mut Tokens, values: &[T]) -> io::Result<T> {
+    let name = tokens.bare()?;
+    values
+        .iter()
+        .find(|v| format!("{v:?}") == name)
+        .copied()
+        .ok_or_else(|| invalid_data(format!("unknown name: {name}")))
+}
+
+trait Field: Sized {
+    fn write(&self, line: &mut Line);
+    fn read(tokens: &mut Tokens) -> io::Result<Self>;
+}
+
+macro_rules! parse_field {
+    ($($t:ty),*) => {
+        $(
+            impl Field for $t {
+                fn write(&self, line: &mut Line) {
+                    line.push(self);
+                }
+
+                fn read(tokens: &mut Tokens) -> io::Result<Self> {
+                    tokens.parse()
+                }
+            }
+        )*
+    };
+}
+
+parse_field!(bool, u8, i32, u32, u64, usize, isize, f32);
+
+macro_rules! name_field {
+    ($($t:ty => [$($v:ident),* $(,)?]),* $(,)?) => {
+        $(
+            impl Field for $t {
+                fn write(&self, line: &mut Line) {
+                    line.push(format!("{self:?}"));
+                }
+
+                fn read(tokens: &mut Tokens) -> io::Result<Self> {
+                    read_name(tokens, &[$(<$t>::$v),*])
+                }
+            }
+        )*
+    };
+}
+
+name_field! {
+    KeyState => [Pressed, Released],
+    MouseWheelAxis => [Vertical, Horizontal],
+    PointerType => [Mouse, Touch, Pen, Touchpad],
+    PointerPhase => [Down, Move, Up, Cancel],
+    gesture::GesturePhase => [Begin, Update, Inertia, End],
+    ScrollPhase => [Begin, Update, Momentum, End],
+    ResizingEdge => [Left, Right, Top, Bottom, TopLeft, TopRight, BottomLeft, BottomRight],
+    ColorModeState => [Light, Dark],
+    NcHitTestValue => [
+        Border, Bottom, BottomLeft, BottomRight, Left, Right, Top, TopLeft, TopRight, Caption,
+        Client, Size, Help, HScroll, VScroll, Menu, MaxButton, MinButton, CloseButton, SysMenu,
+        Error, Transparent,
+    ],
+}
+
+macro_rules! struct_field {
+    ($($(#[cfg($c:meta)])? $t:ty { $($f:ident),* $(,)? }),* $(,)?) => {
+        $(
+            $(#[cfg($c)])?
+            impl Field for $t {
+                fn write(&self, line: &mut Line) {
+                    $(self.$f.write(line);)*
+                }
+
+                fn read(tokens: &mut Tokens) -> io::Result<Self> {
+                    Ok(Self {
+                        $($f: Field::read(tokens)?,)*
+                    })
+                }
+            }
+        )*
+    };
+}
+
+struct_field! {
+    event::Draw { invalidate_rect },
+    event::Moved { position },
+    event::Resizing { size, edge },
+    event::Resized { size },
+    event::MouseInput { button, button_state, click_count, mouse_state },
+    event::CursorMoved { mouse_state },
+    event::DragStarted { button, origin, mouse_state },
+    event::CursorEntered { mouse_state },
+    event::CursorLeft { position },
+    event::MouseWheel { axis, distance, delta, steps, phase, mouse_state },
+    event::Pointer {
+        id,
+        pointer_type,
+        phase,
+        position,
+        is_primary,
+        in_contact,
+        properties,
+        modifiers,
+    },
+    pointer::PointerProperties { pressure, tilt_x, tilt_y, twist, contact_size, pen_buttons },
+    gesture::Vector { x, y },
+    gesture::Pan { phase, position, translation, delta, velocity },
+    gesture::Pinch { phase, center, scale, delta, velocity },
+    gesture::Rotate { phase, center, angle, delta, velocity },
+    gesture::Tap { position, fingers },
+    gesture::LongPress { position },
+    event::KeyInput { key_code, key_state, prev_pressed, modifiers, logical_key, text },
+    event::Accelerator { id, chord },
+    event::CharInput { c },
+    event::TextInput { text },
+    event::DeadCharInput { c },
+    event::SysCharInput { c },
+    event::RawMouseMotion { device, x, y, absolute },
+    event::RawKeyInput { device, key_code, key_state },
+    event::RawHidInput { device, size, data },
+    event::DeviceAdded { device },
+    event::DeviceRemoved { device },
+    event::ImeUpdateComposition { chars, clauses, cursor_position },
+    event::ImeEndComposition { result },
+    event::ImeUpdateCandidateList { selection, items },
+    #[cfg(windows)]
+    event::MenuCommand { index, handle },
+    event::ContextMenu { clicked_window, position },
+    event::Focused { focused },
+    event::Maximized { size },
+    event::Restored { size },
+    event::FullscreenChanged { fullscreen },
+    event::DpiChanged { new_dpi },
+    #[cfg(windows)]
+    event::NotifyIcon { id, event },
+    #[cfg(windows)]
+    notify_icon::event::MouseInput { button, button_state, position },
+    event::ColorModeChanged { current, previous },
+    event::App { index, value0, value1 },
+    event::Other { msg, wparam, lparam },
+    ImeBeginComposition { dpi },
+    NcHitTest { position },
+    #[cfg(windows)]
+    DragDrop { position, modifier_keys, files, effect },
+}
+
+impl Field for char {
+    fn write(&self, line: &mut Line) {
+        line.push(format!("{self:?}"));
+    }
+
+    fn read(tokens: &mut Tokens) -> io::Result<Self> {
+        let s = tokens.quoted()?;
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(invalid_data(format!("invalid char: {s:?}"))),
+        }
+    }
+}
+
+impl Field for String {
+    fn write(&self, line: &mut Line) {
+        line.push(format!("{self:?}"));
+    }
+
+    fn read(tokens: &mut Tokens) -> io::Result<Self> {
+        tokens.quoted()
+    }
+}
+
+#[cfg(windows)]
+impl Field for PathBuf {
+    fn write(&self, line: &mut Line) {
+        self.to_string_lossy().into_owned().write(line);
+    }
+
+    fn read(tokens: &mut Tokens) -> io::Result<Self> {
+        Ok(String::read(tokens)?.into())
+    }
+}
+
+impl<T: Field> Field for Option<T> {
+    fn write(&self, line: &mut Line) {
+        match self {
+            Some(v) => v.write(line),
+            None => line.push("-"),
+        }
+    }
+
+    fn read(tokens: &mut Tokens) -> io::Result<Self> {
+        if tokens.none() {
+            Ok(None)
+        } else {
+            T::read(tokens).map(Some)
+        }
+    }
+}
+
+impl<T: Field> Field for Vec<T> {
+    fn write(&self, line: &mut Line) {
+        line.push(self.len());
+        for v in self {
+            v.write(line);
+        }
+    }
+
+    fn read(tokens: &mut Tokens) -> io::Result<Self> {
+        let len: usize = tokens.parse()?;
+        (0..len).map(|_| T::read(tokens)).collect()
+    }
+}
+
+impl<T: Field, Coord> Field for Position<T, Coord> {
+    fn write(&self, line: &mut Line) {
+        self.x.write(line);
+        self.y.write(line);
+    }
+
+    fn read(tokens: &mut Tokens) -> io::Result<Self> {
+        Ok(Self::new(T::read(tokens)?, T::read(tokens)?))
+    }
+}
+
+impl<T: Field, Coord> Field for Size<T, Coord> {
+    fn write(&self, line: &mut Line) {
+        self.width.write(line);
+        self.height.write(line);
+    }
+
+    fn read(tokens: &mut Tokens) -> io::Result<Self> {
+        Ok(Self::new(T::read(tokens)?, T::read(tokens)?))
+    }
+}
+
+impl<T: Field, Coord> Field for Rect<T, Coord> {
+    fn write(&self, line: &mut Line) {
+        self.left.write(line);
+        self.top.write(line);
+        self.right.write(line);
+        self.bottom.write(line);
+    }
+
+    fn read(tokens: &mut Tokens) -> io::Result<Self> {
+        Ok(Self::new(
+            T::read(tokens)?,
+            T::read(tokens)?,
+            T::read(tokens)?,
+            T::read(tokens)?,
+        ))
+    }
+}
+
+impl Field for WindowHandle {
+    fn write(&self, line: &mut Line) {
+        line.push(self.raw_id());
+    }
+
+    fn read(tokens: &mut Tokens) -> io::Result<Self> {
+        Ok(Self::replayed(tokens.parse()?))
+    }
+}
+
+impl Field for RecordedWindow {
+    fn write(&self, line: &mut Line) {
+        match *self {
+            Self::Window(handle) => {
+                line.push("W");
+                line.push(handle);
+            }
+            Self::InnerWindow { parent, handle } => {
+                line.push("I");
+                line.push(parent);
+                line.push(handle);
+            }
+        }
+    }
+
+    fn read(tokens: &mut Tokens) -> io::Result<Self> {
+        match tokens.bare()? {
+            "W" => Ok(Self::Window(tokens.parse()?)),
+            "I" => Ok(Self::InnerWindow {
+                parent: tokens.parse()?,
+                handle: tokens.parse()?,
+            }),
+            kind => Err(invalid_data(format!("unknown window: {kind}"))),
+        }
+    }
+}
+
+impl Field for RecordedReply {
+    fn write(&self, line: &mut Line) {
+        match self {
+            Self::NcHitTest(v) => {
+                line.push("NcHitTest");
+                v.write(line);
+            }
+            Self::ImeBeginComposition(v) => {
+                line.push("ImeBeginComposition");
+                v.write(line);
+            }
+            #[cfg(windows)]
+            Self::Drag(v) => {
+                line.push("Drag");
+                v.write(line);
+            }
+        }
+    }
+
+    fn read(tokens: &mut Tokens) -> io::Result<Self> {
+        match tokens.bare()? {
+            "NcHitTest" => Ok(Self::NcHitTest(Field::read(tokens)?)),
+            "ImeBeginComposition" => Ok(Self::ImeBeginComposition(Field::read(tokens)?)),
+            #[cfg(windows)]
+            "Drag" => Ok(Self::Drag(Field::read(tokens)?)),
+            name => Err(invalid_data(format!("unknown reply: {name}"))),
+        }
+    }
+}
+
+impl Field for MouseButton {
+    fn write(&self, line: &mut Line) {
+        match self {
+            Self::Ex(n) => {
+                line.push("Ex");
+                line.push(n);
+            }
+            _ => line.push(format!("{self:?}")),
+        }
+    }
+
+    fn read(tokens: &mut Tokens) -> io::Result<Self> {
+        match tokens.bare()? {
+            "Left" => Ok(Self::Left),
+            "Right" => Ok(Self::Right),
+            "Middle" => Ok(Self::Middle),
+            "Ex" => Ok(Self::Ex(tokens.parse()?)),
+            name => Err(invalid_data(format!("unknown mouse button: {name}"))),
+        }
+    }
+}
+
+impl Field for VirtualKey {
+    fn write(&self, line: &mut Line) {
+        match self {
+            Self::Other(v) => {
+                line.push("Other");
+                line.push(v);
+            }
+            _ => line.push(format!("{self:?}")),
+        }
+    }
+
+    fn read(tokens: &mut Tokens) -> io::Result<Self> {
+        if tokens.s.trim_start().starts_with("Other ") {
+            tokens.bare()?;
+            return Ok(Self::Other(tokens.parse()?));
+        }
+        read_name(tokens, &VIRTUAL_KEYS)
+    }
+}
+
+impl Field for KeyCode {
+    fn write(&self, line: &mut Line) {
+        self.vkey.write(line);
+        self.scan_code.0.write(line);
+    }
+
+    fn read(tokens: &mut Tokens) -> io::Result<Self> {
+        Ok(Self::new(
+            VirtualKey::read(tokens)?,
+            ScanCode(tokens.parse()?),
+        ))
+    }
+}
+
+impl Field for LogicalKey {
+    fn write(&self, line: &mut Line) {
+        match self {
+            Self::Character(s) => {
+                line.push("Character");
+                s.write(line);
+            }
+            Self::Dead(c) => {
+                line.push("Dead");
+                c.write(line);
+            }
+            Self::Named(key) => {
+                line.push("Named");
+                key.write(line);
+            }
+        }
+    }
+
+    fn read(tokens: &mut Tokens) -> io::Result<Self> {
+        match tokens.bare()? {
+            "Character" => Ok(Self::Character(Field::read(tokens)?)),
+            "Dead" => Ok(Self::Dead(Field::read(tokens)?)),
+            "Named" => Ok(Self::Named(Field::read(tokens)?)),
+            name => Err(invalid_data(format!("unknown logical key: {name}"))),
+        }
+    }
+}
+
+impl Field for ScrollDelta {
+    fn write(&self, line: &mut Line) {
+        match self {
+            Self::Lines(v) => {
+                line.push("Lines");
+                v.write(line);
+            }
+            Self::Pages(v) => {
+                line.push("Pages");
+                v.write(line);
+            }
+        }
+    }
+
+    fn read(tokens: &mut Tokens) -> io::Result<Self> {
+        match tokens.bare()? {
+            "Lines" => Ok(Self::Lines(Field::read(tokens)?)),
+            "Pages" => Ok(Self::Pages(Field::read(tokens)?)),
+            name => Err(invalid_data(format!("unknown scroll delta: {name}"))),
+        }
+    }
+}
+
+impl Field for gesture::Gesture {
+    fn write(&self, line: &mut Line) {
+        match self {
+            Self::Pan(g) => {
+                line.push("Pan");
+                g.write(line);
+            }
+            Self::Pinch(g) => {
+                line.push("Pinch");
+                g.write(line);
+            }
+            Self::Rotate(g) => {
+                line.push("Rotate");
+                g.write(line);
+            }
+            Self::Tap(g) => {
+                line.push("Tap");
+                g.write(line);
+            }
+            Self::LongPress(g) => {
+                line.push("LongPress");
+                g.write(line);
+            }
+        }
+    }
+
+    fn read(tokens: &mut Tokens) -> io::Result<Self> {
+        match tokens.bare()? {
+            "Pan" => Ok(Self::Pan(Field::read(tokens)?)),
+            "Pinch" => Ok(Self::Pinch(Field::read(tokens)?)),
+            "Rotate" => Ok(Self::Rotate(Field::read(tokens)?)),
+            "Tap" => Ok(Self::Tap(Field::read(tokens)?)),
+            "LongPress" => Ok(Self::LongPress(Field::read(tokens)?)),
+            name => Err(invalid_data(format!("unknown gesture: {name}"))),
+        }
+    }
+}
+
+impl Field for Chord {
+    fn write(&self, line: &mut Line) {
+        line.push(self);
+    }
+
+    fn read(tokens: &mut Tokens) -> io::Result<Self> {
+        let token = tokens.bare()?;
+        token
+            .parse()
+            .map_err(|e| invalid_data(format!("invalid chord: {e}")))
+    }
+}
+
+impl Field for MouseState {
+    fn write(&self, line: &mut Line) {
+        self.position.write(line);
+        self.buttons.to_vec().write(line);
+        self.keys.contains(VirtualKey::Ctrl).write(line);
+        self.keys.contains(VirtualKey::Shift).write(line);
+        self.modifiers.write(line);
+    }
+
+    fn read(tokens: &mut Tokens) -> io::Result<Self> {
+        let position = Field::read(tokens)?;
+        let buttons = Vec::<MouseButton>::read(tokens)?;
+        let ctrl = bool::read(tokens)?;
+        let shift = bool::read(tokens)?;
+        Ok(Self {
+            position,
+            buttons: buttons.into(),
+            keys: MouseStateVirtualKeys::new(ctrl, shift),
+            modifiers: Field::read(tokens)?,
+        })
+    }
+}
+
+impl Field for ModifierState {
+    fn write(&self, line: &mut Line) {
+        line.push(self.bits());
+    }
+
+    fn read(tokens: &mut Tokens) -> io::Result<Self> {
+        Ok(Self::from_bits_retain(tokens.parse()?))
+    }
+}
+
+impl Field for PointerId {
+    fn write(&self, line: &mut Line) {
+        self.raw().write(line);
+    }
+
+    fn read(tokens: &mut Tokens) -> io::Result<Self> {
+        Ok(Self::new(Field::read(tokens)?))
+    }
+}
+
+impl Field for PenButtons {
+    fn write(&self, line: &mut Line) {
+        line.push(self.bits());
+    }
+
+    fn read(tokens: &mut Tokens) -> io::Result<Self> {
+        Ok(Self::from_bits_retain(tokens.parse()?))
+    }
+}
+
+impl Field for DeviceId {
+    fn write(&self, line: &mut Line) {
+        self.raw().write(line);
+    }
+
+    fn read(tokens: &mut Tokens) -> io::Result<Self> {
+        Ok(Self::from_raw(Field::read(tokens)?))
+    }
+}
+
+impl Field for ime::Clause {
+    fn write(&self, line: &mut Line) {
+        self.range.start.write(line);
+        self.range.end.write(line);
+        self.targeted.write(line);
+    }
+
+    fn read(tokens: &mut Tokens) -> io::Result<Self> {
+        let start = usize::read(tokens)?;
+        let end = usize::read(tokens)?;
+        Ok(Self {
+            range: start..end,
+            targeted: bool::read(tokens)?,
+        })
+    }
+}
+
+#[cfg(windows)]
+impl Field for MenuHandle {
+    fn write(&self, line: &mut Line) {
+        line.push(self.raw_id());
+    }
+
+    fn read(tokens: &mut Tokens) -> io::Result<Self> {
+        Ok(Self::from_raw_id(tokens.parse()?))
+    }
+}
+
+#[cfg(windows)]
+impl Field for NotifyIcon {
+    fn write(&self, line: &mut Line) {
+        line.push(self.id());
+    }
+
+    fn read(tokens: &mut Tokens) -> io::Result<Self> {
+        Ok(Self::from_id(tokens.parse()?))
+    }
+}
+
+#[cfg(windows)]
+impl Field for NotifyIconEvent {
+    fn write(&self, line: &mut Line) {
+        match self {
+            Self::MouseInput(ev) => {
+                line.push("MouseInput");
+                ev.write(line);
+            }
+            Self::CursorMoved(position) => {
+                line.push("CursorMoved");
+                position.write(line);
+            }
+            Self::ContextMenu(position) => {
+                line.push("ContextMenu");
+                position.write(line);
+            }
+            Self::PopupOpen(position) => {
+                line.push("PopupOpen");
+                position.write(line);
+            }
+            Self::PopupClose => line.push("PopupClose"),
+            Self::Select(position) => {
+                line.push("Select");
+                position.write(line);
+            }
+            Self::KeySelect(position) => {
+                line.push("KeySelect");
+                position.write(line);
+            }
+            Self::Other(ev) => {
+                line.push("Other");
+                ev.write(line);
+            }
+        }
+    }
+
+    fn read(tokens: &mut Tokens) -> io::Result<Self> {
+        match tokens.bare()? {
+            "MouseInput" => Ok(Self::MouseInput(Field::read(tokens)?)),
+            "CursorMoved" => Ok(Self::CursorMoved(Field::read(tokens)?)),
+            "ContextMenu" => Ok(Self::ContextMenu(Field::read(tokens)?)),
+            "PopupOpen" => Ok(Self::PopupOpen(Field::read(tokens)?)),
+            "PopupClose" => Ok(Self::PopupClose),
+            "Select" => Ok(Self::Select(Field::read(tokens)?)),
+            "KeySelect" => Ok(Self::KeySelect(Field::read(tokens)?)),
+            "Other" => Ok(Self::Other(Field::read(tokens)?)),
+            name => Err(invalid_data(format!("unknown notify icon event: {name}"))),
+        }
+    }
+}
+
+#[cfg(windows)]
+impl Field for drag_drop::Effect {
+    fn write(&self, line: &mut Line) {
+        line.push(self.bits());
+    }
+
+    fn read(tokens: &mut Tokens) -> io::Result<Self> {
+        Ok(Self::from_bits_retain(tokens.parse()?))
+    }
+}
+
+#[cfg(windows)]
+impl Field for ModifierKey {
+    fn write(&self, line: &mut Line) {
+        line.push(self.bits());
+    }
+
+    fn read(tokens: &mut Tokens) -> io::Result<Self> {
+        Ok(Self::from_bits_retain(tokens.parse()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mouse_state(x: i32, y: i32) -> MouseState {
+        MouseState {
+            position: PhysicalPosition::new(x, y),
+            buttons: vec![MouseButton::Left, MouseButton::Ex(2)].into(),
+            keys: MouseStateVirtualKeys::new(true, false),
+            modifiers: ModifierState::LEFT_CTRL | ModifierState::CAPS_LOCK,
+        }
+    }
+
+    pub(super) fn events() -> Vec<RecordedEvent> {
+        let key_code = KeyCode::new(VirtualKey::A, ScanCode(0x1e));
+        let vector = gesture::Vector::new(1.5, -2.25);
+        let center = PhysicalPosition::new(10.5, -3.0);
+        vec![
+            RecordedEvent::Activated,
+            RecordedEvent::Inactivated,
+            RecordedEvent::EnterResizing,
+            RecordedEvent::CaptureLost,
+            RecordedEvent::ImeBeginCandidateList,
+            RecordedEvent::ImeEndCandidateList,
+            RecordedEvent::Minizmized,
+            RecordedEvent::MonitorsChanged,
+            #[cfg(windows)]
+            RecordedEvent::DragLeave,
+            RecordedEvent::CloseRequest,
+            RecordedEvent::Closed,
+            RecordedEvent::Focused(event::Focused { focused: true }),
+            RecordedEvent::Draw(event::Draw {
+                invalidate_rect: PhysicalRect::new(1, 2, 3, 4),
+            }),
+            RecordedEvent::Moved(event::Moved {
+                position: ScreenPosition::new(-100, 200),
+            }),
+            RecordedEvent::Resizing(event::Resizing {
+                size: PhysicalSize::new(640, 480),
+                edge: ResizingEdge::BottomRight,
+            }),
+            RecordedEvent::Resized(event::Resized {
+                size: PhysicalSize::new(800, 600),
+            }),
+            RecordedEvent::MouseInput(event::MouseInput {
+                button: MouseButton::Ex(1),
+                button_state: ButtonState::Pressed,
+                click_count: 2,
+                mouse_state: mouse_state(1, 2),
+            }),
+            RecordedEvent::CursorMoved(event::CursorMoved {
+                mouse_state: mouse_state(-3, 4),
+            }),
+            RecordedEvent::DragStarted(event::DragStarted {
+                button: MouseButton::Left,
+                origin: PhysicalPosition::new(5, 6),
+                mouse_state: mouse_state(7, 8),
+            }),
+            RecordedEvent::CursorEntered(event::CursorEntered {
+                mouse_state: mouse_state(0, 0),
+            }),
+            RecordedEvent::CursorLeft(event::CursorLeft {
+                position: PhysicalPosition::new(9, 10),
+            }),
+            RecordedEvent::MouseWheel(event::MouseWheel {
+                axis: MouseWheelAxis::Horizontal,
+                distance: -240,
+                delta: ScrollDelta::Pages(0.5),
+                steps: -2,
+                phase: Some(ScrollPhase::Momentum),
+                mouse_state: mouse_state(11, 12),
+            }),
+            RecordedEvent::MouseWheel(event::MouseWheel {
+                axis: MouseWheelAxis::Vertical,
+                distance: 120,
+                delta: ScrollDelta::Lines(3.0),
+                steps: 1,
+                phase: None,
+                mouse_state: mouse_state(13, 14),
+            }),
+            RecordedEvent::Pointer(event::Pointer {
+                id: PointerId::new(3),
+                pointer_type: PointerType::Pen,
+                phase: PointerPhase::Move,
+                position: PhysicalPosition::new(15, 16),
+                is_primary: true,
+                in_contact: false,
+                properties: PointerProperties {
+                    pressure: Some(0.75),
+                    tilt_x: -10,
+                    tilt_y: 20,
+                    twist: 359,
+                    contact_size: Some(PhysicalSize::new(4, 5)),
+                    pen_buttons: PenButtons::BARREL | PenButtons::ERASER,
+                },
+                modifiers: ModifierState::RIGHT_SHIFT,
+            }),
+            RecordedEvent::Gesture(gesture::Gesture::Pan(gesture::Pan {
+                phase: gesture::GesturePhase::Inertia,
+                position: center,
+                translation: vector,
+                delta: vector,
+                velocity: vector,
+            })),
+            RecordedEvent::Gesture(gesture::Gesture::Pinch(gesture::Pinch {
+                phase: gesture::GesturePhase::Begin,
+                center,
+                scale: 1.25,
+                delta: 0.125,
+                velocity: -0.5,
+            })),
+            RecordedEvent::Gesture(gesture::Gesture::Rotate(gesture::Rotate {
+                phase: gesture::GesturePhase::End,
+                center,
+                angle: -0.75,
+                delta: 0.25,
+                velocity: 1.0,
+            })),
+            RecordedEvent::Gesture(gesture::Gesture::Tap(gesture::Tap {
+                position: center,
+                fingers: 2,
+            })),
+            RecordedEvent::Gesture(gesture::Gesture::LongPress(gesture::LongPress {
+                position: center,
+            })),
+            RecordedEvent::KeyInput(event::KeyInput {
+                key_code,
+                key_state: KeyState::Released,
+                prev_pressed: true,
+                modifiers: ModifierState::LEFT_ALT,
+                logical_key: LogicalKey::Character("A".into()),
+                text: Some("a".into()),
+            }),
+            RecordedEvent::KeyInput(event::KeyInput {
+                key_code: KeyCode::new(VirtualKey::Other(0xff), ScanCode(0)),
+                key_state: KeyState::Pressed,
+                prev_pressed: false,
+                modifiers: ModifierState::empty(),
+                logical_key: LogicalKey::Dead(Some('^')),
+                text: None,
+            }),
+            RecordedEvent::KeyInput(event::KeyInput {
+                key_code: KeyCode::new(VirtualKey::Enter, ScanCode(0x1c)),
+                key_state: KeyState::Pressed,
+                prev_pressed: false,
+                modifiers: ModifierState::empty(),
+                logical_key: LogicalKey::Named(VirtualKey::Enter),
+                text: Some("\r".into()),
+            }),
+            RecordedEvent::Accelerator(event::Accelerator {
+                id: 7,
+                chord: "Ctrl+Shift+S".parse().unwrap(),
+            }),
+            RecordedEvent::CharInput(event::CharInput { c: 'x' }),
+            RecordedEvent::TextInput(event::TextInput {
+                text: "text".into(),
+            }),
+            RecordedEvent::DeadCharInput(event::DeadCharInput { c: '`' }),
+            RecordedEvent::SysCharInput(event::SysCharInput { c: 'f' }),
+            RecordedEvent::RawMouseMotion(event::RawMouseMotion {
+                device: DeviceId::from_raw(1),
+                x: -5,
+                y: 6,
+                absolute: true,
+            }),
+            RecordedEvent::RawKeyInput(event::RawKeyInput {
+                device: DeviceId::from_raw(2),
+                key_code,
+                key_state: KeyState::Pressed,
+            }),
+            RecordedEvent::RawHidInput(event::RawHidInput {
+                device: DeviceId::from_raw(3),
+                size: 2,
+                data: vec![0, 255, 1, 128],
+            }),
+            RecordedEvent::DeviceAdded(event::DeviceAdded {
+                device: DeviceId::from_raw(4),
+            }),
+            RecordedEvent::DeviceRemoved(event::DeviceRemoved {
+                device: DeviceId::from_raw(5),
+            }),
+            RecordedEvent::ImeBeginComposition(ImeBeginComposition { dpi: 144 }),
+            RecordedEvent::ImeUpdateComposition(event::ImeUpdateComposition {
+                chars: vec!['か', 'な', '\u{20bb7}'],
+                clauses: vec![
+                    ime::Clause {
+                        range: 0..2,
+                        targeted: true,
+                    },
+                    ime::Clause {
+                        range: 2..3,
+                        targeted: false,
+                    },
+                ],
+                cursor_position: 1,
+            }),
+            RecordedEvent::ImeEndComposition(event::ImeEndComposition {
+                result: Some("かな".into()),
+            }),
+            RecordedEvent::ImeEndComposition(event::ImeEndComposition { result: None }),
+            RecordedEvent::ImeUpdateCandidateList(event::ImeUpdateCandidateList {
+                selection: 1,
+                items: vec!["仮名".into(), "かな".into(), "".into()],
+            }),
+            #[cfg(windows)]
+            RecordedEvent::MenuCommand(event::MenuCommand {
+                index: 3,
+                handle: MenuHandle::from_raw_id(0x1234),
+            }),
+            RecordedEvent::ContextMenu(event::ContextMenu {
+                clicked_window: WindowHandle::from_raw_id(42),
+                position: ScreenPosition::new(-1, -2),
+            }),
+            RecordedEvent::Maximized(event::Maximized {
+                size: PhysicalSize::new(1920, 1080),
+            }),
+            RecordedEvent::Restored(event::Restored {
+                size: PhysicalSize::new(640, 480),
+            }),
+            RecordedEvent::FullscreenChanged(event::FullscreenChanged { fullscreen: true }),
+            RecordedEvent::DpiChanged(event::DpiChanged { new_dpi: 192 }),
+            RecordedEvent::NcHitTest(NcHitTest {
+                position: PhysicalPosition::new(20, -30),
+            }),
+            #[cfg(windows)]
+            RecordedEvent::NotifyIcon(event::NotifyIcon {
+                id: NotifyIcon::from_id(1),
+                event: NotifyIconEvent::MouseInput(notify_icon::event::MouseInput {
+                    button: MouseButton::Right,
+                    button_state: ButtonState::Released,
+                    position: ScreenPosition::new(1, 2),
+                }),
+            }),
+            #[cfg(windows)]
+            RecordedEvent::NotifyIcon(event::NotifyIcon {
+                id: NotifyIcon::from_id(2),
+                event: NotifyIconEvent::PopupClose,
+            }),
+            #[cfg(windows)]
+            RecordedEvent::NotifyIcon(event::NotifyIcon {
+                id: NotifyIcon::from_id(3),
+                event: NotifyIconEvent::Other(event::Other {
+                    msg: 0x400,
+                    wparam: 1,
+                    lparam: -1,
+                }),
+            }),
+            RecordedEvent::ColorModeChanged(event::ColorModeChanged {
+                current: ColorModeState::Dark,
+                previous: ColorModeState::Light,
+            }),
+            #[cfg(windows)]
+            RecordedEvent::DragEnter(DragDrop {
+                position: PhysicalPosition::new(1, 2),
+                modifier_keys: ModifierKey::CONTROL,
+                files: vec![PathBuf::from("C:\\a b\\\"c\".txt")],
+                effect: drag_drop::Effect::COPY,
+            }),
+            #[cfg(windows)]
+            RecordedEvent::DragOver(DragDrop {
+                position: PhysicalPosition::new(3, 4),
+                modifier_keys: ModifierKey::SHIFT,
+                files: vec![],
+                effect: drag_drop::Effect::MOVE | drag_drop::Effect::LINK,
+            }),
+            #[cfg(windows)]
+            RecordedEvent::Drop(DragDrop {
+                position: PhysicalPosition::new(5, 6),
+                modifier_keys: ModifierKey::empty(),
+                files: vec![PathBuf::from("x"), PathBuf::from("\u{1f600}")],
+                effect: drag_drop::Effect::NONE,
+            }),
+            RecordedEvent::App(event::App {
+                index: 1,
+                value0: usize::MAX,
+                value1: isize::MIN,
+            }),
+            RecordedEvent::Other(event::Other {
+                msg: 0x0113,
+                wparam: 2,
+                lparam: -3,
+            }),
+        ]
+    }
+
+    pub(super) fn replies() -> Vec<RecordedReply> {
+        vec![
+            RecordedReply::NcHitTest(Some(NcHitTestValue::Caption)),
+            RecordedReply::NcHitTest(Some(NcHitTestValue::Transparent)),
+            RecordedReply::NcHitTest(None),
+            RecordedReply::ImeBeginComposition(PhysicalPosition::new(-7, 8)),
+            #[cfg(windows)]
+            RecordedReply::Drag(drag_drop::Effect::COPY | drag_drop::Effect::LINK),
+        ]
+    }
+
+    fn event_line(seq: u64, window: RecordedWindow, event: &RecordedEvent) -> String {
+        let mut line = Line::new("E");
+        line.push(seq);
+        line.push(seq * 1000);
+        window.write(&mut line);
+        event.write(&mut line);
+        line.0
+    }
+
+    fn reply_line(seq: u64, reply: &RecordedReply) -> String {
+        let mut line = Line::new("R");
+        line.push(seq);
+        reply.write(&mut line);
+        line.0
+    }
+
+    fn replay(lines: &[String]) -> Result<ReplayState> {
+        let mut data = format!("{MAGIC} {FORMAT_VERSION}\n");
+        for line in lines {
+            data.push_str(line);
+            data.push('\n');
+        }
+        ReplayState::new(data.as_bytes())
+    }
+
+    fn round_trip(event: RecordedEvent) -> RecordedEvent {
+        let window = RecordedWindow::Window(1);
+        let state = replay(&[event_line(0, window, &event)]).unwrap();
+        assert_eq!(state.entries.len(), 1);
+        state.entries[0].event.clone()
+    }
+
+    #[test]
+    fn round_trip_events() {
+        let events = events();
+        let windows = [
+            RecordedWindow::Window(1),
+            RecordedWindow::InnerWindow {
+                parent: 1,
+                handle: u64::MAX,
+            },
+        ];
+        let lines = events
+            .iter()
+            .enumerate()
+            .map(|(i, event)| event_line(i as u64, windows[i % 2], event))
+            .collect::<Vec<_>>();
+        let state = replay(&lines).unwrap();
+        assert_eq!(state.entries.len(), events.len());
+        for (i, (entry, event)) in state.entries.iter().zip(&events).enumerate() {
+            assert_eq!(entry.seq, i as u64);
+            assert_eq!(entry.timestamp, Duration::from_nanos(i as u64 * 1000));
+            assert_eq!(entry.window, windows[i % 2]);
+            assert_eq!(format!("{:?}", entry.event), format!("{event:?}"));
+            assert!(entry.reply.is_none());
+        }
+    }
+
+    #[test]
+    fn round_trip_replies() {
+        let event = RecordedEvent::NcHitTest(NcHitTest {
+            position: PhysicalPosition::new(0, 0),
+        });
+        let replies = replies();
+        let mut lines = vec![];
+        for (i, reply) in replies.iter().enumerate() {
+            lines.push(event_line(i as u64, RecordedWindow::Window(1), &event));
+            lines.push(reply_line(i as u64, reply));
+        }
+        let state = replay(&lines).unwrap();
+        let actual = state.entries.iter().map(|e| e.reply).collect::<Vec<_>>();
+        assert_eq!(actual, replies.into_iter().map(Some).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn round_trip_strings() {
+        let strings = [
+            "",
+            " ",
+            "a b  c ",
+            "\"quoted\" 'single'",
+            "\\\"",
+            "line\nbreak\r\n\ttab\0",
+            "\u{1b}[0m\u{7f}",
+            "- -",
+            "\u{1f600}\u{20bb7}\u{10ffff}",
+        ];
+        for s in strings {
+            let RecordedEvent::TextInput(ev) =
+                round_trip(RecordedEvent::TextInput(event::TextInput {
+                    text: s.into(),
+                }))
+            else {
+                panic!("not TextInput");
+            };
+            assert_eq!(ev.text, s);
+            let RecordedEvent::ImeEndComposition(ev) =
+                round_trip(RecordedEvent::ImeEndComposition(event::ImeEndComposition {
+                    result: Some(s.into()),
+                }))
+            else {
+                panic!("not ImeEndComposition");
+            };
+            assert_eq!(ev.result.as_deref(), Some(s));
+        }
+        let RecordedEvent::ImeUpdateCandidateList(ev) = round_trip(
+            RecordedEvent::ImeUpdateCandidateList(event::ImeUpdateCandidateList {
+                selection: 0,
+                items: strings.iter().map(|s| s.to_string()).collect(),
+            }),
+        ) else {
+            panic!("not ImeUpdateCandidateList");
+        };
+        assert_eq!(ev.items, strings);
+        for c in " \"'\\\n\0-\u{1f600}\u{10ffff}".chars() {
+            let RecordedEvent::CharInput(ev) =
+                round_trip(RecordedEvent::CharInput(event::CharInput { c }))
+            else {
+                panic!("not CharInput");
+            };
+            assert_eq!(ev.c, c);
+        }
+    }
+
+    #[test]
+    fn reject_bad_magic() {
+        for data in ["", "\n", "\"wiard-record\" 5\n"] {
+            assert!(ReplayState::new(data.as_bytes()).is_err(), "{data:?}");
+        }
+        for data in ["wiard-recorder 5\n", "E 0 0 W 1 Closed\n"] {
+            let e = ReplayState::new(data.as_bytes()).err().unwrap();
+            assert!(e.to_string().contains("not a wiard recording"), "{data:?}");
+        }
+    }
+
+    #[test]
+    fn reject_bad_version() {
+        for version in [
+            (FORMAT_VERSION - 1).to_string(),
+            (FORMAT_VERSION + 1).to_string(),
+            "x".to_string(),
+            String::new(),
+        ] {
+            let data = format!("{MAGIC} {version}\n");
+            assert!(ReplayState::new(data.as_bytes()).is_err(), "{data:?}");
+        }
+        let data = format!("{MAGIC} {FORMAT_VERSION} extra\n");
+        assert!(ReplayState::new(data.as_bytes()).is_ok());
+    }
+
+    #[test]
+    fn reject_truncated_lines() {
+        let lines = events()
+            .iter()
+            .map(|event| event_line(0, RecordedWindow::Window(1), event))
+            .chain(
+                replies()
+                    .iter()
+                    .map(|reply| format!("E 0 0 W 1 Closed\n{}", reply_line(0, reply))),
+            )
+            .collect::<Vec<_>>();
+        for line in lines {
+            let (head, last) = line.rsplit_once('\n').unwrap_or(("", &line));
+            let tokens = last.split(' ').collect::<Vec<_>>();
+            for n in 1..tokens.len() {
+                let truncated = format!("{head}\n{}", tokens[..n].join(" "));
+                assert!(
+                    replay(std::slice::from_ref(&truncated)).is_err(),
+                    "{truncated:?}"
+                );
+            }
+        }
+        let unterminated = "E 0 0 W 1 TextInput \"abc".to_string();
+        assert!(replay(&[unterminated]).is_err());
+        let trailing = "E 0 0 W 1 Closed extra".to_string();
+        assert!(replay(&[trailing]).is_err());
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::tests::{events, replies};
+    use super::*;
+
+    fn round_trip<T>(value: &T) -> T
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        let json = serde_json::to_string(value).unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn round_trip_events() {
+        for event in events() {
+            assert_eq!(format!("{:?}", round_trip(&event)), format!("{event:?}"));
+        }
+    }
+
+    #[test]
+    fn round_trip_entries() {
+        let windows = [
+            RecordedWindow::Window(1),
+            RecordedWindow::InnerWindow {
+                parent: 1,
+                handle: u64::MAX,
+            },
+        ];
+        let replies = replies();
+        for (i, event) in events().into_iter().enumerate() {
+            let entry = Entry {
+                seq: i as u64,
+                timestamp: Duration::from_nanos(i as u64 * 1000 + 1),
+                window: windows[i % 2],
+                event,
+                reply: replies.get(i).copied(),
+            };
+            assert_eq!(format!("{:?}", round_trip(&entry)), format!("{entry:?}"));
+        }
+    }
+
+    #[test]
+    fn round_trip_mismatches() {
+        for reply in replies() {
+            let mismatch = ReplyMismatch {
+                seq: 3,
+                expected: Some(reply),
+                actual: RecordedReply::NcHitTest(None),
+            };
+            assert_eq!(round_trip(&mismatch), mismatch);
+        }
+    }
+}
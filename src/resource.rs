@@ -1,10 +1,19 @@
 use crate::*;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use windows::Win32::{Foundation::HINSTANCE, UI::WindowsAndMessaging::*};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use windows::Win32::{
+    Foundation::HINSTANCE,
+    Graphics::Gdi::{CreateBitmap, DeleteObject},
+    UI::HiDpi::GetSystemMetricsForDpi,
+    UI::WindowsAndMessaging::*,
+};
 use windows::core::{HSTRING, PCWSTR};
 
 /// Represents icons.
 #[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Icon {
     Resource(u16),
     File(PathBuf),
@@ -15,6 +24,7 @@ pub enum Icon {
     Question,
     WinLogo,
     Shield,
+    Custom(CustomIcon),
 }
 
 impl Icon {
@@ -24,22 +34,41 @@ impl Icon {
         Icon::File(path.as_ref().into())
     }
 
-    pub(crate) fn load(&self, hinst: Option<HINSTANCE>) -> Result<HICON> {
+    /// An icon from non-premultiplied RGBA pixels.
+    ///
+    /// `pixels` must have `width * height * 4` bytes in rows from the top. The image is scaled
+    /// to the icon sizes of the DPI.
+    pub fn from_rgba(width: u32, height: u32, pixels: &[u8]) -> Result<Icon> {
+        let image = IconImage::rgba(width, height, pixels.to_vec())?;
+        Ok(Icon::Custom(CustomIcon::new(image)))
+    }
+
+    /// An icon from bytes of an `.ico` file.
+    ///
+    /// The entry nearest to the icon sizes of the DPI is used.
+    pub fn from_ico_bytes(data: &[u8]) -> Result<Icon> {
+        let image = IconImage::ico(data.to_vec())?;
+        Ok(Icon::Custom(CustomIcon::new(image)))
+    }
+
+    /// Loads the icon of `SM_CXICON` and `SM_CYICON` at `dpi`.
+    pub(crate) fn load(&self, hinst: Option<HINSTANCE>, dpi: u32) -> Result<HICON> {
         unsafe {
             self.load_impl(
                 hinst,
-                GetSystemMetrics(SM_CXICON),
-                GetSystemMetrics(SM_CYICON),
+                GetSystemMetricsForDpi(SM_CXICON, dpi),
+                GetSystemMetricsForDpi(SM_CYICON, dpi),
             )
         }
     }
 
-    pub(crate) fn load_small(&self, hinst: Option<HINSTANCE>) -> Result<HICON> {
+    /// Loads the icon of `SM_CXSMICON` and `SM_CYSMICON` at `dpi`.
+    pub(crate) fn load_small(&self, hinst: Option<HINSTANCE>, dpi: u32) -> Result<HICON> {
         unsafe {
             self.load_impl(
                 hinst,
-                GetSystemMetrics(SM_CXSMICON),
-                GetSystemMetrics(SM_CYSMICON),
+                GetSystemMetricsForDpi(SM_CXSMICON, dpi),
+                GetSystemMetricsForDpi(SM_CYSMICON, dpi),
             )
         }
     }
@@ -77,13 +106,155 @@ impl Icon {
                 Icon::Question => Ok(LoadIconW(None, IDI_QUESTION)?),
                 Icon::WinLogo => Ok(LoadIconW(None, IDI_WINLOGO)?),
                 Icon::Shield => Ok(LoadIconW(None, IDI_SHIELD)?),
+                Icon::Custom(custom) => custom.load(cx, cy),
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+enum IconImage {
+    Rgba(image::RgbaImage),
+    Ico {
+        dir: image::IconDir,
+        #[cfg(feature = "serde")]
+        data: Vec<u8>,
+    },
+}
+
+impl IconImage {
+    fn rgba(width: u32, height: u32, pixels: Vec<u8>) -> Result<Self> {
+        Ok(Self::Rgba(image::RgbaImage::new(width, height, pixels)?))
+    }
+
+    fn ico(data: Vec<u8>) -> Result<Self> {
+        Ok(Self::Ico {
+            dir: image::IconDir::decode(&data)?,
+            #[cfg(feature = "serde")]
+            data,
+        })
+    }
+}
+
+#[derive(Debug)]
+struct CustomIconData {
+    image: IconImage,
+    /// `HICON`s for each size.
+    cache: Mutex<HashMap<(i32, i32), isize>>,
+}
+
+impl Drop for CustomIconData {
+    fn drop(&mut self) {
+        let cache = self.cache.get_mut().unwrap_or_else(|e| e.into_inner());
+        for &handle in cache.values() {
+            unsafe {
+                DestroyIcon(HICON(handle as _)).ok();
             }
         }
     }
 }
 
+/// An icon created by `Icon::from_rgba` or `Icon::from_ico_bytes`.
+///
+/// `HICON`s are created for each size when they are needed, and cached until all clones are
+/// dropped.
+///
+/// With the `serde` feature, this type is serialized as its source: the RGBA pixels, or the bytes
+/// of the `.ico` file.
+#[derive(Clone, Debug)]
+pub struct CustomIcon(Arc<CustomIconData>);
+
+impl CustomIcon {
+    fn new(image: IconImage) -> Self {
+        Self(Arc::new(CustomIconData {
+            image,
+            cache: Mutex::new(HashMap::new()),
+        }))
+    }
+
+    fn load(&self, cx: i32, cy: i32) -> Result<HICON> {
+        let mut cache = self.0.cache.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(&handle) = cache.get(&(cx, cy)) {
+            return Ok(HICON(handle as _));
+        }
+        let icon = match &self.0.image {
+            IconImage::Rgba(image) => {
+                let image = image.resize(PhysicalSize::new(cx as u32, cy as u32));
+                create_icon_from_rgba(&image, None)?
+            }
+            IconImage::Ico { dir, .. } => {
+                let entry = dir.best_entry(cx.max(cy) as u32).unwrap();
+                unsafe {
+                    CreateIconFromResourceEx(
+                        &entry.data,
+                        true,
+                        0x00030000,
+                        cx,
+                        cy,
+                        LR_DEFAULTCOLOR,
+                    )?
+                }
+            }
+        };
+        cache.insert((cx, cy), icon.0 as isize);
+        Ok(icon)
+    }
+}
+
+impl PartialEq for CustomIcon {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for CustomIcon {}
+
+/// The source of `CustomIcon` which is serialized.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+enum CustomIconSource {
+    Rgba {
+        width: u32,
+        height: u32,
+        pixels: Vec<u8>,
+    },
+    Ico(Vec<u8>),
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for CustomIcon {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> std::result::Result<S::Ok, S::Error> {
+        let source = match &self.0.image {
+            IconImage::Rgba(image) => CustomIconSource::Rgba {
+                width: image.size().width,
+                height: image.size().height,
+                pixels: image.pixels().to_vec(),
+            },
+            IconImage::Ico { data, .. } => CustomIconSource::Ico(data.clone()),
+        };
+        serde::Serialize::serialize(&source, s)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for CustomIcon {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> std::result::Result<Self, D::Error> {
+        let image = match <CustomIconSource as serde::Deserialize>::deserialize(d)? {
+            CustomIconSource::Rgba {
+                width,
+                height,
+                pixels,
+            } => IconImage::rgba(width, height, pixels),
+            CustomIconSource::Ico(data) => IconImage::ico(data),
+        };
+        Ok(Self::new(image.map_err(serde::de::Error::custom)?))
+    }
+}
+
 /// Represents mouse cursors.
 #[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Cursor {
     AppStaring,
     Arrow,
@@ -99,9 +270,32 @@ pub enum Cursor {
     SizeWE,
     UpArrow,
     Wait,
+    Custom(CustomCursor),
 }
 
 impl Cursor {
+    /// A cursor from non-premultiplied RGBA pixels at 96 DPI.
+    ///
+    /// `pixels` must have `width * height * 4` bytes in rows from the top. The image is scaled
+    /// for the DPI of each window.
+    pub fn from_rgba(
+        width: u32,
+        height: u32,
+        hotspot: PhysicalPosition<u32>,
+        pixels: &[u8],
+    ) -> Result<Self> {
+        let image = CursorImage::rgba(width, height, hotspot, pixels.to_vec())?;
+        Ok(Self::Custom(CustomCursor::new(image)))
+    }
+
+    /// A cursor from a `.cur` or `.ani` file.
+    ///
+    /// The entry nearest to the cursor size of the DPI (`SM_CXCURSOR`) is used for each window.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let image = CursorImage::load(path.as_ref().to_path_buf())?;
+        Ok(Self::Custom(CustomCursor::new(image)))
+    }
+
     fn system_defined_name(&self) -> PCWSTR {
         match self {
             Self::AppStaring => IDC_APPSTARTING,
@@ -118,12 +312,26 @@ impl Cursor {
             Self::SizeWE => IDC_SIZEWE,
             Self::UpArrow => IDC_UPARROW,
             Self::Wait => IDC_WAIT,
+            Self::Custom(_) => IDC_ARROW,
         }
     }
 
-    pub(crate) fn set(&self) {
+    /// Sets the cursor for `dpi`, and returns the duration of `step` if the cursor is animated.
+    pub(crate) fn set(&self, dpi: u32, step: usize) -> Option<Duration> {
         unsafe {
+            if let Self::Custom(custom) = self {
+                match custom.frame(dpi, step) {
+                    Ok((handle, duration)) => {
+                        SetCursor(Some(handle));
+                        return duration;
+                    }
+                    Err(e) => {
+                        error!("{e}");
+                    }
+                }
+            }
             SetCursor(Some(LoadCursorW(None, self.system_defined_name()).unwrap()));
+            None
         }
     }
 }
@@ -134,3 +342,275 @@ impl Default for Cursor {
         Self::Arrow
     }
 }
+
+#[derive(Debug)]
+enum CursorImage {
+    Rgba {
+        image: image::RgbaImage,
+        hotspot: PhysicalPosition<u32>,
+    },
+    Static {
+        dir: image::IconDir,
+        #[cfg(feature = "serde")]
+        path: PathBuf,
+    },
+    Animated {
+        ani: image::AniCursor,
+        #[cfg(feature = "serde")]
+        path: PathBuf,
+    },
+}
+
+impl CursorImage {
+    fn rgba(
+        width: u32,
+        height: u32,
+        hotspot: PhysicalPosition<u32>,
+        pixels: Vec<u8>,
+    ) -> Result<Self> {
+        let image = image::RgbaImage::new(width, height, pixels)?;
+        Ok(Self::Rgba { image, hotspot })
+    }
+
+    fn load(path: PathBuf) -> Result<Self> {
+        let data = std::fs::read(&path)?;
+        if image::AniCursor::is_ani(&data) {
+            Ok(Self::Animated {
+                ani: image::AniCursor::decode(&data)?,
+                #[cfg(feature = "serde")]
+                path,
+            })
+        } else {
+            Ok(Self::Static {
+                dir: image::IconDir::decode(&data)?,
+                #[cfg(feature = "serde")]
+                path,
+            })
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CustomCursorData {
+    image: CursorImage,
+    /// `HCURSOR`s of frames for each DPI.
+    cache: Mutex<HashMap<u32, Vec<isize>>>,
+}
+
+impl Drop for CustomCursorData {
+    fn drop(&mut self) {
+        let cache = self.cache.get_mut().unwrap_or_else(|e| e.into_inner());
+        for &handle in cache.values().flatten() {
+            unsafe {
+                DestroyCursor(HCURSOR(handle as _)).ok();
+            }
+        }
+    }
+}
+
+/// A cursor created by `Cursor::from_rgba` or `Cursor::from_file`.
+///
+/// `HCURSOR`s are created for each DPI when they are needed, and cached until all clones are
+/// dropped.
+///
+/// With the `serde` feature, this type is serialized as its source: the path of the file, or
+/// the RGBA pixels and the hotspot. Deserializing a cursor from a file reads the file again, and
+/// fails if the file cannot be loaded.
+#[derive(Clone, Debug)]
+pub struct CustomCursor(Arc<CustomCursorData>);
+
+impl CustomCursor {
+    fn new(image: CursorImage) -> Self {
+        Self(Arc::new(CustomCursorData {
+            image,
+            cache: Mutex::new(HashMap::new()),
+        }))
+    }
+
+    fn frame(&self, dpi: u32, step: usize) -> Result<(HCURSOR, Option<Duration>)> {
+        let mut cache = self.0.cache.lock().unwrap_or_else(|e| e.into_inner());
+        let frames = match cache.entry(dpi) {
+            std::collections::hash_map::Entry::Occupied(e) => e.into_mut(),
+            std::collections::hash_map::Entry::Vacant(e) => e.insert(self.create(dpi)?),
+        };
+        match &self.0.image {
+            CursorImage::Animated { ani, .. } => {
+                let step = ani.steps[step % ani.steps.len()];
+                Ok((HCURSOR(frames[step.frame] as _), Some(step.duration)))
+            }
+            _ => Ok((HCURSOR(frames[0] as _), None)),
+        }
+    }
+
+    fn create(&self, dpi: u32) -> Result<Vec<isize>> {
+        let entries = match &self.0.image {
+            CursorImage::Rgba { image, hotspot } => {
+                let scale = |v: u32| (v as f32 * dpi as f32 / 96.0).round() as u32;
+                let size = image.size();
+                let image = image.resize(PhysicalSize::new(scale(size.width), scale(size.height)));
+                let size = image.size();
+                let hotspot = PhysicalPosition::new(
+                    scale(hotspot.x).min(size.width - 1),
+                    scale(hotspot.y).min(size.height - 1),
+                );
+                return Ok(vec![
+                    create_icon_from_rgba(&image, Some(hotspot))?.0 as isize,
+                ]);
+            }
+            CursorImage::Static { dir, .. } => vec![dir],
+            CursorImage::Animated { ani, .. } => ani.frames.iter().collect(),
+        };
+        let size = unsafe { GetSystemMetricsForDpi(SM_CXCURSOR, dpi) } as u32;
+        let mut frames = Vec::with_capacity(entries.len());
+        for dir in entries {
+            let entry = dir.best_entry(size).unwrap();
+            match create_cursor_from_entry(entry) {
+                Ok(handle) => frames.push(handle.0 as isize),
+                Err(e) => {
+                    for handle in frames {
+                        unsafe {
+                            DestroyCursor(HCURSOR(handle as _)).ok();
+                        }
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        Ok(frames)
+    }
+}
+
+impl PartialEq for CustomCursor {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for CustomCursor {}
+
+/// The source of `CustomCursor` which is serialized.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+enum CustomCursorSource {
+    Rgba {
+        width: u32,
+        height: u32,
+        hotspot: PhysicalPosition<u32>,
+        pixels: Vec<u8>,
+    },
+    File(PathBuf),
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for CustomCursor {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> std::result::Result<S::Ok, S::Error> {
+        let source = match &self.0.image {
+            CursorImage::Rgba { image, hotspot } => CustomCursorSource::Rgba {
+                width: image.size().width,
+                height: image.size().height,
+                hotspot: *hotspot,
+                pixels: image.pixels().to_vec(),
+            },
+            CursorImage::Static { path, .. } | CursorImage::Animated { path, .. } => {
+                CustomCursorSource::File(path.clone())
+            }
+        };
+        serde::Serialize::serialize(&source, s)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for CustomCursor {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> std::result::Result<Self, D::Error> {
+        let image = match <CustomCursorSource as serde::Deserialize>::deserialize(d)? {
+            CustomCursorSource::Rgba {
+                width,
+                height,
+                hotspot,
+                pixels,
+            } => CursorImage::rgba(width, height, hotspot, pixels),
+            CustomCursorSource::File(path) => CursorImage::load(path),
+        };
+        Ok(Self::new(image.map_err(serde::de::Error::custom)?))
+    }
+}
+
+/// Creates an icon, or a cursor if `hotspot` is `Some`.
+fn create_icon_from_rgba(
+    image: &image::RgbaImage,
+    hotspot: Option<PhysicalPosition<u32>>,
+) -> Result<HICON> {
+    unsafe {
+        let size = image.size();
+        let (width, height) = (size.width as i32, size.height as i32);
+        let bgra = image.to_bgra();
+        let mask = vec![0u8; size.width.div_ceil(16) as usize * 2 * size.height as usize];
+        let color = CreateBitmap(width, height, 1, 32, Some(bgra.as_ptr() as _));
+        let mask = CreateBitmap(width, height, 1, 1, Some(mask.as_ptr() as _));
+        let info = ICONINFO {
+            fIcon: hotspot.is_none().into(),
+            xHotspot: hotspot.map_or(0, |p| p.x),
+            yHotspot: hotspot.map_or(0, |p| p.y),
+            hbmMask: mask,
+            hbmColor: color,
+        };
+        let icon = CreateIconIndirect(&info);
+        let _ = DeleteObject(color.into());
+        let _ = DeleteObject(mask.into());
+        Ok(icon?)
+    }
+}
+
+/// Creates a cursor from DIB or PNG bytes of a CUR entry, which are preceded by the hotspot.
+fn create_cursor_from_entry(entry: &image::IconEntry) -> Result<HCURSOR> {
+    let mut bits = Vec::with_capacity(entry.data.len() + 4);
+    bits.extend((entry.hotspot.x as u16).to_le_bytes());
+    bits.extend((entry.hotspot.y as u16).to_le_bytes());
+    bits.extend(&entry.data);
+    unsafe {
+        let icon = CreateIconFromResourceEx(&bits, false, 0x00030000, 0, 0, LR_DEFAULTCOLOR)?;
+        Ok(HCURSOR(icon.0))
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    fn round_trip(cursor: &Cursor) -> String {
+        let json = serde_json::to_string(cursor).unwrap();
+        let cursor: Cursor = serde_json::from_str(&json).unwrap();
+        assert!(matches!(cursor, Cursor::Custom(_)));
+        assert_eq!(serde_json::to_string(&cursor).unwrap(), json);
+        json
+    }
+
+    #[test]
+    fn serialize_custom_icon() {
+        let pixels = (0..2 * 3 * 4).collect::<Vec<u8>>();
+        let json = serde_json::to_string(&Icon::from_rgba(2, 3, &pixels).unwrap()).unwrap();
+        let icon: Icon = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&icon).unwrap(), json);
+
+        let data = include_bytes!("../tests/data/arrow.cur");
+        let json = serde_json::to_string(&Icon::from_ico_bytes(data).unwrap()).unwrap();
+        let icon: Icon = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&icon).unwrap(), json);
+        let json = json.replacen("[0,", "[1,", 1);
+        assert!(serde_json::from_str::<Icon>(&json).is_err());
+    }
+
+    #[test]
+    fn serialize_custom_cursor() {
+        let pixels = (0..2 * 3 * 4).collect::<Vec<u8>>();
+        let cursor = Cursor::from_rgba(2, 3, PhysicalPosition::new(1, 2), &pixels).unwrap();
+        round_trip(&cursor);
+
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/arrow.cur");
+        let json = round_trip(&Cursor::from_file(path).unwrap());
+        assert!(json.contains("arrow.cur"));
+        let missing = json.replace("arrow.cur", "missing.cur");
+        assert!(serde_json::from_str::<Cursor>(&missing).is_err());
+    }
+}
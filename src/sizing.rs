@@ -0,0 +1,294 @@
+//! Constraints of window sizes.
+//!
+//! [`SizeConstraints`] limits the client area of a window with the minimum and maximum sizes,
+//! an aspect ratio and resize increments. Windows apply them to `WM_GETMINMAXINFO` and
+//! `WM_SIZING`.
+//!
+//! ```
+//! use wiard::sizing::*;
+//! use wiard::{PhysicalSize, ResizingEdge};
+//!
+//! let constraints = SizeConstraints::new()
+//!     .min(PhysicalSize::new(100, 100))
+//!     .increments(PhysicalSize::new(8, 16), PhysicalSize::new(4, 4));
+//! let size = constraints.constrain(PhysicalSize::new(90, 135), ResizingEdge::BottomRight, 96);
+//! assert_eq!(size, PhysicalSize::new(100, 132));
+//! ```
+
+use crate::*;
+
+/// A size in logical or physical pixels.
+///
+/// Logical sizes are converted for the DPI of the window whenever constraints are applied.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DpiSize {
+    Logical(LogicalSize<u32>),
+    Physical(PhysicalSize<u32>),
+}
+
+impl DpiSize {
+    #[inline]
+    pub fn to_physical(&self, dpi: u32) -> PhysicalSize<u32> {
+        match self {
+            Self::Logical(size) => size.to_physical(dpi),
+            Self::Physical(size) => *size,
+        }
+    }
+}
+
+impl From<LogicalSize<u32>> for DpiSize {
+    #[inline]
+    fn from(value: LogicalSize<u32>) -> Self {
+        Self::Logical(value)
+    }
+}
+
+impl From<PhysicalSize<u32>> for DpiSize {
+    #[inline]
+    fn from(value: PhysicalSize<u32>) -> Self {
+        Self::Physical(value)
+    }
+}
+
+/// Constraints of the client area of a window.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SizeConstraints {
+    pub min: Option<DpiSize>,
+    pub max: Option<DpiSize>,
+    /// The ratio of the width to the height like `(16, 9)`.
+    pub aspect_ratio: Option<(u32, u32)>,
+    /// The steps of the size counted from `base`.
+    pub increments: Option<DpiSize>,
+    /// The size which is not a multiple of `increments`, like paddings around terminal cells.
+    pub base: Option<DpiSize>,
+}
+
+fn snap(value: u32, base: u32, step: u32, min: u32) -> u32 {
+    if step == 0 || value <= base {
+        return value;
+    }
+    let mut value = base + (value - base) / step * step;
+    if value < min {
+        value += (min - value).div_ceil(step) * step;
+    }
+    value
+}
+
+fn scale(value: u32, num: u32, denom: u32) -> u32 {
+    ((value as u64 * num as u64 + denom as u64 / 2) / denom as u64) as u32
+}
+
+impl SizeConstraints {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn min(mut self, size: impl Into<DpiSize>) -> Self {
+        self.min = Some(size.into());
+        self
+    }
+
+    #[inline]
+    pub fn max(mut self, size: impl Into<DpiSize>) -> Self {
+        self.max = Some(size.into());
+        self
+    }
+
+    #[inline]
+    pub fn aspect_ratio(mut self, width: u32, height: u32) -> Self {
+        self.aspect_ratio = Some((width, height));
+        self
+    }
+
+    #[inline]
+    pub fn increments(mut self, step: impl Into<DpiSize>, base: impl Into<DpiSize>) -> Self {
+        self.increments = Some(step.into());
+        self.base = Some(base.into());
+        self
+    }
+
+    /// Checks if no constraints are set.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Returns the minimum and the maximum sizes at `dpi`.
+    pub fn min_max(&self, dpi: u32) -> (PhysicalSize<u32>, PhysicalSize<u32>) {
+        let min = self
+            .min
+            .map_or(PhysicalSize::new(0, 0), |size| size.to_physical(dpi));
+        let max = self
+            .max
+            .map_or(PhysicalSize::new(u32::MAX, u32::MAX), |size| {
+                size.to_physical(dpi)
+            });
+        let max = PhysicalSize::new(max.width.max(min.width), max.height.max(min.height));
+        (min, max)
+    }
+
+    /// Constrains `size` of the client area resized from `edge` at `dpi`.
+    ///
+    /// The size is clamped by `min` and `max`, and snapped down to `increments`. Then the side
+    /// which is not dragged follows `aspect_ratio`; corners keep the width.
+    pub fn constrain(
+        &self,
+        size: PhysicalSize<u32>,
+        edge: ResizingEdge,
+        dpi: u32,
+    ) -> PhysicalSize<u32> {
+        let (min, max) = self.min_max(dpi);
+        let clamp_w = |w: u32| w.clamp(min.width, max.width);
+        let clamp_h = |h: u32| h.clamp(min.height, max.height);
+        let mut width = clamp_w(size.width);
+        let mut height = clamp_h(size.height);
+        if let Some(step) = self.increments.map(|step| step.to_physical(dpi)) {
+            let base = self
+                .base
+                .map_or(PhysicalSize::new(0, 0), |base| base.to_physical(dpi));
+            width = snap(width, base.width, step.width, min.width);
+            height = snap(height, base.height, step.height, min.height);
+        }
+        if let Some((rw, rh)) = self.aspect_ratio.filter(|&(w, h)| w > 0 && h > 0) {
+            if matches!(edge, ResizingEdge::Top | ResizingEdge::Bottom) {
+                width = scale(height, rw, rh);
+                if clamp_w(width) != width {
+                    width = clamp_w(width);
+                    height = clamp_h(scale(width, rh, rw));
+                }
+            } else {
+                height = scale(width, rh, rw);
+                if clamp_h(height) != height {
+                    height = clamp_h(height);
+                    width = clamp_w(scale(height, rw, rh));
+                }
+            }
+        }
+        PhysicalSize::new(width, height)
+    }
+
+    /// Adjusts `rect` of the window for `WM_SIZING`.
+    ///
+    /// `frame` is the difference between the window size and the client size. The dragged
+    /// sides move, and the right or bottom side moves for a side changed by `aspect_ratio`.
+    pub fn adjust_rect(
+        &self,
+        rect: PhysicalRect<i32>,
+        edge: ResizingEdge,
+        frame: PhysicalSize<i32>,
+        dpi: u32,
+    ) -> PhysicalRect<i32> {
+        let client = PhysicalSize::new(
+            (rect.right - rect.left - frame.width).max(0) as u32,
+            (rect.bottom - rect.top - frame.height).max(0) as u32,
+        );
+        let size = self.constrain(client, edge, dpi);
+        let width = size.width as i32 + frame.width;
+        let height = size.height as i32 + frame.height;
+        let mut rect = rect;
+        match edge {
+            ResizingEdge::Left | ResizingEdge::TopLeft | ResizingEdge::BottomLeft => {
+                rect.left = rect.right - width;
+            }
+            _ => rect.right = rect.left + width,
+        }
+        match edge {
+            ResizingEdge::Top | ResizingEdge::TopLeft | ResizingEdge::TopRight => {
+                rect.top = rect.bottom - height;
+            }
+            _ => rect.bottom = rect.top + height,
+        }
+        rect
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn size(width: u32, height: u32) -> PhysicalSize<u32> {
+        PhysicalSize::new(width, height)
+    }
+
+    #[test]
+    fn min_max_with_dpi() {
+        let c = SizeConstraints::new()
+            .min(LogicalSize::new(100, 50))
+            .max(PhysicalSize::new(300, 300));
+        let edge = ResizingEdge::BottomRight;
+        assert_eq!(c.constrain(size(10, 10), edge, 96), size(100, 50));
+        assert_eq!(c.constrain(size(10, 10), edge, 192), size(200, 100));
+        assert_eq!(c.constrain(size(500, 200), edge, 96), size(300, 200));
+        assert_eq!(c.constrain(size(500, 500), edge, 384), size(400, 300));
+    }
+
+    #[test]
+    fn aspect_ratio() {
+        let c = SizeConstraints::new()
+            .aspect_ratio(16, 9)
+            .max(PhysicalSize::new(1600, 800));
+        assert_eq!(
+            c.constrain(size(320, 100), ResizingEdge::Right, 96),
+            size(320, 180)
+        );
+        assert_eq!(
+            c.constrain(size(320, 90), ResizingEdge::Bottom, 96),
+            size(160, 90)
+        );
+        assert_eq!(
+            c.constrain(size(1600, 100), ResizingEdge::TopLeft, 96),
+            size(1422, 800)
+        );
+    }
+
+    #[test]
+    fn increments() {
+        let c = SizeConstraints::new()
+            .min(PhysicalSize::new(20, 0))
+            .increments(PhysicalSize::new(10, 20), PhysicalSize::new(5, 5));
+        let edge = ResizingEdge::BottomRight;
+        assert_eq!(c.constrain(size(59, 64), edge, 96), size(55, 45));
+        assert_eq!(c.constrain(size(3, 3), edge, 96), size(25, 3));
+        let c = SizeConstraints::new().increments(LogicalSize::new(10, 20), LogicalSize::new(5, 5));
+        assert_eq!(c.constrain(size(59, 64), edge, 192), size(50, 50));
+    }
+
+    #[test]
+    fn adjust_rect() {
+        let c =
+            SizeConstraints::new().increments(PhysicalSize::new(10, 10), PhysicalSize::new(0, 0));
+        let frame = PhysicalSize::new(16, 39);
+        let rect = PhysicalRect::new(100, 100, 100 + 16 + 55, 100 + 39 + 55);
+        let adjust = |edge| c.adjust_rect(rect, edge, frame, 96);
+        assert_eq!(
+            adjust(ResizingEdge::BottomRight),
+            PhysicalRect::new(100, 100, 166, 189)
+        );
+        assert_eq!(
+            adjust(ResizingEdge::TopLeft),
+            PhysicalRect::new(105, 105, 171, 194)
+        );
+        assert_eq!(
+            adjust(ResizingEdge::Left),
+            PhysicalRect::new(105, 100, 171, 189)
+        );
+        assert_eq!(
+            adjust(ResizingEdge::TopRight),
+            PhysicalRect::new(100, 105, 166, 194)
+        );
+        let c = SizeConstraints::new().aspect_ratio(2, 1);
+        let rect = PhysicalRect::new(0, 0, 16 + 100, 39 + 100);
+        assert_eq!(
+            c.adjust_rect(rect, ResizingEdge::Top, frame, 96),
+            PhysicalRect::new(0, 0, 16 + 200, 39 + 100)
+        );
+        assert_eq!(
+            c.adjust_rect(rect, ResizingEdge::Left, frame, 96),
+            PhysicalRect::new(0, 0, 16 + 100, 39 + 50)
+        );
+    }
+}
@@ -0,0 +1,92 @@
+//! Text input decoding.
+//!
+//! `WM_CHAR` carries a UTF-16 code unit, so a character out of the BMP like an emoji arrives as
+//! two messages of a surrogate pair. [`Utf16Decoder`] reassembles them into `char`.
+//!
+//! ```
+//! use wiard::text::Utf16Decoder;
+//!
+//! let mut decoder = Utf16Decoder::new();
+//! assert!(decoder.push(0xd83d).next().is_none());
+//! assert_eq!(decoder.push(0xde00).collect::<String>(), "\u{1f600}");
+//! ```
+
+/// A UTF-16 decoder which receives a code unit at a time.
+///
+/// Unpaired surrogates are decoded as `char::REPLACEMENT_CHARACTER`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Utf16Decoder {
+    high: Option<u16>,
+}
+
+impl Utf16Decoder {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pushes a code unit, and returns decoded characters.
+    ///
+    /// This returns two characters at most when a high surrogate is followed by other than
+    /// a low surrogate.
+    pub fn push(&mut self, unit: u16) -> impl Iterator<Item = char> + use<> {
+        let (prev, c) = match (self.high.take(), unit) {
+            (Some(high), 0xdc00..=0xdfff) => {
+                let c = 0x10000 + (((high as u32) - 0xd800) << 10) + ((unit as u32) - 0xdc00);
+                (None, char::from_u32(c))
+            }
+            (high, 0xd800..=0xdbff) => {
+                self.high = Some(unit);
+                (high.map(|_| char::REPLACEMENT_CHARACTER), None)
+            }
+            (high, 0xdc00..=0xdfff) => (
+                high.map(|_| char::REPLACEMENT_CHARACTER),
+                Some(char::REPLACEMENT_CHARACTER),
+            ),
+            (high, unit) => (
+                high.map(|_| char::REPLACEMENT_CHARACTER),
+                char::from_u32(unit as u32),
+            ),
+        };
+        [prev, c].into_iter().flatten()
+    }
+
+    /// Checks if the decoder waits for a low surrogate.
+    #[inline]
+    pub fn is_pending(&self) -> bool {
+        self.high.is_some()
+    }
+
+    /// Clears the pending high surrogate, and returns `char::REPLACEMENT_CHARACTER` if it exists.
+    #[inline]
+    pub fn flush(&mut self) -> Option<char> {
+        self.high.take().map(|_| char::REPLACEMENT_CHARACTER)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(units: &[u16]) -> String {
+        let mut decoder = Utf16Decoder::new();
+        let mut s: String = units.iter().flat_map(|u| decoder.push(*u)).collect();
+        s.extend(decoder.flush());
+        s
+    }
+
+    #[test]
+    fn surrogate_pairs() {
+        let text = "a\u{1f600}b\u{20bb7}";
+        let units = text.encode_utf16().collect::<Vec<_>>();
+        assert_eq!(decode(&units), text);
+    }
+
+    #[test]
+    fn unpaired_surrogates() {
+        assert_eq!(decode(&[0xd83d, 0x61]), "\u{fffd}a");
+        assert_eq!(decode(&[0xde00, 0x61]), "\u{fffd}a");
+        assert_eq!(decode(&[0xd83d, 0xd83d, 0xde00]), "\u{fffd}\u{1f600}");
+        assert_eq!(decode(&[0x61, 0xd83d]), "a\u{fffd}");
+    }
+}
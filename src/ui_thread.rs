@@ -1,19 +1,26 @@
 use crate::*;
 
-use std::cell::RefCell;
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+#[cfg(windows)]
 use std::os::windows::prelude::*;
 use std::sync::{Mutex, OnceLock, mpsc};
+#[cfg(windows)]
 use windows::Win32::{
     Foundation::{HANDLE, LPARAM, WPARAM},
     System::Ole::{OleInitialize, OleUninitialize},
     System::Threading::GetThreadId,
     UI::HiDpi::*,
+    UI::Input::Pointer::EnableMouseInPointer,
     UI::WindowsAndMessaging::{
-        DispatchMessageW, GetMessageW, IsGUIThread, MSG, PostThreadMessageW, TranslateMessage,
+        DispatchMessageW, GetMessageW, IsGUIThread, MSG, PostQuitMessage, PostThreadMessageW,
+        TranslateMessage,
     },
 };
+#[cfg(windows)]
 use windows::core::BOOL;
 
+#[cfg(windows)]
 fn enable_dpi_awareness() {
     unsafe {
         if SetProcessDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2).is_ok() {
@@ -36,6 +43,71 @@ struct Task(Box<dyn FnOnce() + Send>);
 
 thread_local! {
     static FINISH_HANDLER: RefCell<Vec<Box<dyn FnOnce() + Send>>> = RefCell::new(vec![]);
+    static QUIT: Cell<Option<u32>> = const { Cell::new(None) };
+}
+
+/// Requests to finish the main loop of the headless backend like `PostQuitMessage`.
+#[cfg_attr(not(feature = "headless"), allow(dead_code))]
+pub(crate) fn post_quit_message(exit_code: u32) {
+    QUIT.set(Some(exit_code));
+}
+
+fn send_panic(e: Box<dyn Any + Send>) {
+    backend!(
+        Context::send_panic(e),
+        headless::context::Context::send_panic(e)
+    );
+}
+
+fn cleanup() {
+    backend!(Context::cleanup(), headless::context::Context::cleanup());
+}
+
+/// Runs the message loop until `WM_QUIT`. Returns the exit code, or a panic from a task.
+#[cfg(windows)]
+fn win32_main_loop(task_rx: &mpsc::Receiver<Task>) -> std::thread::Result<u32> {
+    unsafe {
+        let mut msg = MSG::default();
+        loop {
+            let ret = GetMessageW(&mut msg, None, 0, 0);
+            if ret == BOOL(0) || ret == BOOL(-1) {
+                return Ok(msg.wParam.0 as u32);
+            }
+            match msg.message {
+                WM_APP_POST_TASK => {
+                    std::panic::catch_unwind(|| {
+                        for task in task_rx.try_iter() {
+                            task.0();
+                        }
+                    })?;
+                }
+                _ => {
+                    if procedure::translate_accelerator(&msg) {
+                        continue;
+                    }
+                    let _ = TranslateMessage(&msg);
+                    DispatchMessageW(&msg);
+                    if let Some(e) = procedure::get_unwind() {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Runs tasks until `post_quit_message`. Returns the exit code, or a panic from a task.
+#[cfg(feature = "headless")]
+fn headless_main_loop(task_rx: &mpsc::Receiver<Task>) -> std::thread::Result<u32> {
+    loop {
+        if let Some(exit_code) = QUIT.take() {
+            return Ok(exit_code);
+        }
+        let Ok(task) = task_rx.recv() else {
+            return Ok(0);
+        };
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| task.0()))?;
+    }
 }
 
 struct Thread {
@@ -55,52 +127,42 @@ impl Thread {
         let (block_tx, block_rx) = mpsc::channel::<()>();
         let th = std::thread::Builder::new()
             .name("wiard_UiThread".into())
-            .spawn(move || unsafe {
+            .spawn(move || {
                 (builder.on_thread_start)();
-                let _ = IsGUIThread(true);
-                Context::init().unwrap();
+                backend!(
+                    unsafe {
+                        let _ = IsGUIThread(true);
+                        Context::init().unwrap();
+                    },
+                    {}
+                );
                 (builder.on_main_loop_start)();
                 block_tx.send(()).ok();
                 std::mem::drop(block_tx);
-                let mut msg = MSG::default();
-                let ret = loop {
-                    let ret = GetMessageW(&mut msg, None, 0, 0);
-                    if ret == BOOL(0) || ret == BOOL(-1) {
+                let ret = match backend!(win32_main_loop(&task_rx), headless_main_loop(&task_rx)) {
+                    Ok(exit_code) => {
                         let ret = std::panic::catch_unwind(|| {
                             (builder.on_main_loop_end)();
                         });
-                        if let Err(e) = ret {
-                            Context::send_panic(e);
-                            break 1;
-                        }
-                        let finish_handler = FINISH_HANDLER.take();
-                        for handler in finish_handler.into_iter() {
-                            handler();
-                        }
-                        Context::cleanup();
-                        break msg.wParam.0 as u32;
-                    }
-                    match msg.message {
-                        WM_APP_POST_TASK => {
-                            let ret = std::panic::catch_unwind(|| {
-                                for task in task_rx.try_iter() {
-                                    task.0();
+                        match ret {
+                            Ok(_) => {
+                                let finish_handler = FINISH_HANDLER.take();
+                                for handler in finish_handler.into_iter() {
+                                    handler();
                                 }
-                            });
-                            if let Err(e) = ret {
-                                Context::send_panic(e);
-                                break 1;
+                                cleanup();
+                                exit_code
                             }
-                        }
-                        _ => {
-                            let _ = TranslateMessage(&msg);
-                            DispatchMessageW(&msg);
-                            if let Some(e) = procedure::get_unwind() {
-                                Context::send_panic(e);
-                                break 1;
+                            Err(e) => {
+                                send_panic(e);
+                                1
                             }
                         }
                     }
+                    Err(e) => {
+                        send_panic(e);
+                        1
+                    }
                 };
                 (builder.on_thread_end)();
                 ret
@@ -113,6 +175,7 @@ impl Thread {
         }
     }
 
+    #[cfg(windows)]
     fn post_message(&self, msg: u32, wparam: WPARAM, lparam: LPARAM) {
         unsafe {
             if let Some(th) = self.th.as_ref() {
@@ -124,7 +187,12 @@ impl Thread {
 
     fn send_task(&self, f: impl FnOnce() + Send + 'static) {
         self.task_tx.send(Task(Box::new(f))).ok();
-        self.post_message(WM_APP_POST_TASK, WPARAM(0), LPARAM(0));
+        backend!(
+            {
+                self.post_message(WM_APP_POST_TASK, WPARAM(0), LPARAM(0));
+            },
+            {}
+        );
     }
 }
 
@@ -135,6 +203,7 @@ pub struct Builder<Ts = (), Ms = (), Me = (), Te = ()> {
     on_main_loop_start: Ms,
     on_main_loop_end: Me,
     on_thread_end: Te,
+    mouse_in_pointer: bool,
 }
 
 impl Builder<(), (), (), ()> {
@@ -145,14 +214,25 @@ impl Builder<(), (), (), ()> {
         impl FnOnce() + Send + 'static,
     > {
         Builder {
-            on_thread_start: || unsafe {
-                OleInitialize(None).ok();
+            on_thread_start: || {
+                backend!(
+                    unsafe {
+                        OleInitialize(None).ok();
+                    },
+                    {}
+                );
             },
             on_main_loop_start: || {},
             on_main_loop_end: || {},
-            on_thread_end: || unsafe {
-                OleUninitialize();
+            on_thread_end: || {
+                backend!(
+                    unsafe {
+                        OleUninitialize();
+                    },
+                    {}
+                );
             },
+            mouse_in_pointer: false,
         }
     }
 }
@@ -174,6 +254,7 @@ where
             on_main_loop_start: self.on_main_loop_start,
             on_main_loop_end: self.on_main_loop_end,
             on_thread_end: self.on_thread_end,
+            mouse_in_pointer: self.mouse_in_pointer,
         }
     }
 
@@ -187,6 +268,7 @@ where
             on_main_loop_start: f,
             on_main_loop_end: self.on_main_loop_end,
             on_thread_end: self.on_thread_end,
+            mouse_in_pointer: self.mouse_in_pointer,
         }
     }
 
@@ -200,6 +282,7 @@ where
             on_main_loop_start: self.on_main_loop_start,
             on_main_loop_end: f,
             on_thread_end: self.on_thread_end,
+            mouse_in_pointer: self.mouse_in_pointer,
         }
     }
 
@@ -213,13 +296,34 @@ where
             on_main_loop_start: self.on_main_loop_start,
             on_main_loop_end: self.on_main_loop_end,
             on_thread_end: f,
+            mouse_in_pointer: self.mouse_in_pointer,
         }
     }
 
+    /// Receives mouse inputs as `WM_POINTER*` messages by calling `EnableMouseInPointer`.
+    ///
+    /// This affects the whole process and cannot be reverted. Windows which enable
+    /// `WindowBuilder::pointer_input` receive mouse inputs as `Event::Pointer` only with this.
+    /// The default is `false`. The simulated backend ignores this.
+    pub fn mouse_in_pointer(mut self, flag: bool) -> Self {
+        self.mouse_in_pointer = flag;
+        self
+    }
+
     /// Initializes UI thread.
     pub fn init(self) {
         THREAD.get_or_init(|| {
-            enable_dpi_awareness();
+            backend!(
+                {
+                    enable_dpi_awareness();
+                    if self.mouse_in_pointer {
+                        unsafe {
+                            EnableMouseInPointer(true).ok();
+                        }
+                    }
+                },
+                {}
+            );
             Mutex::new(Thread::new(self))
         });
     }
@@ -239,6 +343,7 @@ impl UiThread {
     }
 
     /// Start to build UI thread.
+    #[allow(clippy::new_ret_no_self)]
     #[inline]
     pub fn new() -> Builder<
         impl FnOnce() + Send + 'static,
@@ -284,7 +389,10 @@ impl UiThread {
     ///
     #[inline]
     pub fn set_receiver_for_panic(rx: &impl IsReceiver) {
-        Context::set_panic_receiver(rx)
+        backend!(
+            Context::set_panic_receiver(rx),
+            headless::context::Context::set_panic_receiver(rx)
+        )
     }
 
     #[inline]
@@ -318,3 +426,16 @@ impl UiThread {
             .map(|th| th.thread().id())
     }
 }
+
+/// Shutdown UI thread.
+#[inline]
+pub fn shutdown() {
+    UiThread::send_task(|| {
+        backend!(
+            unsafe {
+                PostQuitMessage(0);
+            },
+            post_quit_message(0)
+        );
+    });
+}
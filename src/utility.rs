@@ -133,6 +133,11 @@ impl<F> Symbol<F> {
             _f: std::marker::PhantomData,
         }
     }
+
+    /// Checks if the function was not found. A null symbol must not be called.
+    pub fn is_null(&self) -> bool {
+        self.ptr.is_none()
+    }
 }
 
 impl<F> std::ops::Deref for Symbol<F> {
@@ -169,10 +174,4 @@ impl Drop for Library {
 unsafe impl Send for Library {}
 unsafe impl Sync for Library {}
 
-/// Shutdown UI thread.
-#[inline]
-pub fn shutdown() {
-    UiThread::send_task(|| unsafe {
-        PostQuitMessage(0);
-    });
-}
\ No newline at end of file
+pub use crate::ui_thread::shutdown;
\ No newline at end of file
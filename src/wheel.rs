@@ -0,0 +1,386 @@
+//! Normalization of mouse wheel distances.
+//!
+//! `WM_MOUSEWHEEL` and `WM_MOUSEHWHEEL` report a distance in multiples of [`WHEEL_DELTA`] for
+//! notched wheels, and in fractions of it for precision touchpads. [`normalize`] converts a
+//! distance to lines or pages with [`ScrollSettings`], and [`WheelAccumulator`] keeps the
+//! remainder which does not reach a whole line or page yet.
+//!
+//! ```
+//! use std::time::Duration;
+//! use wiard::MouseWheelAxis;
+//! use wiard::wheel::*;
+//!
+//! let settings = ScrollSettings { lines: 3, chars: 3 };
+//! assert_eq!(normalize(MouseWheelAxis::Vertical, 120, &settings), ScrollDelta::Lines(3.0));
+//!
+//! let mut acc = WheelAccumulator::new();
+//! let steps = acc.process(MouseWheelAxis::Vertical, 30, Duration::ZERO, &settings);
+//! assert_eq!(steps[0].steps, 0);
+//! let steps = acc.process(MouseWheelAxis::Vertical, 30, Duration::from_millis(10), &settings);
+//! assert_eq!(steps[0].steps, 1);
+//! ```
+
+use crate::*;
+use std::time::Duration;
+#[cfg(windows)]
+use windows::Win32::UI::WindowsAndMessaging::{
+    SPI_GETWHEELSCROLLCHARS, SPI_GETWHEELSCROLLLINES, SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS,
+    SystemParametersInfoW,
+};
+
+/// `ScrollSettings::lines` which scrolls a page per a wheel notch.
+pub const WHEEL_PAGESCROLL: u32 = u32::MAX;
+
+/// The interval of wheel events after which a precise scroll ends.
+pub const SCROLL_END_TIMEOUT: Duration = Duration::from_millis(150);
+
+/// The number of decreasing distances after which a precise scroll is regarded as momentum.
+const MOMENTUM_EVENTS: u32 = 3;
+
+/// The amount of scrolling per a wheel notch.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScrollSettings {
+    /// Lines per a vertical notch (`SPI_GETWHEELSCROLLLINES`), or `WHEEL_PAGESCROLL`.
+    pub lines: u32,
+    /// Characters per a horizontal notch (`SPI_GETWHEELSCROLLCHARS`).
+    pub chars: u32,
+}
+
+impl ScrollSettings {
+    /// Gets the settings of the system.
+    ///
+    /// Returns the default values when the settings cannot be retrieved or on the headless
+    /// backend.
+    #[inline]
+    pub fn system() -> Self {
+        backend!(Self::query_system(), Self::default())
+    }
+
+    #[cfg(windows)]
+    fn query_system() -> Self {
+        let get = |action, default: u32| unsafe {
+            let mut value = 0u32;
+            SystemParametersInfoW(
+                action,
+                0,
+                Some(&mut value as *mut u32 as *mut _),
+                SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS::default(),
+            )
+            .map(|_| value)
+            .unwrap_or(default)
+        };
+        let default = Self::default();
+        Self {
+            lines: get(SPI_GETWHEELSCROLLLINES, default.lines),
+            chars: get(SPI_GETWHEELSCROLLCHARS, default.chars),
+        }
+    }
+
+    /// Returns `lines` or `chars` for `axis`.
+    #[inline]
+    pub fn amount(&self, axis: MouseWheelAxis) -> u32 {
+        match axis {
+            MouseWheelAxis::Vertical => self.lines,
+            MouseWheelAxis::Horizontal => self.chars,
+        }
+    }
+}
+
+impl Default for ScrollSettings {
+    #[inline]
+    fn default() -> Self {
+        Self { lines: 3, chars: 3 }
+    }
+}
+
+/// A normalized wheel distance.
+///
+/// The sign is the same as the distance of `WM_MOUSEWHEEL` and `WM_MOUSEHWHEEL`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ScrollDelta {
+    Lines(f32),
+    Pages(f32),
+}
+
+impl ScrollDelta {
+    #[inline]
+    pub fn value(&self) -> f32 {
+        match *self {
+            Self::Lines(v) | Self::Pages(v) => v,
+        }
+    }
+
+    /// Converts to pixels with the height of a line and a page.
+    #[inline]
+    pub fn to_pixels(&self, line: f32, page: f32) -> f32 {
+        match *self {
+            Self::Lines(v) => v * line,
+            Self::Pages(v) => v * page,
+        }
+    }
+
+    #[inline]
+    fn with_value(&self, value: f32) -> Self {
+        match self {
+            Self::Lines(_) => Self::Lines(value),
+            Self::Pages(_) => Self::Pages(value),
+        }
+    }
+}
+
+impl Default for ScrollDelta {
+    #[inline]
+    fn default() -> Self {
+        Self::Lines(0.0)
+    }
+}
+
+/// The phase of a precise scroll.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ScrollPhase {
+    Begin,
+    Update,
+    /// Distances keep decreasing, as a touchpad does after fingers are lifted.
+    Momentum,
+    /// No wheel event came within `SCROLL_END_TIMEOUT`. The delta is zero.
+    End,
+}
+
+/// Converts a wheel distance to lines or pages.
+#[inline]
+pub fn normalize(axis: MouseWheelAxis, distance: i32, settings: &ScrollSettings) -> ScrollDelta {
+    let notches = distance as f32 / WHEEL_DELTA as f32;
+    match settings.amount(axis) {
+        WHEEL_PAGESCROLL => ScrollDelta::Pages(notches),
+        amount => ScrollDelta::Lines(notches * amount as f32),
+    }
+}
+
+/// A wheel event processed by [`WheelAccumulator`].
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct WheelStep {
+    pub axis: MouseWheelAxis,
+    pub delta: ScrollDelta,
+    /// Whole lines or pages which the remainder reached.
+    pub steps: i32,
+    /// `None` for notched wheels.
+    pub phase: Option<ScrollPhase>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct LastWheel {
+    axis: MouseWheelAxis,
+    time: Duration,
+    distance: i32,
+    delta: ScrollDelta,
+    decreasing: u32,
+    precise: bool,
+}
+
+/// Accumulates wheel distances per an axis.
+///
+/// A scroll is precise when a distance is not a multiple of `WHEEL_DELTA`. The remainder is
+/// discarded when the direction changes.
+#[derive(Clone, Debug, Default)]
+pub struct WheelAccumulator {
+    remainder: [i32; 2],
+    last: Option<LastWheel>,
+}
+
+impl WheelAccumulator {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the remainder in lines or pages.
+    #[inline]
+    pub fn remainder(&self, axis: MouseWheelAxis) -> f32 {
+        self.remainder[axis as usize] as f32 / WHEEL_DELTA as f32
+    }
+
+    /// Checks if `poll` may end a precise scroll.
+    #[inline]
+    pub fn needs_poll(&self) -> bool {
+        self.last.is_some_and(|last| last.precise)
+    }
+
+    /// Processes a wheel distance.
+    ///
+    /// Returns `End` of the previous precise scroll first when the axis changed or it timed out.
+    pub fn process(
+        &mut self,
+        axis: MouseWheelAxis,
+        distance: i32,
+        time: Duration,
+        settings: &ScrollSettings,
+    ) -> Vec<WheelStep> {
+        let mut steps = vec![];
+        let last = self.last.filter(|last| {
+            last.axis == axis && time.saturating_sub(last.time) < SCROLL_END_TIMEOUT
+        });
+        if last.is_none()
+            && let Some(end) = self.end()
+        {
+            steps.push(end);
+        }
+        let delta = normalize(axis, distance, settings);
+        let amount = match settings.amount(axis) {
+            WHEEL_PAGESCROLL => 1,
+            amount => amount as i32,
+        };
+        let remainder = &mut self.remainder[axis as usize];
+        if remainder.signum() * distance.signum() < 0 {
+            *remainder = 0;
+        }
+        *remainder = remainder.saturating_add(distance.saturating_mul(amount));
+        let whole = *remainder / WHEEL_DELTA;
+        *remainder -= whole * WHEEL_DELTA;
+        let precise = distance % WHEEL_DELTA != 0 || last.is_some_and(|last| last.precise);
+        let decreasing = match last {
+            Some(last) if distance.abs() < last.distance.abs() => last.decreasing + 1,
+            Some(last) if distance.abs() == last.distance.abs() => last.decreasing,
+            _ => 0,
+        };
+        let phase = precise.then_some(match last {
+            None => ScrollPhase::Begin,
+            Some(_) if decreasing >= MOMENTUM_EVENTS => ScrollPhase::Momentum,
+            Some(_) => ScrollPhase::Update,
+        });
+        self.last = Some(LastWheel {
+            axis,
+            time,
+            distance,
+            delta,
+            decreasing,
+            precise,
+        });
+        steps.push(WheelStep {
+            axis,
+            delta,
+            steps: whole,
+            phase,
+        });
+        steps
+    }
+
+    /// Ends a precise scroll when no wheel event came within `SCROLL_END_TIMEOUT`.
+    pub fn poll(&mut self, time: Duration) -> Option<WheelStep> {
+        let last = self.last?;
+        if time.saturating_sub(last.time) < SCROLL_END_TIMEOUT {
+            return None;
+        }
+        self.end()
+    }
+
+    fn end(&mut self) -> Option<WheelStep> {
+        let last = self.last.take()?;
+        last.precise.then(|| WheelStep {
+            axis: last.axis,
+            delta: last.delta.with_value(0.0),
+            steps: 0,
+            phase: Some(ScrollPhase::End),
+        })
+    }
+}
+
+pub(crate) fn to_event(
+    step: &WheelStep,
+    distance: i32,
+    mouse_state: MouseState,
+) -> event::MouseWheel {
+    event::MouseWheel {
+        axis: step.axis,
+        distance: if step.phase == Some(ScrollPhase::End) {
+            0
+        } else {
+            distance
+        },
+        delta: step.delta,
+        steps: step.steps,
+        phase: step.phase,
+        mouse_state,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn normalize_lines_and_pages() {
+        let settings = ScrollSettings { lines: 3, chars: 5 };
+        assert_eq!(
+            normalize(MouseWheelAxis::Vertical, -240, &settings),
+            ScrollDelta::Lines(-6.0)
+        );
+        assert_eq!(
+            normalize(MouseWheelAxis::Horizontal, 60, &settings),
+            ScrollDelta::Lines(2.5)
+        );
+        let settings = ScrollSettings {
+            lines: WHEEL_PAGESCROLL,
+            chars: 3,
+        };
+        let delta = normalize(MouseWheelAxis::Vertical, 60, &settings);
+        assert_eq!(delta, ScrollDelta::Pages(0.5));
+        assert_eq!(delta.to_pixels(16.0, 400.0), 200.0);
+    }
+
+    #[test]
+    fn notched_wheel_has_no_phase() {
+        let settings = ScrollSettings::default();
+        let mut acc = WheelAccumulator::new();
+        let steps = acc.process(MouseWheelAxis::Vertical, 120, ms(0), &settings);
+        assert_eq!(steps.len(), 1);
+        assert_eq!(steps[0].steps, 3);
+        assert!(steps[0].phase.is_none());
+        assert!(!acc.needs_poll());
+        assert!(acc.poll(ms(1000)).is_none());
+    }
+
+    #[test]
+    fn remainder_and_direction() {
+        let settings = ScrollSettings { lines: 1, chars: 1 };
+        let mut acc = WheelAccumulator::new();
+        let v = MouseWheelAxis::Vertical;
+        assert_eq!(acc.process(v, 100, ms(0), &settings)[0].steps, 0);
+        assert_eq!(acc.process(v, 100, ms(10), &settings)[0].steps, 1);
+        assert_eq!(acc.remainder(v), 80.0 / 120.0);
+        assert_eq!(acc.process(v, -20, ms(20), &settings)[0].steps, 0);
+        assert_eq!(acc.remainder(v), -20.0 / 120.0);
+        assert_eq!(acc.remainder(MouseWheelAxis::Horizontal), 0.0);
+    }
+
+    #[test]
+    fn precise_phases() {
+        let settings = ScrollSettings::default();
+        let mut acc = WheelAccumulator::new();
+        let v = MouseWheelAxis::Vertical;
+        let phases = [40, 60, 50, 30, 20, 10]
+            .into_iter()
+            .enumerate()
+            .flat_map(|(i, d)| acc.process(v, d, ms(i as u64 * 10), &settings))
+            .map(|step| step.phase.unwrap())
+            .collect::<Vec<_>>();
+        use ScrollPhase::*;
+        assert_eq!(phases, [Begin, Update, Update, Update, Momentum, Momentum]);
+        assert!(acc.poll(ms(100)).is_none());
+        let end = acc.poll(ms(300)).unwrap();
+        assert_eq!(end.phase, Some(End));
+        assert_eq!(end.delta, ScrollDelta::Lines(0.0));
+        acc.process(v, 40, ms(400), &settings);
+        let steps = acc.process(MouseWheelAxis::Horizontal, 40, ms(410), &settings);
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0].axis, v);
+        assert_eq!(steps[0].phase, Some(End));
+        assert_eq!(steps[1].phase, Some(Begin));
+    }
+}
@@ -1,275 +1,135 @@
+#[cfg(windows)]
+mod win32;
+
+#[cfg(windows)]
 use crate::drag_drop::DropTarget;
 use crate::*;
-use std::any::Any;
-use std::sync::atomic::{self, AtomicU64};
 use tokio::sync::oneshot;
-use windows::Win32::System::Ole::{IDropTarget, RegisterDragDrop};
+#[cfg(windows)]
 use windows::Win32::{
-    Foundation::{HINSTANCE, HWND, LPARAM, POINT, RECT, WPARAM},
-    Graphics::Dwm::*,
-    Graphics::Gdi::{GetStockObject, HBRUSH, ScreenToClient, WHITE_BRUSH},
-    System::LibraryLoader::GetModuleHandleW,
-    UI::HiDpi::GetDpiForWindow,
-    UI::Input::KeyboardAndMouse::SetFocus,
-    UI::WindowsAndMessaging::*,
+    Foundation::{HWND, LPARAM, WPARAM},
+    System::Ole::IDropTarget,
+    UI::WindowsAndMessaging::{
+        CW_USEDEFAULT, GWLP_HINSTANCE, GetWindowLongPtrW, WINDOW_EX_STYLE, WINDOW_STYLE, WS_CHILD,
+    },
 };
-use windows::core::{BOOL, HSTRING, PCWSTR};
-
-const WINDOW_CLASS_NAME: PCWSTR = windows::core::w!("wiard_window_class");
-
-pub(crate) fn register_class() {
-    unsafe {
-        let wc = WNDCLASSEXW {
-            cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
-            style: CS_VREDRAW | CS_HREDRAW,
-            lpfnWndProc: Some(procedure::window_proc),
-            hInstance: HINSTANCE(GetModuleHandleW(None).unwrap().0),
-            lpszClassName: WINDOW_CLASS_NAME,
-            hbrBackground: HBRUSH(GetStockObject(WHITE_BRUSH).0),
-            hCursor: LoadCursorW(None, IDC_ARROW).unwrap(),
-            ..Default::default()
-        };
-        if RegisterClassExW(&wc) == 0 {
-            panic!("RegisterClassExW failed");
-        }
-    }
-}
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
-pub struct WindowHandle(HWND);
+#[cfg(windows)]
+pub(crate) use win32::Context;
 
-impl WindowHandle {
-    pub(crate) fn new(hwnd: HWND) -> Self {
-        Self(hwnd)
-    }
+#[cfg(windows)]
+type DefaultStyle = WindowStyle;
+#[cfg(not(windows))]
+type DefaultStyle = ();
 
-    #[inline]
-    pub fn as_hwnd(&self) -> HWND {
-        self.0
-    }
-}
+#[cfg(windows)]
+type DefaultDropTarget = fn(&WindowHandle) -> IDropTarget;
+#[cfg(not(windows))]
+type DefaultDropTarget = ();
 
-unsafe impl Send for WindowHandle {}
-unsafe impl Sync for WindowHandle {}
+/// Represents a handle of a window.
+///
+/// On the headless backend, this holds an ID of a simulated window instead of `HWND`.
+#[derive(Clone, Copy, Default)]
+pub struct WindowHandle(
+    #[cfg(windows)] HWND,
+    #[cfg(not(windows))] u64,
+    /// `true` if the handle is created by `Replayer` and does not refer to any live window.
+    bool,
+);
 
-impl From<WindowHandle> for HWND {
-    fn from(value: WindowHandle) -> Self {
-        value.as_hwnd()
+impl WindowHandle {
+    #[cfg(windows)]
+    pub(crate) fn new(hwnd: HWND) -> Self {
+        Self(hwnd, false)
     }
-}
 
-impl std::hash::Hash for WindowHandle {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        state.write_usize(self.0.0.addr());
+    #[cfg(windows)]
+    pub(crate) fn from_raw_id(id: u64) -> Self {
+        Self(HWND(id as usize as *mut _), false)
     }
-}
 
-impl IsWindow for WindowHandle {
-    fn window_handle(&self) -> WindowHandle {
-        self.clone()
+    #[cfg(not(windows))]
+    pub(crate) fn from_raw_id(id: u64) -> Self {
+        Self(id, false)
     }
-}
-
-/// `EventReceive` and `AsyncEventReceiver` are implement this trait.
-pub trait IsReceiver {
-    fn id(&self) -> u64;
-}
-
-/// `Window` and `AsyncWindow` are implement this trait.
-pub trait IsWindow {
-    fn window_handle(&self) -> WindowHandle;
-}
 
-/// Represents `Window` or `InnerWindow`
-#[derive(Clone, PartialEq, Eq, Debug)]
-pub enum WindowKind {
-    Window(Window),
-    InnerWindow(InnerWindow),
-}
-
-impl IsWindow for WindowKind {
-    fn window_handle(&self) -> WindowHandle {
-        match self {
-            Self::Window(w) => w.handle,
-            Self::InnerWindow(w) => w.handle,
-        }
+    /// Creates an inert handle of a replayed window. Methods of the window do nothing.
+    pub(crate) fn replayed(id: u64) -> Self {
+        Self(Self::from_raw_id(id).0, true)
     }
-}
-
-/// Represents `AsyncWindow` or `AsyncInnerWindow`
-#[derive(Clone, PartialEq, Eq, Debug)]
-pub enum AsyncWindowKind {
-    Window(AsyncWindow),
-    InnerWindow(AsyncInnerWindow),
-}
 
-impl IsWindow for AsyncWindowKind {
-    fn window_handle(&self) -> WindowHandle {
-        match self {
-            Self::Window(w) => w.handle,
-            Self::InnerWindow(w) => w.handle,
-        }
+    pub(crate) fn is_replayed(&self) -> bool {
+        self.1
     }
-}
-
-type Receiver<T> = tokio::sync::mpsc::UnboundedReceiver<T>;
-pub(crate) type Sender<T> = tokio::sync::mpsc::UnboundedSender<T>;
-
-pub(crate) enum RecvEventOrPanic {
-    Event(RecvEvent),
-    Panic(Box<dyn Any + Send>),
-}
-
-/// The type of receiving event from UI thread.
-pub type RecvEvent = (Event, WindowKind);
 
-/// The async version type of receiving event from UI thread.
-pub type AsyncRecvEvent = (Event, AsyncWindowKind);
-
-fn gen_id() -> u64 {
-    static ID: AtomicU64 = AtomicU64::new(0);
-    ID.fetch_add(1, atomic::Ordering::SeqCst)
-}
-
-/// This object which receives an event from UI thread.
-pub struct EventReceiver {
-    id: u64,
-    rx: Receiver<RecvEventOrPanic>,
-}
+    #[cfg(windows)]
+    pub(crate) fn raw_id(&self) -> u64 {
+        self.0.0 as usize as u64
+    }
 
-impl EventReceiver {
-    /// Creates a new event receiver.
-    #[inline]
-    #[allow(clippy::new_without_default)]
-    pub fn new() -> Self {
-        let id = gen_id();
-        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
-        Context::register_event_tx(id, tx);
-        Self { id, rx }
+    #[cfg(not(windows))]
+    pub(crate) fn raw_id(&self) -> u64 {
+        self.0
     }
 
-    /// Attempts to wait for an event from UI thread.
-    ///
-    /// When UI thread shutdowns, this function returns `None`.
-    ///
+    #[cfg(windows)]
     #[inline]
-    pub fn recv(&mut self) -> Option<RecvEvent> {
-        match self.rx.blocking_recv()? {
-            RecvEventOrPanic::Event(ret) => Some(ret),
-            RecvEventOrPanic::Panic(e) => std::panic::resume_unwind(e),
-        }
+    pub fn as_hwnd(&self) -> HWND {
+        self.0
     }
+}
 
-    /// Attempts to return a pending event from UI Thread.
-    ///
-    /// This function do not block.
-    /// When UI thread shutdowns, this function returns `Err(TryRecvError::Disconnected)`.
-    ///
-    #[inline]
-    pub fn try_recv(&mut self) -> TryRecvResult<RecvEvent> {
-        use tokio::sync::mpsc;
+#[cfg(windows)]
+unsafe impl Send for WindowHandle {}
+#[cfg(windows)]
+unsafe impl Sync for WindowHandle {}
 
-        match self.rx.try_recv() {
-            Ok(ret) => match ret {
-                RecvEventOrPanic::Event(re) => Ok(re),
-                RecvEventOrPanic::Panic(e) => std::panic::resume_unwind(e),
-            },
-            Err(mpsc::error::TryRecvError::Empty) => Err(TryRecvError::Empty),
-            Err(mpsc::error::TryRecvError::Disconnected) => Err(TryRecvError::Disconnected),
-        }
+/// Serializes the raw `HWND` value, which means nothing in another process.
+#[cfg(feature = "serde")]
+impl serde::Serialize for WindowHandle {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> std::result::Result<S::Ok, S::Error> {
+        s.serialize_u64(self.raw_id())
     }
 }
 
-impl IsReceiver for EventReceiver {
-    #[inline]
-    fn id(&self) -> u64 {
-        self.id
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for WindowHandle {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> std::result::Result<Self, D::Error> {
+        <u64 as serde::Deserialize>::deserialize(d).map(Self::from_raw_id)
     }
 }
 
-/// This object which receives an event from UI thread.
-pub struct AsyncEventReceiver {
-    id: u64,
-    rx: Receiver<RecvEventOrPanic>,
+#[cfg(windows)]
+impl From<WindowHandle> for HWND {
+    fn from(value: WindowHandle) -> Self {
+        value.as_hwnd()
+    }
 }
 
-impl AsyncEventReceiver {
-    /// Creates a new event receiver.
-    #[inline]
-    #[allow(clippy::new_without_default)]
-    pub fn new() -> Self {
-        let id = gen_id();
-        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
-        Context::register_event_tx(id, tx);
-        Self { id, rx }
+impl std::fmt::Debug for WindowHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("WindowHandle").field(&self.0).finish()
     }
+}
 
-    /// Attempts to wait for an event from UI thread.
-    ///
-    /// When UI thread shutdowns, this function returns `None`.
-    ///
-    #[inline]
-    pub async fn recv(&mut self) -> Option<AsyncRecvEvent> {
-        let ret = {
-            match self.rx.recv().await? {
-                RecvEventOrPanic::Event(re) => re,
-                RecvEventOrPanic::Panic(e) => std::panic::resume_unwind(e),
-            }
-        };
-        match ret.1 {
-            WindowKind::Window(w) => Some((
-                ret.0,
-                AsyncWindowKind::Window(AsyncWindow { handle: w.handle }),
-            )),
-            WindowKind::InnerWindow(w) => Some((
-                ret.0,
-                AsyncWindowKind::InnerWindow(AsyncInnerWindow {
-                    parent: w.parent,
-                    handle: w.handle,
-                }),
-            )),
-        }
+impl PartialEq for WindowHandle {
+    fn eq(&self, other: &Self) -> bool {
+        self.raw_id() == other.raw_id()
     }
+}
 
-    /// Attempts to return a pending event from UI Thread.
-    ///
-    /// This function do not block.
-    /// When UI thread shutdowns, this function returns `Err(TryRecvError::Disconnected)`.
-    ///
-    #[inline]
-    pub fn try_recv(&mut self) -> TryRecvResult<AsyncRecvEvent> {
-        use tokio::sync::mpsc;
-
-        match self.rx.try_recv() {
-            Ok(ret) => {
-                let ret = match ret {
-                    RecvEventOrPanic::Event(re) => re,
-                    RecvEventOrPanic::Panic(e) => std::panic::resume_unwind(e),
-                };
-                match ret.1 {
-                    WindowKind::Window(w) => Ok((
-                        ret.0,
-                        AsyncWindowKind::Window(AsyncWindow { handle: w.handle }),
-                    )),
-                    WindowKind::InnerWindow(w) => Ok((
-                        ret.0,
-                        AsyncWindowKind::InnerWindow(AsyncInnerWindow {
-                            parent: w.parent,
-                            handle: w.handle,
-                        }),
-                    )),
-                }
-            }
-            Err(mpsc::error::TryRecvError::Empty) => Err(TryRecvError::Empty),
-            Err(mpsc::error::TryRecvError::Disconnected) => Err(TryRecvError::Disconnected),
-        }
+impl Eq for WindowHandle {}
+
+impl std::hash::Hash for WindowHandle {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        state.write_u64(self.raw_id());
     }
 }
 
-impl IsReceiver for AsyncEventReceiver {
-    #[inline]
-    fn id(&self) -> u64 {
-        self.id
+impl IsWindow for WindowHandle {
+    fn window_handle(&self) -> WindowHandle {
+        *self
     }
 }
 
@@ -279,23 +139,39 @@ pub struct WindowBuilder<
     Rx,
     Title = &'static str,
     Sz = LogicalSize<u32>,
-    Sty = WindowStyle,
-    Dt = fn(&WindowHandle) -> IDropTarget,
+    Sty = DefaultStyle,
+    Dt = DefaultDropTarget,
 > {
     event_rx: &'a Rx,
     title: Title,
     position: PhysicalPosition<i32>,
     inner_size: Sz,
+    #[cfg(feature = "headless")]
+    dpi: u32,
     style: Sty,
     visibility: bool,
     enable_ime: bool,
     visible_ime_candidate_window: bool,
     auto_close: bool,
     nc_hittest: bool,
+    size_constraints: SizeConstraints,
+    placement: Option<WindowPlacement>,
+    always_on_top: bool,
+    owned: bool,
+    modal: bool,
+    auto_capture: bool,
+    #[cfg(windows)]
     icon: Option<Icon>,
+    #[cfg(windows)]
     cursor: Cursor,
     parent: Option<WindowHandle>,
+    #[cfg(windows)]
     menu: Option<MenuBar>,
+    accelerators: Accelerators,
+    batch_text_input: bool,
+    pointer_input: bool,
+    gestures: bool,
+    raw_input: RawInputDevices,
     drop_target: Dt,
 }
 
@@ -307,19 +183,41 @@ impl<'a, Rx> WindowBuilder<'a, Rx> {
         Self {
             event_rx,
             title: "",
-            position: PhysicalPosition::new(CW_USEDEFAULT, CW_USEDEFAULT),
+            position: backend!(
+                PhysicalPosition::new(CW_USEDEFAULT, CW_USEDEFAULT),
+                PhysicalPosition::new(0, 0),
+            ),
             inner_size: LogicalSize::new(1024, 768),
-            style: WindowStyle::default(),
+            #[cfg(feature = "headless")]
+            dpi: DEFAULT_DPI,
+            style: DefaultStyle::default(),
             visibility: true,
             enable_ime: true,
             visible_ime_candidate_window: true,
             auto_close: true,
             nc_hittest: false,
+            size_constraints: SizeConstraints::new(),
+            placement: None,
+            always_on_top: false,
+            owned: false,
+            modal: false,
+            auto_capture: true,
+            #[cfg(windows)]
             icon: None,
+            #[cfg(windows)]
             cursor: Cursor::default(),
             parent: None,
+            #[cfg(windows)]
             menu: None,
+            accelerators: Accelerators::new(),
+            batch_text_input: false,
+            pointer_input: false,
+            gestures: false,
+            raw_input: RawInputDevices::new(),
+            #[cfg(windows)]
             drop_target: |window| DropTarget::new(window).into(),
+            #[cfg(not(windows))]
+            drop_target: (),
         }
     }
 }
@@ -335,16 +233,32 @@ impl<'a, Rx, Title, Sz, Sty, Dt> WindowBuilder<'a, Rx, Title, Sz, Sty, Dt> {
             title,
             position: self.position,
             inner_size: self.inner_size,
+            #[cfg(feature = "headless")]
+            dpi: self.dpi,
             style: self.style,
             visibility: self.visibility,
             enable_ime: self.enable_ime,
             visible_ime_candidate_window: self.visible_ime_candidate_window,
             auto_close: self.auto_close,
             nc_hittest: self.nc_hittest,
+            size_constraints: self.size_constraints,
+            placement: self.placement,
+            always_on_top: self.always_on_top,
+            owned: self.owned,
+            modal: self.modal,
+            auto_capture: self.auto_capture,
+            #[cfg(windows)]
             icon: self.icon,
+            #[cfg(windows)]
             cursor: self.cursor,
             parent: self.parent,
+            #[cfg(windows)]
             menu: self.menu,
+            accelerators: self.accelerators,
+            batch_text_input: self.batch_text_input,
+            pointer_input: self.pointer_input,
+            gestures: self.gestures,
+            raw_input: self.raw_input,
             drop_target: self.drop_target,
         }
     }
@@ -365,20 +279,47 @@ impl<'a, Rx, Title, Sz, Sty, Dt> WindowBuilder<'a, Rx, Title, Sz, Sty, Dt> {
             title: self.title,
             position: self.position,
             inner_size: size,
+            #[cfg(feature = "headless")]
+            dpi: self.dpi,
             style: self.style,
             visibility: self.visibility,
             enable_ime: self.enable_ime,
             visible_ime_candidate_window: self.visible_ime_candidate_window,
             auto_close: self.auto_close,
             nc_hittest: self.nc_hittest,
+            size_constraints: self.size_constraints,
+            placement: self.placement,
+            always_on_top: self.always_on_top,
+            owned: self.owned,
+            modal: self.modal,
+            auto_capture: self.auto_capture,
+            #[cfg(windows)]
             icon: self.icon,
+            #[cfg(windows)]
             cursor: self.cursor,
             parent: self.parent,
+            #[cfg(windows)]
             menu: self.menu,
+            accelerators: self.accelerators,
+            batch_text_input: self.batch_text_input,
+            pointer_input: self.pointer_input,
+            gestures: self.gestures,
+            raw_input: self.raw_input,
             drop_target: self.drop_target,
         }
     }
 
+    /// Specifies DPI of the simulated monitor which the window is created on.
+    ///
+    /// The Win32 backend ignores this and uses DPI of the actual monitor.
+    #[cfg(feature = "headless")]
+    #[inline]
+    pub fn dpi(mut self, dpi: u32) -> Self {
+        self.dpi = dpi;
+        self
+    }
+
+    #[cfg(windows)]
     #[inline]
     pub fn style<T>(self, style: T) -> WindowBuilder<'a, Rx, Title, Sz, T, Dt>
     where
@@ -389,16 +330,32 @@ impl<'a, Rx, Title, Sz, Sty, Dt> WindowBuilder<'a, Rx, Title, Sz, Sty, Dt> {
             title: self.title,
             position: self.position,
             inner_size: self.inner_size,
+            #[cfg(feature = "headless")]
+            dpi: self.dpi,
             style,
             visibility: self.visibility,
             enable_ime: self.enable_ime,
             visible_ime_candidate_window: self.visible_ime_candidate_window,
             auto_close: self.auto_close,
             nc_hittest: self.nc_hittest,
+            size_constraints: self.size_constraints,
+            placement: self.placement,
+            always_on_top: self.always_on_top,
+            owned: self.owned,
+            modal: self.modal,
+            auto_capture: self.auto_capture,
+            #[cfg(windows)]
             icon: self.icon,
+            #[cfg(windows)]
             cursor: self.cursor,
             parent: self.parent,
+            #[cfg(windows)]
             menu: self.menu,
+            accelerators: self.accelerators,
+            batch_text_input: self.batch_text_input,
+            pointer_input: self.pointer_input,
+            gestures: self.gestures,
+            raw_input: self.raw_input,
             drop_target: self.drop_target,
         }
     }
@@ -427,12 +384,14 @@ impl<'a, Rx, Title, Sz, Sty, Dt> WindowBuilder<'a, Rx, Title, Sz, Sty, Dt> {
         self
     }
 
+    #[cfg(windows)]
     #[inline]
     pub fn icon(mut self, icon: Icon) -> Self {
         self.icon = Some(icon);
         self
     }
 
+    #[cfg(windows)]
     #[inline]
     pub fn cursor(mut self, cursor: Cursor) -> Self {
         self.cursor = cursor;
@@ -445,18 +404,134 @@ impl<'a, Rx, Title, Sz, Sty, Dt> WindowBuilder<'a, Rx, Title, Sz, Sty, Dt> {
         self
     }
 
+    /// Makes the window owned by `owner`.
+    ///
+    /// An owned window stays above the owner, is minimized with it and does not appear in the
+    /// taskbar. It is closed with the owner like `parent`.
+    #[inline]
+    pub fn owner(mut self, owner: &impl IsWindow) -> Self {
+        self.parent = Some(owner.window_handle());
+        self.owned = true;
+        self
+    }
+
+    /// Disables the owner until the window is closed. This requires `owner`.
+    ///
+    /// The result passed to `Window::end_modal` is received by `Window::modal_result`.
+    #[inline]
+    pub fn modal(mut self, flag: bool) -> Self {
+        self.modal = flag;
+        self
+    }
+
     #[inline]
     pub fn hook_nc_hittest(mut self, flag: bool) -> Self {
         self.nc_hittest = flag;
         self
     }
 
+    /// Captures the mouse while a button is pressed. The default is `true`.
+    ///
+    /// The capture is released when all buttons are released. A capture taken by
+    /// `Window::set_capture` is kept until `Window::release_capture` is called.
+    ///
+    /// See `Window::set_capture`.
+    #[inline]
+    pub fn auto_capture(mut self, flag: bool) -> Self {
+        self.auto_capture = flag;
+        self
+    }
+
+    /// Sets the minimum size of the client area in logical or physical pixels.
+    #[inline]
+    pub fn min_inner_size(mut self, size: impl Into<DpiSize>) -> Self {
+        self.size_constraints.min = Some(size.into());
+        self
+    }
+
+    /// Sets the maximum size of the client area in logical or physical pixels.
+    #[inline]
+    pub fn max_inner_size(mut self, size: impl Into<DpiSize>) -> Self {
+        self.size_constraints.max = Some(size.into());
+        self
+    }
+
+    /// Sets the constraints of the client area including the aspect ratio and resize increments.
+    #[inline]
+    pub fn size_constraints(mut self, constraints: SizeConstraints) -> Self {
+        self.size_constraints = constraints;
+        self
+    }
+
+    /// Restores the position, the size and the state saved by `Window::placement`.
+    ///
+    /// The placement is fitted onto the current monitors by [`WindowPlacement::resolve`], and
+    /// overrides `position` and `inner_size`.
+    #[inline]
+    pub fn placement(mut self, placement: &WindowPlacement) -> Self {
+        self.placement = Some(placement.clone());
+        self
+    }
+
+    /// Keeps the window above non-topmost windows.
+    #[inline]
+    pub fn always_on_top(mut self, flag: bool) -> Self {
+        self.always_on_top = flag;
+        self
+    }
+
+    #[cfg(windows)]
     #[inline]
     pub fn menu(mut self, menu: &MenuBar) -> Self {
         self.menu = Some(menu.clone());
         self
     }
 
+    /// Sets accelerators of the window.
+    #[inline]
+    pub fn accelerators(mut self, accelerators: &Accelerators) -> Self {
+        self.accelerators = accelerators.clone();
+        self
+    }
+
+    /// Receives successive characters like pasting as `Event::TextInput` instead of `CharInput`.
+    #[inline]
+    pub fn batch_text_input(mut self, flag: bool) -> Self {
+        self.batch_text_input = flag;
+        self
+    }
+
+    /// Receives mouse, touch and pen inputs as `Event::Pointer`.
+    ///
+    /// Mouse inputs are received as `Event::Pointer` only when UI thread is built with
+    /// `UiThread::new().mouse_in_pointer(true)`, and are also received as `MouseInput` and
+    /// `CursorMoved`. Touch and pen inputs of the window are not converted to mouse inputs.
+    #[inline]
+    pub fn pointer_input(mut self, flag: bool) -> Self {
+        self.pointer_input = flag;
+        self
+    }
+
+    /// Receives gestures as `Event::Gesture`.
+    ///
+    /// With `pointer_input`, gestures are recognized from touch pointers by
+    /// `gesture::GestureRecognizer`. Otherwise, gestures are received from `WM_GESTURE`.
+    #[inline]
+    pub fn gestures(mut self, flag: bool) -> Self {
+        self.gestures = flag;
+        self
+    }
+
+    /// Registers raw input devices for the window.
+    ///
+    /// See `Window::set_raw_input`.
+    #[inline]
+    pub fn raw_input(mut self, devices: &RawInputDevices) -> Self {
+        self.raw_input = devices.clone();
+        self
+    }
+
+    #[cfg(windows)]
     #[inline]
     pub fn drop_target<T>(self, drop_target: T) -> WindowBuilder<'a, Rx, Title, Sz, Sty, T>
     where
@@ -467,70 +542,122 @@ impl<'a, Rx, Title, Sz, Sty, Dt> WindowBuilder<'a, Rx, Title, Sz, Sty, Dt> {
             title: self.title,
             position: self.position,
             inner_size: self.inner_size,
+            #[cfg(feature = "headless")]
+            dpi: self.dpi,
             style: self.style,
             visibility: self.visibility,
             enable_ime: self.enable_ime,
             visible_ime_candidate_window: self.visible_ime_candidate_window,
             auto_close: self.auto_close,
             nc_hittest: self.nc_hittest,
+            size_constraints: self.size_constraints,
+            placement: self.placement,
+            always_on_top: self.always_on_top,
+            owned: self.owned,
+            modal: self.modal,
+            auto_capture: self.auto_capture,
+            #[cfg(windows)]
             icon: self.icon,
+            #[cfg(windows)]
             cursor: self.cursor,
             parent: self.parent,
+            #[cfg(windows)]
             menu: self.menu,
+            accelerators: self.accelerators,
+            batch_text_input: self.batch_text_input,
+            pointer_input: self.pointer_input,
+            gestures: self.gestures,
+            raw_input: self.raw_input,
             drop_target,
         }
     }
 }
 
-struct BuilderProps<Pos, Sz, Dt = fn(&WindowHandle) -> IDropTarget> {
-    title: HSTRING,
-    position: Pos,
-    inner_size: Sz,
-    style: WINDOW_STYLE,
-    ex_style: WINDOW_EX_STYLE,
-    visiblity: bool,
-    enable_ime: bool,
-    visible_ime_candidate_window: bool,
-    auto_close: bool,
-    icon: Option<Icon>,
-    cursor: Cursor,
-    nc_hittest: bool,
-    event_rx_id: u64,
-    parent: Option<WindowHandle>,
-    parent_inner: Option<WindowHandle>,
-    menu: Option<MenuBar>,
-    set_attr: bool,
-    color_mode: ColorMode,
-    drop_target: Option<Dt>,
+pub(crate) struct BuilderProps<Pos, Sz, Dt = DefaultDropTarget> {
+    pub title: String,
+    pub position: Pos,
+    pub inner_size: Sz,
+    #[cfg(feature = "headless")]
+    pub dpi: Option<u32>,
+    #[cfg(windows)]
+    pub style: WINDOW_STYLE,
+    #[cfg(windows)]
+    pub ex_style: WINDOW_EX_STYLE,
+    pub visiblity: bool,
+    pub enable_ime: bool,
+    pub visible_ime_candidate_window: bool,
+    pub auto_close: bool,
+    #[cfg(windows)]
+    pub icon: Option<Icon>,
+    #[cfg(windows)]
+    pub cursor: Cursor,
+    pub nc_hittest: bool,
+    pub size_constraints: SizeConstraints,
+    pub placement: Option<WindowPlacement>,
+    pub always_on_top: bool,
+    pub auto_capture: bool,
+    pub event_rx_id: u64,
+    pub parent: Option<WindowHandle>,
+    pub parent_inner: Option<WindowHandle>,
+    pub owned: bool,
+    pub modal: bool,
+    #[cfg(windows)]
+    pub menu: Option<MenuBar>,
+    pub accelerators: Accelerators,
+    pub batch_text_input: bool,
+    pub pointer_input: bool,
+    pub gestures: bool,
+    pub raw_input: RawInputDevices,
+    pub color_mode: ColorMode,
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub drop_target: Option<Dt>,
 }
 
 impl<Sz, Dt> BuilderProps<PhysicalPosition<i32>, Sz, Dt> {
-    fn new<Rx, Title, Sty>(builder: WindowBuilder<Rx, Title, Sz, Sty, Dt>) -> Self
+    fn new<Rx, Title, Sty>(
+        builder: WindowBuilder<Rx, Title, Sz, Sty, Dt>,
+        #[cfg(windows)] style: (WINDOW_STYLE, WINDOW_EX_STYLE),
+    ) -> Self
     where
         Rx: IsReceiver,
         Title: Into<String>,
         Sz: ToPhysical<u32, Output<u32> = PhysicalSize<u32>> + Send + 'static,
-        Sty: Style,
-        Dt: FnOnce(&WindowHandle) -> IDropTarget + Send + 'static,
     {
         Self {
-            title: HSTRING::from(builder.title.into()),
+            title: builder.title.into(),
             position: builder.position,
             inner_size: builder.inner_size,
-            style: builder.style.style(),
-            ex_style: builder.style.ex_style(),
+            #[cfg(feature = "headless")]
+            dpi: Some(builder.dpi),
+            #[cfg(windows)]
+            style: style.0,
+            #[cfg(windows)]
+            ex_style: style.1,
             visiblity: builder.visibility,
             enable_ime: builder.enable_ime,
             visible_ime_candidate_window: builder.visible_ime_candidate_window,
             auto_close: builder.auto_close,
+            #[cfg(windows)]
             icon: builder.icon,
+            #[cfg(windows)]
             cursor: builder.cursor,
             nc_hittest: builder.nc_hittest,
+            size_constraints: builder.size_constraints,
+            placement: builder.placement,
+            always_on_top: builder.always_on_top,
+            auto_capture: builder.auto_capture,
             event_rx_id: builder.event_rx.id(),
             parent: builder.parent,
             parent_inner: None,
+            owned: builder.owned,
+            modal: builder.modal,
+            #[cfg(windows)]
             menu: builder.menu,
-            set_attr: true,
+            accelerators: builder.accelerators,
+            batch_text_input: builder.batch_text_input,
+            pointer_input: builder.pointer_input,
+            gestures: builder.gestures,
+            raw_input: builder.raw_input,
             color_mode: ColorMode::System,
             drop_target: Some(builder.drop_target),
         }
@@ -544,170 +671,84 @@ impl<Pos, Sz> BuilderProps<Pos, Sz> {
         Sz: ToPhysical<u32, Output<u32> = PhysicalSize<u32>> + Send + 'static,
     {
         Self {
-            title: HSTRING::from(String::new()),
+            title: String::new(),
             position: builder.position,
             inner_size: builder.size,
+            #[cfg(feature = "headless")]
+            dpi: None,
+            #[cfg(windows)]
             style: WS_CHILD,
+            #[cfg(windows)]
             ex_style: WINDOW_EX_STYLE(0),
             visiblity: builder.visibility,
             enable_ime: builder.enable_ime,
             visible_ime_candidate_window: builder.visible_ime_candidate_window,
             auto_close: true,
+            #[cfg(windows)]
             icon: None,
+            #[cfg(windows)]
             cursor: builder.cursor,
             nc_hittest: builder.nc_hittest,
+            size_constraints: SizeConstraints::new(),
+            placement: None,
+            always_on_top: false,
+            auto_capture: builder.auto_capture,
             event_rx_id: builder.event_rx.id(),
             parent: None,
             parent_inner: Some(builder.parent_inner),
+            owned: false,
+            modal: false,
+            #[cfg(windows)]
             menu: None,
-            set_attr: false,
+            accelerators: Accelerators::new(),
+            batch_text_input: false,
+            pointer_input: false,
+            gestures: false,
+            raw_input: RawInputDevices::new(),
             color_mode: ColorMode::System,
             drop_target: None,
         }
     }
 }
 
-pub(crate) struct WindowProps {
-    pub imm_context: ime::ImmContext,
-    pub visible_ime_candidate_window: bool,
-    pub auto_close: bool,
-    pub cursor: Cursor,
-    pub parent: Option<WindowHandle>,
-    pub nc_hittest: bool,
-    pub redrawing: bool,
-    pub resizing: bool,
-    pub minimized: bool,
-    pub _menu: Option<MenuBar>,
-    pub theme_menu: Theme,
-    pub color_mode: ColorMode,
-    pub color_mode_state: ColorModeState,
-}
-
-fn create_window<Pos, Sz, Dt>(
-    props: BuilderProps<Pos, Sz, Dt>,
-    f: impl FnOnce(WindowHandle) -> WindowKind,
-) -> Result<WindowHandle>
+#[cfg(windows)]
+impl<Title, Sz, Sty, Dt> WindowBuilder<'_, EventReceiver, Title, Sz, Sty, Dt>
 where
-    Pos: ToPhysical<i32, Output<i32> = PhysicalPosition<i32>> + Send + 'static,
+    Title: Into<String>,
     Sz: ToPhysical<u32, Output<u32> = PhysicalSize<u32>> + Send + 'static,
+    Sty: Style,
     Dt: FnOnce(&WindowHandle) -> IDropTarget + Send + 'static,
 {
-    unsafe {
-        let dpi = get_dpi_from_point(ScreenPosition::new(0, 0));
-        let position = props.position.to_physical(dpi as i32);
-        let size = props.inner_size.to_physical(dpi);
-        let rc = adjust_window_rect_ex_for_dpi(
-            size,
-            props.style,
-            props.menu.is_some(),
-            props.ex_style,
-            dpi,
-        );
-        let hinstance: Option<HINSTANCE> = Some(GetModuleHandleW(None).unwrap().into());
-        let parent = props.parent_inner.as_ref().map(|p| p.as_hwnd());
-        let hwnd = CreateWindowExW(
-            props.ex_style,
-            WINDOW_CLASS_NAME,
-            &props.title,
-            props.style,
-            position.x,
-            position.y,
-            rc.right - rc.left,
-            rc.bottom - rc.top,
-            parent,
-            props.menu.as_ref().map(|m| m.as_hmenu()),
-            hinstance,
-            None,
-        )?;
-        let handle = WindowHandle::new(hwnd);
-        let dark_mode = BOOL::from(is_system_dark_mode());
-        if dark_mode.as_bool() {
-            info!("Dark mode");
-        } else {
-            info!("Light mode");
-        }
-        if props.set_attr {
-            let ret = DwmSetWindowAttribute(
-                hwnd,
-                DWMWA_USE_IMMERSIVE_DARK_MODE,
-                &dark_mode as *const BOOL as *const std::ffi::c_void,
-                std::mem::size_of::<BOOL>() as u32,
-            );
-            if let Err(e) = ret {
-                error!("DwmSetWindowAttribute DWMWA_USE_IMMERSIVE_DARK_MODE: {e}");
-            }
-        }
-        let imm_context = ime::ImmContext::new(handle);
-        if props.enable_ime {
-            imm_context.enable();
-        } else {
-            imm_context.disable();
-        }
-        if let Some(icon) = props.icon {
-            if let Ok(big) = icon.load(hinstance) {
-                PostMessageW(
-                    Some(hwnd),
-                    WM_SETICON,
-                    WPARAM(ICON_BIG as usize),
-                    LPARAM(big.0 as isize),
-                )
-                .ok();
-            }
-            if let Ok(small) = icon.load_small(hinstance) {
-                PostMessageW(
-                    Some(hwnd),
-                    WM_SETICON,
-                    WPARAM(ICON_SMALL as usize),
-                    LPARAM(small.0 as isize),
-                )
-                .ok();
-            }
-        }
-        if let Some(drop_target) = props.drop_target {
-            RegisterDragDrop(hwnd, &drop_target(&handle))?;
-        }
-        set_preferred_app_mode(APPMODE_ALLOWDARK);
-        refresh_immersive_color_policy_state();
-        let window_props = WindowProps {
-            imm_context,
-            visible_ime_candidate_window: props.visible_ime_candidate_window,
-            auto_close: props.auto_close,
-            cursor: props.cursor,
-            parent: props.parent,
-            nc_hittest: props.nc_hittest,
-            redrawing: false,
-            resizing: false,
-            minimized: false,
-            _menu: props.menu,
-            theme_menu: Theme::new(hwnd, &["Menu"]),
-            color_mode: props.color_mode,
-            color_mode_state: if dark_mode.as_bool() {
-                ColorModeState::Dark
-            } else {
-                ColorModeState::Light
-            },
+    /// Builds a window.
+    pub fn build(self) -> Result<Window> {
+        let (tx, rx) = oneshot::channel::<Result<WindowHandle>>();
+        let style = (self.style.style(), self.style.ex_style());
+        let props = BuilderProps::new(self, style);
+        UiThread::send_task(move || {
+            tx.send(methods::create_window(props, |handle| {
+                WindowKind::Window(Window { handle })
+            }))
+            .ok();
+        });
+        let Ok(ret) = rx.blocking_recv() else {
+            return Err(Error::UiThreadClosed);
         };
-        Context::register_window(f(handle), window_props, props.event_rx_id);
-        if props.visiblity {
-            let _ = ShowWindow(hwnd, SW_SHOW);
-        }
-        Ok(handle)
+        Ok(Window { handle: ret? })
     }
 }
 
-impl<Title, Sz, Sty, Dt> WindowBuilder<'_, EventReceiver, Title, Sz, Sty, Dt>
+#[cfg(not(windows))]
+impl<Title, Sz> WindowBuilder<'_, EventReceiver, Title, Sz>
 where
     Title: Into<String>,
     Sz: ToPhysical<u32, Output<u32> = PhysicalSize<u32>> + Send + 'static,
-    Sty: Style,
-    Dt: FnOnce(&WindowHandle) -> IDropTarget + Send + 'static,
 {
     /// Builds a window.
     pub fn build(self) -> Result<Window> {
-        let (tx, rx) = tokio::sync::oneshot::channel::<Result<WindowHandle>>();
+        let (tx, rx) = oneshot::channel::<Result<WindowHandle>>();
         let props = BuilderProps::new(self);
         UiThread::send_task(move || {
-            tx.send(create_window(props, |handle| {
+            tx.send(methods::create_window(props, |handle| {
                 WindowKind::Window(Window { handle })
             }))
             .ok();
@@ -719,6 +760,7 @@ where
     }
 }
 
+#[cfg(windows)]
 impl<Title, Sz, Sty, Dt> WindowBuilder<'_, AsyncEventReceiver, Title, Sz, Sty, Dt>
 where
     Title: Into<String>,
@@ -728,10 +770,34 @@ where
 {
     /// Builds a window.
     pub async fn build(self) -> Result<AsyncWindow> {
-        let (tx, rx) = tokio::sync::oneshot::channel::<Result<WindowHandle>>();
+        let (tx, rx) = oneshot::channel::<Result<WindowHandle>>();
+        let style = (self.style.style(), self.style.ex_style());
+        let props = BuilderProps::new(self, style);
+        UiThread::send_task(move || {
+            tx.send(methods::create_window(props, |handle| {
+                WindowKind::Window(Window { handle })
+            }))
+            .ok();
+        });
+        let Ok(ret) = rx.await else {
+            return Err(Error::UiThreadClosed);
+        };
+        Ok(AsyncWindow { handle: ret? })
+    }
+}
+
+#[cfg(not(windows))]
+impl<Title, Sz> WindowBuilder<'_, AsyncEventReceiver, Title, Sz>
+where
+    Title: Into<String>,
+    Sz: ToPhysical<u32, Output<u32> = PhysicalSize<u32>> + Send + 'static,
+{
+    /// Builds a window.
+    pub async fn build(self) -> Result<AsyncWindow> {
+        let (tx, rx) = oneshot::channel::<Result<WindowHandle>>();
         let props = BuilderProps::new(self);
         UiThread::send_task(move || {
-            tx.send(create_window(props, |handle| {
+            tx.send(methods::create_window(props, |handle| {
                 WindowKind::Window(Window { handle })
             }))
             .ok();
@@ -743,7 +809,9 @@ where
     }
 }
 
-impl<Title, Sz, Sty, Dt> std::future::IntoFuture for WindowBuilder<'_, AsyncEventReceiver, Title, Sz, Sty, Dt>
+#[cfg(windows)]
+impl<Title, Sz, Sty, Dt> std::future::IntoFuture
+    for WindowBuilder<'_, AsyncEventReceiver, Title, Sz, Sty, Dt>
 where
     Title: Into<String>,
     Sz: ToPhysical<u32, Output<u32> = PhysicalSize<u32>> + Send + 'static,
@@ -754,10 +822,11 @@ where
     type IntoFuture = std::pin::Pin<Box<dyn std::future::Future<Output = Self::Output>>>;
 
     fn into_future(self) -> Self::IntoFuture {
-        let (tx, rx) = tokio::sync::oneshot::channel::<Result<WindowHandle>>();
-        let props = BuilderProps::new(self);
+        let (tx, rx) = oneshot::channel::<Result<WindowHandle>>();
+        let style = (self.style.style(), self.style.ex_style());
+        let props = BuilderProps::new(self, style);
         UiThread::send_task(move || {
-            tx.send(create_window(props, |handle| {
+            tx.send(methods::create_window(props, |handle| {
                 WindowKind::Window(Window { handle })
             }))
             .ok();
@@ -771,59 +840,148 @@ where
     }
 }
 
-mod methods {
-    use windows::Win32::Graphics::Gdi::{RDW_INVALIDATE, RedrawWindow};
-
-    use super::*;
+#[cfg(not(windows))]
+impl<Title, Sz> std::future::IntoFuture for WindowBuilder<'_, AsyncEventReceiver, Title, Sz>
+where
+    Title: Into<String>,
+    Sz: ToPhysical<u32, Output<u32> = PhysicalSize<u32>> + Send + 'static,
+{
+    type Output = Result<AsyncWindow>;
+    type IntoFuture = std::pin::Pin<Box<dyn std::future::Future<Output = Self::Output>>>;
 
-    #[inline]
-    pub fn position(handle: WindowHandle) -> oneshot::Receiver<ScreenPosition<i32>> {
-        let (tx, rx) = oneshot::channel::<ScreenPosition<i32>>();
+    fn into_future(self) -> Self::IntoFuture {
+        let (tx, rx) = oneshot::channel::<Result<WindowHandle>>();
+        let props = BuilderProps::new(self);
         UiThread::send_task(move || {
-            let rc = get_window_rect(handle.into());
-            tx.send((rc.left, rc.top).into()).ok();
+            tx.send(methods::create_window(props, |handle| {
+                WindowKind::Window(Window { handle })
+            }))
+            .ok();
         });
-        rx
+        Box::pin(async move {
+            let Ok(ret) = rx.await else {
+                return Err(Error::UiThreadClosed);
+            };
+            Ok(AsyncWindow { handle: ret? })
+        })
+    }
+}
+
+mod methods {
+    use super::*;
+    #[cfg(feature = "headless")]
+    use crate::headless::window as headless;
+
+    /// The result of a method of a replayed window, which does not refer to any live window.
+    trait Inert {
+        fn inert() -> Self;
+    }
+
+    impl Inert for () {
+        fn inert() -> Self {}
+    }
+
+    impl Inert for bool {
+        fn inert() -> Self {
+            true
+        }
+    }
+
+    impl<T> Inert for Option<T> {
+        fn inert() -> Self {
+            None
+        }
+    }
+
+    impl<T> Inert for oneshot::Receiver<T> {
+        fn inert() -> Self {
+            oneshot::channel().1
+        }
+    }
+
+    impl Inert for ModalResult {
+        fn inert() -> Self {
+            ModalResult::new(oneshot::channel().1)
+        }
+    }
+
+    /// Same as `backend!`, but does nothing for a replayed window.
+    macro_rules! dispatch {
+        ($handle:expr, $win32:expr, $headless:expr $(,)?) => {
+            if $handle.is_replayed() {
+                Inert::inert()
+            } else {
+                backend!($win32, $headless)
+            }
+        };
+    }
+
+    #[cfg(windows)]
+    pub fn create_window<Pos, Sz, Dt>(
+        props: BuilderProps<Pos, Sz, Dt>,
+        f: impl FnOnce(WindowHandle) -> WindowKind,
+    ) -> Result<WindowHandle>
+    where
+        Pos: ToPhysical<i32, Output<i32> = PhysicalPosition<i32>> + Send + 'static,
+        Sz: ToPhysical<u32, Output<u32> = PhysicalSize<u32>> + Send + 'static,
+        Dt: FnOnce(&WindowHandle) -> IDropTarget + Send + 'static,
+    {
+        backend!(
+            win32::create_window(props, f),
+            headless::create_window(props, f)
+        )
+    }
+
+    #[cfg(not(windows))]
+    pub fn create_window<Pos, Sz, Dt>(
+        props: BuilderProps<Pos, Sz, Dt>,
+        f: impl FnOnce(WindowHandle) -> WindowKind,
+    ) -> Result<WindowHandle>
+    where
+        Pos: ToPhysical<i32, Output<i32> = PhysicalPosition<i32>> + Send + 'static,
+        Sz: ToPhysical<u32, Output<u32> = PhysicalSize<u32>> + Send + 'static,
+    {
+        headless::create_window(props, f)
+    }
+
+    #[inline]
+    pub fn position(handle: WindowHandle) -> oneshot::Receiver<ScreenPosition<i32>> {
+        dispatch!(handle, win32::position(handle), headless::position(handle))
+    }
+
+    #[inline]
+    pub fn inner_position(
+        handle: WindowHandle,
+        parent: WindowHandle,
+    ) -> oneshot::Receiver<PhysicalPosition<i32>> {
+        dispatch!(
+            handle,
+            win32::inner_position(handle, parent),
+            headless::inner_position(handle, parent)
+        )
     }
 
     #[inline]
     pub fn inner_size(handle: WindowHandle) -> oneshot::Receiver<PhysicalSize<u32>> {
-        let (tx, rx) = oneshot::channel::<PhysicalSize<u32>>();
-        UiThread::send_task(move || {
-            let rc = get_client_rect(handle.into());
-            tx.send(PhysicalSize::new(
-                (rc.right - rc.left) as u32,
-                (rc.bottom - rc.top) as u32,
-            ))
-            .ok();
-        });
-        rx
+        dispatch!(
+            handle,
+            win32::inner_size(handle),
+            headless::inner_size(handle)
+        )
     }
 
     #[inline]
     pub fn dpi(handle: WindowHandle) -> oneshot::Receiver<u32> {
-        let (tx, rx) = oneshot::channel::<u32>();
-        UiThread::send_task(move || unsafe {
-            let dpi = GetDpiForWindow(handle.as_hwnd());
-            tx.send(dpi).ok();
-        });
-        rx
+        dispatch!(handle, win32::dpi(handle), headless::dpi(handle))
     }
 
     #[inline]
     pub fn enable_ime(handle: WindowHandle, enabled: bool) -> oneshot::Receiver<()> {
-        let (tx, rx) = oneshot::channel::<()>();
-        UiThread::send_task(move || {
-            Context::set_window_props(handle, |props| {
-                if enabled {
-                    props.imm_context.enable();
-                } else {
-                    props.imm_context.disable();
-                }
-            });
-            tx.send(()).ok();
-        });
-        rx
+        dispatch!(
+            handle,
+            win32::enable_ime(handle, enabled),
+            headless::enable_ime(handle, enabled)
+        )
     }
 
     #[inline]
@@ -831,20 +989,11 @@ mod methods {
     where
         T: ToPhysical<i32, Output<i32> = PhysicalPosition<i32>> + Send + 'static,
     {
-        UiThread::send_task(move || unsafe {
-            let dpi = GetDpiForWindow(handle.as_hwnd()) as i32;
-            let position = position.to_physical(dpi);
-            SetWindowPos(
-                handle.as_hwnd(),
-                None,
-                position.x,
-                position.y,
-                0,
-                0,
-                SWP_NOZORDER | SWP_NOSIZE,
-            )
-            .ok();
-        });
+        dispatch!(
+            handle,
+            win32::set_position(handle, position),
+            headless::set_position(handle, position)
+        )
     }
 
     #[inline]
@@ -852,241 +1001,741 @@ mod methods {
     where
         T: ToPhysical<u32, Output<u32> = PhysicalSize<u32>> + Send + 'static,
     {
-        UiThread::send_task(move || unsafe {
-            let dpi = GetDpiForWindow(handle.as_hwnd());
-            let size = size.to_physical(dpi);
-            SetWindowPos(
-                handle.as_hwnd(),
-                None,
-                0,
-                0,
-                size.width as i32,
-                size.height as i32,
-                SWP_NOZORDER | SWP_NOMOVE,
-            )
-            .ok();
-        });
+        dispatch!(
+            handle,
+            win32::set_size(handle, size),
+            headless::set_size(handle, size)
+        )
     }
 
     #[inline]
     pub fn close(handle: WindowHandle) {
-        unsafe {
-            PostMessageW(Some(handle.as_hwnd()), WM_CLOSE, WPARAM(0), LPARAM(0)).ok();
-        }
+        dispatch!(handle, win32::close(handle), headless::close(handle))
     }
 
     #[inline]
     pub fn show(handle: WindowHandle) {
-        unsafe {
-            let _ = ShowWindowAsync(handle.as_hwnd(), SW_SHOW);
-        }
+        dispatch!(handle, win32::show(handle), headless::show(handle))
     }
 
     #[inline]
     pub fn hide(handle: WindowHandle) {
-        unsafe {
-            let _ = ShowWindowAsync(handle.as_hwnd(), SW_HIDE);
-        }
+        dispatch!(handle, win32::hide(handle), headless::hide(handle))
     }
 
     #[inline]
     pub fn minimize(handle: WindowHandle) {
-        unsafe {
-            let _ = ShowWindowAsync(handle.as_hwnd(), SW_MINIMIZE);
-        }
+        dispatch!(handle, win32::minimize(handle), headless::minimize(handle))
     }
 
     #[inline]
     pub fn maximize(handle: WindowHandle) {
-        unsafe {
-            let _ = ShowWindowAsync(handle.as_hwnd(), SW_SHOWMAXIMIZED);
-        }
+        dispatch!(handle, win32::maximize(handle), headless::maximize(handle))
     }
 
     #[inline]
     pub fn restore(handle: WindowHandle) {
-        unsafe {
-            let _ = ShowWindowAsync(handle.as_hwnd(), SW_RESTORE);
-        }
+        dispatch!(handle, win32::restore(handle), headless::restore(handle))
     }
 
+    #[inline]
+    pub fn is_closed(handle: WindowHandle) -> bool {
+        dispatch!(
+            handle,
+            win32::is_closed(handle),
+            headless::is_closed(handle)
+        )
+    }
+
+    #[cfg(windows)]
+    #[inline]
+    pub fn cursor(handle: WindowHandle) -> Option<Cursor> {
+        dispatch!(handle, win32::cursor(handle), None)
+    }
+
+    #[cfg(windows)]
     #[inline]
     pub fn set_cursor(handle: WindowHandle, cursor: Cursor) {
-        UiThread::send_task(move || {
-            cursor.set();
-            Context::set_window_props(handle, |props| {
-                props.cursor = cursor;
-            });
-        });
+        dispatch!(handle, win32::set_cursor(handle, cursor), ())
+    }
+
+    #[inline]
+    pub fn set_capture(handle: WindowHandle) {
+        dispatch!(
+            handle,
+            win32::set_capture(handle),
+            headless::set_capture(handle)
+        )
+    }
+
+    #[inline]
+    pub fn release_capture(handle: WindowHandle) {
+        dispatch!(
+            handle,
+            win32::release_capture(handle),
+            headless::release_capture(handle)
+        )
+    }
+
+    #[inline]
+    pub fn confine_cursor(handle: WindowHandle, rect: Option<PhysicalRect<i32>>) {
+        dispatch!(
+            handle,
+            win32::confine_cursor(handle, rect),
+            headless::confine_cursor(handle, rect)
+        )
+    }
+
+    #[inline]
+    pub fn hide_cursor(handle: WindowHandle, hidden: bool) {
+        dispatch!(
+            handle,
+            win32::hide_cursor(handle, hidden),
+            headless::hide_cursor(handle, hidden)
+        )
+    }
+
+    #[inline]
+    pub fn lock_cursor(handle: WindowHandle, locked: bool) {
+        dispatch!(
+            handle,
+            win32::lock_cursor(handle, locked),
+            headless::lock_cursor(handle, locked)
+        )
+    }
+
+    #[inline]
+    pub fn accelerators(handle: WindowHandle) -> Option<Accelerators> {
+        dispatch!(
+            handle,
+            win32::accelerators(handle),
+            headless::accelerators(handle)
+        )
+    }
+
+    #[inline]
+    pub fn set_accelerators(handle: WindowHandle, accelerators: Accelerators) {
+        dispatch!(
+            handle,
+            win32::set_accelerators(handle, accelerators),
+            headless::set_accelerators(handle, accelerators)
+        )
+    }
+
+    #[inline]
+    pub fn raw_input(handle: WindowHandle) -> Option<RawInputDevices> {
+        dispatch!(
+            handle,
+            win32::raw_input(handle),
+            headless::raw_input(handle)
+        )
+    }
+
+    #[inline]
+    pub fn set_raw_input(handle: WindowHandle, devices: RawInputDevices) {
+        dispatch!(
+            handle,
+            win32::set_raw_input(handle, devices),
+            headless::set_raw_input(handle, devices)
+        )
     }
 
     #[inline]
     pub fn redraw(handle: WindowHandle, invalidate_rect: Option<PhysicalRect<i32>>) {
-        UiThread::send_task(move || unsafe {
-            let rc: Option<RECT> = invalidate_rect.map(|rc| rc.into());
-            let p = rc.as_ref().map(|p| p as *const RECT);
-            let redrawing = Context::get_window_props(handle, |props| props.redrawing);
-            if !redrawing.unwrap_or(true) {
-                let _ = RedrawWindow(Some(handle.as_hwnd()), p, None, RDW_INVALIDATE);
-                Context::set_window_props(handle, |props| {
-                    props.redrawing = true;
-                });
-            }
-        });
+        dispatch!(
+            handle,
+            win32::redraw(handle, invalidate_rect),
+            headless::redraw(handle, invalidate_rect)
+        )
+    }
+
+    #[inline]
+    pub fn title(handle: WindowHandle) -> oneshot::Receiver<String> {
+        dispatch!(handle, win32::title(handle), headless::title(handle))
+    }
+
+    #[inline]
+    pub fn set_title(handle: WindowHandle, title: String) {
+        dispatch!(
+            handle,
+            win32::set_title(handle, title),
+            headless::set_title(handle, title)
+        )
+    }
+
+    #[cfg(windows)]
+    #[inline]
+    pub fn set_icon(handle: WindowHandle, icon: Option<Icon>) {
+        dispatch!(handle, win32::set_icon(handle, icon), ())
+    }
+
+    #[cfg(windows)]
+    #[inline]
+    pub fn set_style(handle: WindowHandle, style: WINDOW_STYLE, ex_style: WINDOW_EX_STYLE) {
+        dispatch!(handle, win32::set_style(handle, style, ex_style), ())
+    }
+
+    #[inline]
+    pub fn set_resizable(handle: WindowHandle, flag: bool) {
+        dispatch!(
+            handle,
+            win32::set_resizable(handle, flag),
+            headless::set_resizable(handle, flag)
+        )
+    }
+
+    #[cfg(windows)]
+    #[inline]
+    pub fn set_menu(handle: WindowHandle, menu: Option<MenuBar>) {
+        dispatch!(handle, win32::set_menu(handle, menu), ())
+    }
+
+    #[inline]
+    pub fn size_constraints(handle: WindowHandle) -> Option<SizeConstraints> {
+        dispatch!(
+            handle,
+            win32::size_constraints(handle),
+            headless::size_constraints(handle)
+        )
+    }
+
+    #[inline]
+    pub fn set_size_constraints(handle: WindowHandle, constraints: SizeConstraints) {
+        dispatch!(
+            handle,
+            win32::set_size_constraints(handle, constraints),
+            headless::set_size_constraints(handle, constraints)
+        )
+    }
+
+    #[inline]
+    pub fn current_monitor(handle: WindowHandle) -> Option<Monitor> {
+        dispatch!(
+            handle,
+            win32::current_monitor(handle),
+            headless::current_monitor(handle)
+        )
+    }
+
+    #[inline]
+    pub fn placement(handle: WindowHandle) -> Option<WindowPlacement> {
+        dispatch!(
+            handle,
+            win32::placement(handle),
+            headless::placement(handle)
+        )
+    }
+
+    #[inline]
+    pub fn fullscreen(handle: WindowHandle) -> Option<Fullscreen> {
+        dispatch!(
+            handle,
+            win32::fullscreen(handle),
+            headless::fullscreen(handle)
+        )
+    }
+
+    #[inline]
+    pub fn set_fullscreen(handle: WindowHandle, fullscreen: Option<Fullscreen>) {
+        dispatch!(
+            handle,
+            win32::set_fullscreen(handle, fullscreen),
+            headless::set_fullscreen(handle, fullscreen)
+        )
+    }
+
+    #[inline]
+    pub fn is_visible(handle: WindowHandle) -> oneshot::Receiver<bool> {
+        dispatch!(
+            handle,
+            win32::is_visible(handle),
+            headless::is_visible(handle)
+        )
+    }
+
+    #[inline]
+    pub fn is_minimized(handle: WindowHandle) -> oneshot::Receiver<bool> {
+        dispatch!(
+            handle,
+            win32::is_minimized(handle),
+            headless::is_minimized(handle)
+        )
+    }
+
+    #[inline]
+    pub fn is_maximized(handle: WindowHandle) -> oneshot::Receiver<bool> {
+        dispatch!(
+            handle,
+            win32::is_maximized(handle),
+            headless::is_maximized(handle)
+        )
+    }
+
+    #[inline]
+    pub fn has_focus(handle: WindowHandle) -> oneshot::Receiver<bool> {
+        dispatch!(
+            handle,
+            win32::has_focus(handle),
+            headless::has_focus(handle)
+        )
+    }
+
+    #[inline]
+    pub fn is_active(handle: WindowHandle) -> oneshot::Receiver<bool> {
+        dispatch!(
+            handle,
+            win32::is_active(handle),
+            headless::is_active(handle)
+        )
+    }
+
+    #[inline]
+    pub fn is_topmost(handle: WindowHandle) -> oneshot::Receiver<bool> {
+        dispatch!(
+            handle,
+            win32::is_topmost(handle),
+            headless::is_topmost(handle)
+        )
+    }
+
+    #[inline]
+    pub fn set_topmost(handle: WindowHandle, topmost: bool) {
+        dispatch!(
+            handle,
+            win32::set_topmost(handle, topmost),
+            headless::set_topmost(handle, topmost)
+        )
+    }
+
+    #[inline]
+    pub fn show_without_activation(handle: WindowHandle) {
+        dispatch!(
+            handle,
+            win32::show_without_activation(handle),
+            headless::show_without_activation(handle)
+        )
+    }
+
+    #[inline]
+    pub fn minimize_without_activation(handle: WindowHandle) {
+        dispatch!(
+            handle,
+            win32::minimize_without_activation(handle),
+            headless::minimize_without_activation(handle)
+        )
+    }
+
+    #[inline]
+    pub fn is_enabled(handle: WindowHandle) -> oneshot::Receiver<bool> {
+        dispatch!(
+            handle,
+            win32::is_enabled(handle),
+            headless::is_enabled(handle)
+        )
+    }
+
+    #[inline]
+    pub fn modal_result(handle: WindowHandle) -> ModalResult {
+        dispatch!(
+            handle,
+            win32::modal_result(handle),
+            headless::modal_result(handle)
+        )
+    }
+
+    #[inline]
+    pub fn end_modal(handle: WindowHandle, result: usize) {
+        dispatch!(
+            handle,
+            win32::end_modal(handle, result),
+            headless::end_modal(handle, result)
+        )
     }
 
     #[inline]
     pub fn post_app_event(handle: WindowHandle, app: event::App) {
-        unsafe {
-            PostMessageW(
-                Some(handle.as_hwnd()),
-                OFFSET_WM_APP + app.index,
-                WPARAM(app.value0),
-                LPARAM(app.value1),
-            )
-            .ok();
-        }
+        dispatch!(
+            handle,
+            win32::post_app_event(handle, app),
+            headless::post_app_event(handle, app)
+        )
     }
 
     #[inline]
     pub fn set_foreground(handle: WindowHandle) {
-        unsafe {
-            let _ = SetForegroundWindow(handle.as_hwnd());
-        }
+        dispatch!(
+            handle,
+            win32::set_foreground(handle),
+            headless::set_foreground(handle)
+        )
     }
 
     #[inline]
     pub fn set_focus(handle: WindowHandle) {
-        unsafe {
-            let _ = SetFocus(Some(handle.as_hwnd()));
-        }
+        dispatch!(
+            handle,
+            win32::set_focus(handle),
+            headless::set_focus(handle)
+        )
     }
 
     #[inline]
     pub fn color_mode(handle: WindowHandle) -> Option<ColorMode> {
-        Context::get_window_props(handle, |props| props.color_mode)
+        dispatch!(
+            handle,
+            win32::color_mode(handle),
+            headless::color_mode(handle)
+        )
     }
 
     #[inline]
     pub fn set_color_mode(handle: WindowHandle, mode: ColorMode) {
-        UiThread::send_task(move || {
-            procedure::change_color_mode(handle.as_hwnd(), mode);
-        });
+        dispatch!(
+            handle,
+            win32::set_color_mode(handle, mode),
+            headless::set_color_mode(handle, mode)
+        )
     }
 
+    #[cfg(windows)]
     #[inline]
     pub fn add_raw_procedure_handler<F>(handle: WindowHandle, f: F)
     where
         F: Fn(u32, WPARAM, LPARAM) + Send + 'static,
     {
-        UiThread::send_task(move || {
-            procedure::add_raw_procedure_handler(handle, f);
-        });
+        dispatch!(handle, win32::add_raw_procedure_handler(handle, f), ())
+    }
+}
+
+/// Represents a window.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Window {
+    pub(crate) handle: WindowHandle,
+}
+
+impl Window {
+    #[inline]
+    pub fn builder<T>(event_rx: &T) -> WindowBuilder<'_, T> {
+        WindowBuilder::new(event_rx)
+    }
+
+    #[inline]
+    pub fn position(&self) -> Option<ScreenPosition<i32>> {
+        let rx = methods::position(self.window_handle());
+        rx.blocking_recv().ok()
+    }
+
+    #[inline]
+    pub fn inner_size(&self) -> Option<PhysicalSize<u32>> {
+        let rx = methods::inner_size(self.window_handle());
+        rx.blocking_recv().ok()
+    }
+
+    #[inline]
+    pub fn dpi(&self) -> Option<u32> {
+        let rx = methods::dpi(self.window_handle());
+        rx.blocking_recv().ok()
+    }
+
+    #[inline]
+    pub fn set_position<T>(&self, position: T)
+    where
+        T: ToPhysical<i32, Output<i32> = PhysicalPosition<i32>> + Send + 'static,
+    {
+        methods::set_position(self.window_handle(), position);
+    }
+
+    #[inline]
+    pub fn set_inner_size<T>(&self, size: T)
+    where
+        T: ToPhysical<u32, Output<u32> = PhysicalSize<u32>> + Send + 'static,
+    {
+        methods::set_size(self.window_handle(), size);
+    }
+
+    #[inline]
+    pub fn enable_ime(&self, enabled: bool) {
+        methods::enable_ime(self.window_handle(), enabled)
+            .blocking_recv()
+            .ok();
+    }
+
+    #[inline]
+    pub fn show(&self) {
+        methods::show(self.window_handle());
+    }
+
+    #[inline]
+    pub fn hide(&self) {
+        methods::hide(self.window_handle());
+    }
+
+    #[inline]
+    pub fn minimize(&self) {
+        methods::minimize(self.window_handle());
+    }
+
+    #[inline]
+    pub fn maximize(&self) {
+        methods::maximize(self.window_handle());
+    }
+
+    #[inline]
+    pub fn restore(&self) {
+        methods::restore(self.window_handle());
+    }
+
+    /// Shows the window without activating it.
+    #[inline]
+    pub fn show_without_activation(&self) {
+        methods::show_without_activation(self.window_handle());
+    }
+
+    /// Minimizes the window without activating it.
+    #[inline]
+    pub fn minimize_without_activation(&self) {
+        methods::minimize_without_activation(self.window_handle());
+    }
+
+    #[inline]
+    pub fn is_visible(&self) -> Option<bool> {
+        let rx = methods::is_visible(self.window_handle());
+        rx.blocking_recv().ok()
+    }
+
+    #[inline]
+    pub fn is_minimized(&self) -> Option<bool> {
+        let rx = methods::is_minimized(self.window_handle());
+        rx.blocking_recv().ok()
+    }
+
+    #[inline]
+    pub fn is_maximized(&self) -> Option<bool> {
+        let rx = methods::is_maximized(self.window_handle());
+        rx.blocking_recv().ok()
+    }
+
+    /// Checks if the window has the keyboard focus.
+    #[inline]
+    pub fn has_focus(&self) -> Option<bool> {
+        let rx = methods::has_focus(self.window_handle());
+        rx.blocking_recv().ok()
+    }
+
+    /// Checks if the window is the active window.
+    #[inline]
+    pub fn is_active(&self) -> Option<bool> {
+        let rx = methods::is_active(self.window_handle());
+        rx.blocking_recv().ok()
+    }
+
+    /// Checks if the window stays above non-topmost windows.
+    #[inline]
+    pub fn is_topmost(&self) -> Option<bool> {
+        let rx = methods::is_topmost(self.window_handle());
+        rx.blocking_recv().ok()
+    }
+
+    /// Makes the window stay above non-topmost windows, or reverts it.
+    #[inline]
+    pub fn set_topmost(&self, topmost: bool) {
+        methods::set_topmost(self.window_handle(), topmost);
+    }
+
+    #[inline]
+    pub fn is_enabled(&self) -> Option<bool> {
+        let rx = methods::is_enabled(self.window_handle());
+        rx.blocking_recv().ok()
+    }
+
+    /// Returns a future which resolves when the modal window is closed.
+    ///
+    /// Call this before the window is closed. The output is `None` if the window is not modal.
+    #[inline]
+    pub fn modal_result(&self) -> ModalResult {
+        methods::modal_result(self.window_handle())
+    }
+
+    /// Closes the modal window, and resolves `modal_result` with `result`.
+    #[inline]
+    pub fn end_modal(&self, result: usize) {
+        methods::end_modal(self.window_handle(), result);
+    }
+
+    #[cfg(windows)]
+    #[inline]
+    pub fn cursor(&self) -> Option<Cursor> {
+        methods::cursor(self.window_handle())
+    }
+
+    #[cfg(windows)]
+    #[inline]
+    pub fn set_cursor(&self, cursor: Cursor) {
+        let hwnd = self.window_handle();
+        methods::set_cursor(hwnd, cursor);
+    }
+
+    #[inline]
+    pub fn accelerators(&self) -> Option<Accelerators> {
+        methods::accelerators(self.window_handle())
+    }
+
+    #[inline]
+    pub fn set_accelerators(&self, accelerators: &Accelerators) {
+        methods::set_accelerators(self.window_handle(), accelerators.clone());
+    }
+
+    /// Returns registered raw input devices of the window.
+    #[inline]
+    pub fn raw_input(&self) -> Option<RawInputDevices> {
+        methods::raw_input(self.window_handle())
+    }
+
+    /// Registers raw input devices, and unregisters devices which are not in `devices`.
+    ///
+    /// Raw input devices are registered for each process, so the last registered window
+    /// receives inputs.
+    #[inline]
+    pub fn set_raw_input(&self, devices: &RawInputDevices) {
+        methods::set_raw_input(self.window_handle(), devices.clone());
+    }
+
+    #[inline]
+    pub fn redraw(&self, invalidate_rect: Option<PhysicalRect<i32>>) {
+        methods::redraw(self.window_handle(), invalidate_rect);
+    }
+
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        methods::is_closed(self.window_handle())
+    }
+
+    #[inline]
+    pub fn close(&self) {
+        methods::close(self.window_handle());
+    }
+
+    /// Captures the mouse so that the window receives mouse inputs outside of it.
+    ///
+    /// The window receives `Event::CaptureLost` when another window takes the capture.
+    #[inline]
+    pub fn set_capture(&self) {
+        methods::set_capture(self.window_handle());
     }
-}
-
-/// Represents a window.
-#[derive(Clone, PartialEq, Eq, Debug)]
-pub struct Window {
-    handle: WindowHandle,
-}
 
-impl Window {
     #[inline]
-    pub fn builder<T>(event_rx: &T) -> WindowBuilder<'_, T> {
-        WindowBuilder::new(event_rx)
+    pub fn release_capture(&self) {
+        methods::release_capture(self.window_handle());
     }
 
+    /// Confines the cursor to `rect` in client coordinates while the window is in the foreground.
+    ///
+    /// `None` releases the confinement.
     #[inline]
-    pub fn position(&self) -> Option<ScreenPosition<i32>> {
-        let rx = methods::position(self.window_handle());
-        rx.blocking_recv().ok()
+    pub fn confine_cursor(&self, rect: Option<PhysicalRect<i32>>) {
+        methods::confine_cursor(self.window_handle(), rect);
     }
 
+    /// Hides the cursor on the client area.
     #[inline]
-    pub fn inner_size(&self) -> Option<PhysicalSize<u32>> {
-        let rx = methods::inner_size(self.window_handle());
-        rx.blocking_recv().ok()
+    pub fn hide_cursor(&self, hidden: bool) {
+        methods::hide_cursor(self.window_handle(), hidden);
     }
 
+    /// Hides the cursor and keeps it at the center of the client area.
+    ///
+    /// Use `raw_input` to receive motions of the mouse while the cursor is locked.
     #[inline]
-    pub fn dpi(&self) -> Option<u32> {
-        let rx = methods::dpi(self.window_handle());
-        rx.blocking_recv().ok()
+    pub fn lock_cursor(&self, locked: bool) {
+        methods::lock_cursor(self.window_handle(), locked);
     }
 
+    /// Returns the title of the window.
     #[inline]
-    pub fn set_position<T>(&self, position: T)
-    where
-        T: ToPhysical<i32, Output<i32> = PhysicalPosition<i32>> + Send + 'static,
-    {
-        methods::set_position(self.window_handle(), position);
+    pub fn title(&self) -> Option<String> {
+        methods::title(self.window_handle()).blocking_recv().ok()
     }
 
     #[inline]
-    pub fn set_inner_size<T>(&self, size: T)
-    where
-        T: ToPhysical<u32, Output<u32> = PhysicalSize<u32>> + Send + 'static,
-    {
-        methods::set_size(self.window_handle(), size);
+    pub fn set_title(&self, title: impl Into<String>) {
+        methods::set_title(self.window_handle(), title.into());
     }
 
+    /// Sets the icon of the window, or removes it with `None`.
+    ///
+    /// The icon is selected for the DPI of the window, and selected again when the DPI changes.
+    #[cfg(windows)]
     #[inline]
-    pub fn enable_ime(&self, enabled: bool) {
-        methods::enable_ime(self.window_handle(), enabled)
-            .blocking_recv()
-            .ok();
+    pub fn set_icon(&self, icon: Option<Icon>) {
+        methods::set_icon(self.window_handle(), icon);
     }
 
+    /// Changes the style of the window keeping the client size.
+    #[cfg(windows)]
     #[inline]
-    pub fn show(&self) {
-        methods::show(self.window_handle());
+    pub fn set_style(&self, style: impl Style) {
+        methods::set_style(self.window_handle(), style.style(), style.ex_style());
     }
 
+    /// Switches the resizable frame of the window keeping the client size.
     #[inline]
-    pub fn hide(&self) {
-        methods::hide(self.window_handle());
+    pub fn set_resizable(&self, flag: bool) {
+        methods::set_resizable(self.window_handle(), flag);
     }
 
+    /// Sets the menu bar of the window keeping the client size.
+    #[cfg(windows)]
     #[inline]
-    pub fn minimize(&self) {
-        methods::minimize(self.window_handle());
+    pub fn set_menu(&self, menu: &MenuBar) {
+        methods::set_menu(self.window_handle(), Some(menu.clone()));
     }
 
+    /// Removes the menu bar of the window keeping the client size.
+    #[cfg(windows)]
     #[inline]
-    pub fn maximize(&self) {
-        methods::maximize(self.window_handle());
+    pub fn remove_menu(&self) {
+        methods::set_menu(self.window_handle(), None);
     }
 
+    /// Returns the constraints of the client size.
     #[inline]
-    pub fn restore(&self) {
-        methods::restore(self.window_handle());
+    pub fn size_constraints(&self) -> Option<SizeConstraints> {
+        methods::size_constraints(self.window_handle())
     }
 
+    /// Sets the constraints of the client size, and resizes the window to satisfy them.
     #[inline]
-    pub fn cursor(&self) -> Option<Cursor> {
-        Context::get_window_props(self.window_handle(), |props| props.cursor.clone())
+    pub fn set_size_constraints(&self, constraints: SizeConstraints) {
+        methods::set_size_constraints(self.window_handle(), constraints);
     }
 
+    /// Returns the monitor which the window is mostly on.
     #[inline]
-    pub fn set_cursor(&self, cursor: Cursor) {
-        let hwnd = self.window_handle();
-        methods::set_cursor(hwnd, cursor);
+    pub fn current_monitor(&self) -> Option<Monitor> {
+        methods::current_monitor(self.window_handle())
     }
 
+    /// Returns the placement of the window to restore it with `WindowBuilder::placement`.
+    ///
+    /// The placement of a fullscreen window is the one before entering fullscreen.
     #[inline]
-    pub fn redraw(&self, invalidate_rect: Option<PhysicalRect<i32>>) {
-        methods::redraw(self.window_handle(), invalidate_rect);
+    pub fn placement(&self) -> Option<WindowPlacement> {
+        methods::placement(self.window_handle())
     }
 
+    /// Returns the fullscreen mode of the window, or `None` if the window is not fullscreen.
     #[inline]
-    pub fn is_closed(&self) -> bool {
-        Context::window_is_none(self.window_handle())
+    pub fn fullscreen(&self) -> Option<Fullscreen> {
+        methods::fullscreen(self.window_handle())
     }
 
+    /// Enters fullscreen, or leaves it with `None`.
+    ///
+    /// The style, the placement and the maximized state are restored when leaving fullscreen.
+    /// The menu bar is hidden while the window is fullscreen.
     #[inline]
-    pub fn close(&self) {
-        methods::close(self.window_handle());
+    pub fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
+        methods::set_fullscreen(self.window_handle(), fullscreen);
     }
 
     #[inline]
@@ -1114,6 +1763,7 @@ impl Window {
         methods::set_color_mode(self.window_handle(), mode);
     }
 
+    #[cfg(windows)]
     #[inline]
     pub fn add_raw_procedure_handler<F>(&self, f: F)
     where
@@ -1122,9 +1772,11 @@ impl Window {
         methods::add_raw_procedure_handler(self.window_handle(), f);
     }
 
+    /// Returns a null pointer on the headless backend.
+    #[cfg(windows)]
     #[inline]
     pub fn raw_handle(&self) -> *mut std::ffi::c_void {
-        self.window_handle().as_hwnd().0
+        backend!(self.window_handle().as_hwnd().0, std::ptr::null_mut())
     }
 }
 
@@ -1138,7 +1790,7 @@ impl IsWindow for Window {
 /// Represents a window of async version.
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct AsyncWindow {
-    handle: WindowHandle,
+    pub(crate) handle: WindowHandle,
 }
 
 impl AsyncWindow {
@@ -1213,17 +1865,121 @@ impl AsyncWindow {
         methods::restore(self.window_handle());
     }
 
+    /// Shows the window without activating it.
+    #[inline]
+    pub fn show_without_activation(&self) {
+        methods::show_without_activation(self.window_handle());
+    }
+
+    /// Minimizes the window without activating it.
+    #[inline]
+    pub fn minimize_without_activation(&self) {
+        methods::minimize_without_activation(self.window_handle());
+    }
+
+    #[inline]
+    pub async fn is_visible(&self) -> Option<bool> {
+        let rx = methods::is_visible(self.window_handle());
+        rx.await.ok()
+    }
+
+    #[inline]
+    pub async fn is_minimized(&self) -> Option<bool> {
+        let rx = methods::is_minimized(self.window_handle());
+        rx.await.ok()
+    }
+
+    #[inline]
+    pub async fn is_maximized(&self) -> Option<bool> {
+        let rx = methods::is_maximized(self.window_handle());
+        rx.await.ok()
+    }
+
+    /// Checks if the window has the keyboard focus.
+    #[inline]
+    pub async fn has_focus(&self) -> Option<bool> {
+        let rx = methods::has_focus(self.window_handle());
+        rx.await.ok()
+    }
+
+    /// Checks if the window is the active window.
+    #[inline]
+    pub async fn is_active(&self) -> Option<bool> {
+        let rx = methods::is_active(self.window_handle());
+        rx.await.ok()
+    }
+
+    /// Checks if the window stays above non-topmost windows.
+    #[inline]
+    pub async fn is_topmost(&self) -> Option<bool> {
+        let rx = methods::is_topmost(self.window_handle());
+        rx.await.ok()
+    }
+
+    /// Makes the window stay above non-topmost windows, or reverts it.
+    #[inline]
+    pub fn set_topmost(&self, topmost: bool) {
+        methods::set_topmost(self.window_handle(), topmost);
+    }
+
+    #[inline]
+    pub async fn is_enabled(&self) -> Option<bool> {
+        let rx = methods::is_enabled(self.window_handle());
+        rx.await.ok()
+    }
+
+    /// Returns a future which resolves when the modal window is closed.
+    ///
+    /// Call this before the window is closed. The output is `None` if the window is not modal.
+    #[inline]
+    pub fn modal_result(&self) -> ModalResult {
+        methods::modal_result(self.window_handle())
+    }
+
+    /// Closes the modal window, and resolves `modal_result` with `result`.
+    #[inline]
+    pub fn end_modal(&self, result: usize) {
+        methods::end_modal(self.window_handle(), result);
+    }
+
+    #[cfg(windows)]
     #[inline]
     pub fn cursor(&self) -> Option<Cursor> {
-        Context::get_window_props(self.window_handle(), |props| props.cursor.clone())
+        methods::cursor(self.window_handle())
     }
 
+    #[cfg(windows)]
     #[inline]
     pub fn set_cursor(&self, cursor: Cursor) {
         let hwnd = self.window_handle();
         methods::set_cursor(hwnd, cursor);
     }
 
+    #[inline]
+    pub fn accelerators(&self) -> Option<Accelerators> {
+        methods::accelerators(self.window_handle())
+    }
+
+    #[inline]
+    pub fn set_accelerators(&self, accelerators: &Accelerators) {
+        methods::set_accelerators(self.window_handle(), accelerators.clone());
+    }
+
+    /// Returns registered raw input devices of the window.
+    #[inline]
+    pub fn raw_input(&self) -> Option<RawInputDevices> {
+        methods::raw_input(self.window_handle())
+    }
+
+    /// Registers raw input devices, and unregisters devices which are not in `devices`.
+    ///
+    /// Raw input devices are registered for each process, so the last registered window
+    /// receives inputs.
+    #[inline]
+    pub fn set_raw_input(&self, devices: &RawInputDevices) {
+        methods::set_raw_input(self.window_handle(), devices.clone());
+    }
+
     #[inline]
     pub fn redraw(&self, invalidate_rect: Option<PhysicalRect<i32>>) {
         methods::redraw(self.window_handle(), invalidate_rect);
@@ -1231,7 +1987,7 @@ impl AsyncWindow {
 
     #[inline]
     pub fn is_closed(&self) -> bool {
-        Context::window_is_none(self.window_handle())
+        methods::is_closed(self.window_handle())
     }
 
     #[inline]
@@ -1239,6 +1995,129 @@ impl AsyncWindow {
         methods::close(self.window_handle());
     }
 
+    /// Captures the mouse so that the window receives mouse inputs outside of it.
+    ///
+    /// The window receives `Event::CaptureLost` when another window takes the capture.
+    #[inline]
+    pub fn set_capture(&self) {
+        methods::set_capture(self.window_handle());
+    }
+
+    #[inline]
+    pub fn release_capture(&self) {
+        methods::release_capture(self.window_handle());
+    }
+
+    /// Confines the cursor to `rect` in client coordinates while the window is in the foreground.
+    ///
+    /// `None` releases the confinement.
+    #[inline]
+    pub fn confine_cursor(&self, rect: Option<PhysicalRect<i32>>) {
+        methods::confine_cursor(self.window_handle(), rect);
+    }
+
+    /// Hides the cursor on the client area.
+    #[inline]
+    pub fn hide_cursor(&self, hidden: bool) {
+        methods::hide_cursor(self.window_handle(), hidden);
+    }
+
+    /// Hides the cursor and keeps it at the center of the client area.
+    ///
+    /// Use `raw_input` to receive motions of the mouse while the cursor is locked.
+    #[inline]
+    pub fn lock_cursor(&self, locked: bool) {
+        methods::lock_cursor(self.window_handle(), locked);
+    }
+
+    /// Returns the title of the window.
+    #[inline]
+    pub async fn title(&self) -> Option<String> {
+        methods::title(self.window_handle()).await.ok()
+    }
+
+    #[inline]
+    pub fn set_title(&self, title: impl Into<String>) {
+        methods::set_title(self.window_handle(), title.into());
+    }
+
+    /// Sets the icon of the window, or removes it with `None`.
+    ///
+    /// The icon is selected for the DPI of the window, and selected again when the DPI changes.
+    #[cfg(windows)]
+    #[inline]
+    pub fn set_icon(&self, icon: Option<Icon>) {
+        methods::set_icon(self.window_handle(), icon);
+    }
+
+    /// Changes the style of the window keeping the client size.
+    #[cfg(windows)]
+    #[inline]
+    pub fn set_style(&self, style: impl Style) {
+        methods::set_style(self.window_handle(), style.style(), style.ex_style());
+    }
+
+    /// Switches the resizable frame of the window keeping the client size.
+    #[inline]
+    pub fn set_resizable(&self, flag: bool) {
+        methods::set_resizable(self.window_handle(), flag);
+    }
+
+    /// Sets the menu bar of the window keeping the client size.
+    #[cfg(windows)]
+    #[inline]
+    pub fn set_menu(&self, menu: &MenuBar) {
+        methods::set_menu(self.window_handle(), Some(menu.clone()));
+    }
+
+    /// Removes the menu bar of the window keeping the client size.
+    #[cfg(windows)]
+    #[inline]
+    pub fn remove_menu(&self) {
+        methods::set_menu(self.window_handle(), None);
+    }
+
+    /// Returns the constraints of the client size.
+    #[inline]
+    pub fn size_constraints(&self) -> Option<SizeConstraints> {
+        methods::size_constraints(self.window_handle())
+    }
+
+    /// Sets the constraints of the client size, and resizes the window to satisfy them.
+    #[inline]
+    pub fn set_size_constraints(&self, constraints: SizeConstraints) {
+        methods::set_size_constraints(self.window_handle(), constraints);
+    }
+
+    /// Returns the monitor which the window is mostly on.
+    #[inline]
+    pub fn current_monitor(&self) -> Option<Monitor> {
+        methods::current_monitor(self.window_handle())
+    }
+
+    /// Returns the placement of the window to restore it with `WindowBuilder::placement`.
+    ///
+    /// The placement of a fullscreen window is the one before entering fullscreen.
+    #[inline]
+    pub fn placement(&self) -> Option<WindowPlacement> {
+        methods::placement(self.window_handle())
+    }
+
+    /// Returns the fullscreen mode of the window, or `None` if the window is not fullscreen.
+    #[inline]
+    pub fn fullscreen(&self) -> Option<Fullscreen> {
+        methods::fullscreen(self.window_handle())
+    }
+
+    /// Enters fullscreen, or leaves it with `None`.
+    ///
+    /// The style, the placement and the maximized state are restored when leaving fullscreen.
+    /// The menu bar is hidden while the window is fullscreen.
+    #[inline]
+    pub fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
+        methods::set_fullscreen(self.window_handle(), fullscreen);
+    }
+
     #[inline]
     pub fn post_app_event(&self, app: event::App) {
         methods::post_app_event(self.window_handle(), app);
@@ -1264,6 +2143,7 @@ impl AsyncWindow {
         methods::set_color_mode(self.window_handle(), mode);
     }
 
+    #[cfg(windows)]
     #[inline]
     pub fn add_raw_procedure_handler<F>(&self, f: F)
     where
@@ -1272,9 +2152,11 @@ impl AsyncWindow {
         methods::add_raw_procedure_handler(self.window_handle(), f);
     }
 
+    /// Returns a null pointer on the headless backend.
+    #[cfg(windows)]
     #[inline]
     pub fn raw_handle(&self) -> *mut std::ffi::c_void {
-        self.window_handle().as_hwnd().0
+        backend!(self.window_handle().as_hwnd().0, std::ptr::null_mut())
     }
 }
 
@@ -1288,26 +2170,40 @@ impl IsWindow for AsyncWindow {
 fn to_raw_window_handle(
     this: &impl IsWindow,
 ) -> std::result::Result<raw_window_handle::WindowHandle<'_>, raw_window_handle::HandleError> {
-    use raw_window_handle::{RawWindowHandle, Win32WindowHandle, WindowHandle};
-    use std::num::NonZero;
-    let this = this.window_handle();
-    unsafe {
-        let mut handle = Win32WindowHandle::new(
-            std::mem::transmute::<usize, std::num::NonZeroIsize>(this.0.0.addr()),
-        );
-        handle.hinstance = NonZero::new(GetWindowLongPtrW(this.as_hwnd(), GWLP_HINSTANCE));
-        Ok(WindowHandle::borrow_raw(RawWindowHandle::Win32(handle)))
-    }
+    if this.window_handle().is_replayed() {
+        return Err(raw_window_handle::HandleError::Unavailable);
+    }
+    backend!(
+        {
+            use raw_window_handle::{RawWindowHandle, Win32WindowHandle, WindowHandle};
+            use std::num::NonZero;
+            let this = this.window_handle();
+            unsafe {
+                let mut handle = Win32WindowHandle::new(std::mem::transmute::<
+                    usize,
+                    std::num::NonZeroIsize,
+                >(this.as_hwnd().0.addr()));
+                handle.hinstance = NonZero::new(GetWindowLongPtrW(this.as_hwnd(), GWLP_HINSTANCE));
+                Ok(WindowHandle::borrow_raw(RawWindowHandle::Win32(handle)))
+            }
+        },
+        Err(raw_window_handle::HandleError::NotSupported)
+    )
 }
 
 fn to_raw_display_handle(
     _this: &impl IsWindow,
 ) -> std::result::Result<raw_window_handle::DisplayHandle<'_>, raw_window_handle::HandleError> {
-    Ok(unsafe {
-        raw_window_handle::DisplayHandle::borrow_raw(raw_window_handle::RawDisplayHandle::Windows(
-            raw_window_handle::WindowsDisplayHandle::new(),
-        ))
-    })
+    backend!(
+        Ok(unsafe {
+            raw_window_handle::DisplayHandle::borrow_raw(
+                raw_window_handle::RawDisplayHandle::Windows(
+                    raw_window_handle::WindowsDisplayHandle::new(),
+                ),
+            )
+        }),
+        Err(raw_window_handle::HandleError::NotSupported)
+    )
 }
 
 impl raw_window_handle::HasWindowHandle for Window {
@@ -1362,9 +2258,12 @@ pub struct InnerWindowBuilder<'a, Rx, Pos = (), Sz = ()> {
     visibility: bool,
     enable_ime: bool,
     visible_ime_candidate_window: bool,
+    #[cfg(windows)]
     accept_drop_files: bool,
+    #[cfg(windows)]
     cursor: Cursor,
     nc_hittest: bool,
+    auto_capture: bool,
 }
 
 impl<'a, Rx> InnerWindowBuilder<'a, Rx> {
@@ -1382,10 +2281,12 @@ impl<'a, Rx> InnerWindowBuilder<'a, Rx> {
             visibility: true,
             enable_ime: true,
             visible_ime_candidate_window: true,
+            #[cfg(windows)]
             accept_drop_files: false,
-            cursor: Context::get_window_props(parent.window_handle(), |props| props.cursor.clone())
-                .unwrap_or_default(),
+            #[cfg(windows)]
+            cursor: methods::cursor(parent.window_handle()).unwrap_or_default(),
             nc_hittest: false,
+            auto_capture: true,
         }
     }
 }
@@ -1404,9 +2305,12 @@ impl<'a, Rx, Pos, Sz> InnerWindowBuilder<'a, Rx, Pos, Sz> {
             visibility: self.visibility,
             enable_ime: self.enable_ime,
             visible_ime_candidate_window: self.visible_ime_candidate_window,
+            #[cfg(windows)]
             accept_drop_files: self.accept_drop_files,
+            #[cfg(windows)]
             cursor: self.cursor,
             nc_hittest: self.nc_hittest,
+            auto_capture: self.auto_capture,
         }
     }
 
@@ -1423,9 +2327,12 @@ impl<'a, Rx, Pos, Sz> InnerWindowBuilder<'a, Rx, Pos, Sz> {
             visibility: self.visibility,
             enable_ime: self.enable_ime,
             visible_ime_candidate_window: self.visible_ime_candidate_window,
+            #[cfg(windows)]
             accept_drop_files: self.accept_drop_files,
+            #[cfg(windows)]
             cursor: self.cursor,
             nc_hittest: self.nc_hittest,
+            auto_capture: self.auto_capture,
         }
     }
 
@@ -1447,6 +2354,7 @@ impl<'a, Rx, Pos, Sz> InnerWindowBuilder<'a, Rx, Pos, Sz> {
         self
     }
 
+    #[cfg(windows)]
     #[inline]
     pub fn accept_drop_files(mut self, flag: bool) -> Self {
         self.accept_drop_files = flag;
@@ -1458,6 +2366,18 @@ impl<'a, Rx, Pos, Sz> InnerWindowBuilder<'a, Rx, Pos, Sz> {
         self.nc_hittest = flag;
         self
     }
+
+    /// Captures the mouse while a button is pressed. The default is `true`.
+    ///
+    /// The capture is released when all buttons are released. A capture taken by
+    /// `Window::set_capture` is kept until `Window::release_capture` is called.
+    ///
+    /// See `Window::set_capture`.
+    #[inline]
+    pub fn auto_capture(mut self, flag: bool) -> Self {
+        self.auto_capture = flag;
+        self
+    }
 }
 
 impl<Pos, Sz> InnerWindowBuilder<'_, EventReceiver, Pos, Sz>
@@ -1472,7 +2392,7 @@ where
         let parent = self.parent_inner;
         let props = BuilderProps::new_inner(self);
         UiThread::send_task(move || {
-            tx.send(create_window(props, |handle| {
+            tx.send(methods::create_window(props, |handle| {
                 WindowKind::InnerWindow(InnerWindow { parent, handle })
             }))
             .ok();
@@ -1499,7 +2419,7 @@ where
         let parent = self.parent_inner;
         let props = BuilderProps::new_inner(self);
         UiThread::send_task(move || {
-            tx.send(create_window(props, |handle| {
+            tx.send(methods::create_window(props, |handle| {
                 WindowKind::InnerWindow(InnerWindow { parent, handle })
             }))
             .ok();
@@ -1517,8 +2437,8 @@ where
 /// Represents an inner window.
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct InnerWindow {
-    parent: WindowHandle,
-    handle: WindowHandle,
+    pub(crate) parent: WindowHandle,
+    pub(crate) handle: WindowHandle,
 }
 
 impl InnerWindow {
@@ -1533,17 +2453,8 @@ impl InnerWindow {
 
     #[inline]
     pub fn position(&self) -> Option<PhysicalPosition<i32>> {
-        let self_rx = methods::position(self.handle);
-        let self_pos = self_rx.blocking_recv().ok()?;
-        let position = unsafe {
-            let mut pt = POINT {
-                x: self_pos.x,
-                y: self_pos.y,
-            };
-            let _ = ScreenToClient(self.parent.as_hwnd(), &mut pt);
-            PhysicalPosition::new(pt.x, pt.y)
-        };
-        Some(position)
+        let rx = methods::inner_position(self.handle, self.parent);
+        rx.blocking_recv().ok()
     }
 
     #[inline]
@@ -1581,11 +2492,13 @@ impl InnerWindow {
             .ok();
     }
 
+    #[cfg(windows)]
     #[inline]
     pub fn cursor(&self) -> Option<Cursor> {
-        Context::get_window_props(self.handle, |props| props.cursor.clone())
+        methods::cursor(self.handle)
     }
 
+    #[cfg(windows)]
     #[inline]
     pub fn set_cursor(&self, cursor: Cursor) {
         methods::set_cursor(self.handle, cursor);
@@ -1601,11 +2514,47 @@ impl InnerWindow {
         methods::set_color_mode(self.window_handle(), mode);
     }
 
+    /// Captures the mouse so that the window receives mouse inputs outside of it.
+    ///
+    /// The window receives `Event::CaptureLost` when another window takes the capture.
+    #[inline]
+    pub fn set_capture(&self) {
+        methods::set_capture(self.window_handle());
+    }
+
+    #[inline]
+    pub fn release_capture(&self) {
+        methods::release_capture(self.window_handle());
+    }
+
+    /// Confines the cursor to `rect` in client coordinates while the window is in the foreground.
+    ///
+    /// `None` releases the confinement.
+    #[inline]
+    pub fn confine_cursor(&self, rect: Option<PhysicalRect<i32>>) {
+        methods::confine_cursor(self.window_handle(), rect);
+    }
+
+    /// Hides the cursor on the client area.
+    #[inline]
+    pub fn hide_cursor(&self, hidden: bool) {
+        methods::hide_cursor(self.window_handle(), hidden);
+    }
+
+    /// Hides the cursor and keeps it at the center of the client area.
+    ///
+    /// Use `raw_input` to receive motions of the mouse while the cursor is locked.
+    #[inline]
+    pub fn lock_cursor(&self, locked: bool) {
+        methods::lock_cursor(self.window_handle(), locked);
+    }
+
     #[inline]
     pub fn post_app_event(&self, app: event::App) {
         methods::post_app_event(self.handle, app);
     }
 
+    #[cfg(windows)]
     #[inline]
     pub fn add_raw_procedure_handler<F>(&self, f: F)
     where
@@ -1614,17 +2563,19 @@ impl InnerWindow {
         methods::add_raw_procedure_handler(self.window_handle(), f);
     }
 
+    /// Returns a null pointer on the headless backend.
+    #[cfg(windows)]
     #[inline]
     pub fn raw_handle(&self) -> *mut std::ffi::c_void {
-        self.handle.as_hwnd().0
+        backend!(self.handle.as_hwnd().0, std::ptr::null_mut())
     }
 }
 
 /// Represents an inner window of async version.
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct AsyncInnerWindow {
-    parent: WindowHandle,
-    handle: WindowHandle,
+    pub(crate) parent: WindowHandle,
+    pub(crate) handle: WindowHandle,
 }
 
 impl AsyncInnerWindow {
@@ -1641,17 +2592,8 @@ impl AsyncInnerWindow {
 
     #[inline]
     pub async fn position(&self) -> Option<PhysicalPosition<i32>> {
-        let self_rx = methods::position(self.handle);
-        let self_pos = self_rx.await.ok()?;
-        let position = unsafe {
-            let mut pt = POINT {
-                x: self_pos.x,
-                y: self_pos.y,
-            };
-            let _ = ScreenToClient(self.parent.as_hwnd(), &mut pt);
-            PhysicalPosition::new(pt.x, pt.y)
-        };
-        Some(position)
+        let rx = methods::inner_position(self.handle, self.parent);
+        rx.await.ok()
     }
 
     #[inline]
@@ -1689,16 +2631,53 @@ impl AsyncInnerWindow {
             .ok();
     }
 
+    #[cfg(windows)]
     #[inline]
     pub fn cursor(&self) -> Option<Cursor> {
-        Context::get_window_props(self.handle, |props| props.cursor.clone())
+        methods::cursor(self.handle)
     }
 
+    #[cfg(windows)]
     #[inline]
     pub fn set_cursor(&self, cursor: Cursor) {
         methods::set_cursor(self.handle, cursor);
     }
 
+    /// Captures the mouse so that the window receives mouse inputs outside of it.
+    ///
+    /// The window receives `Event::CaptureLost` when another window takes the capture.
+    #[inline]
+    pub fn set_capture(&self) {
+        methods::set_capture(self.window_handle());
+    }
+
+    #[inline]
+    pub fn release_capture(&self) {
+        methods::release_capture(self.window_handle());
+    }
+
+    /// Confines the cursor to `rect` in client coordinates while the window is in the foreground.
+    ///
+    /// `None` releases the confinement.
+    #[inline]
+    pub fn confine_cursor(&self, rect: Option<PhysicalRect<i32>>) {
+        methods::confine_cursor(self.window_handle(), rect);
+    }
+
+    /// Hides the cursor on the client area.
+    #[inline]
+    pub fn hide_cursor(&self, hidden: bool) {
+        methods::hide_cursor(self.window_handle(), hidden);
+    }
+
+    /// Hides the cursor and keeps it at the center of the client area.
+    ///
+    /// Use `raw_input` to receive motions of the mouse while the cursor is locked.
+    #[inline]
+    pub fn lock_cursor(&self, locked: bool) {
+        methods::lock_cursor(self.window_handle(), locked);
+    }
+
     #[inline]
     pub fn post_app_event(&self, app: event::App) {
         methods::post_app_event(self.handle, app);
@@ -1714,6 +2693,7 @@ impl AsyncInnerWindow {
         methods::set_color_mode(self.window_handle(), mode);
     }
 
+    #[cfg(windows)]
     #[inline]
     pub fn add_raw_procedure_handler<F>(&self, f: F)
     where
@@ -1722,9 +2702,11 @@ impl AsyncInnerWindow {
         methods::add_raw_procedure_handler(self.window_handle(), f);
     }
 
+    /// Returns a null pointer on the headless backend.
+    #[cfg(windows)]
     #[inline]
     pub fn raw_handle(&self) -> *mut std::ffi::c_void {
-        self.handle.as_hwnd().0
+        backend!(self.handle.as_hwnd().0, std::ptr::null_mut())
     }
 }
 
@@ -1779,59 +2761,3 @@ impl raw_window_handle::HasDisplayHandle for AsyncInnerWindow {
         to_raw_display_handle(self)
     }
 }
-
-impl PartialEq<Window> for WindowKind {
-    #[inline]
-    fn eq(&self, other: &Window) -> bool {
-        self.window_handle() == other.window_handle()
-    }
-}
-
-impl PartialEq<InnerWindow> for WindowKind {
-    #[inline]
-    fn eq(&self, other: &InnerWindow) -> bool {
-        self.window_handle() == other.handle
-    }
-}
-
-impl PartialEq<WindowKind> for Window {
-    #[inline]
-    fn eq(&self, other: &WindowKind) -> bool {
-        self.window_handle() == other.window_handle()
-    }
-}
-
-impl PartialEq<WindowKind> for InnerWindow {
-    #[inline]
-    fn eq(&self, other: &WindowKind) -> bool {
-        self.handle == other.window_handle()
-    }
-}
-
-impl PartialEq<AsyncWindowKind> for AsyncWindow {
-    #[inline]
-    fn eq(&self, other: &AsyncWindowKind) -> bool {
-        self.window_handle() == other.window_handle()
-    }
-}
-
-impl PartialEq<AsyncWindowKind> for AsyncInnerWindow {
-    #[inline]
-    fn eq(&self, other: &AsyncWindowKind) -> bool {
-        self.handle == other.window_handle()
-    }
-}
-
-impl PartialEq<AsyncWindow> for AsyncWindowKind {
-    #[inline]
-    fn eq(&self, other: &AsyncWindow) -> bool {
-        self.window_handle() == other.window_handle()
-    }
-}
-
-impl PartialEq<AsyncInnerWindow> for AsyncWindowKind {
-    #[inline]
-    fn eq(&self, other: &AsyncInnerWindow) -> bool {
-        self.window_handle() == other.handle
-    }
-}
@@ -0,0 +1,1085 @@
+use crate::*;
+use std::sync::OnceLock;
+use tokio::sync::oneshot;
+use windows::Win32::System::Ole::{IDropTarget, RegisterDragDrop};
+use windows::Win32::{
+    Foundation::{HINSTANCE, HWND, LPARAM, POINT, RECT, WPARAM},
+    Graphics::Dwm::*,
+    Graphics::Gdi::{
+        GetStockObject, HBRUSH, RDW_INVALIDATE, RedrawWindow, ScreenToClient, WHITE_BRUSH,
+    },
+    System::LibraryLoader::GetModuleHandleW,
+    UI::HiDpi::GetDpiForWindow,
+    UI::Input::KeyboardAndMouse::{
+        EnableWindow, GetActiveWindow, GetFocus, IsWindowEnabled, SetFocus,
+    },
+    UI::Input::Touch::{GESTURECONFIG, GESTURECONFIG_ID, SetGestureConfig},
+    UI::WindowsAndMessaging::*,
+};
+use windows::core::{BOOL, HSTRING, PCWSTR};
+
+const GC_ALLGESTURES: u32 = 0x01;
+
+const WINDOW_CLASS_NAME: PCWSTR = windows::core::w!("wiard_window_class");
+
+pub(crate) fn register_class() {
+    unsafe {
+        let wc = WNDCLASSEXW {
+            cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
+            style: CS_VREDRAW | CS_HREDRAW | CS_DBLCLKS,
+            lpfnWndProc: Some(procedure::window_proc),
+            hInstance: HINSTANCE(GetModuleHandleW(None).unwrap().0),
+            lpszClassName: WINDOW_CLASS_NAME,
+            hbrBackground: HBRUSH(GetStockObject(WHITE_BRUSH).0),
+            hCursor: LoadCursorW(None, IDC_ARROW).unwrap(),
+            ..Default::default()
+        };
+        if RegisterClassExW(&wc) == 0 {
+            panic!("RegisterClassExW failed");
+        }
+    }
+}
+
+/// The state of a window saved when entering fullscreen.
+pub(crate) struct FullscreenState {
+    pub fullscreen: Fullscreen,
+    pub style: WINDOW_STYLE,
+    pub ex_style: WINDOW_EX_STYLE,
+    pub placement: WINDOWPLACEMENT,
+}
+
+pub(crate) struct WindowProps {
+    pub imm_context: ime::ImmContext,
+    pub visible_ime_candidate_window: bool,
+    pub auto_close: bool,
+    pub icon: Option<Icon>,
+    pub cursor: Cursor,
+    pub cursor_step: usize,
+    pub parent: Option<WindowHandle>,
+    pub modal: Option<ModalState>,
+    pub nc_hittest: bool,
+    pub size_constraints: SizeConstraints,
+    pub auto_capture: bool,
+    pub capturing: bool,
+    pub auto_captured: bool,
+    pub cursor_confine: Option<PhysicalRect<i32>>,
+    pub cursor_hidden: bool,
+    pub cursor_locked: bool,
+    pub redrawing: bool,
+    pub resizing: bool,
+    pub minimized: bool,
+    pub fullscreen: Option<FullscreenState>,
+    pub menu: Option<MenuBar>,
+    pub accelerators: Accelerators,
+    pub batch_text_input: bool,
+    pub pointer_input: bool,
+    pub pointers: pointer::PointerTracker,
+    pub gestures: bool,
+    pub gesture_recognizer: gesture::GestureRecognizer,
+    pub gesture_info: gesture::GestureInfoDecoder,
+    pub input_clock: std::time::Instant,
+    pub wheel: wheel::WheelAccumulator,
+    pub wheel_mouse_state: Option<MouseState>,
+    pub clicks: click::ClickTracker,
+    pub raw_input: RawInputDevices,
+    pub utf16: text::Utf16Decoder,
+    pub theme_menu: Theme,
+    pub color_mode: ColorMode,
+    pub color_mode_state: ColorModeState,
+}
+
+/// Win32 windows.
+pub(crate) type Context = context::Context<WindowProps>;
+
+static CONTEXT: OnceLock<context::ContextImpl<WindowProps>> = OnceLock::new();
+
+impl context::Props for WindowProps {
+    fn context() -> &'static context::ContextImpl<Self> {
+        CONTEXT.get_or_init(context::ContextImpl::new)
+    }
+
+    fn parent(&self) -> Option<WindowHandle> {
+        self.parent
+    }
+
+    fn on_register(handle: WindowHandle) {
+        UiThread::send_task(move || {
+            procedure::new_raw_procedure_handler(handle);
+        });
+    }
+
+    fn close_child(handle: WindowHandle) {
+        unsafe {
+            PostMessageW(Some(handle.as_hwnd()), WM_CLOSE, WPARAM(0), LPARAM(0)).ok();
+        }
+    }
+
+    fn on_cleanup() {
+        ime::shutdown_text_service();
+    }
+}
+
+impl Context {
+    pub fn init() -> Result<()> {
+        register_class();
+        ime::init_text_service();
+        Ok(())
+    }
+}
+
+/// Returns the offset from workspace coordinates of `WINDOWPLACEMENT` to screen coordinates.
+fn workspace_offset(hwnd: HWND) -> PhysicalPosition<i32> {
+    unsafe {
+        let ex_style = WINDOW_EX_STYLE(GetWindowLongPtrW(hwnd, GWL_EXSTYLE) as u32);
+        if ex_style.contains(WS_EX_TOOLWINDOW) {
+            return PhysicalPosition::new(0, 0);
+        }
+    }
+    Monitor::primary()
+        .info()
+        .map_or(PhysicalPosition::new(0, 0), |info| {
+            PhysicalPosition::new(
+                info.work_area.left - info.bounds.left,
+                info.work_area.top - info.bounds.top,
+            )
+        })
+}
+
+pub fn create_window<Pos, Sz, Dt>(
+    props: BuilderProps<Pos, Sz, Dt>,
+    f: impl FnOnce(WindowHandle) -> WindowKind,
+) -> Result<WindowHandle>
+where
+    Pos: ToPhysical<i32, Output<i32> = PhysicalPosition<i32>> + Send + 'static,
+    Sz: ToPhysical<u32, Output<u32> = PhysicalSize<u32>> + Send + 'static,
+    Dt: FnOnce(&WindowHandle) -> IDropTarget + Send + 'static,
+{
+    unsafe {
+        let ex_style = if props.always_on_top {
+            props.ex_style | WS_EX_TOPMOST
+        } else {
+            props.ex_style
+        };
+        let dpi = get_dpi_from_point(ScreenPosition::new(0, 0));
+        let position = props.position.to_physical(dpi as i32);
+        let size = props.inner_size.to_physical(dpi);
+        let rc =
+            adjust_window_rect_ex_for_dpi(size, props.style, props.menu.is_some(), ex_style, dpi);
+        let placement_rect = props.placement.as_ref().map(|placement| {
+            let monitors = Monitor::all()
+                .iter()
+                .filter_map(|m| m.info())
+                .collect::<Vec<_>>();
+            placement.resolve(&monitors)
+        });
+        let (position, rc) = match placement_rect {
+            Some(prc) => (
+                PhysicalPosition::new(prc.left, prc.top),
+                RECT {
+                    left: 0,
+                    top: 0,
+                    right: prc.right - prc.left,
+                    bottom: prc.bottom - prc.top,
+                },
+            ),
+            None => (position, rc),
+        };
+        let hinstance: Option<HINSTANCE> = Some(GetModuleHandleW(None).unwrap().into());
+        let parent = props
+            .parent_inner
+            .or(props.parent.filter(|_| props.owned))
+            .map(|p| p.as_hwnd());
+        let modal_owner = props.parent.filter(|_| props.owned && props.modal);
+        let hwnd = CreateWindowExW(
+            ex_style,
+            WINDOW_CLASS_NAME,
+            &HSTRING::from(props.title.as_str()),
+            props.style,
+            position.x,
+            position.y,
+            rc.right - rc.left,
+            rc.bottom - rc.top,
+            parent,
+            props.menu.as_ref().map(|m| m.as_hmenu()),
+            hinstance,
+            None,
+        )?;
+        let handle = WindowHandle::new(hwnd);
+        let dark_mode = BOOL::from(is_system_dark_mode());
+        if dark_mode.as_bool() {
+            info!("Dark mode");
+        } else {
+            info!("Light mode");
+        }
+        if props.parent_inner.is_none() {
+            let ret = DwmSetWindowAttribute(
+                hwnd,
+                DWMWA_USE_IMMERSIVE_DARK_MODE,
+                &dark_mode as *const BOOL as *const std::ffi::c_void,
+                std::mem::size_of::<BOOL>() as u32,
+            );
+            if let Err(e) = ret {
+                error!("DwmSetWindowAttribute DWMWA_USE_IMMERSIVE_DARK_MODE: {e}");
+            }
+        }
+        let imm_context = ime::ImmContext::new(handle);
+        if props.enable_ime {
+            imm_context.enable();
+        } else {
+            imm_context.disable();
+        }
+        if let Some(icon) = props.icon.as_ref() {
+            procedure::set_window_icon(hwnd, icon, GetDpiForWindow(hwnd));
+        }
+        if let Some(drop_target) = props.drop_target {
+            RegisterDragDrop(hwnd, &drop_target(&handle))?;
+        }
+        if props.gestures && !props.pointer_input {
+            let config = GESTURECONFIG {
+                dwID: GESTURECONFIG_ID(0),
+                dwWant: GC_ALLGESTURES,
+                dwBlock: 0,
+            };
+            SetGestureConfig(
+                hwnd,
+                0,
+                &[config],
+                std::mem::size_of::<GESTURECONFIG>() as u32,
+            )?;
+        }
+        if !props.raw_input.is_empty() {
+            raw_input::register(hwnd, &RawInputDevices::new(), &props.raw_input)?;
+        }
+        set_preferred_app_mode(APPMODE_ALLOWDARK);
+        refresh_immersive_color_policy_state();
+        let window_props = WindowProps {
+            imm_context,
+            visible_ime_candidate_window: props.visible_ime_candidate_window,
+            auto_close: props.auto_close,
+            icon: props.icon,
+            cursor: props.cursor,
+            cursor_step: 0,
+            parent: props.parent,
+            modal: modal_owner.map(ModalState::new),
+            nc_hittest: props.nc_hittest,
+            size_constraints: props.size_constraints,
+            auto_capture: props.auto_capture,
+            capturing: false,
+            auto_captured: false,
+            cursor_confine: None,
+            cursor_hidden: false,
+            cursor_locked: false,
+            redrawing: false,
+            resizing: false,
+            minimized: false,
+            fullscreen: None,
+            menu: props.menu,
+            accelerators: props.accelerators,
+            batch_text_input: props.batch_text_input,
+            pointer_input: props.pointer_input,
+            pointers: pointer::PointerTracker::new(),
+            gestures: props.gestures,
+            gesture_recognizer: gesture::GestureRecognizer::new(),
+            gesture_info: gesture::GestureInfoDecoder::new(),
+            input_clock: std::time::Instant::now(),
+            wheel: wheel::WheelAccumulator::new(),
+            wheel_mouse_state: None,
+            clicks: click::ClickTracker::new(),
+            raw_input: props.raw_input,
+            utf16: text::Utf16Decoder::new(),
+            theme_menu: Theme::new(hwnd, &["Menu"]),
+            color_mode: props.color_mode,
+            color_mode_state: if dark_mode.as_bool() {
+                ColorModeState::Dark
+            } else {
+                ColorModeState::Light
+            },
+        };
+        Context::register_window(f(handle), window_props, props.event_rx_id);
+        if let Some(owner) = modal_owner {
+            let _ = EnableWindow(owner.as_hwnd(), false);
+        }
+        match (props.placement.as_ref(), placement_rect) {
+            (Some(placement), Some(rc)) => {
+                let show_cmd = match placement.state {
+                    _ if !props.visiblity => SW_HIDE,
+                    PlacementState::Normal => SW_SHOWNORMAL,
+                    PlacementState::Maximized => SW_SHOWMAXIMIZED,
+                    PlacementState::Minimized => SW_SHOWMINIMIZED,
+                };
+                let offset = workspace_offset(hwnd);
+                let wp = WINDOWPLACEMENT {
+                    length: std::mem::size_of::<WINDOWPLACEMENT>() as u32,
+                    showCmd: show_cmd.0 as u32,
+                    rcNormalPosition: RECT {
+                        left: rc.left - offset.x,
+                        top: rc.top - offset.y,
+                        right: rc.right - offset.x,
+                        bottom: rc.bottom - offset.y,
+                    },
+                    ..Default::default()
+                };
+                if let Err(e) = SetWindowPlacement(hwnd, &wp) {
+                    error!("SetWindowPlacement: {e}");
+                }
+            }
+            _ if props.visiblity => {
+                let _ = ShowWindow(hwnd, SW_SHOW);
+            }
+            _ => {}
+        }
+        Ok(handle)
+    }
+}
+
+#[inline]
+pub fn position(handle: WindowHandle) -> oneshot::Receiver<ScreenPosition<i32>> {
+    let (tx, rx) = oneshot::channel::<ScreenPosition<i32>>();
+    UiThread::send_task(move || {
+        let rc = get_window_rect(handle.into());
+        tx.send((rc.left, rc.top).into()).ok();
+    });
+    rx
+}
+
+#[inline]
+pub fn inner_position(
+    handle: WindowHandle,
+    parent: WindowHandle,
+) -> oneshot::Receiver<PhysicalPosition<i32>> {
+    let (tx, rx) = oneshot::channel::<PhysicalPosition<i32>>();
+    UiThread::send_task(move || unsafe {
+        let rc = get_window_rect(handle.into());
+        let mut pt = POINT {
+            x: rc.left,
+            y: rc.top,
+        };
+        let _ = ScreenToClient(parent.as_hwnd(), &mut pt);
+        tx.send(PhysicalPosition::new(pt.x, pt.y)).ok();
+    });
+    rx
+}
+
+#[inline]
+pub fn inner_size(handle: WindowHandle) -> oneshot::Receiver<PhysicalSize<u32>> {
+    let (tx, rx) = oneshot::channel::<PhysicalSize<u32>>();
+    UiThread::send_task(move || {
+        let rc = get_client_rect(handle.into());
+        tx.send(PhysicalSize::new(
+            (rc.right - rc.left) as u32,
+            (rc.bottom - rc.top) as u32,
+        ))
+        .ok();
+    });
+    rx
+}
+
+#[inline]
+pub fn dpi(handle: WindowHandle) -> oneshot::Receiver<u32> {
+    let (tx, rx) = oneshot::channel::<u32>();
+    UiThread::send_task(move || unsafe {
+        let dpi = GetDpiForWindow(handle.as_hwnd());
+        tx.send(dpi).ok();
+    });
+    rx
+}
+
+#[inline]
+pub fn enable_ime(handle: WindowHandle, enabled: bool) -> oneshot::Receiver<()> {
+    let (tx, rx) = oneshot::channel::<()>();
+    UiThread::send_task(move || {
+        Context::set_window_props(handle, |props| {
+            if enabled {
+                props.imm_context.enable();
+            } else {
+                props.imm_context.disable();
+            }
+        });
+        tx.send(()).ok();
+    });
+    rx
+}
+
+#[inline]
+pub fn set_position<T>(handle: WindowHandle, position: T)
+where
+    T: ToPhysical<i32, Output<i32> = PhysicalPosition<i32>> + Send + 'static,
+{
+    UiThread::send_task(move || unsafe {
+        let dpi = GetDpiForWindow(handle.as_hwnd()) as i32;
+        let position = position.to_physical(dpi);
+        SetWindowPos(
+            handle.as_hwnd(),
+            None,
+            position.x,
+            position.y,
+            0,
+            0,
+            SWP_NOZORDER | SWP_NOSIZE,
+        )
+        .ok();
+    });
+}
+
+#[inline]
+pub fn set_size<T>(handle: WindowHandle, size: T)
+where
+    T: ToPhysical<u32, Output<u32> = PhysicalSize<u32>> + Send + 'static,
+{
+    UiThread::send_task(move || unsafe {
+        let dpi = GetDpiForWindow(handle.as_hwnd());
+        let size = size.to_physical(dpi);
+        SetWindowPos(
+            handle.as_hwnd(),
+            None,
+            0,
+            0,
+            size.width as i32,
+            size.height as i32,
+            SWP_NOZORDER | SWP_NOMOVE,
+        )
+        .ok();
+    });
+}
+
+#[inline]
+pub fn close(handle: WindowHandle) {
+    unsafe {
+        PostMessageW(Some(handle.as_hwnd()), WM_CLOSE, WPARAM(0), LPARAM(0)).ok();
+    }
+}
+
+#[inline]
+pub fn show(handle: WindowHandle) {
+    unsafe {
+        let _ = ShowWindowAsync(handle.as_hwnd(), SW_SHOW);
+    }
+}
+
+#[inline]
+pub fn hide(handle: WindowHandle) {
+    unsafe {
+        let _ = ShowWindowAsync(handle.as_hwnd(), SW_HIDE);
+    }
+}
+
+#[inline]
+pub fn minimize(handle: WindowHandle) {
+    unsafe {
+        let _ = ShowWindowAsync(handle.as_hwnd(), SW_MINIMIZE);
+    }
+}
+
+#[inline]
+pub fn maximize(handle: WindowHandle) {
+    unsafe {
+        let _ = ShowWindowAsync(handle.as_hwnd(), SW_SHOWMAXIMIZED);
+    }
+}
+
+#[inline]
+pub fn restore(handle: WindowHandle) {
+    unsafe {
+        let _ = ShowWindowAsync(handle.as_hwnd(), SW_RESTORE);
+    }
+}
+
+#[inline]
+pub fn is_closed(handle: WindowHandle) -> bool {
+    Context::window_is_none(handle)
+}
+
+#[inline]
+pub fn cursor(handle: WindowHandle) -> Option<Cursor> {
+    Context::get_window_props(handle, |props| props.cursor.clone())
+}
+
+#[inline]
+pub fn set_cursor(handle: WindowHandle, cursor: Cursor) {
+    UiThread::send_task(move || {
+        Context::set_window_props(handle, |props| {
+            props.cursor = cursor;
+            props.cursor_step = 0;
+        });
+        procedure::update_cursor_mode(handle);
+    });
+}
+
+#[inline]
+pub fn set_capture(handle: WindowHandle) {
+    UiThread::send_task(move || procedure::set_capture(handle));
+}
+
+#[inline]
+pub fn release_capture(handle: WindowHandle) {
+    UiThread::send_task(move || procedure::release_capture(handle));
+}
+
+#[inline]
+pub fn confine_cursor(handle: WindowHandle, rect: Option<PhysicalRect<i32>>) {
+    UiThread::send_task(move || {
+        Context::set_window_props(handle, |props| props.cursor_confine = rect);
+        procedure::update_cursor_mode(handle);
+    });
+}
+
+#[inline]
+pub fn hide_cursor(handle: WindowHandle, hidden: bool) {
+    UiThread::send_task(move || {
+        Context::set_window_props(handle, |props| props.cursor_hidden = hidden);
+        procedure::update_cursor_mode(handle);
+    });
+}
+
+#[inline]
+pub fn lock_cursor(handle: WindowHandle, locked: bool) {
+    UiThread::send_task(move || {
+        Context::set_window_props(handle, |props| props.cursor_locked = locked);
+        procedure::update_cursor_mode(handle);
+    });
+}
+
+#[inline]
+pub fn accelerators(handle: WindowHandle) -> Option<Accelerators> {
+    Context::get_window_props(handle, |props| props.accelerators.clone())
+}
+
+#[inline]
+pub fn set_accelerators(handle: WindowHandle, accelerators: Accelerators) {
+    UiThread::send_task(move || {
+        Context::set_window_props(handle, |props| {
+            props.accelerators = accelerators;
+        });
+    });
+}
+
+#[inline]
+pub fn raw_input(handle: WindowHandle) -> Option<RawInputDevices> {
+    Context::get_window_props(handle, |props| props.raw_input.clone())
+}
+
+#[inline]
+pub fn set_raw_input(handle: WindowHandle, devices: RawInputDevices) {
+    UiThread::send_task(move || {
+        let Some(prev) = Context::get_window_props(handle, |props| props.raw_input.clone()) else {
+            return;
+        };
+        if let Err(e) = raw_input::register(handle.as_hwnd(), &prev, &devices) {
+            error!("RegisterRawInputDevices: {e}");
+            return;
+        }
+        Context::set_window_props(handle, |props| {
+            props.raw_input = devices;
+        });
+    });
+}
+
+#[inline]
+pub fn redraw(handle: WindowHandle, invalidate_rect: Option<PhysicalRect<i32>>) {
+    UiThread::send_task(move || unsafe {
+        let rc: Option<RECT> = invalidate_rect.map(|rc| rc.into());
+        let p = rc.as_ref().map(|p| p as *const RECT);
+        let redrawing = Context::get_window_props(handle, |props| props.redrawing);
+        if !redrawing.unwrap_or(true) {
+            let _ = RedrawWindow(Some(handle.as_hwnd()), p, None, RDW_INVALIDATE);
+            Context::set_window_props(handle, |props| {
+                props.redrawing = true;
+            });
+        }
+    });
+}
+
+#[inline]
+pub fn title(handle: WindowHandle) -> oneshot::Receiver<String> {
+    let (tx, rx) = oneshot::channel::<String>();
+    UiThread::send_task(move || unsafe {
+        let hwnd = handle.as_hwnd();
+        let mut buf = vec![0u16; GetWindowTextLengthW(hwnd) as usize + 1];
+        let len = GetWindowTextW(hwnd, &mut buf);
+        tx.send(String::from_utf16_lossy(&buf[..len as usize])).ok();
+    });
+    rx
+}
+
+#[inline]
+pub fn set_title(handle: WindowHandle, title: String) {
+    UiThread::send_task(move || unsafe {
+        SetWindowTextW(handle.as_hwnd(), &HSTRING::from(title)).ok();
+    });
+}
+
+#[inline]
+pub fn set_icon(handle: WindowHandle, icon: Option<Icon>) {
+    UiThread::send_task(move || unsafe {
+        let hwnd = handle.as_hwnd();
+        match icon.as_ref() {
+            Some(icon) => procedure::set_window_icon(hwnd, icon, GetDpiForWindow(hwnd)),
+            None => {
+                for kind in [ICON_BIG, ICON_SMALL] {
+                    SendMessageW(
+                        hwnd,
+                        WM_SETICON,
+                        Some(WPARAM(kind as usize)),
+                        Some(LPARAM(0)),
+                    );
+                }
+            }
+        }
+        Context::set_window_props(handle, |props| props.icon = icon);
+    });
+}
+
+/// Resizes the window frame to keep `size` of the client area.
+fn keep_inner_size(hwnd: HWND, size: PhysicalSize<u32>) {
+    unsafe {
+        let mut flags = SWP_NOMOVE | SWP_NOZORDER | SWP_NOACTIVATE | SWP_FRAMECHANGED;
+        if IsZoomed(hwnd).as_bool() || IsIconic(hwnd).as_bool() {
+            flags |= SWP_NOSIZE;
+        }
+        let rc = adjust_window_rect_ex_for_dpi(
+            size,
+            WINDOW_STYLE(GetWindowLongPtrW(hwnd, GWL_STYLE) as u32),
+            !GetMenu(hwnd).is_invalid(),
+            WINDOW_EX_STYLE(GetWindowLongPtrW(hwnd, GWL_EXSTYLE) as u32),
+            GetDpiForWindow(hwnd),
+        );
+        SetWindowPos(
+            hwnd,
+            None,
+            0,
+            0,
+            rc.right - rc.left,
+            rc.bottom - rc.top,
+            flags,
+        )
+        .ok();
+    }
+}
+
+fn inner_size_of(hwnd: HWND) -> PhysicalSize<u32> {
+    let rc = get_client_rect(hwnd);
+    PhysicalSize::new((rc.right - rc.left) as u32, (rc.bottom - rc.top) as u32)
+}
+
+fn update_style(
+    hwnd: HWND,
+    f: impl FnOnce(WINDOW_STYLE, WINDOW_EX_STYLE) -> (WINDOW_STYLE, WINDOW_EX_STYLE),
+) {
+    unsafe {
+        let handle = WindowHandle::new(hwnd);
+        let fullscreen = Context::get_window_props(handle, |props| props.fullscreen.is_some());
+        if fullscreen.unwrap_or(false) {
+            Context::set_window_props(handle, |props| {
+                if let Some(state) = props.fullscreen.as_mut() {
+                    (state.style, state.ex_style) = f(state.style, state.ex_style);
+                }
+            });
+            return;
+        }
+        let size = inner_size_of(hwnd);
+        let (style, ex_style) = f(
+            WINDOW_STYLE(GetWindowLongPtrW(hwnd, GWL_STYLE) as u32),
+            WINDOW_EX_STYLE(GetWindowLongPtrW(hwnd, GWL_EXSTYLE) as u32),
+        );
+        SetWindowLongPtrW(hwnd, GWL_STYLE, style.0 as isize);
+        SetWindowLongPtrW(hwnd, GWL_EXSTYLE, ex_style.0 as isize);
+        keep_inner_size(hwnd, size);
+    }
+}
+
+#[inline]
+pub fn set_style(handle: WindowHandle, style: WINDOW_STYLE, ex_style: WINDOW_EX_STYLE) {
+    UiThread::send_task(move || {
+        update_style(handle.as_hwnd(), |current, _| {
+            let state = current & (WS_VISIBLE | WS_MINIMIZE | WS_MAXIMIZE | WS_DISABLED);
+            (style | state, ex_style)
+        });
+    });
+}
+
+#[inline]
+pub fn set_resizable(handle: WindowHandle, flag: bool) {
+    UiThread::send_task(move || {
+        update_style(handle.as_hwnd(), |style, ex_style| {
+            if flag {
+                (style | WS_THICKFRAME, ex_style)
+            } else {
+                (style & !WS_THICKFRAME, ex_style)
+            }
+        });
+    });
+}
+
+#[inline]
+pub fn set_menu(handle: WindowHandle, menu: Option<MenuBar>) {
+    UiThread::send_task(move || unsafe {
+        let hwnd = handle.as_hwnd();
+        let fullscreen = Context::get_window_props(handle, |props| props.fullscreen.is_some());
+        if fullscreen.unwrap_or(false) {
+            Context::set_window_props(handle, |props| props.menu = menu);
+            return;
+        }
+        let size = inner_size_of(hwnd);
+        if let Err(e) = SetMenu(hwnd, menu.as_ref().map(|m| m.as_hmenu())) {
+            error!("SetMenu: {e}");
+            return;
+        }
+        keep_inner_size(hwnd, size);
+        Context::set_window_props(handle, |props| props.menu = menu);
+    });
+}
+
+#[inline]
+pub fn size_constraints(handle: WindowHandle) -> Option<SizeConstraints> {
+    Context::get_window_props(handle, |props| props.size_constraints)
+}
+
+#[inline]
+pub fn set_size_constraints(handle: WindowHandle, constraints: SizeConstraints) {
+    UiThread::send_task(move || unsafe {
+        let hwnd = handle.as_hwnd();
+        Context::set_window_props(handle, |props| props.size_constraints = constraints);
+        if IsZoomed(hwnd).as_bool() || IsIconic(hwnd).as_bool() {
+            return;
+        }
+        let size = inner_size_of(hwnd);
+        let constrained =
+            constraints.constrain(size, ResizingEdge::BottomRight, GetDpiForWindow(hwnd));
+        if constrained != size {
+            keep_inner_size(hwnd, constrained);
+        }
+    });
+}
+
+#[inline]
+pub fn current_monitor(handle: WindowHandle) -> Option<Monitor> {
+    if Context::window_is_none(handle) {
+        return None;
+    }
+    Some(Monitor::from_window(handle.as_hwnd()))
+}
+
+pub fn placement(handle: WindowHandle) -> Option<WindowPlacement> {
+    unsafe {
+        let hwnd = handle.as_hwnd();
+        let saved = Context::get_window_props(handle, |props| {
+            props.fullscreen.as_ref().map(|state| state.placement)
+        })?;
+        let wp = match saved {
+            Some(wp) => wp,
+            None => {
+                let mut wp = WINDOWPLACEMENT {
+                    length: std::mem::size_of::<WINDOWPLACEMENT>() as u32,
+                    ..Default::default()
+                };
+                GetWindowPlacement(hwnd, &mut wp).ok()?;
+                wp
+            }
+        };
+        let state = match SHOW_WINDOW_CMD(wp.showCmd as i32) {
+            SW_SHOWMAXIMIZED => PlacementState::Maximized,
+            SW_SHOWMINIMIZED => PlacementState::Minimized,
+            _ => PlacementState::Normal,
+        };
+        let offset = workspace_offset(hwnd);
+        let rc = wp.rcNormalPosition;
+        Some(WindowPlacement {
+            rect: ScreenRect::new(
+                rc.left + offset.x,
+                rc.top + offset.y,
+                rc.right + offset.x,
+                rc.bottom + offset.y,
+            ),
+            state,
+            monitor_id: Monitor::from_window(hwnd)
+                .info()
+                .map(|info| info.device_id)
+                .unwrap_or_default(),
+            dpi: GetDpiForWindow(hwnd),
+        })
+    }
+}
+
+#[inline]
+pub fn fullscreen(handle: WindowHandle) -> Option<Fullscreen> {
+    Context::get_window_props(handle, |props| {
+        props.fullscreen.as_ref().map(|state| state.fullscreen)
+    })
+    .flatten()
+}
+
+fn enter_fullscreen(handle: WindowHandle, fullscreen: Fullscreen) {
+    unsafe {
+        let hwnd = handle.as_hwnd();
+        let monitor = match fullscreen {
+            Fullscreen::Borderless(monitor) => {
+                monitor.unwrap_or_else(|| Monitor::from_window(hwnd))
+            }
+        };
+        let Some(rc) = monitor.bounds() else {
+            error!("the monitor for fullscreen was not found");
+            return;
+        };
+        let Some(entered) = Context::get_window_props(handle, |props| props.fullscreen.is_some())
+        else {
+            return;
+        };
+        if !entered {
+            let mut placement = WINDOWPLACEMENT {
+                length: std::mem::size_of::<WINDOWPLACEMENT>() as u32,
+                ..Default::default()
+            };
+            GetWindowPlacement(hwnd, &mut placement).ok();
+            let style = WINDOW_STYLE(GetWindowLongPtrW(hwnd, GWL_STYLE) as u32);
+            let ex_style = WINDOW_EX_STYLE(GetWindowLongPtrW(hwnd, GWL_EXSTYLE) as u32);
+            Context::set_window_props(handle, |props| {
+                props.fullscreen = Some(FullscreenState {
+                    fullscreen,
+                    style,
+                    ex_style,
+                    placement,
+                });
+            });
+            if IsZoomed(hwnd).as_bool() {
+                let _ = ShowWindow(hwnd, SW_RESTORE);
+            }
+            let ex_frame =
+                WS_EX_DLGMODALFRAME | WS_EX_WINDOWEDGE | WS_EX_CLIENTEDGE | WS_EX_STATICEDGE;
+            SetWindowLongPtrW(
+                hwnd,
+                GWL_STYLE,
+                (style & !(WS_OVERLAPPEDWINDOW | WS_MAXIMIZE)).0 as isize,
+            );
+            SetWindowLongPtrW(hwnd, GWL_EXSTYLE, (ex_style & !ex_frame).0 as isize);
+            if !GetMenu(hwnd).is_invalid() {
+                SetMenu(hwnd, None).ok();
+            }
+        } else {
+            Context::set_window_props(handle, |props| {
+                if let Some(state) = props.fullscreen.as_mut() {
+                    state.fullscreen = fullscreen;
+                }
+            });
+        }
+        SetWindowPos(
+            hwnd,
+            Some(HWND_TOP),
+            rc.left,
+            rc.top,
+            rc.right - rc.left,
+            rc.bottom - rc.top,
+            SWP_NOOWNERZORDER | SWP_FRAMECHANGED,
+        )
+        .ok();
+        if !entered {
+            Context::send_event(
+                handle,
+                Event::FullscreenChanged(event::FullscreenChanged { fullscreen: true }),
+            );
+        }
+    }
+}
+
+fn leave_fullscreen(handle: WindowHandle) {
+    unsafe {
+        let hwnd = handle.as_hwnd();
+        let Some(menu) = Context::get_window_props(handle, |props| props.menu.clone()) else {
+            return;
+        };
+        let mut state = None;
+        Context::set_window_props(handle, |props| state = props.fullscreen.take());
+        let Some(state) = state else {
+            return;
+        };
+        SetWindowLongPtrW(hwnd, GWL_STYLE, state.style.0 as isize);
+        SetWindowLongPtrW(hwnd, GWL_EXSTYLE, state.ex_style.0 as isize);
+        if let Some(menu) = menu.as_ref() {
+            SetMenu(hwnd, Some(menu.as_hmenu())).ok();
+        }
+        SetWindowPos(
+            hwnd,
+            None,
+            0,
+            0,
+            0,
+            0,
+            SWP_NOMOVE | SWP_NOSIZE | SWP_NOZORDER | SWP_NOOWNERZORDER | SWP_FRAMECHANGED,
+        )
+        .ok();
+        SetWindowPlacement(hwnd, &state.placement).ok();
+        Context::send_event(
+            handle,
+            Event::FullscreenChanged(event::FullscreenChanged { fullscreen: false }),
+        );
+    }
+}
+
+#[inline]
+pub fn set_fullscreen(handle: WindowHandle, fullscreen: Option<Fullscreen>) {
+    UiThread::send_task(move || match fullscreen {
+        Some(fullscreen) => enter_fullscreen(handle, fullscreen),
+        None => leave_fullscreen(handle),
+    });
+}
+
+fn query(handle: WindowHandle, f: fn(HWND) -> bool) -> oneshot::Receiver<bool> {
+    let (tx, rx) = oneshot::channel::<bool>();
+    UiThread::send_task(move || {
+        tx.send(f(handle.as_hwnd())).ok();
+    });
+    rx
+}
+
+#[inline]
+pub fn is_visible(handle: WindowHandle) -> oneshot::Receiver<bool> {
+    query(handle, |hwnd| unsafe { IsWindowVisible(hwnd).as_bool() })
+}
+
+#[inline]
+pub fn is_minimized(handle: WindowHandle) -> oneshot::Receiver<bool> {
+    query(handle, |hwnd| unsafe { IsIconic(hwnd).as_bool() })
+}
+
+#[inline]
+pub fn is_maximized(handle: WindowHandle) -> oneshot::Receiver<bool> {
+    query(handle, |hwnd| unsafe { IsZoomed(hwnd).as_bool() })
+}
+
+#[inline]
+pub fn has_focus(handle: WindowHandle) -> oneshot::Receiver<bool> {
+    query(handle, |hwnd| unsafe { GetFocus() == hwnd })
+}
+
+#[inline]
+pub fn is_active(handle: WindowHandle) -> oneshot::Receiver<bool> {
+    query(handle, |hwnd| unsafe { GetActiveWindow() == hwnd })
+}
+
+#[inline]
+pub fn is_topmost(handle: WindowHandle) -> oneshot::Receiver<bool> {
+    query(handle, |hwnd| unsafe {
+        let ex_style = WINDOW_EX_STYLE(GetWindowLongPtrW(hwnd, GWL_EXSTYLE) as u32);
+        ex_style.contains(WS_EX_TOPMOST)
+    })
+}
+
+#[inline]
+pub fn set_topmost(handle: WindowHandle, topmost: bool) {
+    UiThread::send_task(move || unsafe {
+        let insert_after = if topmost {
+            HWND_TOPMOST
+        } else {
+            HWND_NOTOPMOST
+        };
+        SetWindowPos(
+            handle.as_hwnd(),
+            Some(insert_after),
+            0,
+            0,
+            0,
+            0,
+            SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+        )
+        .ok();
+    });
+}
+
+#[inline]
+pub fn show_without_activation(handle: WindowHandle) {
+    unsafe {
+        let _ = ShowWindowAsync(handle.as_hwnd(), SW_SHOWNA);
+    }
+}
+
+#[inline]
+pub fn minimize_without_activation(handle: WindowHandle) {
+    unsafe {
+        let _ = ShowWindowAsync(handle.as_hwnd(), SW_SHOWMINNOACTIVE);
+    }
+}
+
+#[inline]
+pub fn is_enabled(handle: WindowHandle) -> oneshot::Receiver<bool> {
+    query(handle, |hwnd| unsafe { IsWindowEnabled(hwnd).as_bool() })
+}
+
+#[inline]
+pub fn modal_result(handle: WindowHandle) -> ModalResult {
+    let (tx, rx) = oneshot::channel::<Option<usize>>();
+    UiThread::send_task(move || {
+        if Context::window_is_none(handle) {
+            return;
+        }
+        Context::set_window_props(handle, |props| {
+            if let Some(modal) = props.modal.as_mut() {
+                modal.waiters.push(tx);
+            }
+        });
+    });
+    ModalResult::new(rx)
+}
+
+#[inline]
+pub fn end_modal(handle: WindowHandle, result: usize) {
+    UiThread::send_task(move || unsafe {
+        if Context::window_is_none(handle) {
+            return;
+        }
+        Context::set_window_props(handle, |props| {
+            if let Some(modal) = props.modal.as_mut() {
+                modal.result = Some(result);
+            }
+        });
+        procedure::enable_modal_owner(handle.as_hwnd());
+        DestroyWindow(handle.as_hwnd()).ok();
+    });
+}
+
+#[inline]
+pub fn post_app_event(handle: WindowHandle, app: event::App) {
+    unsafe {
+        PostMessageW(
+            Some(handle.as_hwnd()),
+            OFFSET_WM_APP + app.index,
+            WPARAM(app.value0),
+            LPARAM(app.value1),
+        )
+        .ok();
+    }
+}
+
+#[inline]
+pub fn set_foreground(handle: WindowHandle) {
+    unsafe {
+        let _ = SetForegroundWindow(handle.as_hwnd());
+    }
+}
+
+#[inline]
+pub fn set_focus(handle: WindowHandle) {
+    unsafe {
+        let _ = SetFocus(Some(handle.as_hwnd()));
+    }
+}
+
+#[inline]
+pub fn color_mode(handle: WindowHandle) -> Option<ColorMode> {
+    Context::get_window_props(handle, |props| props.color_mode)
+}
+
+#[inline]
+pub fn set_color_mode(handle: WindowHandle, mode: ColorMode) {
+    UiThread::send_task(move || {
+        procedure::change_color_mode(handle.as_hwnd(), mode);
+    });
+}
+
+#[inline]
+pub fn add_raw_procedure_handler<F>(handle: WindowHandle, f: F)
+where
+    F: Fn(u32, WPARAM, LPARAM) + Send + 'static,
+{
+    UiThread::send_task(move || {
+        procedure::add_raw_procedure_handler(handle, f);
+    });
+}
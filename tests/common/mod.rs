@@ -0,0 +1,46 @@
+#![allow(dead_code)]
+
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// Runs `f` on a new thread with the simulated backend and panics if it does not finish in 3 seconds.
+pub fn run(f: impl FnOnce() + Send + 'static) {
+    wiard::headless::enable();
+    let (tx, rx) = mpsc::channel::<()>();
+    let t = std::thread::spawn(move || {
+        f();
+        tx.send(()).ok();
+    });
+    if let Err(mpsc::RecvTimeoutError::Timeout) = rx.recv_timeout(Duration::from_secs(3)) {
+        panic!("timeout");
+    }
+    t.join().unwrap();
+}
+
+/// Returns a builder of an invisible window.
+pub fn builder(event_rx: &wiard::EventReceiver) -> wiard::WindowBuilder<'_, wiard::EventReceiver> {
+    wiard::Window::builder(event_rx).visible(false)
+}
+
+/// Builds an invisible window.
+pub fn window(event_rx: &wiard::EventReceiver) -> wiard::Window {
+    builder(event_rx).build().unwrap()
+}
+
+/// Receives events until all windows are closed and collects the strings returned by `f`.
+pub fn collect(
+    event_rx: &mut wiard::EventReceiver,
+    mut f: impl FnMut(wiard::Event, wiard::WindowKind) -> Option<String>,
+) -> Vec<String> {
+    let mut events = vec![];
+    while let Some((event, window)) = event_rx.recv() {
+        events.extend(f(event, window));
+    }
+    events
+}
+
+/// Closes `window` and waits for all windows to be closed.
+pub fn close(window: &wiard::Window, event_rx: &mut wiard::EventReceiver) {
+    window.close();
+    while event_rx.recv().is_some() {}
+}
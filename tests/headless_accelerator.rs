@@ -0,0 +1,47 @@
+#![cfg(feature = "headless")]
+
+mod common;
+
+use wiard::headless::Driver;
+
+#[test]
+fn headless_accelerator_test() {
+    common::run(|| {
+        let mut event_rx = wiard::EventReceiver::new();
+        let accels = wiard::Accelerators::parse([("Ctrl+S", 1)]).unwrap();
+        let window = common::builder(&event_rx)
+            .accelerators(&accels)
+            .build()
+            .unwrap();
+        let driver = Driver::new(&window);
+        let ctrl = wiard::KeyCode::new(wiard::VirtualKey::Ctrl, wiard::ScanCode(0x1d));
+        let s = wiard::KeyCode::new(wiard::VirtualKey::S, wiard::ScanCode(0x1f));
+        driver.key_input(ctrl, wiard::KeyState::Pressed);
+        driver.key_input(s, wiard::KeyState::Pressed);
+        driver.key_input(s, wiard::KeyState::Released);
+        driver.key_input(ctrl, wiard::KeyState::Released);
+        driver.key_input(s, wiard::KeyState::Pressed);
+        let mut keys = 0;
+        let events = common::collect(&mut event_rx, |event, _| match event {
+            wiard::Event::KeyInput(ev) => {
+                keys += 1;
+                if keys == 4 {
+                    window.close();
+                }
+                Some(format!("key:{:?}:{:?}", ev.key_code.vkey, ev.key_state))
+            }
+            wiard::Event::Accelerator(ev) => Some(format!("accelerator:{}:{}", ev.id, ev.chord)),
+            _ => None,
+        });
+        assert_eq!(
+            events,
+            [
+                "key:Ctrl:Pressed",
+                "accelerator:1:Ctrl+S",
+                "key:S:Released",
+                "key:Ctrl:Released",
+                "key:S:Pressed",
+            ]
+        );
+    });
+}
@@ -0,0 +1,106 @@
+#![cfg(feature = "headless")]
+
+mod common;
+
+use wiard::headless::Driver;
+use wiard::{ButtonState, MouseButton, PhysicalPosition, PhysicalRect};
+
+fn wait_key_input(event_rx: &mut wiard::EventReceiver) -> usize {
+    let mut lost = 0;
+    loop {
+        let (event, _) = event_rx.recv().unwrap();
+        match event {
+            wiard::Event::CaptureLost => lost += 1,
+            wiard::Event::KeyInput(_) => return lost,
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn headless_capture_test() {
+    common::run(|| {
+        let mut event_rx = wiard::EventReceiver::new();
+        let window = common::builder(&event_rx)
+            .inner_size(wiard::PhysicalSize::new(640, 480))
+            .build()
+            .unwrap();
+        let inner = wiard::InnerWindow::builder(&event_rx, &window)
+            .position(PhysicalPosition::new(0, 0))
+            .size(wiard::PhysicalSize::new(100, 100))
+            .auto_capture(false)
+            .build()
+            .unwrap();
+        let pos = PhysicalPosition::new(10, 10);
+        let driver = Driver::new(&window);
+        let inner_driver = Driver::new(&inner);
+        let f1 = wiard::KeyCode::new(wiard::VirtualKey::F1, wiard::ScanCode(0x3b));
+
+        driver.mouse_input(MouseButton::Left, ButtonState::Pressed, pos);
+        driver.key_input(f1, wiard::KeyState::Pressed);
+        assert_eq!(wait_key_input(&mut event_rx), 0);
+        assert_eq!(driver.has_capture(), Some(true));
+
+        driver.capture_lost();
+        driver.capture_lost();
+        driver.key_input(f1, wiard::KeyState::Pressed);
+        assert_eq!(wait_key_input(&mut event_rx), 1);
+        assert_eq!(driver.has_capture(), Some(false));
+
+        driver.mouse_input(MouseButton::Left, ButtonState::Pressed, pos);
+        driver.mouse_input(MouseButton::Right, ButtonState::Pressed, pos);
+        driver.mouse_input(MouseButton::Left, ButtonState::Released, pos);
+        driver.key_input(f1, wiard::KeyState::Pressed);
+        wait_key_input(&mut event_rx);
+        assert_eq!(driver.has_capture(), Some(true));
+        driver.mouse_input(MouseButton::Right, ButtonState::Released, pos);
+        driver.key_input(f1, wiard::KeyState::Pressed);
+        wait_key_input(&mut event_rx);
+        assert_eq!(driver.has_capture(), Some(false));
+
+        window.set_capture();
+        driver.mouse_input(MouseButton::Left, ButtonState::Pressed, pos);
+        driver.mouse_input(MouseButton::Left, ButtonState::Released, pos);
+        driver.key_input(f1, wiard::KeyState::Pressed);
+        wait_key_input(&mut event_rx);
+        assert_eq!(driver.has_capture(), Some(true));
+        window.release_capture();
+        driver.key_input(f1, wiard::KeyState::Pressed);
+        wait_key_input(&mut event_rx);
+        assert_eq!(driver.has_capture(), Some(false));
+
+        inner_driver.mouse_input(MouseButton::Left, ButtonState::Pressed, pos);
+        inner_driver.key_input(f1, wiard::KeyState::Pressed);
+        assert_eq!(wait_key_input(&mut event_rx), 0);
+        assert_eq!(inner_driver.has_capture(), Some(false));
+
+        inner.set_capture();
+        inner.confine_cursor(Some(PhysicalRect::new(10, 20, 30, 40)));
+        inner.hide_cursor(true);
+        inner_driver.key_input(f1, wiard::KeyState::Pressed);
+        wait_key_input(&mut event_rx);
+        assert_eq!(inner_driver.has_capture(), Some(true));
+        assert_eq!(
+            inner_driver.cursor_clip(),
+            Some(PhysicalRect::new(10, 20, 30, 40))
+        );
+        assert_eq!(inner_driver.is_cursor_hidden(), Some(true));
+
+        inner.release_capture();
+        inner.confine_cursor(None);
+        inner.hide_cursor(false);
+        window.lock_cursor(true);
+        driver.key_input(f1, wiard::KeyState::Pressed);
+        wait_key_input(&mut event_rx);
+        assert_eq!(inner_driver.has_capture(), Some(false));
+        assert_eq!(inner_driver.cursor_clip(), None);
+        assert_eq!(inner_driver.is_cursor_hidden(), Some(false));
+        assert_eq!(
+            driver.cursor_clip(),
+            Some(PhysicalRect::new(320, 240, 321, 241))
+        );
+        assert_eq!(driver.is_cursor_hidden(), Some(true));
+
+        common::close(&window, &mut event_rx);
+    });
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "headless")]
+
+mod common;
+
+use std::time::Duration;
+use wiard::headless::Driver;
+use wiard::{ButtonState, MouseButton, PhysicalPosition};
+
+#[test]
+fn headless_click_test() {
+    common::run(|| {
+        let mut event_rx = wiard::EventReceiver::new();
+        let window = common::window(&event_rx);
+        let ms = Duration::from_millis;
+        let pos = PhysicalPosition::new;
+        let driver = Driver::new(&window);
+        let left = MouseButton::Left;
+        for (i, x) in [10, 11, 9].into_iter().enumerate() {
+            let time = ms(i as u64 * 100);
+            driver.mouse_input_at(left, ButtonState::Pressed, pos(x, 10), time);
+            driver.mouse_input_at(left, ButtonState::Released, pos(x, 10), time + ms(50));
+        }
+        driver.mouse_input_at(left, ButtonState::Pressed, pos(10, 10), ms(1000));
+        driver.cursor_moved(pos(11, 11));
+        driver.cursor_moved(pos(20, 10));
+        driver.cursor_moved(pos(30, 10));
+        driver.mouse_input_at(left, ButtonState::Released, pos(30, 10), ms(1100));
+        driver.mouse_input_at(left, ButtonState::Pressed, pos(30, 10), ms(1200));
+        let esc = wiard::KeyCode::new(wiard::VirtualKey::Esc, wiard::ScanCode(0x01));
+        driver.key_input(esc, wiard::KeyState::Pressed);
+        let events = common::collect(&mut event_rx, |event, _| match event {
+            wiard::Event::MouseInput(ev) => {
+                Some(format!("{:?}:{}", ev.button_state, ev.click_count))
+            }
+            wiard::Event::DragStarted(ev) => {
+                assert_eq!(ev.button, left);
+                Some(format!(
+                    "drag:{},{}:{}",
+                    ev.origin.x, ev.origin.y, ev.mouse_state.position.x
+                ))
+            }
+            wiard::Event::KeyInput(_) => {
+                window.close();
+                None
+            }
+            _ => None,
+        });
+        assert_eq!(
+            events,
+            [
+                "Pressed:1",
+                "Released:1",
+                "Pressed:2",
+                "Released:2",
+                "Pressed:3",
+                "Released:3",
+                "Pressed:1",
+                "drag:10,10:20",
+                "Released:1",
+                "Pressed:1",
+            ]
+        );
+    });
+}
@@ -0,0 +1,57 @@
+#![cfg(feature = "headless")]
+
+mod common;
+
+use wiard::{Fullscreen, Monitor, PhysicalSize, ScreenPosition, ScreenRect};
+
+fn wait_fullscreen_changed(event_rx: &mut wiard::EventReceiver) -> bool {
+    loop {
+        let (event, _) = event_rx.recv().unwrap();
+        if let wiard::Event::FullscreenChanged(ev) = event {
+            return ev.fullscreen;
+        }
+    }
+}
+
+#[test]
+fn headless_fullscreen_test() {
+    common::run(|| {
+        let mut event_rx = wiard::EventReceiver::new();
+        let window = common::builder(&event_rx)
+            .position(ScreenPosition::new(100, 200))
+            .inner_size(PhysicalSize::new(640, 480))
+            .build()
+            .unwrap();
+        let primary = Monitor::primary();
+        assert_eq!(primary.bounds(), Some(ScreenRect::new(0, 0, 1920, 1080)));
+        assert_eq!(Monitor::from_point(ScreenPosition::new(5000, -10)), primary);
+
+        window.maximize();
+        window.set_fullscreen(Some(Fullscreen::Borderless(None)));
+        assert!(wait_fullscreen_changed(&mut event_rx));
+        assert_eq!(window.fullscreen(), Some(Fullscreen::Borderless(None)));
+        assert_eq!(window.position(), Some(ScreenPosition::new(0, 0)));
+        assert_eq!(window.inner_size(), Some(PhysicalSize::new(1920, 1080)));
+
+        window.set_fullscreen(Some(Fullscreen::Borderless(Some(primary))));
+        window.set_fullscreen(None);
+        let mut events = vec![];
+        loop {
+            let (event, _) = event_rx.recv().unwrap();
+            match event {
+                wiard::Event::FullscreenChanged(ev) => {
+                    events.push(format!("fullscreen:{}", ev.fullscreen));
+                    break;
+                }
+                wiard::Event::Maximized(_) => events.push("maximized".to_string()),
+                _ => {}
+            }
+        }
+        assert_eq!(events, ["maximized", "fullscreen:false"]);
+        assert_eq!(window.fullscreen(), None);
+        assert_eq!(window.position(), Some(ScreenPosition::new(100, 200)));
+        assert_eq!(window.inner_size(), Some(PhysicalSize::new(640, 480)));
+
+        common::close(&window, &mut event_rx);
+    });
+}
@@ -0,0 +1,73 @@
+#![cfg(feature = "headless")]
+
+mod common;
+
+use std::time::Duration;
+use wiard::PhysicalPosition;
+use wiard::gesture::Gesture;
+use wiard::headless::Driver;
+use wiard::pointer::*;
+
+fn touch(id: u32, flags: PointerFlags, x: i32, y: i32) -> PointerSample {
+    PointerSample::new(
+        PointerId::new(id),
+        PointerType::Touch,
+        flags,
+        PhysicalPosition::new(x, y),
+    )
+}
+
+#[test]
+fn headless_gesture_test() {
+    common::run(|| {
+        let mut event_rx = wiard::EventReceiver::new();
+        let window = common::builder(&event_rx)
+            .pointer_input(true)
+            .gestures(true)
+            .build()
+            .unwrap();
+        let down = PointerFlags::DOWN | PointerFlags::IN_CONTACT | PointerFlags::IN_RANGE;
+        let update = PointerFlags::UPDATE | PointerFlags::IN_CONTACT | PointerFlags::IN_RANGE;
+        let ms = Duration::from_millis;
+        let driver = Driver::new(&window);
+        driver.pointer_input_at(touch(1, down, 100, 100), ms(0));
+        driver.pointer_input_at(touch(2, down, 200, 100), ms(10));
+        driver.pointer_input_at(touch(1, PointerFlags::UP, 100, 100), ms(60));
+        driver.pointer_input_at(touch(2, PointerFlags::UP, 200, 100), ms(80));
+        driver.pointer_input_at(touch(3, down, 100, 100), ms(1000));
+        driver.gesture_timer(ms(1600));
+        driver.pointer_input_at(touch(3, PointerFlags::UP, 100, 100), ms(1700));
+        driver.pointer_input_at(touch(4, down, 100, 100), ms(2000));
+        driver.pointer_input_at(touch(5, down, 200, 100), ms(2000));
+        driver.pointer_input_at(touch(5, update, 300, 100), ms(2100));
+        driver.pointer_input_at(touch(5, PointerFlags::UP, 300, 100), ms(3000));
+        driver.pointer_input_at(touch(4, PointerFlags::UP, 100, 100), ms(3000));
+        let esc = wiard::KeyCode::new(wiard::VirtualKey::Esc, wiard::ScanCode(0x01));
+        driver.key_input(esc, wiard::KeyState::Pressed);
+        let gestures = common::collect(&mut event_rx, |event, _| match event {
+            wiard::Event::Gesture(g) => Some(match g {
+                Gesture::Tap(tap) => format!("Tap:{}", tap.fingers),
+                Gesture::LongPress(lp) => format!("LongPress:{}", lp.position.x),
+                Gesture::Pan(pan) => format!("Pan:{:?}:{}", pan.phase, pan.translation.x),
+                Gesture::Pinch(pinch) => format!("Pinch:{:?}:{}", pinch.phase, pinch.scale),
+                Gesture::Rotate(rotate) => format!("Rotate:{:?}", rotate.phase),
+            }),
+            wiard::Event::KeyInput(_) => {
+                window.close();
+                None
+            }
+            _ => None,
+        });
+        assert_eq!(
+            gestures,
+            [
+                "Tap:2",
+                "LongPress:100",
+                "Pan:Begin:50",
+                "Pinch:Begin:2",
+                "Pinch:End:2",
+                "Pan:End:50",
+            ]
+        );
+    });
+}
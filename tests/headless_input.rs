@@ -0,0 +1,63 @@
+#![cfg(feature = "headless")]
+
+mod common;
+
+use wiard::headless::Driver;
+
+#[test]
+fn headless_input_test() {
+    common::run(|| {
+        let mut event_rx = wiard::EventReceiver::new();
+        let window = common::builder(&event_rx)
+            .inner_size(wiard::PhysicalSize::new(640, 480))
+            .auto_close(false)
+            .hook_nc_hittest(true)
+            .build()
+            .unwrap();
+        let driver = Driver::new(&window);
+        let key = wiard::KeyCode::new(wiard::VirtualKey::A, wiard::ScanCode(0x1e));
+        driver.key_input(key, wiard::KeyState::Pressed);
+        driver.key_input(key, wiard::KeyState::Pressed);
+        driver.char_input('a');
+        driver.change_dpi(192);
+        let hittest = driver.nc_hittest(wiard::PhysicalPosition::new(10, 10));
+        driver.close_request();
+        let events = common::collect(&mut event_rx, |event, _| match event {
+            wiard::Event::KeyInput(ev) => Some(format!("key:{}", ev.prev_pressed)),
+            wiard::Event::CharInput(ev) => Some(format!("char:{}", ev.c)),
+            wiard::Event::Resized(ev) => {
+                Some(format!("resized:{}x{}", ev.size.width, ev.size.height))
+            }
+            wiard::Event::DpiChanged(ev) => Some(format!("dpi:{}", ev.new_dpi)),
+            wiard::Event::NcHitTest(ev) => {
+                ev.set(Some(wiard::NcHitTestValue::Caption));
+                Some("nc_hittest".into())
+            }
+            wiard::Event::CloseRequest(ev) => {
+                assert_eq!(window.dpi(), Some(192));
+                ev.destroy();
+                Some("close_request".into())
+            }
+            wiard::Event::Closed => Some("closed".into()),
+            _ => None,
+        });
+        assert_eq!(
+            hittest.blocking_recv().unwrap(),
+            Some(wiard::NcHitTestValue::Caption)
+        );
+        assert_eq!(
+            events,
+            [
+                "resized:640x480",
+                "key:false",
+                "key:true",
+                "char:a",
+                "resized:1280x960",
+                "dpi:192",
+                "nc_hittest",
+                "close_request",
+                "closed",
+            ]
+        );
+    });
+}
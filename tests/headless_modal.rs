@@ -0,0 +1,65 @@
+#![cfg(feature = "headless")]
+
+use std::time::Duration;
+use wiard::IsWindow;
+
+#[tokio::test]
+async fn headless_modal_test() {
+    wiard::headless::enable();
+    let test = async {
+        let mut event_rx = wiard::AsyncEventReceiver::new();
+        let main = wiard::Window::builder(&event_rx)
+            .visible(false)
+            .build()
+            .await
+            .unwrap();
+
+        let dialog = wiard::Window::builder(&event_rx)
+            .owner(&main)
+            .modal(true)
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(main.is_enabled().await, Some(false));
+        assert_eq!(dialog.is_enabled().await, Some(true));
+        let result = dialog.modal_result();
+        dialog.end_modal(2);
+        assert_eq!(result.await, Some(2));
+        assert_eq!(main.is_enabled().await, Some(true));
+        assert_eq!(main.is_active().await, Some(true));
+
+        let dialog = wiard::Window::builder(&event_rx)
+            .owner(&main)
+            .modal(true)
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(main.is_enabled().await, Some(false));
+        let result = dialog.modal_result();
+        dialog.close();
+        assert_eq!(result.await, None);
+        assert_eq!(main.is_enabled().await, Some(true));
+
+        let owned = wiard::Window::builder(&event_rx)
+            .owner(&main)
+            .visible(false)
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(main.is_enabled().await, Some(true));
+        assert_eq!(owned.modal_result().await, None);
+
+        main.close();
+        let mut closed = vec![];
+        while let Some((event, window)) = event_rx.recv().await {
+            if let wiard::Event::Closed = event {
+                closed.push(window.window_handle());
+            }
+        }
+        assert_eq!(closed.len(), 4);
+        assert!(closed.contains(&owned.window_handle()));
+    };
+    tokio::time::timeout(Duration::from_secs(3), test)
+        .await
+        .expect("timeout");
+}
@@ -0,0 +1,49 @@
+#![cfg(feature = "headless")]
+
+mod common;
+
+use wiard::ModifierState;
+use wiard::headless::Driver;
+
+#[test]
+fn headless_modifiers_test() {
+    common::run(|| {
+        let mut event_rx = wiard::EventReceiver::new();
+        let window = common::window(&event_rx);
+        let driver = Driver::new(&window);
+        let right_ctrl = wiard::KeyCode::new(wiard::VirtualKey::Ctrl, wiard::ScanCode(0xe01d));
+        let caps_lock = wiard::KeyCode::new(wiard::VirtualKey::CapsLock, wiard::ScanCode(0x3a));
+        let a = wiard::KeyCode::new(wiard::VirtualKey::A, wiard::ScanCode(0x1e));
+        driver.key_input(caps_lock, wiard::KeyState::Pressed);
+        driver.key_input(caps_lock, wiard::KeyState::Released);
+        driver.key_input(right_ctrl, wiard::KeyState::Pressed);
+        driver.key_input(a, wiard::KeyState::Pressed);
+        driver.mouse_input(
+            wiard::MouseButton::Left,
+            wiard::ButtonState::Pressed,
+            wiard::PhysicalPosition::new(1, 1),
+        );
+        driver.key_input(right_ctrl, wiard::KeyState::Released);
+        let events = common::collect(&mut event_rx, |event, _| match event {
+            wiard::Event::KeyInput(ev) if ev.key_code.vkey == wiard::VirtualKey::A => {
+                assert_eq!(ev.logical_key, wiard::LogicalKey::Character("A".into()));
+                assert_eq!(ev.text, None);
+                Some(format!("key_a:{:?}", ev.modifiers))
+            }
+            wiard::Event::KeyInput(ev) if ev.is(right_ctrl, wiard::KeyState::Released) => {
+                assert_eq!(ev.modifiers, ModifierState::CAPS_LOCK);
+                assert!(ev.is_extended());
+                window.close();
+                None
+            }
+            wiard::Event::MouseInput(ev) => Some(format!("mouse:{:?}", ev.mouse_state.modifiers)),
+            _ => None,
+        });
+        let expected = ModifierState::RIGHT_CTRL | ModifierState::CAPS_LOCK;
+        assert_eq!(
+            events,
+            [format!("key_a:{expected:?}"), format!("mouse:{expected:?}")]
+        );
+        assert!(expected.ctrl() && !expected.shift());
+    });
+}
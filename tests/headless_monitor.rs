@@ -0,0 +1,59 @@
+#![cfg(feature = "headless")]
+
+mod common;
+
+use wiard::{Monitor, MonitorInfo, Orientation, ScreenPosition, ScreenRect};
+
+fn monitor(name: &str, bounds: ScreenRect<i32>, dpi: u32, is_primary: bool) -> MonitorInfo {
+    MonitorInfo {
+        name: name.into(),
+        device_id: format!("id-{name}"),
+        bounds,
+        work_area: ScreenRect::new(bounds.left, bounds.top, bounds.right, bounds.bottom - 40),
+        dpi,
+        refresh_rate: Some(144),
+        is_primary,
+        orientation: Orientation::Landscape,
+    }
+}
+
+#[test]
+fn headless_monitor_test() {
+    common::run(|| {
+        let mut event_rx = wiard::EventReceiver::new();
+        let window = common::builder(&event_rx)
+            .position(ScreenPosition::new(2200, 100))
+            .inner_size(wiard::PhysicalSize::new(400, 300))
+            .build()
+            .unwrap();
+        let default = Monitor::primary().info().unwrap();
+        assert_eq!(default.bounds, ScreenRect::new(0, 0, 1920, 1080));
+        assert!(default.is_primary);
+
+        wiard::headless::set_monitors(vec![
+            monitor("left", ScreenRect::new(-1920, 0, 0, 1080), 96, false),
+            monitor("main", ScreenRect::new(0, 0, 2560, 1440), 144, true),
+        ]);
+        loop {
+            let (event, _) = event_rx.recv().unwrap();
+            if let wiard::Event::MonitorsChanged = event {
+                break;
+            }
+        }
+        let monitors = Monitor::all();
+        assert_eq!(monitors.len(), 2);
+        let primary = Monitor::primary();
+        assert_eq!(primary, monitors[1]);
+        let info = primary.info().unwrap();
+        assert_eq!(info.name, "main");
+        assert_eq!(info.scale_factor(), 1.5);
+        assert_eq!(primary.work_area(), Some(ScreenRect::new(0, 0, 2560, 1400)));
+        assert_eq!(
+            Monitor::from_point(ScreenPosition::new(-5000, 500)),
+            monitors[0]
+        );
+        assert_eq!(window.current_monitor(), Some(primary));
+
+        common::close(&window, &mut event_rx);
+    });
+}
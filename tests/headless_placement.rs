@@ -0,0 +1,59 @@
+#![cfg(feature = "headless")]
+
+mod common;
+
+use wiard::{MonitorInfo, Orientation, PhysicalSize, PlacementState, ScreenPosition, ScreenRect};
+
+#[test]
+fn headless_placement_test() {
+    common::run(|| {
+        let mut event_rx = wiard::EventReceiver::new();
+        let window = common::builder(&event_rx)
+            .position(ScreenPosition::new(1700, 100))
+            .inner_size(PhysicalSize::new(400, 300))
+            .build()
+            .unwrap();
+        window.maximize();
+        loop {
+            let (event, _) = event_rx.recv().unwrap();
+            if let wiard::Event::Maximized(_) = event {
+                break;
+            }
+        }
+        let placement = window.placement().unwrap();
+        assert_eq!(placement.rect, ScreenRect::new(1700, 100, 2100, 400));
+        assert_eq!(placement.state, PlacementState::Maximized);
+        assert_eq!(placement.monitor_id, "headless-0");
+        assert_eq!(placement.dpi, 96);
+
+        let bounds = ScreenRect::new(0, 0, 1280, 720);
+        wiard::headless::set_monitors(vec![MonitorInfo {
+            name: "small".into(),
+            device_id: "small".into(),
+            bounds,
+            work_area: ScreenRect::new(0, 0, 1280, 680),
+            dpi: 192,
+            refresh_rate: None,
+            is_primary: true,
+            orientation: Orientation::Landscape,
+        }]);
+        let restored_window = common::builder(&event_rx)
+            .placement(&placement)
+            .build()
+            .unwrap();
+        assert_eq!(
+            restored_window.position(),
+            Some(ScreenPosition::new(480, 80))
+        );
+        assert_eq!(
+            restored_window.inner_size(),
+            Some(PhysicalSize::new(800, 600))
+        );
+        let restored = restored_window.placement().unwrap();
+        assert_eq!(restored.state, PlacementState::Maximized);
+        assert_eq!(restored.monitor_id, "small");
+        assert_eq!(restored.dpi, 192);
+        restored_window.close();
+        common::close(&window, &mut event_rx);
+    });
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "headless")]
+
+mod common;
+
+use wiard::PhysicalPosition;
+use wiard::headless::Driver;
+use wiard::pointer::*;
+
+fn sample(id: u32, pointer_type: PointerType, flags: PointerFlags, x: i32) -> PointerSample {
+    PointerSample::new(
+        PointerId::new(id),
+        pointer_type,
+        flags,
+        PhysicalPosition::new(x, 0),
+    )
+}
+
+#[test]
+fn headless_pointer_test() {
+    common::run(|| {
+        let mut event_rx = wiard::EventReceiver::new();
+        let window = common::builder(&event_rx)
+            .pointer_input(true)
+            .build()
+            .unwrap();
+        let other = common::window(&event_rx);
+        let down = PointerFlags::DOWN | PointerFlags::IN_CONTACT | PointerFlags::IN_RANGE;
+        let update = PointerFlags::UPDATE | PointerFlags::IN_CONTACT | PointerFlags::IN_RANGE;
+        let driver = Driver::new(&window);
+        let ctrl = wiard::KeyCode::new(wiard::VirtualKey::Ctrl, wiard::ScanCode(0x1d));
+        driver.key_input(ctrl, wiard::KeyState::Pressed);
+        driver.pointer_input(sample(1, PointerType::Touch, down, 10));
+        driver.pointer_input(sample(2, PointerType::Touch, down, 20));
+        driver.pointer_input(sample(1, PointerType::Touch, PointerFlags::UP, 11));
+        driver.pointer_leave(PointerId::new(2));
+        driver.pointer_input(sample(3, PointerType::Pen, update, 30).properties(
+            PointerProperties {
+                pressure: Some(0.5),
+                tilt_x: 10,
+                tilt_y: -20,
+                twist: 90,
+                contact_size: None,
+                pen_buttons: PenButtons::BARREL,
+            },
+        ));
+        driver.pointer_input(sample(3, PointerType::Pen, PointerFlags::UP, 30));
+        let other_driver = Driver::new(&other);
+        other_driver.pointer_input(sample(4, PointerType::Touch, down, 40));
+        let esc = wiard::KeyCode::new(wiard::VirtualKey::Esc, wiard::ScanCode(0x01));
+        driver.key_input(esc, wiard::KeyState::Pressed);
+        other_driver.key_input(esc, wiard::KeyState::Pressed);
+        let events = common::collect(&mut event_rx, |event, w| match event {
+            wiard::Event::Pointer(ev) => {
+                assert_eq!(w, wiard::WindowKind::Window(window.clone()));
+                assert!(ev.modifiers.ctrl());
+                if ev.pointer_type == PointerType::Pen && ev.phase == PointerPhase::Down {
+                    assert_eq!(ev.properties.pressure, Some(0.5));
+                    assert_eq!(ev.properties.pen_buttons, PenButtons::BARREL);
+                }
+                Some(format!(
+                    "{:?}:{}:{}:{}",
+                    ev.phase,
+                    ev.id.raw(),
+                    ev.position.x,
+                    ev.is_primary
+                ))
+            }
+            wiard::Event::KeyInput(ev) if ev.key_code.vkey == wiard::VirtualKey::Esc => {
+                if w == wiard::WindowKind::Window(window.clone()) {
+                    window.close();
+                } else {
+                    other.close();
+                }
+                None
+            }
+            _ => None,
+        });
+        assert_eq!(
+            events,
+            [
+                "Down:1:10:true",
+                "Down:2:20:false",
+                "Up:1:11:true",
+                "Cancel:2:20:false",
+                "Down:3:30:true",
+                "Up:3:30:true",
+            ]
+        );
+    });
+}
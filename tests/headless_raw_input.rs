@@ -0,0 +1,96 @@
+#![cfg(feature = "headless")]
+
+mod common;
+
+use wiard::headless::Driver;
+use wiard::raw_input::{DecodeError, HEADER_SIZE, RawInputDevices};
+
+fn raw_input(ty: u32, device: usize, background: bool, body: &[u8]) -> Vec<u8> {
+    let mut data = vec![];
+    data.extend(ty.to_le_bytes());
+    data.extend(((HEADER_SIZE + body.len()) as u32).to_le_bytes());
+    data.extend(device.to_le_bytes());
+    data.extend((background as usize).to_le_bytes());
+    data.extend(body);
+    data
+}
+
+fn raw_mouse(device: usize, background: bool, x: i32, y: i32) -> Vec<u8> {
+    let mut body = vec![0; 12];
+    body.extend(x.to_le_bytes());
+    body.extend(y.to_le_bytes());
+    body.extend([0; 4]);
+    raw_input(0, device, background, &body)
+}
+
+fn raw_keyboard(device: usize, make_code: u16, vkey: u16) -> Vec<u8> {
+    let mut body = vec![];
+    body.extend(make_code.to_le_bytes());
+    body.extend(0u16.to_le_bytes());
+    body.extend(0u16.to_le_bytes());
+    body.extend(vkey.to_le_bytes());
+    body.extend(0x0100u32.to_le_bytes());
+    body.extend(0u32.to_le_bytes());
+    raw_input(1, device, false, &body)
+}
+
+#[test]
+fn headless_raw_input_test() {
+    common::run(|| {
+        let mut event_rx = wiard::EventReceiver::new();
+        let window = common::builder(&event_rx)
+            .raw_input(&RawInputDevices::new().mouse(true).keyboard(true))
+            .build()
+            .unwrap();
+        let other = common::window(&event_rx);
+        assert_eq!(window.raw_input().unwrap().usages().len(), 2);
+        let driver = Driver::new(&window);
+        let other_driver = Driver::new(&other);
+        assert_eq!(driver.raw_input(&[0; 4]), Err(DecodeError::TooShort));
+        driver.device_added(wiard::DeviceId::from_raw(1));
+        driver.raw_input(&raw_mouse(1, false, 3, -2)).unwrap();
+        driver.raw_input(&raw_mouse(1, true, 5, 5)).unwrap();
+        driver.device_removed(wiard::DeviceId::from_raw(1));
+        driver.raw_input(&raw_keyboard(2, 0x01, 0x1b)).unwrap();
+        other_driver.device_added(wiard::DeviceId::from_raw(1));
+        other_driver.raw_input(&raw_mouse(1, false, 1, 1)).unwrap();
+        other.set_raw_input(&RawInputDevices::new().mouse(true).background(true));
+        other_driver.raw_input(&raw_mouse(1, true, 7, 8)).unwrap();
+        other_driver
+            .raw_input(&raw_keyboard(2, 0x01, 0x1b))
+            .unwrap();
+        let esc = wiard::KeyCode::new(wiard::VirtualKey::Esc, wiard::ScanCode(0x01));
+        other_driver.key_input(esc, wiard::KeyState::Pressed);
+        let mut other_events = vec![];
+        let events = common::collect(&mut event_rx, |event, w| {
+            let is_other = w != wiard::WindowKind::Window(window.clone());
+            let target = if is_other { &other } else { &window };
+            let text = match event {
+                wiard::Event::RawMouseMotion(ev) => {
+                    format!("motion:{}:{},{}", ev.device.raw(), ev.x, ev.y)
+                }
+                wiard::Event::RawKeyInput(ev) => {
+                    target.close();
+                    format!("key:{}:{:?}", ev.device.raw(), ev.key_code.vkey)
+                }
+                wiard::Event::DeviceAdded(ev) => format!("added:{}", ev.device.raw()),
+                wiard::Event::DeviceRemoved(ev) => format!("removed:{}", ev.device.raw()),
+                wiard::Event::KeyInput(_) => {
+                    target.close();
+                    return None;
+                }
+                _ => return None,
+            };
+            if is_other {
+                other_events.push(text);
+                return None;
+            }
+            Some(text)
+        });
+        assert_eq!(
+            events,
+            ["added:1", "motion:1:3,-2", "removed:1", "key:2:Esc"]
+        );
+        assert_eq!(other_events, ["motion:1:7,8"]);
+    });
+}
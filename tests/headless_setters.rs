@@ -0,0 +1,23 @@
+#![cfg(feature = "headless")]
+
+mod common;
+
+use wiard::headless::Driver;
+
+#[test]
+fn headless_setters_test() {
+    common::run(|| {
+        let mut event_rx = wiard::EventReceiver::new();
+        let window = common::builder(&event_rx).title("first").build().unwrap();
+        let driver = Driver::new(&window);
+        assert_eq!(window.title().as_deref(), Some("first"));
+        assert_eq!(driver.is_resizable(), Some(true));
+        window.set_title("second");
+        window.set_resizable(false);
+        assert_eq!(window.title().as_deref(), Some("second"));
+        assert_eq!(driver.title().as_deref(), Some("second"));
+        assert_eq!(driver.is_resizable(), Some(false));
+        common::close(&window, &mut event_rx);
+        assert_eq!(window.title(), None);
+    });
+}
@@ -0,0 +1,53 @@
+#![cfg(feature = "headless")]
+
+mod common;
+
+use wiard::headless::Driver;
+use wiard::{LogicalSize, PhysicalSize, ResizingEdge, SizeConstraints};
+
+fn wait_resizing(event_rx: &mut wiard::EventReceiver) -> PhysicalSize<u32> {
+    loop {
+        let (event, _) = event_rx.recv().unwrap();
+        if let wiard::Event::Resizing(ev) = event {
+            return ev.size;
+        }
+    }
+}
+
+#[test]
+fn headless_sizing_test() {
+    common::run(|| {
+        let mut event_rx = wiard::EventReceiver::new();
+        let window = common::builder(&event_rx)
+            .dpi(192)
+            .inner_size(PhysicalSize::new(640, 480))
+            .min_inner_size(LogicalSize::new(100, 100))
+            .max_inner_size(PhysicalSize::new(1000, 1000))
+            .build()
+            .unwrap();
+        let driver = Driver::new(&window);
+        assert_eq!(
+            window.size_constraints().unwrap().min,
+            Some(LogicalSize::new(100, 100).into())
+        );
+
+        driver.sizing(ResizingEdge::BottomRight, PhysicalSize::new(50, 2000));
+        assert_eq!(wait_resizing(&mut event_rx), PhysicalSize::new(200, 1000));
+        assert_eq!(window.inner_size(), Some(PhysicalSize::new(200, 1000)));
+
+        window.set_size_constraints(
+            SizeConstraints::new()
+                .aspect_ratio(2, 1)
+                .increments(PhysicalSize::new(10, 10), PhysicalSize::new(0, 0)),
+        );
+        assert_eq!(window.inner_size(), Some(PhysicalSize::new(200, 100)));
+
+        driver.sizing(ResizingEdge::Bottom, PhysicalSize::new(200, 155));
+        assert_eq!(wait_resizing(&mut event_rx), PhysicalSize::new(300, 150));
+        driver.sizing(ResizingEdge::Right, PhysicalSize::new(455, 150));
+        assert_eq!(wait_resizing(&mut event_rx), PhysicalSize::new(450, 225));
+        assert_eq!(window.inner_size(), Some(PhysicalSize::new(450, 225)));
+
+        common::close(&window, &mut event_rx);
+    });
+}
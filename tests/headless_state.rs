@@ -0,0 +1,82 @@
+#![cfg(feature = "headless")]
+
+mod common;
+
+use wiard::PhysicalSize;
+use wiard::headless::Driver;
+
+fn wait_focused(event_rx: &mut wiard::EventReceiver) -> Vec<String> {
+    let mut events = vec![];
+    loop {
+        let (event, _) = event_rx.recv().unwrap();
+        match event {
+            wiard::Event::Activated => events.push("activated".to_string()),
+            wiard::Event::Inactivated => events.push("inactivated".to_string()),
+            wiard::Event::Focused(ev) => {
+                events.push(format!("focused:{}", ev.focused));
+                return events;
+            }
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn headless_state_test() {
+    common::run(|| {
+        let mut event_rx = wiard::EventReceiver::new();
+        let window = common::builder(&event_rx)
+            .inner_size(PhysicalSize::new(640, 480))
+            .always_on_top(true)
+            .build()
+            .unwrap();
+        let driver = Driver::new(&window);
+        assert_eq!(window.is_visible(), Some(false));
+        assert_eq!(window.is_active(), Some(false));
+        assert_eq!(window.has_focus(), Some(false));
+        assert_eq!(window.is_topmost(), Some(true));
+
+        window.set_topmost(false);
+        window.show_without_activation();
+        assert_eq!(window.is_visible(), Some(true));
+        assert_eq!(window.is_active(), Some(false));
+        assert_eq!(window.is_topmost(), Some(false));
+
+        window.show();
+        assert_eq!(wait_focused(&mut event_rx), ["activated", "focused:true"]);
+        assert_eq!(window.is_active(), Some(true));
+        assert_eq!(window.has_focus(), Some(true));
+
+        driver.focus(false);
+        assert_eq!(wait_focused(&mut event_rx), ["focused:false"]);
+        assert_eq!(window.is_active(), Some(true));
+        assert_eq!(window.has_focus(), Some(false));
+
+        window.maximize();
+        assert_eq!(window.is_maximized(), Some(true));
+        window.minimize();
+        assert_eq!(window.is_minimized(), Some(true));
+        window.restore();
+        assert_eq!(window.is_minimized(), Some(false));
+        assert_eq!(window.is_maximized(), Some(false));
+
+        driver.focus(true);
+        window.hide();
+        assert_eq!(wait_focused(&mut event_rx), ["focused:true"]);
+        assert_eq!(
+            wait_focused(&mut event_rx),
+            ["inactivated", "focused:false"]
+        );
+        assert_eq!(window.is_visible(), Some(false));
+        assert_eq!(window.is_active(), Some(false));
+
+        window.minimize_without_activation();
+        assert_eq!(window.is_visible(), Some(true));
+        assert_eq!(window.is_minimized(), Some(true));
+        assert_eq!(window.is_active(), Some(false));
+
+        common::close(&window, &mut event_rx);
+        assert_eq!(window.is_visible(), None);
+        assert_eq!(window.is_active(), None);
+    });
+}
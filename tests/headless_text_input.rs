@@ -0,0 +1,62 @@
+#![cfg(feature = "headless")]
+
+mod common;
+
+use wiard::headless::Driver;
+
+#[test]
+fn headless_text_input_test() {
+    common::run(|| {
+        let mut event_rx = wiard::EventReceiver::new();
+        let window = common::window(&event_rx);
+        let batched = common::builder(&event_rx)
+            .batch_text_input(true)
+            .build()
+            .unwrap();
+        let driver = Driver::new(&window);
+        driver.utf16_char_input(0xd83d);
+        driver.utf16_char_input(0xde00);
+        driver.utf16_char_input(0xd83d);
+        driver.char_input('a');
+        driver.text_input("b\u{20bb7}");
+        let batched_driver = Driver::new(&batched);
+        batched_driver.text_input("ab\u{1f600}");
+        batched_driver.char_input('c');
+        let esc = wiard::KeyCode::new(wiard::VirtualKey::Esc, wiard::ScanCode(0x01));
+        driver.key_input(esc, wiard::KeyState::Pressed);
+        batched_driver.key_input(esc, wiard::KeyState::Pressed);
+        let mut batched_events = vec![];
+        let events = common::collect(&mut event_rx, |event, w| {
+            let is_batched = w != wiard::WindowKind::Window(window.clone());
+            let text = match event {
+                wiard::Event::CharInput(ev) => format!("char:{}", ev.c),
+                wiard::Event::TextInput(ev) => format!("text:{}", ev.text),
+                wiard::Event::KeyInput(_) => {
+                    if is_batched {
+                        batched.close();
+                    } else {
+                        window.close();
+                    }
+                    return None;
+                }
+                _ => return None,
+            };
+            if is_batched {
+                batched_events.push(text);
+                return None;
+            }
+            Some(text)
+        });
+        assert_eq!(
+            events,
+            [
+                "char:\u{1f600}",
+                "char:\u{fffd}",
+                "char:a",
+                "char:b",
+                "char:\u{20bb7}",
+            ]
+        );
+        assert_eq!(batched_events, ["text:ab\u{1f600}", "text:c"]);
+    });
+}
@@ -0,0 +1,56 @@
+#![cfg(feature = "headless")]
+
+mod common;
+
+use std::time::Duration;
+use wiard::headless::Driver;
+use wiard::{MouseWheelAxis, PhysicalPosition, ScrollDelta};
+
+#[test]
+fn headless_wheel_test() {
+    common::run(|| {
+        let mut event_rx = wiard::EventReceiver::new();
+        let window = common::window(&event_rx);
+        let ms = Duration::from_millis;
+        let driver = Driver::new(&window);
+        let v = MouseWheelAxis::Vertical;
+        driver.mouse_wheel_at(v, 120, PhysicalPosition::new(1, 2), ms(0));
+        driver.mouse_wheel_at(v, 30, PhysicalPosition::new(3, 4), ms(1000));
+        driver.mouse_wheel_at(v, 30, PhysicalPosition::new(3, 4), ms(1010));
+        driver.wheel_timer(ms(1050));
+        driver.wheel_timer(ms(1500));
+        driver.mouse_wheel(
+            MouseWheelAxis::Horizontal,
+            -240,
+            PhysicalPosition::new(5, 6),
+        );
+        let esc = wiard::KeyCode::new(wiard::VirtualKey::Esc, wiard::ScanCode(0x01));
+        driver.key_input(esc, wiard::KeyState::Pressed);
+        let events = common::collect(&mut event_rx, |event, _| match event {
+            wiard::Event::MouseWheel(ev) => {
+                let ScrollDelta::Lines(lines) = ev.delta else {
+                    panic!("{:?}", ev.delta);
+                };
+                Some(format!(
+                    "{:?}:{}:{}:{}:{:?}:{}",
+                    ev.axis, ev.distance, lines, ev.steps, ev.phase, ev.mouse_state.position.x
+                ))
+            }
+            wiard::Event::KeyInput(_) => {
+                window.close();
+                None
+            }
+            _ => None,
+        });
+        assert_eq!(
+            events,
+            [
+                "Vertical:120:3:3:None:1",
+                "Vertical:30:0.75:0:Some(Begin):3",
+                "Vertical:30:0.75:1:Some(Update):3",
+                "Vertical:0:0:0:Some(End):3",
+                "Horizontal:-240:-6:-6:None:5",
+            ]
+        );
+    });
+}
@@ -1,5 +1,6 @@
 #[test]
 #[should_panic]
+#[allow(clippy::while_let_loop)]
 fn ui_thread_on_main_loop_end() {
     wiard::UiThread::new()
         .on_main_loop_end(|| {
@@ -0,0 +1,90 @@
+#![cfg(feature = "headless")]
+
+mod common;
+
+use wiard::headless::Driver;
+use wiard::record::{RecordedEvent, RecordedReply, Recorder, Replayer};
+
+fn replay(data: &[u8], value: wiard::NcHitTestValue) -> (Vec<String>, Replayer) {
+    let mut replayer = Replayer::new(data).unwrap();
+    let mut events = vec![];
+    while let Some((event, window)) = replayer.recv() {
+        assert!(matches!(window, wiard::WindowKind::Window(_)));
+        match event {
+            wiard::Event::KeyInput(ev) => events.push(format!("key:{:?}", ev.key_code.vkey)),
+            wiard::Event::CharInput(ev) => events.push(format!("char:{}", ev.c)),
+            wiard::Event::CursorEntered(ev) => {
+                let pos = ev.mouse_state.position;
+                events.push(format!("entered:{},{}", pos.x, pos.y));
+            }
+            wiard::Event::NcHitTest(ev) => {
+                ev.set(Some(value));
+                events.push("nc_hittest".into());
+            }
+            wiard::Event::CloseRequest(_) => events.push("close_request".into()),
+            _ => {}
+        }
+    }
+    (events, replayer)
+}
+
+#[test]
+fn record_replay_test() {
+    common::run(|| {
+        let event_rx = wiard::EventReceiver::new();
+        let window = common::builder(&event_rx)
+            .auto_close(false)
+            .hook_nc_hittest(true)
+            .build()
+            .unwrap();
+        let mut recorder = Recorder::new(event_rx, vec![]);
+        let driver = Driver::new(&window);
+        let key = wiard::KeyCode::new(wiard::VirtualKey::Other(0xff), wiard::ScanCode(0));
+        driver.key_input(key, wiard::KeyState::Pressed);
+        driver.char_input('"');
+        driver.cursor_moved(wiard::PhysicalPosition::new(3, -4));
+        let _hittest = driver.nc_hittest(wiard::PhysicalPosition::new(10, 10));
+        driver.close_request();
+        while let Some((event, _)) = recorder.recv() {
+            match event {
+                wiard::Event::NcHitTest(ev) => ev.set(Some(wiard::NcHitTestValue::Caption)),
+                wiard::Event::CloseRequest(ev) => ev.destroy(),
+                _ => {}
+            }
+        }
+        let data = recorder.finish().unwrap();
+
+        let (events, mut replayer) = replay(&data, wiard::NcHitTestValue::Caption);
+        assert_eq!(
+            events,
+            [
+                "key:Other(255)",
+                "char:\"",
+                "entered:3,-4",
+                "nc_hittest",
+                "close_request",
+            ]
+        );
+        assert!(replayer.mismatches().is_empty());
+        let entries = replayer.entries();
+        assert!(matches!(
+            entries.last().unwrap().event,
+            RecordedEvent::Closed
+        ));
+        assert!(entries.windows(2).all(|e| e[0].timestamp <= e[1].timestamp));
+
+        let (_, mut replayer) = replay(&data, wiard::NcHitTestValue::Client);
+        let mismatches = replayer.mismatches();
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(
+            mismatches[0].expected,
+            Some(RecordedReply::NcHitTest(Some(
+                wiard::NcHitTestValue::Caption
+            )))
+        );
+        assert_eq!(
+            mismatches[0].actual,
+            RecordedReply::NcHitTest(Some(wiard::NcHitTestValue::Client))
+        );
+    });
+}
@@ -0,0 +1,44 @@
+#![cfg(feature = "headless")]
+
+mod common;
+
+use wiard::headless::Driver;
+use wiard::record::{Recorder, Replayer};
+
+#[test]
+fn replayed_close_request_test() {
+    common::run(|| {
+        let event_rx = wiard::EventReceiver::new();
+        let window = common::builder(&event_rx)
+            .auto_close(false)
+            .build()
+            .unwrap();
+        let mut recorder = Recorder::new(event_rx, vec![]);
+        Driver::new(&window).close_request();
+        while let Some((event, _)) = recorder.recv() {
+            if let wiard::Event::CloseRequest(_) = event {
+                break;
+            }
+        }
+        let data = recorder.finish().unwrap();
+
+        let mut replayer = Replayer::new(&data[..]).unwrap();
+        let mut close_requests = 0;
+        while let Some((event, w)) = replayer.recv() {
+            let wiard::Event::CloseRequest(ev) = event else {
+                continue;
+            };
+            let wiard::WindowKind::Window(replayed) = w else {
+                panic!("not Window");
+            };
+            ev.destroy();
+            replayed.close();
+            assert!(replayed.is_closed());
+            assert_eq!(replayed.inner_size(), None);
+            close_requests += 1;
+        }
+        assert_eq!(close_requests, 1);
+        assert!(window.inner_size().is_some());
+        assert!(!window.is_closed());
+    });
+}